@@ -0,0 +1,604 @@
+//! Incremental hot backups streamed to pluggable storage
+//!
+//! A backup starts from a checkpoint in a staging directory next to the
+//! database. Checkpoints hard-link the SST files, so staging costs almost no
+//! extra disk space, and the files are streamed from there to a
+//! [`BackupSink`] rather than copied locally first. SST and blob files never
+//! change once written, so each backup only uploads the ones the sink does
+//! not have yet; running backups often keeps every upload small.
+//!
+//! Objects are laid out in the sink as:
+//!
+//! * `shared/<number>_<size>_<crc32c>.<ext>` - SST and blob files, shared by
+//!   every backup. File numbers alone can repeat across databases (or after
+//!   a restore), so the size and checksum tell different files apart
+//! * `<backup id>/<file>` - MANIFEST, CURRENT, OPTIONS and WAL files of one backup
+//! * `<backup id>/BACKUP` - The list of objects making up the backup, written last
+//! * `LATEST` - The id of the most recent complete backup
+//! * `wal/<first sequence number>` - WAL segments saved by [`HotBackup::archive_wal`]
+//!
+//! [`DirectorySink`] stores backups in a local directory (which may be a
+//! mounted network volume); an S3-compatible or other remote store only has
+//! to implement the two methods of [`BackupSink`], plus the two of
+//! [`BackupSource`] to restore with [`BackupRestore`].
+//!
+//! Restoring the latest backup brings back the database as of that backup.
+//! For point-in-time recovery, archive the WAL between backups; a restore
+//! then starts from the newest backup before the target sequence number and
+//! replays archived writes up to it.
+
+use crate::batch::WriteBatch;
+use crate::checkpoint::Checkpoint;
+use crate::checksum::crc32c_extend;
+use crate::db::DB;
+use crate::error::{Error, ErrorKind, Result};
+use crate::options::Options;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Destination for backup files
+///
+/// Keys are `/`-separated relative paths (see the [module docs](self) for
+/// the layout). Uploads must be atomic: after a failed or interrupted
+/// upload, [`BackupSink::contains`] must not report the key as present.
+pub trait BackupSink {
+    /// Check whether an object was stored by an earlier upload
+    fn contains(&mut self, key: &str) -> Result<bool>;
+
+    /// Store `len` bytes read from `data` under `key`, replacing any existing object
+    fn upload(&mut self, key: &str, data: &mut dyn Read, len: u64) -> Result<()>;
+}
+
+/// Origin of backup files for [`BackupRestore`]
+///
+/// Usually implemented by the same type as [`BackupSink`], reading back
+/// what it stored.
+pub trait BackupSource {
+    /// Open an object for reading
+    fn open(&mut self, key: &str) -> Result<Box<dyn Read + '_>>;
+
+    /// List the keys of all objects whose key starts with `prefix`, in any order
+    fn list(&mut self, prefix: &str) -> Result<Vec<String>>;
+}
+
+/// A [`BackupSink`] storing objects as files under a local directory
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, DirectorySink, HotBackup, Options};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let mut sink = DirectorySink::new("/mnt/backups/my_db").unwrap();
+/// let info = HotBackup::new(&db).run(&mut sink).unwrap();
+/// println!("backup {} uploaded {} bytes", info.id, info.uploaded_bytes);
+/// ```
+pub struct DirectorySink {
+    root: PathBuf,
+}
+
+impl DirectorySink {
+    /// Create a sink storing objects under `root`, creating the directory if needed
+    pub fn new<P: AsRef<Path>>(root: P) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root)
+            .map_err(|e| io_error(&format!("Failed to create {}", root.display()), e))?;
+        Ok(DirectorySink { root })
+    }
+
+    /// Get the directory objects are stored in
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl BackupSink for DirectorySink {
+    fn contains(&mut self, key: &str) -> Result<bool> {
+        Ok(self.root.join(key).is_file())
+    }
+
+    fn upload(&mut self, key: &str, data: &mut dyn Read, _len: u64) -> Result<()> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| io_error(&format!("Failed to create {}", parent.display()), e))?;
+        }
+
+        // Write to a temporary name first so a partial file is never visible
+        let tmp_path = path.with_extension("tmp");
+        let result = File::create(&tmp_path)
+            .and_then(|mut file| {
+                io::copy(data, &mut file)?;
+                file.sync_all()
+            })
+            .and_then(|()| fs::rename(&tmp_path, &path));
+
+        result.map_err(|e| {
+            let _ = fs::remove_file(&tmp_path);
+            io_error(&format!("Failed to write {}", path.display()), e)
+        })
+    }
+}
+
+impl BackupSource for DirectorySink {
+    fn open(&mut self, key: &str) -> Result<Box<dyn Read + '_>> {
+        let path = self.root.join(key);
+        let file = File::open(&path)
+            .map_err(|e| io_error(&format!("Failed to open {}", path.display()), e))?;
+        Ok(Box::new(file))
+    }
+
+    fn list(&mut self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut pending = vec![self.root.clone()];
+
+        while let Some(dir) = pending.pop() {
+            let entries = fs::read_dir(&dir)
+                .map_err(|e| io_error(&format!("Failed to list {}", dir.display()), e))?;
+            for entry in entries {
+                let path = entry
+                    .map_err(|e| io_error(&format!("Failed to list {}", dir.display()), e))?
+                    .path();
+                if path.is_dir() {
+                    pending.push(path);
+                } else if path.extension().is_none_or(|ext| ext != "tmp") {
+                    // Keys always use '/', whatever the platform separator is
+                    let key = path
+                        .strip_prefix(&self.root)
+                        .unwrap_or(&path)
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    if key.starts_with(prefix) {
+                        keys.push(key);
+                    }
+                }
+            }
+        }
+
+        Ok(keys)
+    }
+}
+
+/// What a [`HotBackup::run`] stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupInfo {
+    /// Id of the backup, the database's sequence number zero-padded to 20 digits
+    pub id: String,
+    /// Number of files making up the backup
+    pub files: usize,
+    /// Number of files uploaded by this run (the others were already stored)
+    pub uploaded_files: usize,
+    /// Bytes uploaded by this run
+    pub uploaded_bytes: u64,
+}
+
+/// Takes backups of an open database without stopping writes
+///
+/// Each run creates a checkpoint in the staging directory, uploads what the
+/// sink is missing, and removes the staging directory again. The staging
+/// directory must be on the same filesystem as the database for SST files to
+/// be hard-linked; by default it is `<db path>.backup-staging`.
+///
+/// WAL files are included rather than flushing memtables first, so backups
+/// do not force small SST files out of a lightly written database.
+pub struct HotBackup<'a> {
+    db: &'a DB,
+    staging_dir: PathBuf,
+}
+
+impl<'a> HotBackup<'a> {
+    /// Name of the object listing the files of a backup
+    pub const INDEX_NAME: &'static str = "BACKUP";
+    /// Name of the object holding the id of the latest backup
+    pub const LATEST_NAME: &'static str = "LATEST";
+    /// Prefix of the objects shared between backups
+    pub const SHARED_PREFIX: &'static str = "shared";
+    /// Prefix of archived WAL segments
+    pub const WAL_PREFIX: &'static str = "wal";
+
+    /// Create a backup runner for `db` with the default staging directory
+    pub fn new(db: &'a DB) -> Self {
+        HotBackup {
+            db,
+            staging_dir: PathBuf::from(format!("{}.backup-staging", db.path())),
+        }
+    }
+
+    /// Set the staging directory
+    ///
+    /// It must not exist when a run starts and is removed when the run ends;
+    /// one left behind by a crashed process has to be removed by hand.
+    pub fn set_staging_dir<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.staging_dir = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// Take a backup and upload the files the sink does not have yet
+    pub fn run<S: BackupSink + ?Sized>(&self, sink: &mut S) -> Result<BackupInfo> {
+        let id = format!("{:020}", self.db.latest_sequence_number());
+
+        let checkpoint = Checkpoint::new(self.db)?;
+        checkpoint.create_checkpoint_with_log_size(&self.staging_dir, u64::MAX)?;
+        let _staging = StagingDir(&self.staging_dir);
+
+        let mut names: Vec<String> = fs::read_dir(&self.staging_dir)
+            .map_err(|e| io_error("Failed to list checkpoint", e))?
+            .map(|entry| {
+                entry
+                    .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                    .map_err(|e| io_error("Failed to list checkpoint", e))
+            })
+            .collect::<Result<_>>()?;
+        // CURRENT points at the MANIFEST, so store it after everything else
+        names.sort_by_key(|name| name == "CURRENT");
+
+        let mut info = BackupInfo {
+            id: id.clone(),
+            files: names.len(),
+            uploaded_files: 0,
+            uploaded_bytes: 0,
+        };
+        let mut keys = Vec::with_capacity(names.len());
+
+        for name in &names {
+            let path = self.staging_dir.join(name);
+            let key = if is_immutable(name) {
+                shared_key(name, &path)?
+            } else {
+                format!("{}/{}", id, name)
+            };
+
+            if !is_immutable(name) || !sink.contains(&key)? {
+                let mut file = File::open(&path)
+                    .map_err(|e| io_error(&format!("Failed to open {}", path.display()), e))?;
+                let len = file
+                    .metadata()
+                    .map_err(|e| io_error(&format!("Failed to stat {}", path.display()), e))?
+                    .len();
+                sink.upload(&key, &mut file, len)?;
+
+                info.uploaded_files += 1;
+                info.uploaded_bytes += len;
+            }
+            keys.push(key);
+        }
+
+        // The index and LATEST make the backup visible, so they go last
+        let index = keys.join("\n");
+        sink.upload(
+            &format!("{}/{}", id, Self::INDEX_NAME),
+            &mut index.as_bytes(),
+            index.len() as u64,
+        )?;
+        sink.upload(Self::LATEST_NAME, &mut id.as_bytes(), id.len() as u64)?;
+
+        Ok(info)
+    }
+}
+
+impl HotBackup<'_> {
+    /// Upload the writes committed since `since_seq` as one WAL segment
+    ///
+    /// Returns the sequence number to pass to the next call; start with the
+    /// id of the backup to extend (`id.parse::<u64>()? + 1`). The segment is
+    /// assembled in memory, so archive often enough to keep it small, and
+    /// retain WAL files for long enough with
+    /// [`Options::set_wal_ttl_seconds`](crate::Options::set_wal_ttl_seconds)
+    /// that the next call finds them.
+    pub fn archive_wal<S: BackupSink + ?Sized>(&self, sink: &mut S, since_seq: u64) -> Result<u64> {
+        if since_seq > self.db.latest_sequence_number() {
+            return Ok(since_seq);
+        }
+
+        let mut segment = Vec::new();
+        let mut first_seq = None;
+        let mut next_seq = since_seq;
+
+        for item in self.db.get_updates_since(since_seq)? {
+            let (seq, batch) = item?;
+            let end = seq + batch.len() as u64;
+            // The first batch may start before the requested sequence number
+            if end <= since_seq {
+                continue;
+            }
+
+            let data = batch.data();
+            segment.extend_from_slice(&seq.to_be_bytes());
+            segment.extend_from_slice(&(data.len() as u64).to_be_bytes());
+            segment.extend_from_slice(data);
+            first_seq.get_or_insert(seq);
+            next_seq = end;
+        }
+
+        if let Some(first_seq) = first_seq {
+            sink.upload(
+                &format!("{}/{:020}", Self::WAL_PREFIX, first_seq),
+                &mut segment.as_slice(),
+                segment.len() as u64,
+            )?;
+        }
+
+        Ok(next_seq)
+    }
+}
+
+/// Restores backups written by [`HotBackup`]
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{BackupRestore, DB, DirectorySink, Options};
+///
+/// let mut source = DirectorySink::new("/mnt/backups/my_db").unwrap();
+/// let mut restore = BackupRestore::new(&mut source);
+///
+/// // Either the latest backup...
+/// restore.restore_latest("/tmp/my_db_restored").unwrap();
+///
+/// // ...or the state right after sequence number 12345
+/// let opts = Options::default();
+/// restore
+///     .restore_to_sequence("/tmp/my_db_at_12345", &opts, 12345)
+///     .unwrap();
+/// let db = DB::open(&opts, "/tmp/my_db_at_12345").unwrap();
+/// ```
+pub struct BackupRestore<'s, S: BackupSource + ?Sized> {
+    source: &'s mut S,
+}
+
+impl<'s, S: BackupSource + ?Sized> BackupRestore<'s, S> {
+    /// Create a restorer reading from `source`
+    pub fn new(source: &'s mut S) -> Self {
+        BackupRestore { source }
+    }
+
+    /// List the ids of the complete backups, oldest first
+    pub fn list_backups(&mut self) -> Result<Vec<String>> {
+        let suffix = format!("/{}", HotBackup::INDEX_NAME);
+        let mut ids: Vec<String> = self
+            .source
+            .list("")?
+            .into_iter()
+            .filter_map(|key| key.strip_suffix(&suffix).map(str::to_owned))
+            .filter(|id| !id.contains('/') && id.parse::<u64>().is_ok())
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Get the id of the latest complete backup
+    pub fn latest_backup(&mut self) -> Result<String> {
+        let mut id = String::new();
+        self.source
+            .open(HotBackup::LATEST_NAME)?
+            .read_to_string(&mut id)
+            .map_err(|e| io_error("Failed to read latest backup id", e))?;
+        Ok(id.trim().to_owned())
+    }
+
+    /// Restore the latest backup into `target_dir` and return its id
+    pub fn restore_latest<P: AsRef<Path>>(&mut self, target_dir: P) -> Result<String> {
+        let id = self.latest_backup()?;
+        self.restore(&id, target_dir)?;
+        Ok(id)
+    }
+
+    /// Restore a backup into `target_dir`, which must not exist or be empty
+    pub fn restore<P: AsRef<Path>>(&mut self, id: &str, target_dir: P) -> Result<()> {
+        let target_dir = target_dir.as_ref();
+        let is_empty =
+            fs::read_dir(target_dir).map_or(true, |mut entries| entries.next().is_none());
+        if !is_empty {
+            return Err(Error::new(format!(
+                "Restore target {} is not empty",
+                target_dir.display()
+            )));
+        }
+        fs::create_dir_all(target_dir)
+            .map_err(|e| io_error(&format!("Failed to create {}", target_dir.display()), e))?;
+
+        let mut index = String::new();
+        self.source
+            .open(&format!("{}/{}", id, HotBackup::INDEX_NAME))?
+            .read_to_string(&mut index)
+            .map_err(|e| io_error(&format!("Failed to read index of backup {}", id), e))?;
+
+        for key in index.lines() {
+            let path = target_dir.join(restored_name(key));
+            let mut reader = self.source.open(key)?;
+            File::create(&path)
+                .and_then(|mut file| {
+                    io::copy(&mut reader, &mut file)?;
+                    file.sync_all()
+                })
+                .map_err(|e| io_error(&format!("Failed to write {}", path.display()), e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Restore the database as it was right after sequence number `target_seq`
+    ///
+    /// Starts from the newest backup not past `target_seq` and replays the
+    /// archived WAL batches up to it; a batch is applied whole if it starts
+    /// at or before `target_seq`. `options` are used to open the restored
+    /// database for the replay (for every column family). Returns the
+    /// sequence number the restored database ends at. Fails if the archive
+    /// has a gap between the backup and the target.
+    pub fn restore_to_sequence<P: AsRef<Path>>(
+        &mut self,
+        target_dir: P,
+        options: &Options,
+        target_seq: u64,
+    ) -> Result<u64> {
+        let target_dir = target_dir.as_ref();
+        let candidates: Vec<String> = self
+            .list_backups()?
+            .into_iter()
+            .filter(|id| id.parse::<u64>().is_ok_and(|seq| seq <= target_seq))
+            .collect();
+
+        // A backup's checkpoint can include writes made after its id was taken
+        for id in candidates.iter().rev() {
+            self.restore(id, target_dir)?;
+            let replayed = self.replay_wal(target_dir, options, target_seq)?;
+            if let Some(reached) = replayed {
+                return Ok(reached);
+            }
+            let _ = fs::remove_dir_all(target_dir);
+        }
+
+        Err(Error::new(format!(
+            "No backup at or before sequence number {}",
+            target_seq
+        )))
+    }
+
+    /// Replay archived batches onto a restored backup, or return `None` if it is already past the target
+    fn replay_wal(
+        &mut self,
+        dir: &Path,
+        options: &Options,
+        target_seq: u64,
+    ) -> Result<Option<u64>> {
+        let cf_names = DB::list_column_families(options, dir)?;
+        let cf_names: Vec<&str> = cf_names.iter().map(String::as_str).collect();
+        let cf_options: Vec<Options> = cf_names.iter().map(|_| options.clone()).collect();
+        let (db, cf_handles) = DB::open_with_column_families(options, dir, &cf_names, &cf_options)?;
+
+        let mut reached = db.latest_sequence_number();
+        if reached > target_seq {
+            drop(cf_handles);
+            drop(db);
+            return Ok(None);
+        }
+
+        let mut segments = self.source.list(&format!("{}/", HotBackup::WAL_PREFIX))?;
+        segments.sort();
+
+        'segments: for key in segments {
+            let mut data = Vec::new();
+            self.source
+                .open(&key)?
+                .read_to_end(&mut data)
+                .map_err(|e| io_error(&format!("Failed to read {}", key), e))?;
+
+            for (seq, batch) in decode_segment(&key, &data)? {
+                if seq > target_seq {
+                    break 'segments;
+                }
+                if seq <= reached {
+                    continue;
+                }
+                if seq != reached + 1 {
+                    return Err(Error::new(format!(
+                        "WAL archive has a gap between sequence numbers {} and {}",
+                        reached, seq
+                    )));
+                }
+                db.write(&batch)?;
+                reached = db.latest_sequence_number();
+            }
+        }
+
+        drop(cf_handles);
+        drop(db);
+        Ok(Some(reached))
+    }
+}
+
+/// Split a WAL segment into its `(sequence number, batch)` records
+fn decode_segment(key: &str, mut data: &[u8]) -> Result<Vec<(u64, WriteBatch)>> {
+    let truncated = || {
+        Error::with_kind(
+            ErrorKind::Corruption,
+            format!("WAL segment {} is truncated", key),
+        )
+    };
+    let mut batches = Vec::new();
+
+    while !data.is_empty() {
+        let header = data.get(..16).ok_or_else(truncated)?;
+        let seq = u64::from_be_bytes(header[..8].try_into().unwrap());
+        let len = u64::from_be_bytes(header[8..].try_into().unwrap());
+        let end = usize::try_from(len)
+            .ok()
+            .and_then(|len| 16usize.checked_add(len))
+            .filter(|&end| end <= data.len())
+            .ok_or_else(truncated)?;
+        let (record, rest) = data.split_at(end);
+        batches.push((seq, WriteBatch::from_data(&record[16..])?));
+        data = rest;
+    }
+
+    Ok(batches)
+}
+
+/// Removes the staging directory when a backup run ends, successfully or not
+struct StagingDir<'a>(&'a Path);
+
+impl Drop for StagingDir<'_> {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(self.0);
+    }
+}
+
+/// Check whether a database file never changes once written
+fn is_immutable(name: &str) -> bool {
+    name.ends_with(".sst") || name.ends_with(".blob")
+}
+
+/// Get the key an SST or blob file is shared under, from its size and CRC32C
+fn shared_key(name: &str, path: &Path) -> Result<String> {
+    let mut file =
+        File::open(path).map_err(|e| io_error(&format!("Failed to open {}", path.display()), e))?;
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut crc = 0;
+    let mut len = 0u64;
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| io_error(&format!("Failed to read {}", path.display()), e))?;
+        if n == 0 {
+            break;
+        }
+        crc = crc32c_extend(crc, &buf[..n]);
+        len += n as u64;
+    }
+
+    let (stem, ext) = name.rsplit_once('.').unwrap_or((name, ""));
+    Ok(format!(
+        "{}/{}_{}_{:08x}.{}",
+        HotBackup::SHARED_PREFIX,
+        stem,
+        len,
+        crc,
+        ext
+    ))
+}
+
+/// Get the file name a backup object is restored to
+fn restored_name(key: &str) -> String {
+    let name = key.rsplit('/').next().unwrap_or(key);
+    let shared = key
+        .strip_prefix(HotBackup::SHARED_PREFIX)
+        .is_some_and(|rest| rest.starts_with('/'));
+    if shared
+        && let Some((stem, ext)) = name.rsplit_once('.')
+        && let Some((number, _)) = stem.split_once('_')
+    {
+        return format!("{}.{}", number, ext);
+    }
+    name.to_owned()
+}
+
+/// Wrap an I/O error from backup file handling
+fn io_error(context: &str, err: io::Error) -> Error {
+    Error::with_kind(ErrorKind::IOError, format!("{}: {}", context, err))
+}
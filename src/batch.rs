@@ -0,0 +1,744 @@
+//! Atomic write batches
+
+use crate::db::{ColumnFamilyHandle, OwnedRocksDBBytes};
+use crate::error::{Error, ErrorKind, Result};
+use crate::ffi;
+use crate::options::Options;
+use std::ptr::{self, NonNull};
+use std::slice;
+
+/// A group of writes applied atomically with [`DB::write`](crate::DB::write)
+///
+/// Batches are also what [`DB::get_updates_since`](crate::DB::get_updates_since)
+/// yields when tailing the WAL; [`WriteBatch::operations`] decodes them.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options, WriteBatch};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let mut batch = WriteBatch::new();
+/// batch.put(b"key1", b"value1");
+/// batch.delete(b"key2");
+/// db.write(&batch).unwrap();
+/// ```
+#[must_use = "WriteBatch does nothing unless written to a database"]
+pub struct WriteBatch {
+    inner: NonNull<ffi::rocksdb_writebatch_t>,
+    check_ranges: bool,
+    // First range deletion rejected by the check, reported on write
+    invalid_range: Option<Error>,
+}
+
+/// A single operation recorded in a [`WriteBatch`]
+///
+/// `cf_id` is the [`ColumnFamilyHandle::id`] of the target column family.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteBatchOp {
+    /// A key was set to a value
+    Put {
+        cf_id: u32,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    /// A key was deleted
+    Delete { cf_id: u32, key: Vec<u8> },
+    /// A merge operand was added for a key
+    Merge {
+        cf_id: u32,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    /// Every key in `start..end` was deleted
+    DeleteRange {
+        cf_id: u32,
+        start: Vec<u8>,
+        end: Vec<u8>,
+    },
+}
+
+impl WriteBatch {
+    /// Create an empty write batch
+    pub fn new() -> Self {
+        unsafe {
+            let ptr = ffi::rocksdb_writebatch_create();
+            WriteBatch::from_raw(NonNull::new(ptr).expect("Failed to create write batch"))
+        }
+    }
+
+    /// Rebuild a write batch from its serialized form (see [`WriteBatch::data`])
+    ///
+    /// The bytes are checked record by record before RocksDB sees them, so
+    /// data from an untrusted source is safe to pass: a short, truncated or
+    /// miscounted batch fails with [`ErrorKind::Corruption`].
+    pub fn from_data(data: &[u8]) -> Result<Self> {
+        decode(data)?;
+        unsafe {
+            let ptr = ffi::rocksdb_writebatch_create_from(data.as_ptr() as *const i8, data.len());
+            Ok(WriteBatch::from_raw(
+                NonNull::new(ptr).expect("Failed to create write batch"),
+            ))
+        }
+    }
+
+    /// Take ownership of a batch allocated by RocksDB (internal use only)
+    pub(crate) fn from_raw(inner: NonNull<ffi::rocksdb_writebatch_t>) -> Self {
+        WriteBatch {
+            inner,
+            check_ranges: cfg!(debug_assertions),
+            invalid_range: None,
+        }
+    }
+
+    /// Set whether range deletions are checked before being added
+    /// (default: on in debug builds)
+    ///
+    /// While on, a range deletion whose start does not sort before its end
+    /// is still added, but [`DB::write`](crate::DB::write) fails with
+    /// [`ErrorKind::InvalidArgument`] naming the range instead of applying
+    /// the batch. Keys are compared bytewise, the order of the default
+    /// comparator. [`WriteBatch::try_delete_range`] checks whatever this is
+    /// set to, and reports the range as it is added.
+    pub fn set_check_ranges(&mut self, value: bool) -> &mut Self {
+        self.check_ranges = value;
+        self
+    }
+
+    /// Record the first range deletion rejected by the check
+    fn note_range(&mut self, start: &[u8], end: &[u8]) {
+        if self.check_ranges
+            && self.invalid_range.is_none()
+            && let Err(err) = check_range(start, end)
+        {
+            self.invalid_range = Some(err);
+        }
+    }
+
+    /// Fail with the first range deletion rejected by the check, if any (internal use only)
+    pub(crate) fn validate(&self) -> Result<()> {
+        match &self.invalid_range {
+            Some(err) => Err(err.clone()),
+            None => Ok(()),
+        }
+    }
+
+    /// Add a put to the default column family
+    pub fn put(&mut self, key: &[u8], value: &[u8]) {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+        debug_assert!(
+            value.len() < isize::MAX as usize,
+            "Value length exceeds maximum safe size"
+        );
+
+        unsafe {
+            ffi::rocksdb_writebatch_put(
+                self.inner.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+                value.as_ptr() as *const i8,
+                value.len(),
+            );
+        }
+    }
+
+    /// Add a put to a column family
+    pub fn put_cf(&mut self, cf_handle: &ColumnFamilyHandle, key: &[u8], value: &[u8]) {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+        debug_assert!(
+            value.len() < isize::MAX as usize,
+            "Value length exceeds maximum safe size"
+        );
+
+        unsafe {
+            ffi::rocksdb_writebatch_put_cf(
+                self.inner.as_ptr(),
+                cf_handle.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+                value.as_ptr() as *const i8,
+                value.len(),
+            );
+        }
+    }
+
+    /// Add a delete to the default column family
+    pub fn delete(&mut self, key: &[u8]) {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+
+        unsafe {
+            ffi::rocksdb_writebatch_delete(
+                self.inner.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+            );
+        }
+    }
+
+    /// Add a delete to a column family
+    pub fn delete_cf(&mut self, cf_handle: &ColumnFamilyHandle, key: &[u8]) {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+
+        unsafe {
+            ffi::rocksdb_writebatch_delete_cf(
+                self.inner.as_ptr(),
+                cf_handle.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+            );
+        }
+    }
+
+    /// Add a deletion of every key in `start..end` of the default column family
+    ///
+    /// Writes a single range tombstone, however many keys it covers.
+    /// [`WriteBatch::set_check_ranges`] catches empty or inverted ranges.
+    pub fn delete_range(&mut self, start: &[u8], end: &[u8]) {
+        self.note_range(start, end);
+        unsafe {
+            ffi::rocksdb_writebatch_delete_range(
+                self.inner.as_ptr(),
+                start.as_ptr() as *const i8,
+                start.len(),
+                end.as_ptr() as *const i8,
+                end.len(),
+            );
+        }
+    }
+
+    /// Add a deletion of every key in `start..end` of a column family
+    ///
+    /// See [`WriteBatch::delete_range`].
+    pub fn delete_range_cf(&mut self, cf_handle: &ColumnFamilyHandle, start: &[u8], end: &[u8]) {
+        self.note_range(start, end);
+        unsafe {
+            ffi::rocksdb_writebatch_delete_range_cf(
+                self.inner.as_ptr(),
+                cf_handle.as_ptr(),
+                start.as_ptr() as *const i8,
+                start.len(),
+                end.as_ptr() as *const i8,
+                end.len(),
+            );
+        }
+    }
+
+    /// Add a deletion of every key in `start..end` of the default column family,
+    /// failing if the range is empty or inverted
+    ///
+    /// Fails with [`ErrorKind::InvalidArgument`] and leaves the batch
+    /// unchanged unless `start` sorts bytewise before `end`.
+    pub fn try_delete_range(&mut self, start: &[u8], end: &[u8]) -> Result<()> {
+        check_range(start, end)?;
+        self.delete_range(start, end);
+        Ok(())
+    }
+
+    /// Add a deletion of every key in `start..end` of a column family,
+    /// failing if the range is empty or inverted
+    ///
+    /// See [`WriteBatch::try_delete_range`].
+    pub fn try_delete_range_cf(
+        &mut self,
+        cf_handle: &ColumnFamilyHandle,
+        start: &[u8],
+        end: &[u8],
+    ) -> Result<()> {
+        check_range(start, end)?;
+        self.delete_range_cf(cf_handle, start, end);
+        Ok(())
+    }
+
+    /// Remove all operations from the batch
+    pub fn clear(&mut self) {
+        unsafe {
+            ffi::rocksdb_writebatch_clear(self.inner.as_ptr());
+        }
+        self.invalid_range = None;
+    }
+
+    /// Get the number of operations in the batch
+    pub fn len(&self) -> usize {
+        unsafe { ffi::rocksdb_writebatch_count(self.inner.as_ptr()) as usize }
+    }
+
+    /// Check whether the batch contains no operations
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the serialized form of the batch
+    ///
+    /// The bytes can be shipped elsewhere (e.g. to a replica) and turned back
+    /// into a batch with [`WriteBatch::from_data`].
+    pub fn data(&self) -> &[u8] {
+        unsafe {
+            let mut size: usize = 0;
+            let data = ffi::rocksdb_writebatch_data(self.inner.as_ptr(), &mut size);
+            if data.is_null() {
+                &[]
+            } else {
+                slice::from_raw_parts(data as *const u8, size)
+            }
+        }
+    }
+
+    /// Decode the operations in the batch, in the order they were added
+    ///
+    /// Every operation is decoded or none are: fails with
+    /// [`ErrorKind::NotSupported`] if the batch holds a kind of operation
+    /// [`WriteBatchOp`] has no variant for (single deletes, blob indexes or
+    /// wide-column entities written by other clients), and with
+    /// [`ErrorKind::Corruption`] if the serialized batch is malformed.
+    pub fn operations(&self) -> Result<Vec<WriteBatchOp>> {
+        let decoded = decode(self.data())?;
+        match decoded.unsupported {
+            Some(tag) => Err(Error::with_kind(
+                ErrorKind::NotSupported,
+                format!(
+                    "Write batch holds an operation with record tag {:#x} that can't be decoded",
+                    tag
+                ),
+            )),
+            None => Ok(decoded.ops),
+        }
+    }
+
+    /// Get the raw pointer for FFI calls
+    pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_writebatch_t {
+        self.inner.as_ptr()
+    }
+}
+
+/// Reject a range deletion whose start does not sort before its end
+pub(crate) fn check_range(start: &[u8], end: &[u8]) -> Result<()> {
+    if start < end {
+        return Ok(());
+    }
+    Err(Error::with_kind(
+        ErrorKind::InvalidArgument,
+        format!(
+            "Range deletion from {:?} to {:?} is empty: the start must sort before the end",
+            String::from_utf8_lossy(start),
+            String::from_utf8_lossy(end)
+        ),
+    ))
+}
+
+impl Default for WriteBatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for WriteBatch {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_writebatch_destroy(self.inner.as_ptr());
+        }));
+    }
+}
+
+// WriteBatch is safe to send between threads
+unsafe impl Send for WriteBatch {}
+
+/// A write batch that can be read back before it is written
+///
+/// Keeps an index of its own writes, so [`WriteBatchWithIndex::get_from_batch`]
+/// and [`DB::get_with_batch`](crate::DB::get_with_batch) see them, which
+/// lets a multi-step update read its earlier steps without a transaction.
+/// Apply it with [`DB::write_indexed`](crate::DB::write_indexed). A later
+/// write to the same key replaces the earlier one in the index.
+///
+/// Writes take `&self`, like those of [`Transaction`](crate::Transaction),
+/// so the batch implements [`KvWrite`](crate::KvWrite); it can be sent to
+/// another thread but not shared between threads.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options, WriteBatchWithIndex};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let batch = WriteBatchWithIndex::new();
+/// batch.put(b"balance:alice", b"90");
+/// // Sees the pending write, falling back to the database for other keys
+/// let balance = db.get_with_batch(&batch, b"balance:alice").unwrap();
+/// assert_eq!(balance.as_deref(), Some(&b"90"[..]));
+/// db.write_indexed(&batch).unwrap();
+/// ```
+#[must_use = "WriteBatchWithIndex does nothing unless written to a database"]
+pub struct WriteBatchWithIndex {
+    inner: NonNull<ffi::rocksdb_writebatch_wi_t>,
+    // Reads from the batch alone take database options, for the merge operator
+    options: Options,
+}
+
+impl WriteBatchWithIndex {
+    /// Create an empty indexed write batch
+    pub fn new() -> Self {
+        unsafe {
+            let ptr = ffi::rocksdb_writebatch_wi_create(0, 1);
+            WriteBatchWithIndex {
+                inner: NonNull::new(ptr).expect("Failed to create write batch"),
+                options: Options::default(),
+            }
+        }
+    }
+
+    /// Add a put to the default column family
+    pub fn put(&self, key: &[u8], value: &[u8]) {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+        debug_assert!(
+            value.len() < isize::MAX as usize,
+            "Value length exceeds maximum safe size"
+        );
+
+        unsafe {
+            ffi::rocksdb_writebatch_wi_put(
+                self.inner.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+                value.as_ptr() as *const i8,
+                value.len(),
+            );
+        }
+    }
+
+    /// Add a put to a column family
+    pub fn put_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8], value: &[u8]) {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+        debug_assert!(
+            value.len() < isize::MAX as usize,
+            "Value length exceeds maximum safe size"
+        );
+
+        unsafe {
+            ffi::rocksdb_writebatch_wi_put_cf(
+                self.inner.as_ptr(),
+                cf_handle.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+                value.as_ptr() as *const i8,
+                value.len(),
+            );
+        }
+    }
+
+    /// Add a delete to the default column family
+    pub fn delete(&self, key: &[u8]) {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+
+        unsafe {
+            ffi::rocksdb_writebatch_wi_delete(
+                self.inner.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+            );
+        }
+    }
+
+    /// Add a delete to a column family
+    pub fn delete_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8]) {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+
+        unsafe {
+            ffi::rocksdb_writebatch_wi_delete_cf(
+                self.inner.as_ptr(),
+                cf_handle.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+            );
+        }
+    }
+
+    /// Look up a key among the batch's own writes to the default column family
+    ///
+    /// Returns `None` both for a key the batch doesn't touch and for one it
+    /// deletes; use [`DB::get_with_batch`](crate::DB::get_with_batch) to
+    /// fall back to the database.
+    pub fn get_from_batch(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        unsafe {
+            let mut val_len: usize = 0;
+            let mut err: *mut i8 = ptr::null_mut();
+            let val_ptr = ffi::rocksdb_writebatch_wi_get_from_batch(
+                self.inner.as_ptr(),
+                self.options.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+                &mut val_len,
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+            Ok(OwnedRocksDBBytes::from_raw(val_ptr, val_len).map(|bytes| bytes.to_vec()))
+        }
+    }
+
+    /// Remove all operations from the batch
+    pub fn clear(&self) {
+        unsafe {
+            ffi::rocksdb_writebatch_wi_clear(self.inner.as_ptr());
+        }
+    }
+
+    /// Get the number of operations in the batch
+    pub fn len(&self) -> usize {
+        unsafe { ffi::rocksdb_writebatch_wi_count(self.inner.as_ptr()) as usize }
+    }
+
+    /// Check whether the batch contains no operations
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Copy the operations into a plain batch for writing (internal use only)
+    pub(crate) fn to_write_batch(&self) -> Result<WriteBatch> {
+        // Copied while no write can run, since the batch isn't Sync
+        let data = unsafe {
+            let mut size: usize = 0;
+            let data = ffi::rocksdb_writebatch_wi_data(self.inner.as_ptr(), &mut size);
+            if data.is_null() {
+                Vec::new()
+            } else {
+                slice::from_raw_parts(data as *const u8, size).to_vec()
+            }
+        };
+        WriteBatch::from_data(&data)
+    }
+
+    /// Get the raw pointer for FFI calls
+    pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_writebatch_wi_t {
+        self.inner.as_ptr()
+    }
+}
+
+impl Default for WriteBatchWithIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for WriteBatchWithIndex {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_writebatch_wi_destroy(self.inner.as_ptr());
+        }));
+    }
+}
+
+// WriteBatchWithIndex is safe to send between threads; it is not Sync, as
+// writes through &self mutate the batch
+unsafe impl Send for WriteBatchWithIndex {}
+
+// Record tags of the serialized batch format (db/dbformat.h in RocksDB)
+const TAG_DELETION: u8 = 0x0;
+const TAG_VALUE: u8 = 0x1;
+const TAG_MERGE: u8 = 0x2;
+const TAG_LOG_DATA: u8 = 0x3;
+const TAG_CF_DELETION: u8 = 0x4;
+const TAG_CF_VALUE: u8 = 0x5;
+const TAG_CF_MERGE: u8 = 0x6;
+const TAG_SINGLE_DELETION: u8 = 0x7;
+const TAG_CF_SINGLE_DELETION: u8 = 0x8;
+const TAG_BEGIN_PREPARE_XID: u8 = 0x9;
+const TAG_END_PREPARE_XID: u8 = 0xA;
+const TAG_COMMIT_XID: u8 = 0xB;
+const TAG_ROLLBACK_XID: u8 = 0xC;
+const TAG_NOOP: u8 = 0xD;
+const TAG_CF_RANGE_DELETION: u8 = 0xE;
+const TAG_RANGE_DELETION: u8 = 0xF;
+const TAG_CF_BLOB_INDEX: u8 = 0x10;
+const TAG_BLOB_INDEX: u8 = 0x11;
+const TAG_BEGIN_PERSISTED_PREPARE_XID: u8 = 0x12;
+const TAG_BEGIN_UNPREPARE_XID: u8 = 0x13;
+const TAG_COMMIT_XID_AND_TIMESTAMP: u8 = 0x15;
+const TAG_WIDE_COLUMN_ENTITY: u8 = 0x16;
+const TAG_CF_WIDE_COLUMN_ENTITY: u8 = 0x17;
+const TAG_VALUE_PREFERRED_SEQNO: u8 = 0x18;
+const TAG_CF_VALUE_PREFERRED_SEQNO: u8 = 0x19;
+
+/// Size of the batch header: an 8-byte sequence number and a 4-byte count
+const HEADER_LEN: usize = 12;
+
+/// The records of a serialized batch
+struct Decoded {
+    ops: Vec<WriteBatchOp>,
+    // Tag of the first counted record that has no `WriteBatchOp` variant
+    unsupported: Option<u8>,
+}
+
+/// Decode a serialized batch, checking every record and the header count
+fn decode(data: &[u8]) -> Result<Decoded> {
+    if data.len() < HEADER_LEN {
+        return Err(corrupt(format!(
+            "Write batch is {} bytes, shorter than its {}-byte header",
+            data.len(),
+            HEADER_LEN
+        )));
+    }
+    let count = u32::from_le_bytes(data[8..HEADER_LEN].try_into().unwrap()) as usize;
+    let mut input = &data[HEADER_LEN..];
+    let mut decoded = Decoded {
+        ops: Vec::with_capacity(count.min(input.len())),
+        unsupported: None,
+    };
+    let mut found = 0usize;
+
+    while let Some((&tag, rest)) = input.split_first() {
+        input = rest;
+        let cf_id = match tag {
+            TAG_CF_DELETION
+            | TAG_CF_VALUE
+            | TAG_CF_MERGE
+            | TAG_CF_SINGLE_DELETION
+            | TAG_CF_RANGE_DELETION
+            | TAG_CF_BLOB_INDEX
+            | TAG_CF_WIDE_COLUMN_ENTITY
+            | TAG_CF_VALUE_PREFERRED_SEQNO => read_varint32(&mut input)?,
+            _ => 0,
+        };
+        let op = match tag {
+            TAG_VALUE | TAG_CF_VALUE => Some(WriteBatchOp::Put {
+                cf_id,
+                key: read_slice(&mut input)?.to_vec(),
+                value: read_slice(&mut input)?.to_vec(),
+            }),
+            TAG_DELETION | TAG_CF_DELETION => Some(WriteBatchOp::Delete {
+                cf_id,
+                key: read_slice(&mut input)?.to_vec(),
+            }),
+            TAG_MERGE | TAG_CF_MERGE => Some(WriteBatchOp::Merge {
+                cf_id,
+                key: read_slice(&mut input)?.to_vec(),
+                value: read_slice(&mut input)?.to_vec(),
+            }),
+            TAG_RANGE_DELETION | TAG_CF_RANGE_DELETION => Some(WriteBatchOp::DeleteRange {
+                cf_id,
+                start: read_slice(&mut input)?.to_vec(),
+                end: read_slice(&mut input)?.to_vec(),
+            }),
+            TAG_SINGLE_DELETION | TAG_CF_SINGLE_DELETION => {
+                read_slice(&mut input)?;
+                decoded.unsupported.get_or_insert(tag);
+                found += 1;
+                None
+            }
+            TAG_BLOB_INDEX
+            | TAG_CF_BLOB_INDEX
+            | TAG_WIDE_COLUMN_ENTITY
+            | TAG_CF_WIDE_COLUMN_ENTITY
+            | TAG_VALUE_PREFERRED_SEQNO
+            | TAG_CF_VALUE_PREFERRED_SEQNO => {
+                read_slice(&mut input)?;
+                read_slice(&mut input)?;
+                decoded.unsupported.get_or_insert(tag);
+                found += 1;
+                None
+            }
+            // Markers that are not counted as operations
+            TAG_LOG_DATA | TAG_END_PREPARE_XID | TAG_COMMIT_XID | TAG_ROLLBACK_XID => {
+                read_slice(&mut input)?;
+                None
+            }
+            TAG_COMMIT_XID_AND_TIMESTAMP => {
+                read_slice(&mut input)?;
+                read_slice(&mut input)?;
+                None
+            }
+            TAG_NOOP
+            | TAG_BEGIN_PREPARE_XID
+            | TAG_BEGIN_PERSISTED_PREPARE_XID
+            | TAG_BEGIN_UNPREPARE_XID => None,
+            _ => {
+                return Err(corrupt(format!(
+                    "Write batch has unknown record tag {:#x}",
+                    tag
+                )));
+            }
+        };
+        if let Some(op) = op {
+            decoded.ops.push(op);
+            found += 1;
+        }
+    }
+
+    if found != count {
+        return Err(corrupt(format!(
+            "Write batch header counts {} operations but holds {}",
+            count, found
+        )));
+    }
+    Ok(decoded)
+}
+
+/// Read a little-endian base-128 varint of at most 32 bits
+fn read_varint32(input: &mut &[u8]) -> Result<u32> {
+    let mut value = 0u32;
+    for shift in (0..35).step_by(7) {
+        let (&byte, rest) = input
+            .split_first()
+            .ok_or_else(|| corrupt("Write batch ends inside a varint".to_string()))?;
+        *input = rest;
+        value |= u32::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(corrupt(
+        "Write batch has a varint longer than 32 bits".to_string(),
+    ))
+}
+
+/// Read a varint32 length followed by that many bytes
+fn read_slice<'a>(input: &mut &'a [u8]) -> Result<&'a [u8]> {
+    let len = read_varint32(input)? as usize;
+    if input.len() < len {
+        return Err(corrupt(format!(
+            "Write batch record needs {} bytes but {} remain",
+            len,
+            input.len()
+        )));
+    }
+    let (bytes, rest) = input.split_at(len);
+    *input = rest;
+    Ok(bytes)
+}
+
+fn corrupt(msg: String) -> Error {
+    Error::with_kind(ErrorKind::Corruption, msg)
+}
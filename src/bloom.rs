@@ -0,0 +1,90 @@
+//! How well bloom filters are paying off
+//!
+//! A bloom filter costs memory and a probe on every lookup, and pays back
+//! each time it rules out an SST file that would otherwise have been read.
+//! [`BloomFilterReport`] gathers the statistics tickers that show which way
+//! that trade goes for a database.
+
+use crate::options::Options;
+use crate::statistics::Ticker;
+
+/// Bloom filter counters, from [`DB::bloom_filter_report`](crate::DB::bloom_filter_report)
+///
+/// All counts are cumulative for the database and 0 unless
+/// [`Options::enable_statistics`](crate::Options::enable_statistics) was set.
+/// Take a report before and after a workload and use
+/// [`BloomFilterReport::delta`] to measure just that workload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BloomFilterReport {
+    /// Whole-key filter checks that ruled out a file
+    pub useful: u64,
+    /// Whole-key filter checks that did not rule out a file
+    pub full_positive: u64,
+    /// Of those, the checks where the key really was in the file
+    pub full_true_positive: u64,
+    /// Prefix filter checks
+    pub prefix_checked: u64,
+    /// Prefix filter checks that ruled out a file
+    pub prefix_useful: u64,
+}
+
+impl BloomFilterReport {
+    /// Read the bloom filter tickers from the statistics of `options`
+    pub(crate) fn capture(options: &Options) -> Self {
+        BloomFilterReport {
+            useful: options.ticker_count(Ticker::BloomFilterUseful),
+            full_positive: options.ticker_count(Ticker::BloomFilterFullPositive),
+            full_true_positive: options.ticker_count(Ticker::BloomFilterFullTruePositive),
+            prefix_checked: options.ticker_count(Ticker::BloomFilterPrefixChecked),
+            prefix_useful: options.ticker_count(Ticker::BloomFilterPrefixUseful),
+        }
+    }
+
+    /// Get how much each counter grew since an earlier report
+    pub fn delta(&self, earlier: &BloomFilterReport) -> BloomFilterReport {
+        BloomFilterReport {
+            useful: self.useful.saturating_sub(earlier.useful),
+            full_positive: self.full_positive.saturating_sub(earlier.full_positive),
+            full_true_positive: self
+                .full_true_positive
+                .saturating_sub(earlier.full_true_positive),
+            prefix_checked: self.prefix_checked.saturating_sub(earlier.prefix_checked),
+            prefix_useful: self.prefix_useful.saturating_sub(earlier.prefix_useful),
+        }
+    }
+
+    /// Get the number of whole-key filter checks
+    pub fn checked(&self) -> u64 {
+        self.useful + self.full_positive
+    }
+
+    /// Get the whole-key filter positives where the key was not in the file
+    pub fn false_positives(&self) -> u64 {
+        self.full_positive.saturating_sub(self.full_true_positive)
+    }
+
+    /// Get the fraction of whole-key filter checks that ruled out a file
+    ///
+    /// Low values mean most lookups are for keys that exist, which a filter
+    /// can't help with. `None` if no filter was checked.
+    pub fn useful_ratio(&self) -> Option<f64> {
+        let checked = self.checked();
+        (checked > 0).then(|| self.useful as f64 / checked as f64)
+    }
+
+    /// Get the fraction of files without the key that the filter failed to rule out
+    ///
+    /// Compare it with the rate the bits per key were chosen for, about 1%
+    /// for 10. `None` if no filter was checked for a missing key.
+    pub fn false_positive_rate(&self) -> Option<f64> {
+        let negatives = self.useful + self.false_positives();
+        (negatives > 0).then(|| self.false_positives() as f64 / negatives as f64)
+    }
+
+    /// Get the fraction of prefix filter checks that ruled out a file
+    ///
+    /// `None` if no prefix filter was checked.
+    pub fn prefix_useful_ratio(&self) -> Option<f64> {
+        (self.prefix_checked > 0).then(|| self.prefix_useful as f64 / self.prefix_checked as f64)
+    }
+}
@@ -0,0 +1,104 @@
+//! Column family handles that keep their database open
+
+use crate::db::{ColumnFamilyHandle, DB};
+use crate::error::Result;
+use crate::iterator::{DBIterator, KeyRange};
+use crate::owned_iter::OwnedIterator;
+use std::sync::Arc;
+
+/// A column family together with a shared reference to its database
+///
+/// A plain [`ColumnFamilyHandle`] must be dropped before its database, which
+/// is awkward for long-lived services that store handles in structs or move
+/// them into worker threads. A `BoundColumnFamily` holds an `Arc<DB>`, so
+/// the database stays open for as long as any bound handle exists, and the
+/// handle is `'static`, `Send` and `Sync`.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{BoundColumnFamily, DB, Options};
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = Arc::new(DB::open_cf(&opts, "/tmp/my_db", ["users"]).unwrap());
+///
+/// let users = BoundColumnFamily::new(&db, "users").unwrap();
+/// drop(db);
+///
+/// // The database stays open as long as the bound handle does
+/// thread::spawn(move || {
+///     users.put(b"user:1", b"Alice").unwrap();
+/// })
+/// .join()
+/// .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct BoundColumnFamily {
+    // Declared before the database so the handle is released first
+    handle: ColumnFamilyHandle,
+    db: Arc<DB>,
+}
+
+impl BoundColumnFamily {
+    /// Bind the named column family of `db`
+    ///
+    /// Returns `None` if the database has no open column family with that
+    /// name (see [`DB::cf_handle`]).
+    pub fn new(db: &Arc<DB>, name: &str) -> Option<Self> {
+        let handle = db.cf_handle(name)?;
+        Some(BoundColumnFamily {
+            handle,
+            db: Arc::clone(db),
+        })
+    }
+
+    /// Get the database
+    pub fn db(&self) -> &Arc<DB> {
+        &self.db
+    }
+
+    /// Get the column family handle, for use with the `_cf` methods of [`DB`]
+    pub fn handle(&self) -> &ColumnFamilyHandle {
+        &self.handle
+    }
+
+    /// Get the name of the column family
+    pub fn name(&self) -> &str {
+        self.handle.name()
+    }
+
+    /// Write a key-value pair
+    pub fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.db.put_cf(&self.handle, key, value)
+    }
+
+    /// Read the value of a key
+    pub fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.db.get_cf(&self.handle, key)
+    }
+
+    /// Check whether a key exists
+    pub fn contains_key(&self, key: &[u8]) -> Result<bool> {
+        self.db.contains_key_cf(&self.handle, key)
+    }
+
+    /// Delete a key
+    pub fn delete(&self, key: &[u8]) -> Result<()> {
+        self.db.delete_cf(&self.handle, key)
+    }
+
+    /// Create a raw iterator over the column family
+    pub fn raw_iterator(&self) -> DBIterator<'_> {
+        self.db.raw_iterator_cf(&self.handle)
+    }
+
+    /// Iterate forward over the keys in `range`, keeping the database open
+    ///
+    /// The iterator is `'static` and `Send`, like the bound handle itself.
+    pub fn range(&self, range: impl KeyRange) -> OwnedIterator {
+        OwnedIterator::range_cf(&self.db, &self.handle, range)
+    }
+}
@@ -0,0 +1,139 @@
+//! Block and row caches
+
+use crate::ffi;
+use std::ptr::NonNull;
+use std::sync::Arc;
+
+/// Owner of the underlying `rocksdb_cache_t` pointer
+struct CacheInner {
+    ptr: NonNull<ffi::rocksdb_cache_t>,
+}
+
+impl Drop for CacheInner {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_cache_destroy(self.ptr.as_ptr());
+        }));
+    }
+}
+
+// The RocksDB cache is internally synchronized
+unsafe impl Send for CacheInner {}
+unsafe impl Sync for CacheInner {}
+
+/// An LRU cache for SST blocks or rows
+///
+/// Use it as the block cache with [`BlockBasedOptions::set_block_cache`](crate::BlockBasedOptions::set_block_cache)
+/// or as the row cache with [`Options::set_row_cache`](crate::Options::set_row_cache).
+/// One cache can be shared by several column families and databases, which
+/// then compete for its capacity.
+///
+/// `Cache` is cheaply cloneable; clones refer to the same cache.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{BlockBasedOptions, Cache, DB, Options};
+///
+/// let block_cache = Cache::new_lru(256 * 1024 * 1024);
+/// let row_cache = Cache::new_lru(64 * 1024 * 1024);
+///
+/// let mut table_opts = BlockBasedOptions::default();
+/// table_opts.set_block_cache(&block_cache);
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true)
+///     .enable_statistics()
+///     .set_block_based_table_factory(&table_opts)
+///     .set_row_cache(&row_cache);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let stats = db.cache_stats().unwrap();
+/// println!("block cache hit ratio: {:?}", stats.block_cache.hit_ratio());
+/// ```
+#[derive(Clone)]
+pub struct Cache {
+    inner: Arc<CacheInner>,
+}
+
+impl Cache {
+    /// Create an LRU cache holding up to `capacity` bytes
+    pub fn new_lru(capacity: usize) -> Self {
+        unsafe {
+            let ptr = ffi::rocksdb_cache_create_lru(capacity);
+            Cache {
+                inner: Arc::new(CacheInner {
+                    ptr: NonNull::new(ptr).expect("Failed to create cache"),
+                }),
+            }
+        }
+    }
+
+    /// Get the capacity in bytes
+    pub fn capacity(&self) -> usize {
+        unsafe { ffi::rocksdb_cache_get_capacity(self.as_ptr()) }
+    }
+
+    /// Change the capacity, evicting entries if the cache shrinks below its usage
+    pub fn set_capacity(&self, capacity: usize) {
+        unsafe {
+            ffi::rocksdb_cache_set_capacity(self.as_ptr(), capacity);
+        }
+    }
+
+    /// Get the bytes currently held by the cache
+    pub fn usage(&self) -> usize {
+        unsafe { ffi::rocksdb_cache_get_usage(self.as_ptr()) }
+    }
+
+    /// Get the bytes held by entries that are in use and cannot be evicted
+    pub fn pinned_usage(&self) -> usize {
+        unsafe { ffi::rocksdb_cache_get_pinned_usage(self.as_ptr()) }
+    }
+
+    /// Get the raw pointer for FFI calls
+    pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_cache_t {
+        self.inner.ptr.as_ptr()
+    }
+}
+
+/// Usage and effectiveness of one cache
+///
+/// Hits and misses come from the database statistics and are 0 unless
+/// [`Options::enable_statistics`](crate::Options::enable_statistics) was set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheUsage {
+    /// Capacity in bytes
+    pub capacity: usize,
+    /// Bytes currently held
+    pub usage: usize,
+    /// Bytes held by entries in use, which cannot be evicted
+    pub pinned_usage: usize,
+    /// Lookups served from the cache
+    pub hits: u64,
+    /// Lookups that missed the cache
+    pub misses: u64,
+}
+
+impl CacheUsage {
+    /// Get the fraction of lookups that hit, or `None` if there were none
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let lookups = self.hits + self.misses;
+        (lookups > 0).then(|| self.hits as f64 / lookups as f64)
+    }
+}
+
+/// The caches of a database, from [`DB::cache_stats`](crate::DB::cache_stats)
+///
+/// The block cache is reported even when none was configured, as RocksDB
+/// then uses a small default one. If the cache is shared with other
+/// databases, usage and capacity cover all of them while hits and misses
+/// only count this database's lookups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    /// The block cache of the default column family's table options
+    pub block_cache: CacheUsage,
+    /// The row cache, if one was set with [`Options::set_row_cache`](crate::Options::set_row_cache)
+    pub row_cache: Option<CacheUsage>,
+}
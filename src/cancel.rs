@@ -0,0 +1,60 @@
+//! Cooperative cancellation for long-running scans
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A flag that lets one thread ask a scan running elsewhere to stop
+///
+/// Clones share the same flag. Attach a token to an iterator with
+/// [`DBIteratorAdapter::with_cancellation`](crate::DBIteratorAdapter::with_cancellation)
+/// or [`MergeIterator::with_cancellation`](crate::MergeIterator::with_cancellation);
+/// once [`CancellationToken::cancel`] is called the iterator yields a single
+/// error of kind [`ErrorKind::Cancelled`](crate::ErrorKind::Cancelled) and
+/// then ends.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{CancellationToken, DB, Direction, Options};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let token = CancellationToken::new();
+/// let request_token = token.clone();
+///
+/// // e.g. from the HTTP server when the client disconnects
+/// std::thread::spawn(move || request_token.cancel());
+///
+/// for item in db.iter(Direction::Forward).with_cancellation(&token) {
+///     match item {
+///         Ok((key, _)) => println!("{:?}", key),
+///         Err(e) => {
+///             println!("Scan stopped: {}", e);
+///             break;
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a token that has not been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every scan holding this token (or a clone of it) to stop
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Check whether [`CancellationToken::cancel`] has been called
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,115 @@
+//! Checkpoints: consistent on-disk copies of an open database
+//!
+//! A checkpoint is a directory containing an openable snapshot of the
+//! database. SST files are hard-linked when the checkpoint lives on the same
+//! filesystem, so creating one is cheap even for large databases.
+
+use crate::db::DB;
+use crate::error::{Error, Result};
+use crate::ffi;
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::ptr::{self, NonNull};
+
+/// A checkpoint object bound to an open database
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{Checkpoint, DB, Options};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let checkpoint = Checkpoint::new(&db).unwrap();
+/// checkpoint.create_checkpoint("/tmp/my_db_checkpoint").unwrap();
+/// ```
+#[must_use = "Checkpoint must be used to create a checkpoint directory"]
+pub struct Checkpoint<'a> {
+    inner: NonNull<ffi::rocksdb_checkpoint_t>,
+    _phantom: PhantomData<&'a DB>,
+}
+
+impl<'a> Checkpoint<'a> {
+    /// Create a checkpoint object for the given database
+    pub fn new(db: &'a DB) -> Result<Self> {
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            let ptr = ffi::rocksdb_checkpoint_object_create(db.as_ptr(), &mut err);
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            let inner = NonNull::new(ptr)
+                .ok_or_else(|| Error::new("Failed to create checkpoint object"))?;
+
+            Ok(Checkpoint {
+                inner,
+                _phantom: PhantomData,
+            })
+        }
+    }
+
+    /// Write a checkpoint of the database to `path`
+    ///
+    /// The directory must not already exist. Memtables are always flushed
+    /// first, so the checkpoint does not depend on WAL replay.
+    pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let c_path = CString::new(path.as_ref().to_string_lossy().as_bytes())
+            .map_err(|_| Error::new("Invalid path"))?;
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            // log_size_for_flush = 0 forces a flush before the checkpoint
+            ffi::rocksdb_checkpoint_create(self.inner.as_ptr(), c_path.as_ptr(), 0, &mut err);
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Write a checkpoint of the database to `path`, keeping small WALs instead of flushing
+    ///
+    /// Memtables are only flushed if the WAL files add up to at least
+    /// `log_size_for_flush` bytes; otherwise the WALs are included in the
+    /// checkpoint and replayed when it is opened. `u64::MAX` never flushes.
+    pub fn create_checkpoint_with_log_size<P: AsRef<Path>>(
+        &self,
+        path: P,
+        log_size_for_flush: u64,
+    ) -> Result<()> {
+        let c_path = CString::new(path.as_ref().to_string_lossy().as_bytes())
+            .map_err(|_| Error::new("Invalid path"))?;
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_checkpoint_create(
+                self.inner.as_ptr(),
+                c_path.as_ptr(),
+                log_size_for_flush,
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+}
+
+impl<'a> Drop for Checkpoint<'a> {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_checkpoint_object_destroy(self.inner.as_ptr());
+        }));
+    }
+}
@@ -0,0 +1,193 @@
+//! Application-level checksums on values
+//!
+//! RocksDB checksums its blocks and WAL records, but only from the moment the
+//! data reaches RocksDB, and block checksums are only verified when
+//! `paranoid_checks`/`verify_checksums` are in effect for the files being
+//! read. [`ChecksummedDB`] adds an end-to-end check on top: every value is
+//! stored with a CRC32C of its contents, which is verified on every read.
+
+use crate::batch::WriteBatch;
+use crate::db::{ColumnFamilyHandle, DB};
+use crate::error::{Error, ErrorKind, Result};
+
+/// Length of the checksum appended to each value
+const CHECKSUM_LEN: usize = 4;
+
+/// Lookup table for CRC32C (Castagnoli, reflected polynomial 0x82F63B78)
+const CRC32C_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0x82F6_3B78
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Compute the CRC32C of `data`
+pub(crate) fn crc32c(data: &[u8]) -> u32 {
+    crc32c_extend(0, data)
+}
+
+/// Extend the CRC32C `crc` of earlier data with `data`
+pub(crate) fn crc32c_extend(crc: u32, data: &[u8]) -> u32 {
+    let mut crc = !crc;
+    for &byte in data {
+        crc = CRC32C_TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+/// A view of a database that checksums every value it writes and reads
+///
+/// Values are stored with a 4-byte little-endian CRC32C appended; reads
+/// verify and strip it, and a mismatch is reported as an error of kind
+/// [`ErrorKind::Corruption`]. Keys are not checksummed.
+///
+/// All values in the column families accessed through this view must be
+/// written through it (or encoded with [`ChecksummedDB::encode_value`]):
+/// values without a checksum fail verification. Batches and iterators work on
+/// stored values, so use [`ChecksummedDB::encode_value`] and
+/// [`ChecksummedDB::decode_value`] with them.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{ChecksummedDB, DB, ErrorKind, Options};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let store = ChecksummedDB::new(&db);
+/// store.put(b"account:1", b"balance=100").unwrap();
+///
+/// match store.get(b"account:1") {
+///     Ok(value) => println!("{:?}", value),
+///     Err(e) if e.kind() == ErrorKind::Corruption => eprintln!("bad value: {}", e),
+///     Err(e) => eprintln!("read failed: {}", e),
+/// }
+/// ```
+pub struct ChecksummedDB<'a> {
+    db: &'a DB,
+}
+
+impl<'a> ChecksummedDB<'a> {
+    /// Create a checksumming view of `db`
+    pub fn new(db: &'a DB) -> Self {
+        ChecksummedDB { db }
+    }
+
+    /// Get the underlying database
+    pub fn db(&self) -> &'a DB {
+        self.db
+    }
+
+    /// Append the checksum to a value, giving the bytes to store
+    pub fn encode_value(value: &[u8]) -> Vec<u8> {
+        let mut stored = Vec::with_capacity(value.len() + CHECKSUM_LEN);
+        stored.extend_from_slice(value);
+        stored.extend_from_slice(&crc32c(value).to_le_bytes());
+        stored
+    }
+
+    /// Verify and strip the checksum of a stored value
+    pub fn decode_value(stored: &[u8]) -> Result<&[u8]> {
+        split_checksum(stored).map_err(|message| Error::with_kind(ErrorKind::Corruption, message))
+    }
+
+    /// Write a key-value pair to the default column family
+    pub fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.db.put(key, &Self::encode_value(value))
+    }
+
+    /// Write a key-value pair to a column family
+    pub fn put_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8], value: &[u8]) -> Result<()> {
+        self.db.put_cf(cf_handle, key, &Self::encode_value(value))
+    }
+
+    /// Add a checksummed put to the default column family to a batch
+    pub fn batch_put(batch: &mut WriteBatch, key: &[u8], value: &[u8]) {
+        batch.put(key, &Self::encode_value(value));
+    }
+
+    /// Add a checksummed put to a column family to a batch
+    pub fn batch_put_cf(
+        batch: &mut WriteBatch,
+        cf_handle: &ColumnFamilyHandle,
+        key: &[u8],
+        value: &[u8],
+    ) {
+        batch.put_cf(cf_handle, key, &Self::encode_value(value));
+    }
+
+    /// Read and verify a value from the default column family
+    pub fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.db
+            .get(key)?
+            .map(|stored| verified(key, &stored))
+            .transpose()
+    }
+
+    /// Read and verify a value from a column family
+    pub fn get_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.db
+            .get_cf(cf_handle, key)?
+            .map(|stored| verified(key, &stored))
+            .transpose()
+    }
+
+    /// Delete a key from the default column family
+    pub fn delete(&self, key: &[u8]) -> Result<()> {
+        self.db.delete(key)
+    }
+
+    /// Delete a key from a column family
+    pub fn delete_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8]) -> Result<()> {
+        self.db.delete_cf(cf_handle, key)
+    }
+}
+
+/// Split a stored value from its checksum, describing any mismatch
+fn split_checksum(stored: &[u8]) -> std::result::Result<&[u8], String> {
+    if stored.len() < CHECKSUM_LEN {
+        return Err(format!(
+            "Value of {} bytes is too short to hold a checksum",
+            stored.len()
+        ));
+    }
+
+    let (value, checksum) = stored.split_at(stored.len() - CHECKSUM_LEN);
+    let expected = u32::from_le_bytes(checksum.try_into().expect("checksum is 4 bytes"));
+    let actual = crc32c(value);
+    if actual != expected {
+        return Err(format!(
+            "Value checksum mismatch: stored {:08x}, computed {:08x}",
+            expected, actual
+        ));
+    }
+
+    Ok(value)
+}
+
+/// Decode a stored value, naming the key in the error
+fn verified(key: &[u8], stored: &[u8]) -> Result<Vec<u8>> {
+    split_checksum(stored)
+        .map(<[u8]>::to_vec)
+        .map_err(|message| {
+            Error::with_kind(
+                ErrorKind::Corruption,
+                format!("{} for key {:?}", message, String::from_utf8_lossy(key)),
+            )
+        })
+}
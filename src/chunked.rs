@@ -0,0 +1,186 @@
+//! Bulk writes split into bounded batches
+//!
+//! One huge [`WriteBatch`] holds all of its data in memory and is written
+//! to the memtable in one go, which can stall other writers. Many tiny
+//! writes pay the per-write overhead each time. [`ChunkedWriter`] sits in
+//! between: it takes any number of operations and commits them in batches
+//! of bounded size.
+
+use crate::batch::WriteBatch;
+use crate::db::{ColumnFamilyHandle, DB};
+use crate::error::Result;
+use crate::options::WriteMode;
+
+/// A writer that groups operations into batches of bounded size
+///
+/// A batch is committed as soon as it reaches the operation limit, or
+/// before an operation would take it past the byte limit; an operation
+/// larger than the byte limit is committed in a batch of its own. Each batch
+/// is atomic on its own, but the operations as a whole are not: after an
+/// error, batches committed earlier stay written.
+///
+/// Call [`ChunkedWriter::finish`] to commit the last, partial batch.
+/// Operations still pending when the writer is dropped are discarded.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{ChunkedWriter, DB, Options, WriteMode};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let mut writer = ChunkedWriter::new(&db);
+/// writer
+///     .set_max_ops(10_000)
+///     .set_max_bytes(8 * 1024 * 1024)
+///     .set_write_mode(WriteMode::Sync);
+///
+/// for i in 0..1_000_000u32 {
+///     writer.put(&i.to_be_bytes(), b"value").unwrap();
+/// }
+/// let batches = writer.finish().unwrap();
+/// println!("Ingested in {} batches", batches);
+/// ```
+#[must_use = "Pending operations are discarded unless finish() is called"]
+pub struct ChunkedWriter<'a> {
+    db: &'a DB,
+    batch: WriteBatch,
+    max_ops: usize,
+    max_bytes: usize,
+    mode: WriteMode,
+    batches_written: usize,
+    ops_written: u64,
+}
+
+impl<'a> ChunkedWriter<'a> {
+    /// Default limit on the operations in one batch
+    const DEFAULT_MAX_OPS: usize = 1000;
+    /// Default limit on the serialized size of one batch
+    const DEFAULT_MAX_BYTES: usize = 4 * 1024 * 1024;
+
+    /// Create a writer for `db` with batches of at most 1000 operations and 4 MiB
+    pub fn new(db: &'a DB) -> Self {
+        ChunkedWriter {
+            db,
+            batch: WriteBatch::new(),
+            max_ops: Self::DEFAULT_MAX_OPS,
+            max_bytes: Self::DEFAULT_MAX_BYTES,
+            mode: WriteMode::Buffered,
+            batches_written: 0,
+            ops_written: 0,
+        }
+    }
+
+    /// Set the most operations committed in one batch (at least 1)
+    pub fn set_max_ops(&mut self, value: usize) -> &mut Self {
+        self.max_ops = value.max(1);
+        self
+    }
+
+    /// Set the most bytes committed in one batch, as serialized by [`WriteBatch::data`]
+    pub fn set_max_bytes(&mut self, value: usize) -> &mut Self {
+        self.max_bytes = value;
+        self
+    }
+
+    /// Set the durability each batch is committed with
+    pub fn set_write_mode(&mut self, mode: WriteMode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Get the database this writer writes to
+    pub fn db(&self) -> &'a DB {
+        self.db
+    }
+
+    /// Get the number of batches committed so far
+    pub fn batches_written(&self) -> usize {
+        self.batches_written
+    }
+
+    /// Get the number of operations committed so far
+    pub fn ops_written(&self) -> u64 {
+        self.ops_written
+    }
+
+    /// Get the number of operations waiting for the current batch to be committed
+    pub fn pending_ops(&self) -> usize {
+        self.batch.len()
+    }
+
+    /// Add a put to the default column family
+    pub fn put(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.make_room(key.len() + value.len())?;
+        self.batch.put(key, value);
+        self.commit_if_full()
+    }
+
+    /// Add a put to a column family
+    pub fn put_cf(
+        &mut self,
+        cf_handle: &ColumnFamilyHandle,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<()> {
+        self.make_room(key.len() + value.len())?;
+        self.batch.put_cf(cf_handle, key, value);
+        self.commit_if_full()
+    }
+
+    /// Add a delete from the default column family
+    pub fn delete(&mut self, key: &[u8]) -> Result<()> {
+        self.make_room(key.len())?;
+        self.batch.delete(key);
+        self.commit_if_full()
+    }
+
+    /// Add a delete from a column family
+    pub fn delete_cf(&mut self, cf_handle: &ColumnFamilyHandle, key: &[u8]) -> Result<()> {
+        self.make_room(key.len())?;
+        self.batch.delete_cf(cf_handle, key);
+        self.commit_if_full()
+    }
+
+    /// Commit the pending operations now, even if the batch is not full
+    ///
+    /// On error the operations stay pending, so the call can be retried.
+    pub fn flush(&mut self) -> Result<()> {
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+
+        self.db.write_opt(&self.batch, self.mode)?;
+        self.batches_written += 1;
+        self.ops_written += self.batch.len() as u64;
+        self.batch.clear();
+        Ok(())
+    }
+
+    /// Commit the pending operations and return the total number of batches written
+    pub fn finish(mut self) -> Result<usize> {
+        self.flush()?;
+        Ok(self.batches_written)
+    }
+
+    /// Commit the current batch if `bytes` more would take it past the byte limit
+    ///
+    /// The keys and values are all that is counted; the few bytes of framing
+    /// per operation are not.
+    fn make_room(&mut self, bytes: usize) -> Result<()> {
+        if !self.batch.is_empty() && self.batch.data().len() + bytes > self.max_bytes {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Commit the current batch if it reached the operation limit
+    fn commit_if_full(&mut self) -> Result<()> {
+        if self.batch.len() >= self.max_ops {
+            self.flush()?;
+        }
+        Ok(())
+    }
+}
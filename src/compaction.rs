@@ -0,0 +1,323 @@
+//! Progress of whole-database manual compactions, and a scheduler for
+//! maintenance compactions
+//!
+//! [`CompactionScheduler`] codifies the usual off-peak maintenance policy:
+//! watch compaction debt, tombstone density and read amplification, and
+//! compact where they exceed their limits while traffic is low.
+
+use crate::cancel::CancellationToken;
+use crate::db::DB;
+use crate::error::Result;
+use crate::read_amp::ReadAmpReport;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A step of [`DB::compact_all`](crate::DB::compact_all), reported to its callback
+///
+/// Each column family is compacted over its full key range, and reports
+/// [`CompactionProgress::Started`] before and [`CompactionProgress::Finished`]
+/// after. With several column families compacted in parallel the events of
+/// different column families interleave.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CompactionProgress {
+    /// Compaction of a column family has started
+    Started {
+        /// Name of the column family
+        column_family: String,
+        /// Column families finished so far
+        completed: usize,
+        /// Column families to compact in total
+        total: usize,
+    },
+    /// Compaction of a column family has finished
+    Finished {
+        /// Name of the column family
+        column_family: String,
+        /// Column families finished so far, this one included
+        completed: usize,
+        /// Column families to compact in total
+        total: usize,
+        /// Time the column family took
+        elapsed: Duration,
+    },
+}
+
+/// A compaction started by a [`CompactionScheduler`], and why
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum CompactionAction {
+    /// A column family had too many pending compaction bytes and was
+    /// compacted over its full key range
+    PendingCompactionBytes {
+        /// Name of the column family
+        column_family: String,
+        /// The estimate of pending compaction bytes
+        pending: u64,
+        /// The limit set with [`CompactionScheduler::set_max_pending_compaction_bytes`]
+        limit: u64,
+    },
+    /// An SST file held too many tombstones and its key range was suggested
+    /// for compaction
+    Tombstones {
+        /// Name of the column family
+        column_family: String,
+        /// Name of the file
+        file: String,
+        /// Deletion entries in the file
+        deletions: u64,
+        /// All entries in the file
+        entries: u64,
+    },
+    /// Reads since the previous check read too many block bytes per byte
+    /// used, and every column family was compacted
+    ReadAmplification {
+        /// Block bytes read per byte used
+        amplification: f64,
+        /// The limit set with [`CompactionScheduler::set_max_read_amplification`]
+        limit: f64,
+    },
+}
+
+/// Background maintenance compactions driven by database statistics
+///
+/// Each check, during one of the configured off-peak windows (or at any
+/// time if none is configured), the scheduler:
+/// * compacts every column family whose pending compaction bytes reached
+///   [`CompactionScheduler::set_max_pending_compaction_bytes`]
+/// * suggests compaction of every SST file whose share of tombstones
+///   reached [`CompactionScheduler::set_max_tombstone_ratio`]
+/// * compacts every column family if the block read amplification since the
+///   previous check reached [`CompactionScheduler::set_max_read_amplification`],
+///   which needs [`Options::enable_statistics`](crate::Options::enable_statistics)
+///   and [`BlockBasedOptions::set_read_amp_bytes_per_bit`](crate::BlockBasedOptions::set_read_amp_bytes_per_bit)
+///
+/// A scheduler with no limits set does nothing. Checks run one at a time
+/// with [`CompactionScheduler::run_once`], or on a thread of their own with
+/// [`CompactionScheduler::spawn`]. Full-range compactions block the check
+/// until they finish.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{CancellationToken, CompactionScheduler, DB, Options};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = Arc::new(DB::open(&opts, "/tmp/my_db").unwrap());
+///
+/// let mut scheduler = CompactionScheduler::new(&db);
+/// scheduler
+///     .set_max_pending_compaction_bytes(16 * 1024 * 1024 * 1024)
+///     .set_max_tombstone_ratio(0.3)
+///     // 01:00 to 05:00 UTC
+///     .add_off_peak_window(Duration::from_secs(3600), Duration::from_secs(5 * 3600));
+///
+/// let token = CancellationToken::new();
+/// let worker = scheduler.spawn(token.clone(), |action| println!("compacting: {:?}", action));
+/// // ... at shutdown
+/// token.cancel();
+/// worker.join().unwrap().unwrap();
+/// ```
+pub struct CompactionScheduler {
+    db: Arc<DB>,
+    max_pending_compaction_bytes: Option<u64>,
+    max_tombstone_ratio: Option<f64>,
+    max_read_amplification: Option<f64>,
+    // Offsets from midnight UTC
+    off_peak_windows: Vec<(Duration, Duration)>,
+    poll_interval: Duration,
+    // Baseline for the read amplification of the next check
+    last_read_amp: Option<ReadAmpReport>,
+    // Files already suggested, so each is reported once
+    suggested_files: HashSet<String>,
+}
+
+impl CompactionScheduler {
+    /// Default time between checks in [`CompactionScheduler::spawn`]
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(600);
+
+    /// Longest sleep between looks at the cancellation token
+    const CANCEL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Create a scheduler for `db` with no limits and no off-peak windows
+    pub fn new(db: &Arc<DB>) -> Self {
+        CompactionScheduler {
+            db: Arc::clone(db),
+            max_pending_compaction_bytes: None,
+            max_tombstone_ratio: None,
+            max_read_amplification: None,
+            off_peak_windows: Vec::new(),
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+            last_read_amp: None,
+            suggested_files: HashSet::new(),
+        }
+    }
+
+    /// Compact a column family once its pending compaction bytes reach `value`
+    pub fn set_max_pending_compaction_bytes(&mut self, value: u64) -> &mut Self {
+        self.max_pending_compaction_bytes = Some(value);
+        self
+    }
+
+    /// Suggest compacting an SST file once tombstones make up `value` of
+    /// its entries (between 0 and 1)
+    pub fn set_max_tombstone_ratio(&mut self, value: f64) -> &mut Self {
+        self.max_tombstone_ratio = Some(value);
+        self
+    }
+
+    /// Compact every column family once reads use less than one in `value`
+    /// of the block bytes they read
+    pub fn set_max_read_amplification(&mut self, value: f64) -> &mut Self {
+        self.max_read_amplification = Some(value);
+        self
+    }
+
+    /// Allow compactions between `start` and `end`, as offsets from midnight UTC
+    ///
+    /// A window with `end` before `start` spans midnight. Offsets are taken
+    /// modulo a day.
+    pub fn add_off_peak_window(&mut self, start: Duration, end: Duration) -> &mut Self {
+        self.off_peak_windows
+            .push((time_of_day(start), time_of_day(end)));
+        self
+    }
+
+    /// Set how often [`CompactionScheduler::spawn`] checks (default: 10 minutes)
+    pub fn set_poll_interval(&mut self, value: Duration) -> &mut Self {
+        self.poll_interval = value;
+        self
+    }
+
+    /// Check whether compactions are allowed now
+    pub fn in_off_peak_window(&self) -> bool {
+        let now = time_of_day(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default(),
+        );
+        self.off_peak_windows.is_empty()
+            || self.off_peak_windows.iter().any(|&(start, end)| {
+                if start <= end {
+                    start <= now && now < end
+                } else {
+                    now >= start || now < end
+                }
+            })
+    }
+
+    /// Run one check, compacting whatever exceeds its limit
+    ///
+    /// Does nothing outside the off-peak windows. Returns the compactions
+    /// started, in the order they ran.
+    pub fn run_once(&mut self) -> Result<Vec<CompactionAction>> {
+        if !self.in_off_peak_window() {
+            return Ok(Vec::new());
+        }
+
+        let mut actions = Vec::new();
+        if let Some(limit) = self.max_pending_compaction_bytes {
+            for handle in self.db.all_cf_handles()? {
+                let pending = self
+                    .db
+                    .write_gate_cf(&handle)
+                    .debt()?
+                    .pending_compaction_bytes;
+                if pending >= limit {
+                    self.db.compact_range_cf(&handle, None, None);
+                    actions.push(CompactionAction::PendingCompactionBytes {
+                        column_family: handle.name().to_string(),
+                        pending,
+                        limit,
+                    });
+                }
+            }
+        }
+
+        if let Some(ratio) = self.max_tombstone_ratio {
+            let files = self.db.table_properties()?;
+            self.suggested_files
+                .retain(|name| files.iter().any(|file| &file.name == name));
+            for file in files {
+                if file.num_entries == 0
+                    || file.tombstone_ratio() < ratio
+                    || self.suggested_files.contains(&file.name)
+                {
+                    continue;
+                }
+                let Some(handle) = self.db.cf_handle(&file.column_family) else {
+                    continue;
+                };
+                self.db.suggest_compact_range_cf(
+                    &handle,
+                    Some(&file.smallest_key),
+                    Some(&file.largest_key),
+                )?;
+                self.suggested_files.insert(file.name.clone());
+                actions.push(CompactionAction::Tombstones {
+                    column_family: file.column_family,
+                    file: file.name,
+                    deletions: file.num_deletions,
+                    entries: file.num_entries,
+                });
+            }
+        }
+
+        if let Some(limit) = self.max_read_amplification {
+            let report = self.db.read_amp_report();
+            let amplification = self
+                .last_read_amp
+                .replace(report)
+                .and_then(|earlier| report.delta(&earlier).block_read_amplification());
+            if let Some(amplification) = amplification
+                && amplification >= limit
+            {
+                self.db.compact_all(|_| {})?;
+                // Reads before the compaction don't reflect the new layout
+                self.last_read_amp = Some(self.db.read_amp_report());
+                actions.push(CompactionAction::ReadAmplification {
+                    amplification,
+                    limit,
+                });
+            }
+        }
+
+        Ok(actions)
+    }
+
+    /// Run checks on a new thread until `token` is cancelled
+    ///
+    /// Checks every poll interval and passes each compaction started to
+    /// `on_action`. The thread stops at the first error, which joining it
+    /// returns.
+    pub fn spawn<F>(mut self, token: CancellationToken, mut on_action: F) -> JoinHandle<Result<()>>
+    where
+        F: FnMut(&CompactionAction) + Send + 'static,
+    {
+        thread::spawn(move || {
+            while !token.is_cancelled() {
+                for action in self.run_once()? {
+                    on_action(&action);
+                }
+
+                let mut slept = Duration::ZERO;
+                while slept < self.poll_interval && !token.is_cancelled() {
+                    let step = Self::CANCEL_CHECK_INTERVAL.min(self.poll_interval - slept);
+                    thread::sleep(step);
+                    slept += step;
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Reduce a duration to its offset within a day
+fn time_of_day(value: Duration) -> Duration {
+    Duration::from_secs(value.as_secs() % 86_400)
+}
@@ -1,22 +1,52 @@
 //! RocksDB database handle
 
-use crate::error::{Error, Result};
+use crate::batch::{WriteBatch, WriteBatchOp, WriteBatchWithIndex};
+use crate::bloom::BloomFilterReport;
+use crate::cache::{CacheStats, CacheUsage};
+use crate::checkpoint::Checkpoint;
+use crate::compaction::CompactionProgress;
+use crate::env::Env;
+use crate::error::{Error, ErrorKind, ErrorSubcode, Result};
 use crate::ffi;
-use crate::iterator;
-use crate::options::Options;
-use std::ffi::CString;
-use std::path::Path;
+use crate::iterator::{self, KeyRange, prefix_read_options, range_read_options};
+use crate::metadata::{
+    ColumnFamilyMetadata, LevelInfo, SstFileMetadata, TableProperties, covering_range,
+    read_live_files,
+};
+use crate::open_progress::OpenReporter;
+use crate::options::{
+    CompactRangeOptions, FlushOptions, Options, ReadMode, ReadOptions, SizeLimits, WriteMode,
+};
+use crate::pinned::PinnedSlice;
+use crate::probe::ProbeReport;
+use crate::read_amp::ReadAmpReport;
+use crate::rename::PrefixRename;
+use crate::sampling::{SplitMix64, sample_by_scan, split_range};
+use crate::snapshot::{Snapshot, SnapshotInfo, SnapshotInner, SnapshotRegistry};
+use crate::statistics::Ticker;
+use crate::wal::WalIterator;
+use crate::write_gate::WriteGate;
+use std::collections::{HashMap, HashSet};
+use std::ffi::{CStr, CString, c_void};
+use std::fs;
+use std::io;
+use std::os::raw::{c_char, c_int};
+use std::path::{Path, PathBuf};
 use std::ptr::{self, NonNull};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
 
 /// RAII guard for RocksDB write options
 ///
 /// Automatically destroys the write options when dropped, ensuring
 /// no resource leaks even if an error occurs.
-struct WriteOptionsGuard(*mut ffi::rocksdb_writeoptions_t);
+pub(crate) struct WriteOptionsGuard(*mut ffi::rocksdb_writeoptions_t);
 
 impl WriteOptionsGuard {
     /// Create new write options
-    fn new() -> Result<Self> {
+    pub(crate) fn new() -> Result<Self> {
         unsafe {
             let ptr = ffi::rocksdb_writeoptions_create();
             if ptr.is_null() {
@@ -27,8 +57,22 @@ impl WriteOptionsGuard {
         }
     }
 
+    /// Skip the write-ahead log for writes made with these options
+    pub(crate) fn set_disable_wal(&self, disable: bool) {
+        unsafe {
+            ffi::rocksdb_writeoptions_disable_WAL(self.0, disable as c_int);
+        }
+    }
+
+    /// fsync the write-ahead log before a write made with these options returns
+    pub(crate) fn set_sync(&self, sync: bool) {
+        unsafe {
+            ffi::rocksdb_writeoptions_set_sync(self.0, sync as c_int);
+        }
+    }
+
     /// Get the raw pointer for FFI calls
-    fn as_ptr(&self) -> *mut ffi::rocksdb_writeoptions_t {
+    pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_writeoptions_t {
         self.0
     }
 }
@@ -46,11 +90,11 @@ impl Drop for WriteOptionsGuard {
 ///
 /// Automatically destroys the read options when dropped, ensuring
 /// no resource leaks even if an error occurs.
-struct ReadOptionsGuard(*mut ffi::rocksdb_readoptions_t);
+pub(crate) struct ReadOptionsGuard(*mut ffi::rocksdb_readoptions_t);
 
 impl ReadOptionsGuard {
     /// Create new read options
-    fn new() -> Result<Self> {
+    pub(crate) fn new() -> Result<Self> {
         unsafe {
             let ptr = ffi::rocksdb_readoptions_create();
             if ptr.is_null() {
@@ -61,8 +105,15 @@ impl ReadOptionsGuard {
         }
     }
 
+    /// Choose whether blocks read with these options are added to the block cache
+    pub(crate) fn set_fill_cache(&self, fill_cache: bool) {
+        unsafe {
+            ffi::rocksdb_readoptions_set_fill_cache(self.0, fill_cache as u8);
+        }
+    }
+
     /// Get the raw pointer for FFI calls
-    fn as_ptr(&self) -> *mut ffi::rocksdb_readoptions_t {
+    pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_readoptions_t {
         self.0
     }
 }
@@ -76,12 +127,276 @@ impl Drop for ReadOptionsGuard {
     }
 }
 
+/// Read and write options kept for the lifetime of a DB
+///
+/// The convenience methods pick one of these by [`WriteMode`] or
+/// [`ReadMode`] rather than creating and destroying options on every call.
+/// RocksDB only reads the options during an operation, so concurrent
+/// operations can share them.
+struct CachedOptions {
+    write: WriteOptionsGuard,
+    write_sync: WriteOptionsGuard,
+    write_no_wal: WriteOptionsGuard,
+    read: ReadOptionsGuard,
+    read_no_fill_cache: ReadOptionsGuard,
+}
+
+impl CachedOptions {
+    /// Create the options for every mode
+    fn new() -> Result<Self> {
+        let write_sync = WriteOptionsGuard::new()?;
+        write_sync.set_sync(true);
+        let write_no_wal = WriteOptionsGuard::new()?;
+        write_no_wal.set_disable_wal(true);
+        let read_no_fill_cache = ReadOptionsGuard::new()?;
+        read_no_fill_cache.set_fill_cache(false);
+
+        Ok(CachedOptions {
+            write: WriteOptionsGuard::new()?,
+            write_sync,
+            write_no_wal,
+            read: ReadOptionsGuard::new()?,
+            read_no_fill_cache,
+        })
+    }
+
+    /// Get the write options for `mode`
+    ///
+    /// Writes that skip the WAL have nothing to sync, so `skip_wal` takes
+    /// precedence over [`WriteMode::Sync`].
+    fn write(&self, mode: WriteMode, skip_wal: bool) -> &WriteOptionsGuard {
+        match (mode, skip_wal) {
+            (_, true) => &self.write_no_wal,
+            (WriteMode::Buffered, false) => &self.write,
+            (WriteMode::Sync, false) => &self.write_sync,
+        }
+    }
+
+    /// Get the read options for `mode`
+    fn read(&self, mode: ReadMode) -> &ReadOptionsGuard {
+        match mode {
+            ReadMode::Cached => &self.read,
+            ReadMode::NoFillCache => &self.read_no_fill_cache,
+        }
+    }
+}
+
+/// RAII guard for RocksDB external file ingestion options
+///
+/// Automatically destroys the ingestion options when dropped, ensuring
+/// no resource leaks even if an error occurs.
+struct IngestOptionsGuard(*mut ffi::rocksdb_ingestexternalfileoptions_t);
+
+impl IngestOptionsGuard {
+    /// Create new ingestion options
+    fn new() -> Result<Self> {
+        unsafe {
+            let ptr = ffi::rocksdb_ingestexternalfileoptions_create();
+            if ptr.is_null() {
+                Err(Error::new("Failed to create ingestion options"))
+            } else {
+                Ok(IngestOptionsGuard(ptr))
+            }
+        }
+    }
+
+    /// Get the raw pointer for FFI calls
+    fn as_ptr(&self) -> *mut ffi::rocksdb_ingestexternalfileoptions_t {
+        self.0
+    }
+}
+
+impl Drop for IngestOptionsGuard {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_ingestexternalfileoptions_destroy(self.0);
+        }));
+    }
+}
+
+/// Prefix of the column families created by [`DB::with_temp_cf`]
+const TEMP_CF_PREFIX: &str = "__temp_cf_";
+
+/// Distinguishes temporary column families created by one process
+static TEMP_CF_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// RAII guard for a temporary column family
+///
+/// Drops the column family when dropped, so scratch data is cleaned up even
+/// if the closure using it panics.
+struct TempColumnFamily<'a> {
+    db: &'a DB,
+    handle: Option<ColumnFamilyHandle>,
+}
+
+impl<'a> TempColumnFamily<'a> {
+    /// Drop the column family, reporting any failure
+    fn close(mut self) -> Result<()> {
+        match self.handle.take() {
+            Some(handle) => self.db.drop_column_family(handle),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> Drop for TempColumnFamily<'a> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            // Catch panics to prevent double-panic during unwinding
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                let _ = self.db.drop_column_family(handle);
+            }));
+        }
+    }
+}
+
+/// Convert a list of file paths into C strings for FFI calls
+fn paths_to_cstrings<P: AsRef<Path>>(paths: &[P]) -> Result<Vec<CString>> {
+    paths
+        .iter()
+        .map(|p| {
+            CString::new(p.as_ref().to_string_lossy().as_bytes())
+                .map_err(|_| Error::new("Invalid path"))
+        })
+        .collect()
+}
+
+/// Copy a RocksDB-allocated C string into a `String` and free it
+///
+/// # Safety
+///
+/// `ptr` must be null or a null-terminated string allocated by RocksDB that
+/// has not been freed yet.
+pub(crate) unsafe fn take_c_string(ptr: *mut i8) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+
+    let value = unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned();
+    unsafe { ffi::rocksdb_free(ptr as *mut std::ffi::c_void) };
+    Some(value)
+}
+
+/// Take ownership of the output arrays filled in by `rocksdb_multi_get*`
+///
+/// # Safety
+///
+/// Every non-null entry of `values` and `errs` must have been allocated by
+/// RocksDB and not freed yet; `value_sizes` must hold the matching lengths.
+unsafe fn collect_multi_get(
+    values: Vec<*mut i8>,
+    value_sizes: Vec<usize>,
+    errs: Vec<*mut i8>,
+) -> Vec<Result<Option<Vec<u8>>>> {
+    values
+        .into_iter()
+        .zip(value_sizes)
+        .zip(errs)
+        .map(|((value, size), err)| unsafe {
+            if !err.is_null() {
+                // A value is never returned alongside an error, but free it just in case
+                drop(OwnedRocksDBBytes::from_raw(value, size));
+                return Err(Error::from_c_string(err));
+            }
+            Ok(OwnedRocksDBBytes::from_raw(value, size).map(|bytes| bytes.to_vec()))
+        })
+        .collect()
+}
+
+/// Build a key-to-value map from `multi_get` results, dropping missing keys
+fn collect_found<K: AsRef<[u8]>>(
+    keys: &[K],
+    values: Vec<Result<Option<Vec<u8>>>>,
+) -> Result<HashMap<Vec<u8>, Vec<u8>>> {
+    let mut found = HashMap::with_capacity(keys.len());
+    for (key, value) in keys.iter().zip(values) {
+        if let Some(value) = value? {
+            found.insert(key.as_ref().to_vec(), value);
+        }
+    }
+    Ok(found)
+}
+
+/// Release a pinned lookup result, reporting whether the key was found
+///
+/// `rocksdb_get_pinned` returns null when the key does not exist.
+///
+/// # Safety
+///
+/// `slice` must be null or a pinnable slice returned by RocksDB that has not
+/// been destroyed yet.
+unsafe fn release_pinned(slice: *mut ffi::rocksdb_pinnableslice_t) -> bool {
+    if slice.is_null() {
+        return false;
+    }
+    unsafe { ffi::rocksdb_pinnableslice_destroy(slice) };
+    true
+}
+
+/// Key range boundaries laid out as the parallel arrays RocksDB expects
+struct RangeBounds {
+    count: i32,
+    start_ptrs: Vec<*const i8>,
+    start_lens: Vec<usize>,
+    end_ptrs: Vec<*const i8>,
+    end_lens: Vec<usize>,
+}
+
+impl RangeBounds {
+    /// Split `(start, end)` pairs into pointer and length arrays
+    ///
+    /// The pointers borrow from `ranges`, which must outlive the FFI call.
+    fn new(ranges: &[(&[u8], &[u8])]) -> Result<Self> {
+        let count = i32::try_from(ranges.len()).map_err(|_| Error::new("Too many ranges"))?;
+
+        Ok(RangeBounds {
+            count,
+            start_ptrs: ranges
+                .iter()
+                .map(|(s, _)| s.as_ptr() as *const i8)
+                .collect(),
+            start_lens: ranges.iter().map(|(s, _)| s.len()).collect(),
+            end_ptrs: ranges
+                .iter()
+                .map(|(_, e)| e.as_ptr() as *const i8)
+                .collect(),
+            end_lens: ranges.iter().map(|(_, e)| e.len()).collect(),
+        })
+    }
+}
+
+/// Convert an optional compaction bound into the pointer/length pair RocksDB expects
+///
+/// A null pointer tells RocksDB the range is unbounded on that side.
+fn range_bound(key: Option<&[u8]>) -> (*const i8, usize) {
+    match key {
+        Some(key) => (key.as_ptr() as *const i8, key.len()),
+        None => (ptr::null(), 0),
+    }
+}
+
+/// Convert option names and values for `rocksdb_set_options_cf`
+fn option_pairs(options: &[(&str, &str)]) -> Result<(Vec<CString>, Vec<CString>)> {
+    options
+        .iter()
+        .map(|&(key, value)| {
+            let key = CString::new(key).map_err(|_| Error::new("Invalid option name"))?;
+            let value = CString::new(value).map_err(|_| Error::new("Invalid option value"))?;
+            Ok((key, value))
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(|pairs| pairs.into_iter().unzip())
+}
+
 /// RAII wrapper for byte arrays allocated by RocksDB
 ///
 /// This ensures that memory returned by RocksDB (via `rocksdb_get`, etc.)
 /// is properly freed using `rocksdb_free` instead of Rust's allocator.
 /// Implements Deref to allow transparent access to the underlying slice.
-struct OwnedRocksDBBytes {
+pub(crate) struct OwnedRocksDBBytes {
     ptr: *mut u8,
     len: usize,
 }
@@ -93,7 +408,7 @@ impl OwnedRocksDBBytes {
     /// - ptr must be allocated by RocksDB or be null
     /// - if ptr is not null, it must point to at least len bytes
     /// - ptr must not be used after this call (ownership is transferred)
-    unsafe fn from_raw(ptr: *mut i8, len: usize) -> Option<Self> {
+    pub(crate) unsafe fn from_raw(ptr: *mut i8, len: usize) -> Option<Self> {
         if ptr.is_null() {
             None
         } else {
@@ -137,33 +452,145 @@ impl AsRef<[u8]> for OwnedRocksDBBytes {
     }
 }
 
+/// The name of the column family every database has
+pub(crate) const DEFAULT_COLUMN_FAMILY_NAME: &str = "default";
+
 /// A RocksDB column family handle
 ///
 /// Column families provide a way to logically partition data within a single database.
 /// Each column family can have its own configuration and be managed independently.
+///
+/// Cloning a handle is cheap: clones share the underlying RocksDB handle,
+/// which is released when the last clone is dropped.
 #[must_use = "Column family handle must be stored or it will be immediately destroyed"]
+#[derive(Clone)]
 pub struct ColumnFamilyHandle {
-    inner: NonNull<ffi::rocksdb_column_family_handle_t>,
+    inner: Arc<ColumnFamilyHandleInner>,
+}
+
+struct ColumnFamilyHandleInner {
+    ptr: NonNull<ffi::rocksdb_column_family_handle_t>,
+    name: String,
 }
 
 impl ColumnFamilyHandle {
+    /// Wrap a handle returned by RocksDB (internal use only)
+    pub(crate) fn from_ptr(ptr: NonNull<ffi::rocksdb_column_family_handle_t>, name: &str) -> Self {
+        ColumnFamilyHandle {
+            inner: Arc::new(ColumnFamilyHandleInner {
+                ptr,
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    /// Get the numeric ID RocksDB assigned to this column family
+    ///
+    /// IDs are unique within a database and are never reused, even after the
+    /// column family is dropped. The default column family has ID 0.
+    pub fn id(&self) -> u32 {
+        unsafe { ffi::rocksdb_column_family_handle_get_id(self.as_ptr()) }
+    }
+
+    /// Get the name of this column family
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    /// Get the underlying `rocksdb_column_family_handle_t*` for calling C API
+    /// functions this crate does not wrap
+    ///
+    /// # Safety
+    ///
+    /// The pointer is only valid while this handle is alive. It must not be
+    /// passed to `rocksdb_column_family_handle_destroy` or
+    /// `rocksdb_drop_column_family`, since the handle destroys it on drop and
+    /// the database drops column families through
+    /// [`DB::drop_column_family`].
+    pub unsafe fn as_raw(&self) -> *mut c_void {
+        self.as_ptr() as *mut c_void
+    }
+
     /// Get the raw pointer for FFI calls (internal use only)
     pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_column_family_handle_t {
-        self.inner.as_ptr()
+        self.inner.ptr.as_ptr()
     }
 }
 
-impl Drop for ColumnFamilyHandle {
+impl Drop for ColumnFamilyHandleInner {
     fn drop(&mut self) {
         // Catch panics to prevent double-panic during unwinding
         let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
-            ffi::rocksdb_column_family_handle_destroy(self.inner.as_ptr());
+            ffi::rocksdb_column_family_handle_destroy(self.ptr.as_ptr());
         }));
     }
 }
 
-// ColumnFamilyHandle is safe to send between threads
-unsafe impl Send for ColumnFamilyHandle {}
+// The RocksDB column family handle is immutable once created
+unsafe impl Send for ColumnFamilyHandleInner {}
+unsafe impl Sync for ColumnFamilyHandleInner {}
+
+/// A column family name together with the options to open it with
+///
+/// Used with [`DB::open_cf_descriptors`].
+pub struct ColumnFamilyDescriptor {
+    /// The column family name
+    pub name: String,
+    /// The options for the column family
+    pub options: Options,
+}
+
+impl ColumnFamilyDescriptor {
+    /// Describe a column family
+    pub fn new(name: impl Into<String>, options: Options) -> Self {
+        ColumnFamilyDescriptor {
+            name: name.into(),
+            options,
+        }
+    }
+}
+
+/// The outcome of a lookup with [`DB::get_result`]
+///
+/// RocksDB stores an empty value as distinct from no value; this makes the
+/// three cases explicit for callers that treat them differently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GetResult {
+    /// The key exists with a non-empty value
+    Found(Vec<u8>),
+    /// The key exists with an empty value
+    FoundEmpty,
+    /// The key does not exist
+    NotFound,
+}
+
+impl GetResult {
+    /// Check whether the key exists, whatever its value
+    pub fn exists(&self) -> bool {
+        !matches!(self, GetResult::NotFound)
+    }
+
+    /// Convert back to the `Option` returned by [`DB::get`]
+    ///
+    /// [`GetResult::FoundEmpty`] becomes `Some(vec![])`.
+    pub fn into_option(self) -> Option<Vec<u8>> {
+        match self {
+            GetResult::Found(value) => Some(value),
+            GetResult::FoundEmpty => Some(Vec::new()),
+            GetResult::NotFound => None,
+        }
+    }
+}
+
+impl From<Option<Vec<u8>>> for GetResult {
+    fn from(value: Option<Vec<u8>>) -> Self {
+        match value {
+            Some(value) if value.is_empty() => GetResult::FoundEmpty,
+            Some(value) => GetResult::Found(value),
+            None => GetResult::NotFound,
+        }
+    }
+}
 
 /// A RocksDB database handle
 ///
@@ -173,6 +600,19 @@ unsafe impl Send for ColumnFamilyHandle {}
 pub struct DB {
     inner: NonNull<ffi::rocksdb_t>,
     path: String,
+    // Keeps a custom Env alive until after the database is closed
+    _env: Option<Env>,
+    limits: SizeLimits,
+    // A copy of the open options, sharing their statistics and caches
+    options: Options,
+    // IDs of the column families whose writes skip the WAL
+    wal_disabled_cfs: RwLock<HashSet<u32>>,
+    // Handles of the column families opened or created through this DB, by name
+    cf_handles: RwLock<HashMap<String, ColumnFamilyHandle>>,
+    // Long-lived options used by the convenience methods
+    cached_options: CachedOptions,
+    // Snapshots registered by name
+    snapshot_registry: Mutex<SnapshotRegistry>,
 }
 
 impl DB {
@@ -197,6 +637,11 @@ impl DB {
         let c_path = CString::new(path.to_string_lossy().as_bytes())
             .map_err(|_| Error::new("Invalid path"))?;
 
+        options.validate()?;
+        let cached_options = CachedOptions::new()?;
+        let reporter = OpenReporter::start(options, path, path);
+        let options = reporter.options(options);
+
         unsafe {
             let mut err: *mut i8 = ptr::null_mut();
             let db_ptr = ffi::rocksdb_open(options.as_ptr(), c_path.as_ptr(), &mut err);
@@ -208,10 +653,9 @@ impl DB {
             let inner =
                 NonNull::new(db_ptr).ok_or_else(|| Error::new("Failed to open database"))?;
 
-            Ok(DB {
-                inner,
-                path: path.to_string_lossy().into_owned(),
-            })
+            let db = DB::from_raw(inner, path, options, cached_options, &[]);
+            reporter.finish();
+            Ok(db)
         }
     }
 
@@ -258,6 +702,142 @@ impl DB {
         path: P,
         cf_names: &[&str],
         cf_options: &[Options],
+    ) -> Result<(Self, Vec<ColumnFamilyHandle>)> {
+        let cf_options: Vec<&Options> = cf_options.iter().collect();
+        Self::open_column_families(options, path, cf_names, &cf_options, None)
+    }
+
+    /// Open a database with every column family described by `descriptors`
+    ///
+    /// Each descriptor pairs a column family name with its options, so
+    /// column families with different compaction or compression settings
+    /// cannot get their options mixed up. As with
+    /// [`DB::open_with_column_families`], every existing column family must
+    /// be listed, including `"default"`. The handles are kept by the
+    /// database; get them with [`DB::cf_handle`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{ColumnFamilyDescriptor, CompressionType, DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    ///
+    /// let mut logs_opts = Options::default();
+    /// logs_opts.set_compression_type(CompressionType::Zstd);
+    ///
+    /// let descriptors = vec![
+    ///     ColumnFamilyDescriptor::new("default", Options::default()),
+    ///     ColumnFamilyDescriptor::new("logs", logs_opts),
+    /// ];
+    /// let db = DB::open_cf_descriptors(&opts, "/tmp/my_db", &descriptors).unwrap();
+    ///
+    /// let logs = db.cf_handle("logs").unwrap();
+    /// db.put_cf(&logs, b"2024-01-01", b"started").unwrap();
+    /// ```
+    pub fn open_cf_descriptors<P: AsRef<Path>>(
+        options: &Options,
+        path: P,
+        descriptors: &[ColumnFamilyDescriptor],
+    ) -> Result<Self> {
+        let cf_names: Vec<&str> = descriptors.iter().map(|d| d.name.as_str()).collect();
+        let cf_options: Vec<&Options> = descriptors.iter().map(|d| &d.options).collect();
+        let (db, _) = Self::open_column_families(options, path, &cf_names, &cf_options, None)?;
+        Ok(db)
+    }
+
+    /// Open a database with the named column families, creating any that are missing
+    ///
+    /// The column families already in the database are opened too, whether
+    /// listed or not, and `"default"` is always included, so the list only
+    /// needs to name the column families the caller uses. Every column family
+    /// is opened with `options`; use [`DB::open_cf_descriptors`] for per-CF
+    /// settings. The handles are kept by the database; get them with
+    /// [`DB::cf_handle`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    ///
+    /// let db = DB::open_cf(&opts, "/tmp/my_db", ["users", "posts"]).unwrap();
+    /// let users = db.cf_handle("users").unwrap();
+    /// db.put_cf(&users, b"user:1", b"Alice").unwrap();
+    /// ```
+    pub fn open_cf<P, I, N>(options: &Options, path: P, names: I) -> Result<Self>
+    where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = N>,
+        N: AsRef<str>,
+    {
+        let path = path.as_ref();
+
+        // A database that doesn't exist yet has no column families to keep
+        let mut cf_names = match Self::list_column_families(options, path) {
+            Ok(cf_names) => cf_names,
+            Err(e) if e.subcode() == Some(ErrorSubcode::PathNotFound) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        if !cf_names
+            .iter()
+            .any(|name| name == DEFAULT_COLUMN_FAMILY_NAME)
+        {
+            cf_names.insert(0, DEFAULT_COLUMN_FAMILY_NAME.to_string());
+        }
+        for name in names {
+            let name = name.as_ref();
+            if !cf_names.iter().any(|existing| existing == name) {
+                cf_names.push(name.to_string());
+            }
+        }
+
+        let mut options = options.clone();
+        options.create_missing_column_families(true);
+
+        let cf_names: Vec<&str> = cf_names.iter().map(String::as_str).collect();
+        let cf_options: Vec<&Options> = cf_names.iter().map(|_| &options).collect();
+        let (db, _) = Self::open_column_families(&options, path, &cf_names, &cf_options, None)?;
+        Ok(db)
+    }
+
+    /// Wrap a freshly opened database (shared by the open functions)
+    ///
+    /// `cf_handles` are the handles opened along with it, registered for
+    /// [`DB::cf_handle`].
+    fn from_raw(
+        inner: NonNull<ffi::rocksdb_t>,
+        path: &Path,
+        options: &Options,
+        cached_options: CachedOptions,
+        cf_handles: &[ColumnFamilyHandle],
+    ) -> Self {
+        let db = DB {
+            inner,
+            path: path.to_string_lossy().into_owned(),
+            _env: options.env().cloned(),
+            limits: options.size_limits(),
+            options: options.clone(),
+            wal_disabled_cfs: RwLock::new(HashSet::new()),
+            cf_handles: RwLock::new(HashMap::new()),
+            cached_options,
+            snapshot_registry: Mutex::new(SnapshotRegistry::default()),
+        };
+        db.register_cf_handles(cf_handles);
+        db
+    }
+
+    /// Open a database with the given column families, each with a TTL if
+    /// `ttls` is given (shared by the public open functions)
+    fn open_column_families<P: AsRef<Path>>(
+        options: &Options,
+        path: P,
+        cf_names: &[&str],
+        cf_options: &[&Options],
+        ttls: Option<&[i32]>,
     ) -> Result<(Self, Vec<ColumnFamilyHandle>)> {
         if cf_names.len() != cf_options.len() {
             return Err(Error::new(
@@ -291,17 +871,37 @@ impl DB {
         let mut cf_handle_ptrs: Vec<*mut ffi::rocksdb_column_family_handle_t> =
             vec![ptr::null_mut(); cf_names.len()];
 
+        options.validate()?;
+        for cf_opts in cf_options {
+            cf_opts.validate_column_family()?;
+        }
+        let cached_options = CachedOptions::new()?;
+        let reporter = OpenReporter::start(options, path, path);
+        let options = reporter.options(options);
+
         unsafe {
             let mut err: *mut i8 = ptr::null_mut();
-            let db_ptr = ffi::rocksdb_open_column_families(
-                options.as_ptr(),
-                c_path.as_ptr(),
-                cf_names.len() as i32,
-                cf_name_ptrs.as_ptr(),
-                cf_option_ptrs.as_ptr(),
-                cf_handle_ptrs.as_mut_ptr(),
-                &mut err,
-            );
+            let db_ptr = match ttls {
+                Some(ttls) => ffi::rocksdb_open_column_families_with_ttl(
+                    options.as_ptr(),
+                    c_path.as_ptr(),
+                    cf_names.len() as i32,
+                    cf_name_ptrs.as_ptr(),
+                    cf_option_ptrs.as_ptr(),
+                    cf_handle_ptrs.as_mut_ptr(),
+                    ttls.as_ptr(),
+                    &mut err,
+                ),
+                None => ffi::rocksdb_open_column_families(
+                    options.as_ptr(),
+                    c_path.as_ptr(),
+                    cf_names.len() as i32,
+                    cf_name_ptrs.as_ptr(),
+                    cf_option_ptrs.as_ptr(),
+                    cf_handle_ptrs.as_mut_ptr(),
+                    &mut err,
+                ),
+            };
 
             if !err.is_null() {
                 return Err(Error::from_c_string(err));
@@ -311,150 +911,157 @@ impl DB {
                 NonNull::new(db_ptr).ok_or_else(|| Error::new("Failed to open database"))?;
 
             // Convert raw pointers to ColumnFamilyHandle
-            let cf_handles: Result<Vec<ColumnFamilyHandle>> = cf_handle_ptrs
+            let cf_handles: Vec<ColumnFamilyHandle> = cf_handle_ptrs
                 .into_iter()
-                .map(|ptr| {
+                .zip(cf_names)
+                .map(|(ptr, name)| {
                     NonNull::new(ptr)
-                        .map(|inner| ColumnFamilyHandle { inner })
+                        .map(|ptr| ColumnFamilyHandle::from_ptr(ptr, name))
                         .ok_or_else(|| Error::new("Failed to get column family handle"))
                 })
-                .collect();
+                .collect::<Result<_>>()?;
 
-            Ok((
-                DB {
-                    inner,
-                    path: path.to_string_lossy().into_owned(),
-                },
-                cf_handles?,
-            ))
+            let db = DB::from_raw(inner, path, options, cached_options, &cf_handles);
+            reporter.finish();
+
+            Ok((db, cf_handles))
         }
     }
 
-    /// Open a RocksDB database in read-only mode
-    ///
-    /// # Arguments
+    /// Open a database whose entries expire after `ttl_secs` seconds
     ///
-    /// * `options` - Configuration options for the database
-    /// * `path` - Path to the database directory
-    /// * `error_if_wal_file_exists` - If true, error if WAL files exist
+    /// Each write is stamped with its time, and compactions drop entries
+    /// older than the TTL. Expiry is therefore lazy: reads may still return
+    /// expired entries until a compaction has processed them. A `ttl_secs` of
+    /// 0 or less means entries never expire. A database written with a TTL
+    /// must always be reopened with one, as the stored values carry the
+    /// timestamp suffix.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use rust_small_rocksdb::{DB, Options};
     ///
-    /// let opts = Options::default();
-    /// let db = DB::open_for_read_only(&opts, "/tmp/my_db", false).unwrap();
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    ///
+    /// // Sessions expire after a day
+    /// let db = DB::open_with_ttl(&opts, "/tmp/sessions", 24 * 60 * 60).unwrap();
+    /// db.put(b"session:abc", b"user:1").unwrap();
     /// ```
-    pub fn open_for_read_only<P: AsRef<Path>>(
+    pub fn open_with_ttl<P: AsRef<Path>>(
         options: &Options,
         path: P,
-        error_if_wal_file_exists: bool,
+        ttl_secs: i32,
     ) -> Result<Self> {
         let path = path.as_ref();
         let c_path = CString::new(path.to_string_lossy().as_bytes())
             .map_err(|_| Error::new("Invalid path"))?;
 
+        options.validate()?;
+        let cached_options = CachedOptions::new()?;
+        let reporter = OpenReporter::start(options, path, path);
+        let options = reporter.options(options);
+
         unsafe {
             let mut err: *mut i8 = ptr::null_mut();
-            let db_ptr = ffi::rocksdb_open_for_read_only(
-                options.as_ptr(),
-                c_path.as_ptr(),
-                error_if_wal_file_exists as i32,
-                &mut err,
-            );
+            let db_ptr =
+                ffi::rocksdb_open_with_ttl(options.as_ptr(), c_path.as_ptr(), ttl_secs, &mut err);
 
             if !err.is_null() {
                 return Err(Error::from_c_string(err));
             }
 
-            let inner = NonNull::new(db_ptr)
-                .ok_or_else(|| Error::new("Failed to open database in read-only mode"))?;
+            let inner =
+                NonNull::new(db_ptr).ok_or_else(|| Error::new("Failed to open database"))?;
 
-            Ok(DB {
-                inner,
-                path: path.to_string_lossy().into_owned(),
-            })
+            let db = DB::from_raw(inner, path, options, cached_options, &[]);
+            reporter.finish();
+            Ok(db)
         }
     }
 
-    /// Put a key-value pair into the database
+    /// Open a database with column families, each with its own TTL in seconds
+    ///
+    /// Works like [`DB::open_with_column_families`], with `ttls` giving the
+    /// TTL of each column family (same length and order as `cf_names`); see
+    /// [`DB::open_with_ttl`] for how expiry works.
     ///
     /// # Example
     ///
     /// ```no_run
-    /// # use rust_small_rocksdb::{DB, Options};
-    /// # let mut opts = Options::default();
-    /// # opts.create_if_missing(true);
-    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
-    /// db.put(b"my_key", b"my_value").unwrap();
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    ///
+    /// // Keep "default" forever, expire the existing "cache" CF after an hour
+    /// let cf_names = vec!["default", "cache"];
+    /// let cf_opts = vec![Options::default(), Options::default()];
+    /// let (db, cf_handles) = DB::open_with_column_families_and_ttl(
+    ///     &opts,
+    ///     "/tmp/my_db",
+    ///     &cf_names,
+    ///     &cf_opts,
+    ///     &[0, 3600],
+    /// )
+    /// .unwrap();
+    ///
+    /// db.put_cf(&cf_handles[1], b"page:/", b"<html>").unwrap();
     /// ```
-    pub fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
-        // Debug assertions: validate that slices are properly formed
-        debug_assert!(
-            key.len() < isize::MAX as usize,
-            "Key length exceeds maximum safe size"
-        );
-        debug_assert!(
-            value.len() < isize::MAX as usize,
-            "Value length exceeds maximum safe size"
-        );
-
-        let write_opts = WriteOptionsGuard::new()?;
-
-        unsafe {
-            let mut err: *mut i8 = ptr::null_mut();
-            ffi::rocksdb_put(
-                self.inner.as_ptr(),
-                write_opts.as_ptr(),
-                key.as_ptr() as *const i8,
-                key.len(),
-                value.as_ptr() as *const i8,
-                value.len(),
-                &mut err,
-            );
-
-            if !err.is_null() {
-                return Err(Error::from_c_string(err));
-            }
-
-            Ok(())
+    pub fn open_with_column_families_and_ttl<P: AsRef<Path>>(
+        options: &Options,
+        path: P,
+        cf_names: &[&str],
+        cf_options: &[Options],
+        ttls: &[i32],
+    ) -> Result<(Self, Vec<ColumnFamilyHandle>)> {
+        if cf_names.len() != ttls.len() {
+            return Err(Error::new(
+                "Number of column family names must match number of options and TTLs",
+            ));
         }
+
+        let cf_options: Vec<&Options> = cf_options.iter().collect();
+        Self::open_column_families(options, path, cf_names, &cf_options, Some(ttls))
     }
 
-    /// Get a value from the database by key
+    /// Open a RocksDB database in read-only mode
+    ///
+    /// # Arguments
     ///
-    /// Returns `None` if the key doesn't exist.
+    /// * `options` - Configuration options for the database
+    /// * `path` - Path to the database directory
+    /// * `error_if_wal_file_exists` - If true, error if WAL files exist
     ///
     /// # Example
     ///
     /// ```no_run
-    /// # use rust_small_rocksdb::{DB, Options};
-    /// # let mut opts = Options::default();
-    /// # opts.create_if_missing(true);
-    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
-    /// # db.put(b"my_key", b"my_value").unwrap();
-    /// let value = db.get(b"my_key").unwrap();
-    /// assert_eq!(value.as_deref(), Some(&b"my_value"[..]));
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let opts = Options::default();
+    /// let db = DB::open_for_read_only(&opts, "/tmp/my_db", false).unwrap();
     /// ```
-    pub fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
-        // Debug assertion: validate that key slice is properly formed
-        debug_assert!(
-            key.len() < isize::MAX as usize,
-            "Key length exceeds maximum safe size"
-        );
+    pub fn open_for_read_only<P: AsRef<Path>>(
+        options: &Options,
+        path: P,
+        error_if_wal_file_exists: bool,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        let c_path = CString::new(path.to_string_lossy().as_bytes())
+            .map_err(|_| Error::new("Invalid path"))?;
 
-        let read_opts = ReadOptionsGuard::new()?;
+        options.validate_column_family()?;
+        let cached_options = CachedOptions::new()?;
+        let reporter = OpenReporter::start(options, path, path);
+        let options = reporter.options(options);
 
         unsafe {
-            let mut val_len: usize = 0;
             let mut err: *mut i8 = ptr::null_mut();
-            let val_ptr = ffi::rocksdb_get(
-                self.inner.as_ptr(),
-                read_opts.as_ptr(),
-                key.as_ptr() as *const i8,
-                key.len(),
-                &mut val_len,
+            let db_ptr = ffi::rocksdb_open_for_read_only(
+                options.as_ptr(),
+                c_path.as_ptr(),
+                error_if_wal_file_exists as i32,
                 &mut err,
             );
 
@@ -462,40 +1069,63 @@ impl DB {
                 return Err(Error::from_c_string(err));
             }
 
-            // Use OwnedRocksDBBytes to safely manage RocksDB-allocated memory
-            Ok(OwnedRocksDBBytes::from_raw(val_ptr, val_len).map(|bytes| bytes.to_vec()))
+            let inner = NonNull::new(db_ptr)
+                .ok_or_else(|| Error::new("Failed to open database in read-only mode"))?;
+
+            let db = DB::from_raw(inner, path, options, cached_options, &[]);
+            reporter.finish();
+            Ok(db)
         }
     }
 
-    /// Delete a key from the database
+    /// Open a secondary instance following a database another process writes to
+    ///
+    /// A secondary reads the primary's files without locking them, serving
+    /// reads from a separate process while the primary keeps ingesting. It
+    /// sees the primary's state as of opening and advances only when
+    /// [`DB::try_catch_up_with_primary`] is called. Writes fail.
+    ///
+    /// * `primary_path` - Directory of the primary database
+    /// * `secondary_path` - Private directory where the secondary keeps its
+    ///   own info log and metadata; each secondary needs its own
+    ///
+    /// RocksDB recommends `Options::set_max_open_files(-1)` for secondaries,
+    /// so table files deleted by the primary stay readable until catch-up.
     ///
     /// # Example
     ///
     /// ```no_run
-    /// # use rust_small_rocksdb::{DB, Options};
-    /// # let mut opts = Options::default();
-    /// # opts.create_if_missing(true);
-    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
-    /// # db.put(b"my_key", b"my_value").unwrap();
-    /// db.delete(b"my_key").unwrap();
-    /// assert_eq!(db.get(b"my_key").unwrap(), None);
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_max_open_files(-1);
+    /// let replica = DB::open_as_secondary(&opts, "/tmp/my_db", "/tmp/my_db_replica").unwrap();
+    ///
+    /// replica.try_catch_up_with_primary().unwrap();
+    /// let value = replica.get(b"key").unwrap();
     /// ```
-    pub fn delete(&self, key: &[u8]) -> Result<()> {
-        // Debug assertion: validate that key slice is properly formed
-        debug_assert!(
-            key.len() < isize::MAX as usize,
-            "Key length exceeds maximum safe size"
-        );
+    pub fn open_as_secondary<P: AsRef<Path>, S: AsRef<Path>>(
+        options: &Options,
+        primary_path: P,
+        secondary_path: S,
+    ) -> Result<Self> {
+        let primary_path = primary_path.as_ref();
+        let c_primary_path = CString::new(primary_path.to_string_lossy().as_bytes())
+            .map_err(|_| Error::new("Invalid path"))?;
+        let c_secondary_path = CString::new(secondary_path.as_ref().to_string_lossy().as_bytes())
+            .map_err(|_| Error::new("Invalid secondary path"))?;
 
-        let write_opts = WriteOptionsGuard::new()?;
+        options.validate_column_family()?;
+        let cached_options = CachedOptions::new()?;
+        let reporter = OpenReporter::start(options, primary_path, secondary_path.as_ref());
+        let options = reporter.options(options);
 
         unsafe {
             let mut err: *mut i8 = ptr::null_mut();
-            ffi::rocksdb_delete(
-                self.inner.as_ptr(),
-                write_opts.as_ptr(),
-                key.as_ptr() as *const i8,
-                key.len(),
+            let db_ptr = ffi::rocksdb_open_as_secondary(
+                options.as_ptr(),
+                c_primary_path.as_ptr(),
+                c_secondary_path.as_ptr(),
                 &mut err,
             );
 
@@ -503,69 +1133,41 @@ impl DB {
                 return Err(Error::from_c_string(err));
             }
 
-            Ok(())
-        }
-    }
+            let inner = NonNull::new(db_ptr)
+                .ok_or_else(|| Error::new("Failed to open database as secondary"))?;
 
-    /// Get the path where this database is stored
-    pub fn path(&self) -> &str {
-        &self.path
+            let db = DB::from_raw(inner, primary_path, options, cached_options, &[]);
+            reporter.finish();
+            Ok(db)
+        }
     }
 
-    /// Create an iterator to traverse the database
-    ///
-    /// # Example
-    ///
-    /// ```no_run
-    /// use rust_small_rocksdb::{DB, Options, Direction};
-    ///
-    /// let mut opts = Options::default();
-    /// opts.create_if_missing(true);
-    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    /// Bring a secondary instance up to date with its primary
     ///
-    /// // Insert some data
-    /// db.put(b"key1", b"value1").unwrap();
-    /// db.put(b"key2", b"value2").unwrap();
-    ///
-    /// // Iterate forward
-    /// for item in db.iter(Direction::Forward) {
-    ///     let (key, value) = item.unwrap();
-    ///     println!("Key: {:?}, Value: {:?}", key, value);
-    /// }
-    /// ```
-    pub fn iter(&self, direction: iterator::Direction) -> iterator::DBIteratorAdapter<'_> {
-        use iterator::{DBIterator, DBIteratorAdapter};
-
+    /// Replays the primary's new MANIFEST entries and WAL, after which reads
+    /// see everything the primary had written at that point. Fails on a
+    /// database not opened with [`DB::open_as_secondary`].
+    pub fn try_catch_up_with_primary(&self) -> Result<()> {
         unsafe {
-            // Create read options and pass to iterator
-            // RocksDB internally copies what it needs from read_opts, so we can destroy it
-            let read_opts = ReadOptionsGuard::new().expect("Failed to create read options");
-            let iter_ptr = ffi::rocksdb_create_iterator(self.inner.as_ptr(), read_opts.as_ptr());
-
-            // read_opts is automatically destroyed here
-
-            let iter_non_null = NonNull::new(iter_ptr).expect("Failed to create iterator");
-            let mut db_iter = DBIterator::new(iter_non_null);
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_try_catch_up_with_primary(self.inner.as_ptr(), &mut err);
 
-            // Position iterator based on direction
-            match direction {
-                iterator::Direction::Forward => db_iter.seek_to_first(),
-                iterator::Direction::Reverse => db_iter.seek_to_last(),
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
             }
 
-            DBIteratorAdapter::new(db_iter, direction)
+            Ok(())
         }
     }
 
-    /// Create a new column family with the given options
-    ///
-    /// Column families allow you to logically partition your data within a single database.
-    /// Each column family can have its own configuration and be managed independently.
-    ///
-    /// # Arguments
+    /// Delete a database and all of its files
     ///
-    /// * `options` - Configuration options for the new column family
-    /// * `name` - Name of the column family to create
+    /// Unlike removing the directory by hand, this also deletes WAL files
+    /// kept elsewhere and the files under additional database paths
+    /// configured in `options`, so pass the options the database was opened
+    /// with. The database must not be open; if it is, this fails with a lock
+    /// error and nothing is deleted. Destroying a path that holds no database
+    /// succeeds.
     ///
     /// # Example
     ///
@@ -574,67 +1176,76 @@ impl DB {
     ///
     /// let mut opts = Options::default();
     /// opts.create_if_missing(true);
-    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    /// let db = DB::open(&opts, "/tmp/tenant_42").unwrap();
+    /// drop(db);
     ///
-    /// // Create a column family for user data
-    /// let cf_opts = Options::default();
-    /// let cf_handle = db.create_column_family(&cf_opts, "users").unwrap();
+    /// DB::destroy(&opts, "/tmp/tenant_42").unwrap();
     /// ```
-    pub fn create_column_family(
-        &self,
-        options: &Options,
-        name: &str,
-    ) -> Result<ColumnFamilyHandle> {
-        let c_name = CString::new(name).map_err(|_| Error::new("Invalid column family name"))?;
+    pub fn destroy<P: AsRef<Path>>(options: &Options, path: P) -> Result<()> {
+        let c_path = CString::new(path.as_ref().to_string_lossy().as_bytes())
+            .map_err(|_| Error::new("Invalid path"))?;
 
         unsafe {
             let mut err: *mut i8 = ptr::null_mut();
-            let cf_handle = ffi::rocksdb_create_column_family(
-                self.inner.as_ptr(),
-                options.as_ptr(),
-                c_name.as_ptr(),
-                &mut err,
-            );
+            ffi::rocksdb_destroy_db(options.as_ptr(), c_path.as_ptr(), &mut err);
 
             if !err.is_null() {
                 return Err(Error::from_c_string(err));
             }
 
-            let inner = NonNull::new(cf_handle)
-                .ok_or_else(|| Error::new("Failed to create column family"))?;
-
-            Ok(ColumnFamilyHandle { inner })
+            Ok(())
         }
     }
 
-    /// Drop (delete) a column family
+    /// Inspect a database directory without opening it
     ///
-    /// This permanently removes the column family and all of its data.
-    /// The column family handle becomes invalid after this call.
+    /// Checks the `LOCK` file, `CURRENT`, the MANIFEST it names and the
+    /// checksums of the WAL records, and suggests whether to open, repair or
+    /// restore the database. A torn final record in the newest WAL is what a
+    /// crash mid-write leaves behind; recovery drops it, so the suggested
+    /// action is still [`ProbeAction::Open`](crate::ProbeAction::Open).
     ///
-    /// # Arguments
+    /// # Example
     ///
-    /// * `cf_handle` - Handle to the column family to drop
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options, ProbeAction};
+    ///
+    /// let opts = Options::default();
+    /// let report = DB::probe("/tmp/my_db");
+    /// if report.action == ProbeAction::Repair {
+    ///     DB::repair(&opts, "/tmp/my_db").unwrap();
+    /// }
+    /// ```
+    pub fn probe<P: AsRef<Path>>(path: P) -> ProbeReport {
+        crate::probe::probe(path.as_ref())
+    }
+
+    /// Try to salvage a database that fails to open
+    ///
+    /// Rebuilds the MANIFEST from the SST files found in the directory and
+    /// converts WAL files into SST files. Data that cannot be read is lost,
+    /// and deleted keys may reappear if the tombstones covering them were in
+    /// files that could not be recovered, so back up the directory before
+    /// repairing and check the result. The database must not be open.
     ///
     /// # Example
     ///
     /// ```no_run
     /// use rust_small_rocksdb::{DB, Options};
     ///
-    /// let mut opts = Options::default();
-    /// opts.create_if_missing(true);
+    /// let opts = Options::default();
+    /// if DB::open(&opts, "/tmp/my_db").is_err() {
+    ///     DB::repair(&opts, "/tmp/my_db").unwrap();
+    /// }
     /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
-    ///
-    /// let cf_opts = Options::default();
-    /// let cf_handle = db.create_column_family(&cf_opts, "temp").unwrap();
-    ///
-    /// // Drop the column family when no longer needed
-    /// db.drop_column_family(cf_handle).unwrap();
     /// ```
-    pub fn drop_column_family(&self, cf_handle: ColumnFamilyHandle) -> Result<()> {
+    pub fn repair<P: AsRef<Path>>(options: &Options, path: P) -> Result<()> {
+        let c_path = CString::new(path.as_ref().to_string_lossy().as_bytes())
+            .map_err(|_| Error::new("Invalid path"))?;
+
         unsafe {
             let mut err: *mut i8 = ptr::null_mut();
-            ffi::rocksdb_drop_column_family(self.inner.as_ptr(), cf_handle.as_ptr(), &mut err);
+            ffi::rocksdb_repair_db(options.as_ptr(), c_path.as_ptr(), &mut err);
 
             if !err.is_null() {
                 return Err(Error::from_c_string(err));
@@ -644,29 +1255,39 @@ impl DB {
         }
     }
 
-    /// Put a key-value pair into a specific column family
-    ///
-    /// # Arguments
+    /// Put a key-value pair into the database
     ///
-    /// * `cf_handle` - Handle to the column family
-    /// * `key` - The key to store
-    /// * `value` - The value to store
+    /// Fails with [`ErrorKind::InvalidArgument`](crate::ErrorKind::InvalidArgument)
+    /// if the key or value exceeds a limit set with
+    /// [`Options::set_max_key_size`] or [`Options::set_max_value_size`].
     ///
     /// # Example
     ///
     /// ```no_run
-    /// use rust_small_rocksdb::{DB, Options};
-    ///
-    /// let mut opts = Options::default();
-    /// opts.create_if_missing(true);
-    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    /// # use rust_small_rocksdb::{DB, Options};
+    /// # let mut opts = Options::default();
+    /// # opts.create_if_missing(true);
+    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
+    /// db.put(b"my_key", b"my_value").unwrap();
+    /// ```
+    pub fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.put_opt(key, value, WriteMode::Buffered)
+    }
+
+    /// Put a key-value pair with the given durability
     ///
-    /// let cf_opts = Options::default();
-    /// let cf_handle = db.create_column_family(&cf_opts, "users").unwrap();
+    /// # Example
     ///
-    /// db.put_cf(&cf_handle, b"user:1", b"Alice").unwrap();
+    /// ```no_run
+    /// # use rust_small_rocksdb::{DB, Options, WriteMode};
+    /// # let mut opts = Options::default();
+    /// # opts.create_if_missing(true);
+    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
+    /// // Not acknowledged until the WAL is on disk
+    /// db.put_opt(b"order:17", b"paid", WriteMode::Sync).unwrap();
     /// ```
-    pub fn put_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8], value: &[u8]) -> Result<()> {
+    pub fn put_opt(&self, key: &[u8], value: &[u8], mode: WriteMode) -> Result<()> {
+        // Debug assertions: validate that slices are properly formed
         debug_assert!(
             key.len() < isize::MAX as usize,
             "Key length exceeds maximum safe size"
@@ -675,15 +1296,15 @@ impl DB {
             value.len() < isize::MAX as usize,
             "Value length exceeds maximum safe size"
         );
+        self.limits.check(key, value)?;
 
-        let write_opts = WriteOptionsGuard::new()?;
+        let write_opts = self.cached_options.write(mode, false);
 
         unsafe {
             let mut err: *mut i8 = ptr::null_mut();
-            ffi::rocksdb_put_cf(
+            ffi::rocksdb_put(
                 self.inner.as_ptr(),
                 write_opts.as_ptr(),
-                cf_handle.as_ptr(),
                 key.as_ptr() as *const i8,
                 key.len(),
                 value.as_ptr() as *const i8,
@@ -699,14 +1320,2665 @@ impl DB {
         }
     }
 
-    /// Get a value from a specific column family
+    /// Get a value from the database by key
     ///
-    /// Returns `None` if the key doesn't exist in the column family.
+    /// Returns `None` if the key doesn't exist, and `Some(vec![])` if it
+    /// exists with an empty value. Use [`DB::get_result`] to match on the
+    /// two cases, or [`DB::get_non_empty`] to treat them alike.
     ///
-    /// # Arguments
+    /// # Example
     ///
-    /// * `cf_handle` - Handle to the column family
-    /// * `key` - The key to retrieve
+    /// ```no_run
+    /// # use rust_small_rocksdb::{DB, Options};
+    /// # let mut opts = Options::default();
+    /// # opts.create_if_missing(true);
+    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
+    /// # db.put(b"my_key", b"my_value").unwrap();
+    /// let value = db.get(b"my_key").unwrap();
+    /// assert_eq!(value.as_deref(), Some(&b"my_value"[..]));
+    /// ```
+    pub fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.get_opt(key, ReadMode::Cached)
+    }
+
+    /// Get a value with the given caching behaviour
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rust_small_rocksdb::{DB, Options, ReadMode};
+    /// # let mut opts = Options::default();
+    /// # opts.create_if_missing(true);
+    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
+    /// // A one-off lookup that shouldn't evict hot blocks
+    /// let value = db.get_opt(b"archive:2019", ReadMode::NoFillCache).unwrap();
+    /// ```
+    pub fn get_opt(&self, key: &[u8], mode: ReadMode) -> Result<Option<Vec<u8>>> {
+        // Debug assertion: validate that key slice is properly formed
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+
+        let read_opts = self.cached_options.read(mode);
+
+        unsafe {
+            let mut val_len: usize = 0;
+            let mut err: *mut i8 = ptr::null_mut();
+            let val_ptr = ffi::rocksdb_get(
+                self.inner.as_ptr(),
+                read_opts.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+                &mut val_len,
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            // Use OwnedRocksDBBytes to safely manage RocksDB-allocated memory
+            Ok(OwnedRocksDBBytes::from_raw(val_ptr, val_len).map(|bytes| bytes.to_vec()))
+        }
+    }
+
+    /// Look up a key, telling an empty value apart from a missing key
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rust_small_rocksdb::{DB, GetResult, Options};
+    /// # let mut opts = Options::default();
+    /// # opts.create_if_missing(true);
+    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
+    /// db.put(b"tombstone_marker", b"").unwrap();
+    /// match db.get_result(b"tombstone_marker").unwrap() {
+    ///     GetResult::Found(value) => println!("{} bytes", value.len()),
+    ///     GetResult::FoundEmpty => println!("present, no payload"),
+    ///     GetResult::NotFound => println!("absent"),
+    /// }
+    /// ```
+    pub fn get_result(&self, key: &[u8]) -> Result<GetResult> {
+        self.get(key).map(GetResult::from)
+    }
+
+    /// Get a value, treating an empty value as missing
+    ///
+    /// Returns `None` both when the key doesn't exist and when its value is
+    /// empty.
+    pub fn get_non_empty(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.get(key)?.filter(|value| !value.is_empty()))
+    }
+
+    /// Get a value without copying it
+    ///
+    /// The value is read in place from the block cache or memtable, which
+    /// saves the allocation and copy [`DB::get`] makes; this matters for
+    /// values of a few kilobytes and up. See [`PinnedSlice`] for how long
+    /// the value stays pinned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rust_small_rocksdb::{DB, Options};
+    /// # let mut opts = Options::default();
+    /// # opts.create_if_missing(true);
+    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
+    /// if let Some(value) = db.get_pinned(b"my_key").unwrap() {
+    ///     println!("{} bytes, starting {:?}", value.len(), value.first());
+    /// }
+    /// ```
+    pub fn get_pinned(&self, key: &[u8]) -> Result<Option<PinnedSlice<'_>>> {
+        self.get_pinned_inner(None, key)
+    }
+
+    /// Get a value from a column family without copying it
+    ///
+    /// See [`DB::get_pinned`].
+    pub fn get_pinned_cf(
+        &self,
+        cf_handle: &ColumnFamilyHandle,
+        key: &[u8],
+    ) -> Result<Option<PinnedSlice<'_>>> {
+        self.get_pinned_inner(Some(cf_handle), key)
+    }
+
+    /// Read a value into `buf`, reusing its allocation
+    ///
+    /// Returns whether the key was found. `buf` is cleared first, so on a
+    /// miss it is left empty; on a hit it holds exactly the value. The value
+    /// is copied straight from where RocksDB pinned it, so a loop reusing
+    /// one buffer allocates only when a value outgrows it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rust_small_rocksdb::{DB, Options};
+    /// # let mut opts = Options::default();
+    /// # opts.create_if_missing(true);
+    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
+    /// let mut buf = Vec::with_capacity(16 * 1024);
+    /// for i in 0..1_000_000u32 {
+    ///     if db.get_into(&i.to_be_bytes(), &mut buf).unwrap() {
+    ///         println!("{} bytes", buf.len());
+    ///     }
+    /// }
+    /// ```
+    pub fn get_into(&self, key: &[u8], buf: &mut Vec<u8>) -> Result<bool> {
+        buf.clear();
+        match self.get_pinned_inner(None, key)? {
+            Some(value) => {
+                buf.extend_from_slice(&value);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Read a value from a column family into `buf`, reusing its allocation
+    ///
+    /// See [`DB::get_into`].
+    pub fn get_into_cf(
+        &self,
+        cf_handle: &ColumnFamilyHandle,
+        key: &[u8],
+        buf: &mut Vec<u8>,
+    ) -> Result<bool> {
+        buf.clear();
+        match self.get_pinned_inner(Some(cf_handle), key)? {
+            Some(value) => {
+                buf.extend_from_slice(&value);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn get_pinned_inner(
+        &self,
+        cf_handle: Option<&ColumnFamilyHandle>,
+        key: &[u8],
+    ) -> Result<Option<PinnedSlice<'_>>> {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+
+        let read_opts = self.cached_options.read(ReadMode::Cached);
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            let slice = match cf_handle {
+                Some(cf_handle) => ffi::rocksdb_get_pinned_cf(
+                    self.inner.as_ptr(),
+                    read_opts.as_ptr(),
+                    cf_handle.as_ptr(),
+                    key.as_ptr() as *const i8,
+                    key.len(),
+                    &mut err,
+                ),
+                None => ffi::rocksdb_get_pinned(
+                    self.inner.as_ptr(),
+                    read_opts.as_ptr(),
+                    key.as_ptr() as *const i8,
+                    key.len(),
+                    &mut err,
+                ),
+            };
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(NonNull::new(slice).map(|slice| PinnedSlice::new(slice)))
+        }
+    }
+
+    /// Get the values of several keys in one call
+    ///
+    /// Results are returned in the same order as `keys`, with `None` for
+    /// keys that don't exist. Batching lets RocksDB share work between the
+    /// lookups, which is faster than calling [`DB::get`] in a loop.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rust_small_rocksdb::{DB, Options};
+    /// # let mut opts = Options::default();
+    /// # opts.create_if_missing(true);
+    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
+    /// let values = db.multi_get([b"key1", b"key2"]);
+    /// for value in values {
+    ///     println!("{:?}", value.unwrap());
+    /// }
+    /// ```
+    pub fn multi_get<K, I>(&self, keys: I) -> Vec<Result<Option<Vec<u8>>>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        let keys: Vec<K> = keys.into_iter().collect();
+        let key_ptrs: Vec<*const i8> = keys
+            .iter()
+            .map(|k| k.as_ref().as_ptr() as *const i8)
+            .collect();
+        let key_sizes: Vec<usize> = keys.iter().map(|k| k.as_ref().len()).collect();
+
+        let read_opts = self.cached_options.read(ReadMode::Cached);
+
+        let mut values: Vec<*mut i8> = vec![ptr::null_mut(); keys.len()];
+        let mut value_sizes: Vec<usize> = vec![0; keys.len()];
+        let mut errs: Vec<*mut i8> = vec![ptr::null_mut(); keys.len()];
+
+        unsafe {
+            ffi::rocksdb_multi_get(
+                self.inner.as_ptr(),
+                read_opts.as_ptr(),
+                keys.len(),
+                key_ptrs.as_ptr(),
+                key_sizes.as_ptr(),
+                values.as_mut_ptr(),
+                value_sizes.as_mut_ptr(),
+                errs.as_mut_ptr(),
+            );
+
+            collect_multi_get(values, value_sizes, errs)
+        }
+    }
+
+    /// Get the values of several keys in one call and keep the ones that exist
+    ///
+    /// Missing keys are left out of the map. Fails with the first error
+    /// reported for any key.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rust_small_rocksdb::{DB, Options};
+    /// # let mut opts = Options::default();
+    /// # opts.create_if_missing(true);
+    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
+    /// let found = db.get_many([b"user:1", b"user:2"]).unwrap();
+    /// if let Some(name) = found.get(&b"user:1"[..]) {
+    ///     println!("user:1 is {:?}", name);
+    /// }
+    /// ```
+    pub fn get_many<K, I>(&self, keys: I) -> Result<HashMap<Vec<u8>, Vec<u8>>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        let keys: Vec<K> = keys.into_iter().collect();
+        let values = self.multi_get(&keys);
+        collect_found(&keys, values)
+    }
+
+    /// Check whether a key exists without copying its value
+    ///
+    /// The lookup pins the value in the block cache instead of copying it out,
+    /// so this is cheaper than [`DB::get`] when values are large.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rust_small_rocksdb::{DB, Options};
+    /// # let mut opts = Options::default();
+    /// # opts.create_if_missing(true);
+    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
+    /// db.put(b"my_key", b"my_value").unwrap();
+    /// assert!(db.contains_key(b"my_key").unwrap());
+    /// ```
+    pub fn contains_key(&self, key: &[u8]) -> Result<bool> {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+
+        let read_opts = self.cached_options.read(ReadMode::Cached);
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            let slice = ffi::rocksdb_get_pinned(
+                self.inner.as_ptr(),
+                read_opts.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(release_pinned(slice))
+        }
+    }
+
+    /// Delete a key from the database
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rust_small_rocksdb::{DB, Options};
+    /// # let mut opts = Options::default();
+    /// # opts.create_if_missing(true);
+    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
+    /// # db.put(b"my_key", b"my_value").unwrap();
+    /// db.delete(b"my_key").unwrap();
+    /// assert_eq!(db.get(b"my_key").unwrap(), None);
+    /// ```
+    pub fn delete(&self, key: &[u8]) -> Result<()> {
+        self.delete_opt(key, WriteMode::Buffered)
+    }
+
+    /// Delete a key with the given durability
+    pub fn delete_opt(&self, key: &[u8], mode: WriteMode) -> Result<()> {
+        // Debug assertion: validate that key slice is properly formed
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+
+        let write_opts = self.cached_options.write(mode, false);
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_delete(
+                self.inner.as_ptr(),
+                write_opts.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Apply a write batch atomically
+    ///
+    /// Either every operation in the batch is applied or none is. If size
+    /// limits are set on the options the database was opened with, the batch
+    /// is decoded and rejected as a whole when any entry exceeds them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options, WriteBatch};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let mut batch = WriteBatch::new();
+    /// batch.put(b"account:1", b"90");
+    /// batch.put(b"account:2", b"110");
+    /// db.write(&batch).unwrap();
+    /// ```
+    pub fn write(&self, batch: &WriteBatch) -> Result<()> {
+        self.write_opt(batch, WriteMode::Buffered)
+    }
+
+    /// Apply an indexed write batch atomically
+    ///
+    /// Goes through the same checks as [`DB::write`]; the batch is left as
+    /// it was, so clear it before reusing it.
+    pub fn write_indexed(&self, batch: &WriteBatchWithIndex) -> Result<()> {
+        self.write(&batch.to_write_batch()?)
+    }
+
+    /// Look up a key in the default column family as if `batch` had been written
+    ///
+    /// The batch's own writes take precedence, so a key it deletes reads as
+    /// missing; other keys are read from the database.
+    pub fn get_with_batch(
+        &self,
+        batch: &WriteBatchWithIndex,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+
+        let read_opts = self.cached_options.read(ReadMode::Cached);
+
+        unsafe {
+            let mut val_len: usize = 0;
+            let mut err: *mut i8 = ptr::null_mut();
+            let val_ptr = ffi::rocksdb_writebatch_wi_get_from_batch_and_db(
+                batch.as_ptr(),
+                self.inner.as_ptr(),
+                read_opts.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+                &mut val_len,
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+            Ok(OwnedRocksDBBytes::from_raw(val_ptr, val_len).map(|bytes| bytes.to_vec()))
+        }
+    }
+
+    /// Apply a write batch atomically with the given durability
+    ///
+    /// One sync covers the whole batch, so batching many writes under
+    /// [`WriteMode::Sync`] costs about as much as a single synced put.
+    /// While key or value size limits are set, a batch that can't be decoded
+    /// in full (see [`WriteBatch::operations`]) is rejected unwritten.
+    pub fn write_opt(&self, batch: &WriteBatch, mode: WriteMode) -> Result<()> {
+        batch.validate()?;
+        if self.limits.is_set() {
+            for op in batch.operations()? {
+                match op {
+                    WriteBatchOp::Put { key, value, .. }
+                    | WriteBatchOp::Merge { key, value, .. } => self.limits.check(&key, &value)?,
+                    WriteBatchOp::Delete { .. } | WriteBatchOp::DeleteRange { .. } => {}
+                }
+            }
+        }
+
+        let write_opts = self.cached_options.write(mode, self.batch_skips_wal(batch));
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_write(
+                self.inner.as_ptr(),
+                write_opts.as_ptr(),
+                batch.as_ptr(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Get the path where this database is stored
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Copy the latest OPTIONS file of the database into `dir`
+    ///
+    /// Keeps the tuned configuration next to a backup or in a config
+    /// repository, for reopening the database, or a copy of it, with
+    /// [`Options::load_latest`] pointed at `dir`. `dir` is created if
+    /// needed. Returns the path of the copy.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true).set_max_open_files(512);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    /// let saved = db.save_options("/tmp/my_db_options").unwrap();
+    /// println!("options saved to {}", saved.display());
+    /// ```
+    pub fn save_options<P: AsRef<Path>>(&self, dir: P) -> Result<PathBuf> {
+        let io_error =
+            |err: io::Error| Error::with_kind(ErrorKind::IOError, format!("Save options: {}", err));
+
+        // RocksDB numbers OPTIONS files in the order it writes them
+        let latest = fs::read_dir(&self.path)
+            .map_err(io_error)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let number = name.strip_prefix("OPTIONS-")?.parse::<u64>().ok()?;
+                Some((number, name))
+            })
+            .max()
+            .ok_or_else(|| {
+                Error::with_kind(
+                    ErrorKind::NotFound,
+                    format!("No OPTIONS file in {}", self.path),
+                )
+            })?;
+
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir).map_err(io_error)?;
+        let target = dir.join(&latest.1);
+        fs::copy(Path::new(&self.path).join(&latest.1), &target).map_err(io_error)?;
+        Ok(target)
+    }
+
+    /// Change options of the default column family on the open database
+    ///
+    /// Takes option names and values as RocksDB spells them in OPTIONS
+    /// files, e.g. `("write_buffer_size", "134217728")`, and applies them
+    /// without a restart. Only mutable options can be changed this way;
+    /// unknown or immutable ones fail with [`ErrorKind::InvalidArgument`]
+    /// and nothing is changed. RocksDB writes a new OPTIONS file with the
+    /// change, so it survives a reopen with [`Options::load_latest`].
+    ///
+    /// Database-wide options such as `max_background_jobs` are not column
+    /// family options and are rejected here; the C API offers no way to
+    /// change them on an open database, so set them on the [`Options`]
+    /// before opening.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// db.set_options(&[
+    ///     ("write_buffer_size", "134217728"),
+    ///     ("level0_slowdown_writes_trigger", "30"),
+    /// ])
+    /// .unwrap();
+    /// ```
+    pub fn set_options(&self, options: &[(&str, &str)]) -> Result<()> {
+        self.set_options_cf(&self.default_cf_handle()?, options)
+    }
+
+    /// Change options of a column family on the open database
+    ///
+    /// See [`DB::set_options`].
+    pub fn set_options_cf(
+        &self,
+        cf_handle: &ColumnFamilyHandle,
+        options: &[(&str, &str)],
+    ) -> Result<()> {
+        let (keys, values) = option_pairs(options)?;
+        let key_ptrs: Vec<*const c_char> = keys.iter().map(|key| key.as_ptr()).collect();
+        let value_ptrs: Vec<*const c_char> = values.iter().map(|value| value.as_ptr()).collect();
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_set_options_cf(
+                self.inner.as_ptr(),
+                cf_handle.as_ptr(),
+                key_ptrs.len() as c_int,
+                key_ptrs.as_ptr(),
+                value_ptrs.as_ptr(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Read a database property as a string
+    ///
+    /// Returns `None` if RocksDB does not know the property. Useful
+    /// properties include `rocksdb.stats`, `rocksdb.sstables` and
+    /// `rocksdb.levelstats`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// if let Some(stats) = db.property_value("rocksdb.stats").unwrap() {
+    ///     println!("{}", stats);
+    /// }
+    /// ```
+    pub fn property_value(&self, name: &str) -> Result<Option<String>> {
+        let c_name = CString::new(name).map_err(|_| Error::new("Invalid property name"))?;
+
+        unsafe {
+            let value = ffi::rocksdb_property_value(self.inner.as_ptr(), c_name.as_ptr());
+            Ok(take_c_string(value))
+        }
+    }
+
+    /// Read a column family property as a string
+    ///
+    /// Returns `None` if RocksDB does not know the property.
+    pub fn property_value_cf(
+        &self,
+        cf_handle: &ColumnFamilyHandle,
+        name: &str,
+    ) -> Result<Option<String>> {
+        let c_name = CString::new(name).map_err(|_| Error::new("Invalid property name"))?;
+
+        unsafe {
+            let value = ffi::rocksdb_property_value_cf(
+                self.inner.as_ptr(),
+                cf_handle.as_ptr(),
+                c_name.as_ptr(),
+            );
+            Ok(take_c_string(value))
+        }
+    }
+
+    /// Read a numeric database property
+    ///
+    /// Returns `None` if the property is unknown or not numeric. Cheaper and
+    /// more robust than parsing [`DB::property_value`] for metrics such as
+    /// `rocksdb.estimate-num-keys`, `rocksdb.cur-size-all-mem-tables` and
+    /// `rocksdb.num-running-compactions`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let keys = db.property_int_value("rocksdb.estimate-num-keys").unwrap();
+    /// println!("~{} keys", keys.unwrap_or(0));
+    /// ```
+    pub fn property_int_value(&self, name: &str) -> Result<Option<u64>> {
+        let c_name = CString::new(name).map_err(|_| Error::new("Invalid property name"))?;
+
+        unsafe {
+            let mut value: u64 = 0;
+            let status =
+                ffi::rocksdb_property_int(self.inner.as_ptr(), c_name.as_ptr(), &mut value);
+            Ok((status == 0).then_some(value))
+        }
+    }
+
+    /// Read a numeric column family property
+    ///
+    /// Returns `None` if the property is unknown or not numeric.
+    pub fn property_int_value_cf(
+        &self,
+        cf_handle: &ColumnFamilyHandle,
+        name: &str,
+    ) -> Result<Option<u64>> {
+        let c_name = CString::new(name).map_err(|_| Error::new("Invalid property name"))?;
+
+        unsafe {
+            let mut value: u64 = 0;
+            let status = ffi::rocksdb_property_int_cf(
+                self.inner.as_ptr(),
+                cf_handle.as_ptr(),
+                c_name.as_ptr(),
+                &mut value,
+            );
+            Ok((status == 0).then_some(value))
+        }
+    }
+
+    /// Create a gate that asks writers to back off while compactions are behind
+    ///
+    /// The gate watches the default column family and starts with no
+    /// thresholds; see [`WriteGate`].
+    pub fn write_gate(&self) -> WriteGate<'_> {
+        WriteGate::new(self, None)
+    }
+
+    /// Create a write gate watching the compaction debt of a column family
+    ///
+    /// See [`DB::write_gate`].
+    pub fn write_gate_cf<'a>(&'a self, cf_handle: &'a ColumnFamilyHandle) -> WriteGate<'a> {
+        WriteGate::new(self, Some(cf_handle))
+    }
+
+    /// Get usage and hit rates of the block cache and row cache in one call
+    ///
+    /// Hits and misses are only counted with
+    /// [`Options::enable_statistics`] set on the options the database was
+    /// opened with.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{Cache, DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true)
+    ///     .enable_statistics()
+    ///     .set_row_cache(&Cache::new_lru(64 * 1024 * 1024));
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let stats = db.cache_stats().unwrap();
+    /// println!(
+    ///     "block cache {} / {} bytes, row cache hit ratio {:?}",
+    ///     stats.block_cache.usage,
+    ///     stats.block_cache.capacity,
+    ///     stats.row_cache.and_then(|row| row.hit_ratio())
+    /// );
+    /// ```
+    pub fn cache_stats(&self) -> Result<CacheStats> {
+        let property =
+            |name| -> Result<usize> { Ok(self.property_int_value(name)?.unwrap_or(0) as usize) };
+
+        let block_cache = CacheUsage {
+            capacity: property("rocksdb.block-cache-capacity")?,
+            usage: property("rocksdb.block-cache-usage")?,
+            pinned_usage: property("rocksdb.block-cache-pinned-usage")?,
+            hits: self.options.ticker_count(Ticker::BlockCacheHit),
+            misses: self.options.ticker_count(Ticker::BlockCacheMiss),
+        };
+
+        let row_cache = self.options.row_cache().map(|cache| CacheUsage {
+            capacity: cache.capacity(),
+            usage: cache.usage(),
+            pinned_usage: cache.pinned_usage(),
+            hits: self.options.ticker_count(Ticker::RowCacheHit),
+            misses: self.options.ticker_count(Ticker::RowCacheMiss),
+        });
+
+        Ok(CacheStats {
+            block_cache,
+            row_cache,
+        })
+    }
+
+    /// Report read amplification, database-wide
+    ///
+    /// Needs [`Options::enable_statistics`]; see [`ReadAmpReport`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{BlockBasedOptions, DB, Options};
+    ///
+    /// let mut table_opts = BlockBasedOptions::default();
+    /// table_opts.set_read_amp_bytes_per_bit(32);
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true)
+    ///     .enable_statistics()
+    ///     .set_block_based_table_factory(&table_opts);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let before = db.read_amp_report();
+    /// for i in 0..1000u32 {
+    ///     db.get(&i.to_be_bytes()).unwrap();
+    /// }
+    /// let report = db.read_amp_report().delta(&before);
+    /// println!(
+    ///     "point lookups read {:?} block bytes per byte used",
+    ///     report.block_read_amplification()
+    /// );
+    /// ```
+    pub fn read_amp_report(&self) -> ReadAmpReport {
+        ReadAmpReport::capture(&self.options)
+    }
+
+    /// Report how often bloom filters ruled out SST files, database-wide
+    ///
+    /// Needs [`Options::enable_statistics`]; see [`BloomFilterReport`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{BlockBasedOptions, DB, Options};
+    ///
+    /// let mut table_opts = BlockBasedOptions::default();
+    /// table_opts.set_bloom_filter(10.0);
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true)
+    ///     .enable_statistics()
+    ///     .set_block_based_table_factory(&table_opts);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let before = db.bloom_filter_report();
+    /// for i in 0..1000u32 {
+    ///     db.get(&i.to_be_bytes()).unwrap();
+    /// }
+    /// let report = db.bloom_filter_report().delta(&before);
+    /// println!(
+    ///     "filters ruled out {:?} of file checks, false positive rate {:?}",
+    ///     report.useful_ratio(),
+    ///     report.false_positive_rate()
+    /// );
+    /// ```
+    pub fn bloom_filter_report(&self) -> BloomFilterReport {
+        BloomFilterReport::capture(&self.options)
+    }
+
+    /// List the column families of the database at `path`
+    ///
+    /// The database does not need to be open. Use this to discover the
+    /// column families before calling [`DB::open_with_column_families`],
+    /// which requires every existing column family to be listed. The result
+    /// always includes `"default"` for an existing database.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let opts = Options::default();
+    /// let names = DB::list_column_families(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_names: Vec<&str> = names.iter().map(String::as_str).collect();
+    /// let cf_opts: Vec<Options> = names.iter().map(|_| Options::default()).collect();
+    /// let (db, handles) =
+    ///     DB::open_with_column_families(&opts, "/tmp/my_db", &cf_names, &cf_opts).unwrap();
+    /// ```
+    pub fn list_column_families<P: AsRef<Path>>(options: &Options, path: P) -> Result<Vec<String>> {
+        let c_path = CString::new(path.as_ref().to_string_lossy().as_bytes())
+            .map_err(|_| Error::new("Invalid path"))?;
+
+        unsafe {
+            let mut len: usize = 0;
+            let mut err: *mut i8 = ptr::null_mut();
+            let list = ffi::rocksdb_list_column_families(
+                options.as_ptr(),
+                c_path.as_ptr(),
+                &mut len,
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            if list.is_null() {
+                return Ok(Vec::new());
+            }
+
+            let names = (0..len)
+                .map(|i| CStr::from_ptr(*list.add(i)).to_string_lossy().into_owned())
+                .collect();
+            ffi::rocksdb_list_column_families_destroy(list, len);
+
+            Ok(names)
+        }
+    }
+
+    /// Look up an open column family by name
+    ///
+    /// Every column family opened with [`DB::open_with_column_families`] or
+    /// created with [`DB::create_column_family`] can be found here, so
+    /// handles don't have to be passed around by position. `"default"` is
+    /// always available. Returns a clone sharing the stored handle, or `None`
+    /// if no open column family has that name.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let opts = Options::default();
+    /// let cf_names = ["default", "users", "posts"];
+    /// let cf_opts = vec![Options::default(), Options::default(), Options::default()];
+    /// let (db, _) =
+    ///     DB::open_with_column_families(&opts, "/tmp/my_db", &cf_names, &cf_opts).unwrap();
+    ///
+    /// let users = db.cf_handle("users").unwrap();
+    /// db.put_cf(&users, b"user:1", b"Alice").unwrap();
+    /// assert!(db.cf_handle("missing").is_none());
+    /// ```
+    pub fn cf_handle(&self, name: &str) -> Option<ColumnFamilyHandle> {
+        let handle = self
+            .cf_handles
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(name)
+            .cloned();
+
+        match handle {
+            Some(handle) => Some(handle),
+            None if name == DEFAULT_COLUMN_FAMILY_NAME => self.default_cf_handle().ok(),
+            None => None,
+        }
+    }
+
+    /// Remember handles so [`DB::cf_handle`] can find them by name
+    fn register_cf_handles(&self, handles: &[ColumnFamilyHandle]) {
+        let mut cf_handles = self
+            .cf_handles
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        for handle in handles {
+            cf_handles.insert(handle.name().to_string(), handle.clone());
+        }
+    }
+
+    /// Get the default column family followed by the other open ones, by name (internal use only)
+    pub(crate) fn all_cf_handles(&self) -> Result<Vec<ColumnFamilyHandle>> {
+        let mut handles: Vec<ColumnFamilyHandle> = self
+            .cf_handles
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .values()
+            .filter(|handle| handle.name() != DEFAULT_COLUMN_FAMILY_NAME)
+            .cloned()
+            .collect();
+        handles.sort_by(|a, b| a.name().cmp(b.name()));
+        handles.insert(0, self.default_cf_handle()?);
+        Ok(handles)
+    }
+
+    /// Get a handle to the default column family (internal use only)
+    pub(crate) fn default_cf_handle(&self) -> Result<ColumnFamilyHandle> {
+        unsafe {
+            let ptr = ffi::rocksdb_get_default_column_family_handle(self.inner.as_ptr());
+            NonNull::new(ptr)
+                .map(|ptr| ColumnFamilyHandle::from_ptr(ptr, DEFAULT_COLUMN_FAMILY_NAME))
+                .ok_or_else(|| Error::new("Failed to get default column family handle"))
+        }
+    }
+
+    /// Get the underlying `rocksdb_t*` for calling C API functions this crate
+    /// does not wrap
+    ///
+    /// # Safety
+    ///
+    /// The pointer is only valid while this `DB` is alive, and must not be
+    /// closed with `rocksdb_close` since `DB` closes it on drop. Objects
+    /// created through it (iterators, snapshots, column family handles) must
+    /// be released before the `DB` is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    /// use std::os::raw::c_void;
+    ///
+    /// unsafe extern "C" {
+    ///     fn rocksdb_cancel_all_background_work(db: *mut c_void, wait: u8);
+    /// }
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// unsafe { rocksdb_cancel_all_background_work(db.as_raw(), 1) };
+    /// ```
+    pub unsafe fn as_raw(&self) -> *mut c_void {
+        self.inner.as_ptr() as *mut c_void
+    }
+
+    /// Get the raw pointer for FFI calls (internal use only)
+    pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_t {
+        self.inner.as_ptr()
+    }
+
+    /// Copy this database to `path` and open the copy
+    ///
+    /// Creates a [`Checkpoint`](crate::Checkpoint) at `path` and opens it as a
+    /// new, fully independent database. Writes to either database are not
+    /// visible in the other. Useful for test fixtures and for migrations that
+    /// start from a copy of live data.
+    ///
+    /// The copy is opened with the options this database was opened with,
+    /// and with all of its column families, each with the options persisted
+    /// for it in the checkpoint's OPTIONS file (see [`Options::load_latest`]);
+    /// find their handles with [`DB::cf_handle`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    /// db.put(b"key", b"value").unwrap();
+    ///
+    /// let copy = db.clone_to("/tmp/my_db_copy").unwrap();
+    /// assert_eq!(copy.get(b"key").unwrap().as_deref(), Some(&b"value"[..]));
+    /// ```
+    pub fn clone_to<P: AsRef<Path>>(&self, path: P) -> Result<DB> {
+        let path = path.as_ref();
+        Checkpoint::new(self)?.create_checkpoint(path)?;
+
+        let (_, descriptors) = Options::load_latest(path, self.options.env(), None)?;
+        let cf_names: Vec<&str> = descriptors.iter().map(|d| d.name.as_str()).collect();
+        let cf_options: Vec<&Options> = descriptors.iter().map(|d| &d.options).collect();
+        let (db, _) =
+            Self::open_column_families(&self.options, path, &cf_names, &cf_options, None)?;
+        Ok(db)
+    }
+
+    /// Create an iterator to traverse the database
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options, Direction};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// // Insert some data
+    /// db.put(b"key1", b"value1").unwrap();
+    /// db.put(b"key2", b"value2").unwrap();
+    ///
+    /// // Iterate forward
+    /// for item in db.iter(Direction::Forward) {
+    ///     let (key, value) = item.unwrap();
+    ///     println!("Key: {:?}, Value: {:?}", key, value);
+    /// }
+    /// ```
+    pub fn iter(&self, direction: iterator::Direction) -> iterator::DBIteratorAdapter<'_> {
+        use iterator::{DBIterator, DBIteratorAdapter};
+
+        unsafe {
+            // RocksDB copies the read options into the iterator
+            let read_opts = self.cached_options.read(ReadMode::Cached);
+            let iter_ptr = ffi::rocksdb_create_iterator(self.inner.as_ptr(), read_opts.as_ptr());
+
+            let iter_non_null = NonNull::new(iter_ptr).expect("Failed to create iterator");
+            let mut db_iter = DBIterator::new(iter_non_null);
+
+            // Position iterator based on direction
+            match direction {
+                iterator::Direction::Forward => db_iter.seek_to_first(),
+                iterator::Direction::Reverse => db_iter.seek_to_last(),
+            }
+
+            DBIteratorAdapter::new(db_iter, direction)
+        }
+    }
+
+    /// Iterate forward over the keys in `range`
+    ///
+    /// Accepts Rust range syntax (see [`KeyRange`](crate::KeyRange)). The range is enforced by RocksDB through
+    /// iterator bounds, so the scan stops at the end of the range without
+    /// reading the keys beyond it. Keys are compared bytewise, which matches
+    /// the default comparator.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rust_small_rocksdb::{DB, Options};
+    /// # let mut opts = Options::default();
+    /// # opts.create_if_missing(true);
+    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
+    /// // Keys from "a" up to but not including "m"
+    /// for item in db.range(b"a"..b"m") {
+    ///     let (key, value) = item.unwrap();
+    ///     println!("{:?} = {:?}", key, value);
+    /// }
+    ///
+    /// // Everything from "user:" through "user:~" inclusive
+    /// let users = db.range(&b"user:"[..]..=&b"user:~"[..]).count();
+    ///
+    /// // The whole database
+    /// let all = db.range(..).count();
+    /// ```
+    pub fn range(&self, range: impl KeyRange) -> iterator::DBIteratorAdapter<'_> {
+        self.bounded_iterator(None, &range)
+    }
+
+    /// Iterate forward over the keys in `range` in a column family
+    ///
+    /// See [`DB::range`].
+    pub fn range_cf<'a>(
+        &'a self,
+        cf_handle: &'a ColumnFamilyHandle,
+        range: impl KeyRange,
+    ) -> iterator::DBIteratorAdapter<'a> {
+        self.bounded_iterator(Some(cf_handle), &range)
+    }
+
+    /// Stream the keys in `range`, reading them in chunks as they are polled
+    ///
+    /// See [`DbStream`](crate::DbStream). Requires the `async` feature.
+    #[cfg(feature = "async")]
+    pub fn stream(
+        &self,
+        range: impl KeyRange,
+    ) -> crate::stream::DbStream<iterator::DBIteratorAdapter<'_>> {
+        crate::stream::DbStream::new(self.bounded_iterator(None, &range))
+    }
+
+    /// Stream the keys in `range` of a column family
+    ///
+    /// See [`DB::stream`]. Requires the `async` feature.
+    #[cfg(feature = "async")]
+    pub fn stream_cf<'a>(
+        &'a self,
+        cf_handle: &'a ColumnFamilyHandle,
+        range: impl KeyRange,
+    ) -> crate::stream::DbStream<iterator::DBIteratorAdapter<'a>> {
+        crate::stream::DbStream::new(self.bounded_iterator(Some(cf_handle), &range))
+    }
+
+    /// Iterate forward over the keys starting with `prefix`
+    ///
+    /// The scan is bounded by the prefix, so it stops as soon as the keys
+    /// leave it. When the options set a
+    /// [fixed prefix extractor](Options::set_fixed_prefix_extractor) no
+    /// longer than `prefix`, RocksDB also uses it to skip memtables and files
+    /// holding no keys with the prefix.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rust_small_rocksdb::{DB, Options};
+    /// # let mut opts = Options::default();
+    /// # opts.create_if_missing(true);
+    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
+    /// db.put(b"user:1:name", b"ada").unwrap();
+    /// db.put(b"user:1:email", b"ada@example.com").unwrap();
+    /// db.put(b"user:2:name", b"alan").unwrap();
+    ///
+    /// for item in db.prefix_iter(b"user:1:") {
+    ///     let (key, value) = item.unwrap();
+    ///     println!("{:?} = {:?}", key, value);
+    /// }
+    /// ```
+    pub fn prefix_iter(&self, prefix: &[u8]) -> iterator::DBIteratorAdapter<'_> {
+        let mut read_opts = prefix_read_options(prefix);
+        if self
+            .options
+            .prefix_len()
+            .is_some_and(|len| len <= prefix.len())
+        {
+            read_opts.set_prefix_same_as_start(true);
+        }
+
+        let mut db_iter = self.create_iterator(None, read_opts);
+        // With a lower bound set, this seeks to it
+        db_iter.seek_to_first();
+        iterator::DBIteratorAdapter::new(db_iter, iterator::Direction::Forward)
+    }
+
+    /// Prepare moving every key starting with `old_prefix` to start with `new_prefix`
+    ///
+    /// Nothing is written until [`PrefixRename::step`] or
+    /// [`PrefixRename::run`] is called; see [`PrefixRename`] for how the
+    /// rename proceeds and what it requires. Fails if either prefix is a
+    /// prefix of the other.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rust_small_rocksdb::{DB, Options};
+    /// # let mut opts = Options::default();
+    /// # opts.create_if_missing(true);
+    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
+    /// let renamed = db.rename_prefix(b"tenant:17/", b"tenant:42/").unwrap().run().unwrap();
+    /// ```
+    pub fn rename_prefix(&self, old_prefix: &[u8], new_prefix: &[u8]) -> Result<PrefixRename<'_>> {
+        PrefixRename::new(self, None, old_prefix, new_prefix)
+    }
+
+    /// Prepare moving every key of a column family from one prefix to another
+    ///
+    /// See [`DB::rename_prefix`].
+    pub fn rename_prefix_cf<'a>(
+        &'a self,
+        cf_handle: &'a ColumnFamilyHandle,
+        old_prefix: &[u8],
+        new_prefix: &[u8],
+    ) -> Result<PrefixRename<'a>> {
+        PrefixRename::new(self, Some(cf_handle), old_prefix, new_prefix)
+    }
+
+    /// Create a forward iterator limited to `range`, positioned at its start
+    fn bounded_iterator(
+        &self,
+        cf_handle: Option<&ColumnFamilyHandle>,
+        range: &impl KeyRange,
+    ) -> iterator::DBIteratorAdapter<'_> {
+        let mut db_iter = self.create_iterator(cf_handle, range_read_options(range));
+        // With a lower bound set, this seeks to it
+        db_iter.seek_to_first();
+        iterator::DBIteratorAdapter::new(db_iter, iterator::Direction::Forward)
+    }
+
+    /// Create a raw iterator that owns `read_opts`
+    pub(crate) fn create_iterator(
+        &self,
+        cf_handle: Option<&ColumnFamilyHandle>,
+        read_opts: ReadOptions,
+    ) -> iterator::DBIterator<'_> {
+        unsafe {
+            let iter_ptr = match cf_handle {
+                Some(cf_handle) => ffi::rocksdb_create_iterator_cf(
+                    self.inner.as_ptr(),
+                    read_opts.as_ptr(),
+                    cf_handle.as_ptr(),
+                ),
+                None => ffi::rocksdb_create_iterator(self.inner.as_ptr(), read_opts.as_ptr()),
+            };
+            let iter_non_null = NonNull::new(iter_ptr).expect("Failed to create iterator");
+            iterator::DBIterator::with_read_options(iter_non_null, read_opts)
+        }
+    }
+
+    /// Take a snapshot of the current state of the database
+    ///
+    /// Reads through the snapshot see the data as it was when it was taken.
+    /// Keep snapshots short-lived: the versions they can see are kept on disk
+    /// until they are released. Use [`DB::register_snapshot`] for snapshots
+    /// that must outlive the current scope.
+    pub fn snapshot(&self) -> Result<Snapshot<'_>> {
+        Ok(Snapshot::new(self, Arc::new(SnapshotInner::new(self)?)))
+    }
+
+    /// Take a snapshot and keep it in the registry under `name`
+    ///
+    /// With a `ttl`, the snapshot is released automatically once it is that
+    /// old; expired snapshots are released the next time the registry is
+    /// used. Without one it is kept until [`DB::release_snapshot`] or until
+    /// the database is closed. Fails with
+    /// [`ErrorKind::InvalidArgument`](crate::ErrorKind::InvalidArgument) if
+    /// the name is taken.
+    ///
+    /// Returns the sequence number of the snapshot.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rust_small_rocksdb::{DB, Options};
+    /// # use std::time::Duration;
+    /// # let mut opts = Options::default();
+    /// # opts.create_if_missing(true);
+    /// # let db = DB::open(&opts, "/tmp/test").unwrap();
+    /// db.register_snapshot("nightly-export", Some(Duration::from_secs(3600)))
+    ///     .unwrap();
+    ///
+    /// // Later, possibly from another thread
+    /// if let Some(snapshot) = db.named_snapshot("nightly-export") {
+    ///     let value = snapshot.get(b"key").unwrap();
+    /// }
+    ///
+    /// for info in db.snapshots() {
+    ///     println!("{} is {:?} old", info.name, info.age);
+    /// }
+    /// ```
+    pub fn register_snapshot(&self, name: &str, ttl: Option<Duration>) -> Result<u64> {
+        let inner = Arc::new(SnapshotInner::new(self)?);
+        let snapshot = Snapshot::new(self, inner.clone());
+        self.snapshot_registry
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .register(name, inner, ttl)?;
+        Ok(snapshot.sequence_number())
+    }
+
+    /// Get a registered snapshot by name
+    ///
+    /// Returns `None` if no snapshot is registered under `name` or it has
+    /// expired. The returned snapshot stays usable even if it is released
+    /// from the registry meanwhile.
+    pub fn named_snapshot(&self, name: &str) -> Option<Snapshot<'_>> {
+        let inner = self
+            .snapshot_registry
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(name)?;
+        Some(Snapshot::new(self, inner))
+    }
+
+    /// Remove a snapshot from the registry
+    ///
+    /// Returns `false` if no snapshot was registered under `name`. The
+    /// snapshot is released once no [`Snapshot`] obtained from
+    /// [`DB::named_snapshot`] is still using it.
+    pub fn release_snapshot(&self, name: &str) -> bool {
+        self.snapshot_registry
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .release(name)
+    }
+
+    /// Release every registered snapshot whose TTL has run out
+    ///
+    /// Returns how many were released. This happens anyway whenever the
+    /// registry is used; call it periodically if it may go unused for long.
+    pub fn release_expired_snapshots(&self) -> usize {
+        self.snapshot_registry
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .release_expired()
+    }
+
+    /// List the registered snapshots, sorted by name
+    pub fn snapshots(&self) -> Vec<SnapshotInfo> {
+        self.snapshot_registry
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .list()
+    }
+
+    /// Create a new column family with the given options
+    ///
+    /// Column families allow you to logically partition your data within a single database.
+    /// Each column family can have its own configuration and be managed independently.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Configuration options for the new column family
+    /// * `name` - Name of the column family to create
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// // Create a column family for user data
+    /// let cf_opts = Options::default();
+    /// let cf_handle = db.create_column_family(&cf_opts, "users").unwrap();
+    /// ```
+    pub fn create_column_family(
+        &self,
+        options: &Options,
+        name: &str,
+    ) -> Result<ColumnFamilyHandle> {
+        let c_name = CString::new(name).map_err(|_| Error::new("Invalid column family name"))?;
+        options.validate_column_family()?;
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            let cf_handle = ffi::rocksdb_create_column_family(
+                self.inner.as_ptr(),
+                options.as_ptr(),
+                c_name.as_ptr(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            let ptr = NonNull::new(cf_handle)
+                .ok_or_else(|| Error::new("Failed to create column family"))?;
+            let cf_handle = ColumnFamilyHandle::from_ptr(ptr, name);
+            self.register_cf_handles(std::slice::from_ref(&cf_handle));
+
+            Ok(cf_handle)
+        }
+    }
+
+    /// Create several column families with the same options in one call
+    ///
+    /// Returns the handles in the order of `names`. RocksDB rewrites the
+    /// OPTIONS file once for the whole group rather than once per column
+    /// family, which makes creating many column families much faster than
+    /// calling [`DB::create_column_family`] in a loop. The column families
+    /// are still created one after another: if one fails, those before it
+    /// remain and can be found with [`DB::cf_handle`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_opts = Options::default();
+    /// let tenants: Vec<String> = (0..100).map(|i| format!("tenant_{}", i)).collect();
+    /// let names: Vec<&str> = tenants.iter().map(String::as_str).collect();
+    /// let handles = db.create_column_families(&cf_opts, &names).unwrap();
+    /// assert_eq!(handles.len(), 100);
+    /// ```
+    pub fn create_column_families(
+        &self,
+        options: &Options,
+        names: &[&str],
+    ) -> Result<Vec<ColumnFamilyHandle>> {
+        let c_names: Vec<CString> = names
+            .iter()
+            .map(|name| CString::new(*name).map_err(|_| Error::new("Invalid column family name")))
+            .collect::<Result<_>>()?;
+        let name_ptrs: Vec<*const i8> = c_names.iter().map(|name| name.as_ptr()).collect();
+
+        unsafe {
+            let mut len: usize = 0;
+            let mut err: *mut i8 = ptr::null_mut();
+            let list = ffi::rocksdb_create_column_families(
+                self.inner.as_ptr(),
+                options.as_ptr(),
+                names.len() as c_int,
+                name_ptrs.as_ptr(),
+                &mut len,
+                &mut err,
+            );
+
+            // Handles come back in the order of the names, even after a failure
+            let mut handles = Vec::with_capacity(len);
+            if !list.is_null() {
+                for (i, name) in names.iter().enumerate().take(len) {
+                    if let Some(ptr) = NonNull::new(*list.add(i)) {
+                        handles.push(ColumnFamilyHandle::from_ptr(ptr, name));
+                    }
+                }
+                ffi::rocksdb_create_column_families_destroy(list);
+            }
+            self.register_cf_handles(&handles);
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+            if handles.len() != names.len() {
+                return Err(Error::new("Failed to create column families"));
+            }
+
+            Ok(handles)
+        }
+    }
+
+    /// Drop (delete) a column family
+    ///
+    /// This permanently removes the column family and all of its data.
+    /// The column family handle becomes invalid after this call.
+    ///
+    /// # Arguments
+    ///
+    /// * `cf_handle` - Handle to the column family to drop
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_opts = Options::default();
+    /// let cf_handle = db.create_column_family(&cf_opts, "temp").unwrap();
+    ///
+    /// // Drop the column family when no longer needed
+    /// db.drop_column_family(cf_handle).unwrap();
+    /// ```
+    pub fn drop_column_family(&self, cf_handle: ColumnFamilyHandle) -> Result<()> {
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_drop_column_family(self.inner.as_ptr(), cf_handle.as_ptr(), &mut err);
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+        }
+
+        self.wal_disabled_cfs
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&cf_handle.id());
+        self.cf_handles
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(cf_handle.name());
+        Ok(())
+    }
+
+    /// Drop a column family by name
+    ///
+    /// Works for any column family this database opened or created, so
+    /// column families listed in configuration can be dropped after a reopen
+    /// without keeping the original handle around. Other clones of the
+    /// handle stay valid for releasing, but reads and writes through them
+    /// fail. The default column family cannot be dropped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_opts = Options::default();
+    /// let _ = db.create_column_family(&cf_opts, "temp").unwrap();
+    /// db.drop_column_family_by_name("temp").unwrap();
+    /// ```
+    pub fn drop_column_family_by_name(&self, name: &str) -> Result<()> {
+        let cf_handle = self
+            .cf_handle(name)
+            .ok_or_else(|| Error::new(format!("Column family not found: {}", name)))?;
+        self.drop_column_family(cf_handle)
+    }
+
+    /// Run `f` with a new, empty column family that is dropped afterwards
+    ///
+    /// For jobs that need large scratch space: dropping a column family
+    /// removes all of its data at once instead of deleting keys one by one.
+    /// The column family is dropped when `f` returns, or panics. Clones of
+    /// the handle kept past that point fail on every read and write.
+    ///
+    /// Names start with `__temp_cf_` and are unique within the process. A
+    /// crash leaves the column family behind; it must then be listed when
+    /// reopening the database, and can be dropped by name.
+    ///
+    /// The error of `f` is returned in preference to one from dropping the
+    /// column family.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let distinct = db
+    ///     .with_temp_cf(|scratch| {
+    ///         for word in ["a", "b", "a", "c"] {
+    ///             db.put_cf(scratch, word.as_bytes(), b"")?;
+    ///         }
+    ///         let mut iter = db.raw_iterator_cf(scratch);
+    ///         iter.seek_to_first();
+    ///         let mut count = 0;
+    ///         while iter.valid() {
+    ///             count += 1;
+    ///             iter.next();
+    ///         }
+    ///         iter.status()?;
+    ///         Ok(count)
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(distinct, 3);
+    /// ```
+    pub fn with_temp_cf<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&ColumnFamilyHandle) -> Result<T>,
+    {
+        self.with_temp_cf_opt(&Options::default(), f)
+    }
+
+    /// Run `f` with a new column family created with `options`, dropped afterwards
+    ///
+    /// See [`DB::with_temp_cf`].
+    pub fn with_temp_cf_opt<T, F>(&self, options: &Options, f: F) -> Result<T>
+    where
+        F: FnOnce(&ColumnFamilyHandle) -> Result<T>,
+    {
+        let name = format!(
+            "{}{}_{}",
+            TEMP_CF_PREFIX,
+            std::process::id(),
+            TEMP_CF_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let handle = self.create_column_family(options, &name)?;
+        let temp = TempColumnFamily {
+            db: self,
+            handle: Some(handle.clone()),
+        };
+
+        let result = f(&handle);
+        drop(handle);
+        let closed = temp.close();
+        let value = result?;
+        closed?;
+        Ok(value)
+    }
+
+    /// Make every write to a column family skip the write-ahead log
+    ///
+    /// Meant for column families holding data that can be rebuilt, such as
+    /// caches: their writes get cheaper, but anything not yet flushed is lost
+    /// if the process crashes. Applies to [`DB::put_cf`], [`DB::delete_cf`]
+    /// and to [`DB::write`] for batches that only touch WAL-less column
+    /// families; a batch that also writes to a durable column family keeps
+    /// the WAL for all of its writes.
+    ///
+    /// The setting lasts until the database is closed and must be applied
+    /// again after reopening.
+    ///
+    /// Writes that skip the WAL still take sequence numbers, which leaves
+    /// gaps in the log. WAL readers can't cross those gaps, so while any
+    /// column family skips the WAL, [`DB::get_updates_since`] fails with
+    /// [`ErrorKind::NotSupported`], and with it WAL-based replication and
+    /// [`HotBackup::archive_wal`](crate::HotBackup::archive_wal). Reading
+    /// from before a gap keeps failing after the setting is cleared, until
+    /// the WAL files holding it are gone. Don't combine this with WAL
+    /// tailing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_opts = Options::default();
+    /// let cache_cf = db.create_column_family(&cf_opts, "cache").unwrap();
+    /// db.set_disable_wal_cf(&cache_cf, true);
+    ///
+    /// // No WAL write, no fsync on the commit path
+    /// db.put_cf(&cache_cf, b"rendered:/home", b"<html>...").unwrap();
+    /// ```
+    pub fn set_disable_wal_cf(&self, cf_handle: &ColumnFamilyHandle, disable: bool) {
+        let mut cfs = self
+            .wal_disabled_cfs
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if disable {
+            cfs.insert(cf_handle.id());
+        } else {
+            cfs.remove(&cf_handle.id());
+        }
+    }
+
+    /// Check whether writes to a column family skip the write-ahead log
+    ///
+    /// See [`DB::set_disable_wal_cf`].
+    pub fn is_wal_disabled_cf(&self, cf_handle: &ColumnFamilyHandle) -> bool {
+        self.wal_disabled_cfs
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .contains(&cf_handle.id())
+    }
+
+    /// Check whether every write in a batch goes to a WAL-less column family
+    ///
+    /// A batch that can't be decoded in full is logged.
+    fn batch_skips_wal(&self, batch: &WriteBatch) -> bool {
+        let cfs = self
+            .wal_disabled_cfs
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if cfs.is_empty() || batch.is_empty() {
+            return false;
+        }
+
+        batch.operations().is_ok_and(|ops| {
+            ops.iter().all(|op| match op {
+                WriteBatchOp::Put { cf_id, .. }
+                | WriteBatchOp::Delete { cf_id, .. }
+                | WriteBatchOp::Merge { cf_id, .. }
+                | WriteBatchOp::DeleteRange { cf_id, .. } => cfs.contains(cf_id),
+            })
+        })
+    }
+
+    /// Put a key-value pair into a specific column family
+    ///
+    /// # Arguments
+    ///
+    /// * `cf_handle` - Handle to the column family
+    /// * `key` - The key to store
+    /// * `value` - The value to store
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_opts = Options::default();
+    /// let cf_handle = db.create_column_family(&cf_opts, "users").unwrap();
+    ///
+    /// db.put_cf(&cf_handle, b"user:1", b"Alice").unwrap();
+    /// ```
+    pub fn put_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8], value: &[u8]) -> Result<()> {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+        debug_assert!(
+            value.len() < isize::MAX as usize,
+            "Value length exceeds maximum safe size"
+        );
+        self.limits.check(key, value)?;
+
+        let write_opts = self
+            .cached_options
+            .write(WriteMode::Buffered, self.is_wal_disabled_cf(cf_handle));
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_put_cf(
+                self.inner.as_ptr(),
+                write_opts.as_ptr(),
+                cf_handle.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+                value.as_ptr() as *const i8,
+                value.len(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Get a value from a specific column family
+    ///
+    /// Returns `None` if the key doesn't exist in the column family.
+    ///
+    /// # Arguments
+    ///
+    /// * `cf_handle` - Handle to the column family
+    /// * `key` - The key to retrieve
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_opts = Options::default();
+    /// let cf_handle = db.create_column_family(&cf_opts, "users").unwrap();
+    ///
+    /// db.put_cf(&cf_handle, b"user:1", b"Alice").unwrap();
+    /// let value = db.get_cf(&cf_handle, b"user:1").unwrap();
+    /// assert_eq!(value.as_deref(), Some(&b"Alice"[..]));
+    /// ```
+    pub fn get_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+
+        let read_opts = self.cached_options.read(ReadMode::Cached);
+
+        unsafe {
+            let mut val_len: usize = 0;
+            let mut err: *mut i8 = ptr::null_mut();
+            let val_ptr = ffi::rocksdb_get_cf(
+                self.inner.as_ptr(),
+                read_opts.as_ptr(),
+                cf_handle.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+                &mut val_len,
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(OwnedRocksDBBytes::from_raw(val_ptr, val_len).map(|bytes| bytes.to_vec()))
+        }
+    }
+
+    /// Get the values of several keys in a column family in one call
+    ///
+    /// Results are returned in the same order as `keys`, with `None` for
+    /// keys that don't exist.
+    pub fn multi_get_cf<K, I>(
+        &self,
+        cf_handle: &ColumnFamilyHandle,
+        keys: I,
+    ) -> Vec<Result<Option<Vec<u8>>>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        let keys: Vec<K> = keys.into_iter().collect();
+        let key_ptrs: Vec<*const i8> = keys
+            .iter()
+            .map(|k| k.as_ref().as_ptr() as *const i8)
+            .collect();
+        let key_sizes: Vec<usize> = keys.iter().map(|k| k.as_ref().len()).collect();
+        let cf_ptrs: Vec<*const ffi::rocksdb_column_family_handle_t> =
+            vec![cf_handle.as_ptr(); keys.len()];
+
+        let read_opts = self.cached_options.read(ReadMode::Cached);
+
+        let mut values: Vec<*mut i8> = vec![ptr::null_mut(); keys.len()];
+        let mut value_sizes: Vec<usize> = vec![0; keys.len()];
+        let mut errs: Vec<*mut i8> = vec![ptr::null_mut(); keys.len()];
+
+        unsafe {
+            ffi::rocksdb_multi_get_cf(
+                self.inner.as_ptr(),
+                read_opts.as_ptr(),
+                cf_ptrs.as_ptr(),
+                keys.len(),
+                key_ptrs.as_ptr(),
+                key_sizes.as_ptr(),
+                values.as_mut_ptr(),
+                value_sizes.as_mut_ptr(),
+                errs.as_mut_ptr(),
+            );
+
+            collect_multi_get(values, value_sizes, errs)
+        }
+    }
+
+    /// Get the values of several keys in a column family and keep the ones that exist
+    ///
+    /// Missing keys are left out of the map. Fails with the first error
+    /// reported for any key.
+    pub fn get_many_cf<K, I>(
+        &self,
+        cf_handle: &ColumnFamilyHandle,
+        keys: I,
+    ) -> Result<HashMap<Vec<u8>, Vec<u8>>>
+    where
+        K: AsRef<[u8]>,
+        I: IntoIterator<Item = K>,
+    {
+        let keys: Vec<K> = keys.into_iter().collect();
+        let values = self.multi_get_cf(cf_handle, &keys);
+        collect_found(&keys, values)
+    }
+
+    /// Check whether a key exists in a column family without copying its value
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_opts = Options::default();
+    /// let cf_handle = db.create_column_family(&cf_opts, "users").unwrap();
+    ///
+    /// db.put_cf(&cf_handle, b"user:1", b"Alice").unwrap();
+    /// assert!(db.contains_key_cf(&cf_handle, b"user:1").unwrap());
+    /// ```
+    pub fn contains_key_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8]) -> Result<bool> {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+
+        let read_opts = self.cached_options.read(ReadMode::Cached);
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            let slice = ffi::rocksdb_get_pinned_cf(
+                self.inner.as_ptr(),
+                read_opts.as_ptr(),
+                cf_handle.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(release_pinned(slice))
+        }
+    }
+
+    /// Delete a key from a specific column family
+    ///
+    /// # Arguments
+    ///
+    /// * `cf_handle` - Handle to the column family
+    /// * `key` - The key to delete
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_opts = Options::default();
+    /// let cf_handle = db.create_column_family(&cf_opts, "users").unwrap();
+    ///
+    /// db.put_cf(&cf_handle, b"user:1", b"Alice").unwrap();
+    /// db.delete_cf(&cf_handle, b"user:1").unwrap();
+    /// assert_eq!(db.get_cf(&cf_handle, b"user:1").unwrap(), None);
+    /// ```
+    pub fn delete_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8]) -> Result<()> {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+
+        let write_opts = self
+            .cached_options
+            .write(WriteMode::Buffered, self.is_wal_disabled_cf(cf_handle));
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_delete_cf(
+                self.inner.as_ptr(),
+                write_opts.as_ptr(),
+                cf_handle.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Ingest SST files created with [`SstFileWriter`](crate::SstFileWriter)
+    ///
+    /// The files are added to the default column family. They must have been
+    /// written with options compatible with the database (same comparator).
+    /// The files are copied, so the originals are left in place.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options, SstFileWriter};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let mut writer = SstFileWriter::new(&opts).unwrap();
+    /// writer.open("/tmp/data.sst").unwrap();
+    /// writer.put(b"key", b"value").unwrap();
+    /// writer.finish().unwrap();
+    ///
+    /// db.ingest_external_file(&["/tmp/data.sst"]).unwrap();
+    /// ```
+    pub fn ingest_external_file<P: AsRef<Path>>(&self, paths: &[P]) -> Result<()> {
+        let c_paths = paths_to_cstrings(paths)?;
+        let path_ptrs: Vec<*const i8> = c_paths.iter().map(|s| s.as_ptr()).collect();
+        let ingest_opts = IngestOptionsGuard::new()?;
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_ingest_external_file(
+                self.inner.as_ptr(),
+                path_ptrs.as_ptr(),
+                path_ptrs.len(),
+                ingest_opts.as_ptr(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Ingest SST files into a specific column family
+    ///
+    /// The files must have been written by an [`SstFileWriter`](crate::SstFileWriter)
+    /// created from the column family's own options; otherwise RocksDB rejects
+    /// them (for example on a comparator mismatch).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options, SstFileWriter};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_opts = Options::default();
+    /// let cf_handle = db.create_column_family(&cf_opts, "users").unwrap();
+    ///
+    /// let mut writer = SstFileWriter::new(&cf_opts).unwrap();
+    /// writer.open("/tmp/users.sst").unwrap();
+    /// writer.put(b"user:1", b"Alice").unwrap();
+    /// writer.finish().unwrap();
+    ///
+    /// db.ingest_external_file_cf(&cf_handle, &["/tmp/users.sst"]).unwrap();
+    /// ```
+    pub fn ingest_external_file_cf<P: AsRef<Path>>(
+        &self,
+        cf_handle: &ColumnFamilyHandle,
+        paths: &[P],
+    ) -> Result<()> {
+        let c_paths = paths_to_cstrings(paths)?;
+        let path_ptrs: Vec<*const i8> = c_paths.iter().map(|s| s.as_ptr()).collect();
+        let ingest_opts = IngestOptionsGuard::new()?;
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_ingest_external_file_cf(
+                self.inner.as_ptr(),
+                cf_handle.as_ptr(),
+                path_ptrs.as_ptr(),
+                path_ptrs.len(),
+                ingest_opts.as_ptr(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Flush the default column family's memtable to an SST file
+    ///
+    /// Blocks until the flush completes. Use [`DB::flush_opt`] to start a flush
+    /// without waiting.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// db.put(b"key", b"value").unwrap();
+    /// db.flush().unwrap();
+    /// ```
+    pub fn flush(&self) -> Result<()> {
+        self.flush_opt(&FlushOptions::default())
+    }
+
+    /// Flush the default column family's memtable with explicit options
+    pub fn flush_opt(&self, flush_opts: &FlushOptions) -> Result<()> {
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_flush(self.inner.as_ptr(), flush_opts.as_ptr(), &mut err);
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Flush a column family's memtable to an SST file
+    ///
+    /// Blocks until the flush completes.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_opts = Options::default();
+    /// let cf_handle = db.create_column_family(&cf_opts, "users").unwrap();
+    /// db.put_cf(&cf_handle, b"user:1", b"Alice").unwrap();
+    /// db.flush_cf(&cf_handle).unwrap();
+    /// ```
+    pub fn flush_cf(&self, cf_handle: &ColumnFamilyHandle) -> Result<()> {
+        self.flush_cf_opt(cf_handle, &FlushOptions::default())
+    }
+
+    /// Flush a column family's memtable with explicit options
+    pub fn flush_cf_opt(
+        &self,
+        cf_handle: &ColumnFamilyHandle,
+        flush_opts: &FlushOptions,
+    ) -> Result<()> {
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_flush_cf(
+                self.inner.as_ptr(),
+                flush_opts.as_ptr(),
+                cf_handle.as_ptr(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Write buffered WAL entries to the log file
+    ///
+    /// With [`Options::set_manual_wal_flush`] enabled this is what makes
+    /// buffered writes reach the WAL. When `sync` is true the log file is also
+    /// fsynced, so the writes survive a machine crash.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true).set_manual_wal_flush(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// // Group-commit a batch of writes with a single WAL flush
+    /// for i in 0..100u32 {
+    ///     db.put(&i.to_be_bytes(), b"value").unwrap();
+    /// }
+    /// db.flush_wal(true).unwrap();
+    /// ```
+    pub fn flush_wal(&self, sync: bool) -> Result<()> {
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_flush_wal(self.inner.as_ptr(), sync as u8, &mut err);
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Get the sequence number of the most recent write
+    ///
+    /// Every operation committed to the database gets the next sequence
+    /// number, so this is the position a replica or change-data-capture
+    /// consumer is caught up to once it has seen this write. RocksDB rejects
+    /// [`DB::get_updates_since`] for sequence numbers beyond this value, so
+    /// compare against it before resuming a tail.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let before = db.latest_sequence_number();
+    /// db.put(b"key", b"value").unwrap();
+    /// assert_eq!(db.latest_sequence_number(), before + 1);
+    /// ```
+    pub fn latest_sequence_number(&self) -> u64 {
+        unsafe { ffi::rocksdb_get_latest_sequence_number(self.inner.as_ptr()) }
+    }
+
+    /// Iterate over the write batches committed at or after `seq_number`
+    ///
+    /// This is the building block for replication and change data capture:
+    /// persist the sequence number after each batch is processed and resume
+    /// from there. RocksDB keeps WAL files only as long as it needs them, so
+    /// set [`Options::set_wal_ttl_seconds`] or [`Options::set_wal_size_limit_mb`]
+    /// to retain enough history for readers that fall behind. Requesting a
+    /// sequence number whose WAL file is gone fails with an error.
+    ///
+    /// Fails with [`ErrorKind::NotSupported`] while a column family skips
+    /// the WAL (see [`DB::set_disable_wal_cf`]), since its writes leave gaps
+    /// in the log that the iterator can't cross.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true).set_wal_ttl_seconds(3600);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// db.put(b"key", b"value").unwrap();
+    ///
+    /// let mut next_seq = 0;
+    /// for item in db.get_updates_since(next_seq).unwrap() {
+    ///     let (seq, batch) = item.unwrap();
+    ///     for op in batch.operations().unwrap() {
+    ///         println!("{}: {:?}", seq, op);
+    ///     }
+    ///     next_seq = seq + batch.len() as u64;
+    /// }
+    /// ```
+    pub fn get_updates_since(&self, seq_number: u64) -> Result<WalIterator<'_>> {
+        if !self
+            .wal_disabled_cfs
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .is_empty()
+        {
+            return Err(Error::with_kind(
+                ErrorKind::NotSupported,
+                "The WAL can't be read while a column family skips it, \
+                 since its writes leave gaps in the sequence numbers",
+            ));
+        }
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            let iter_ptr = ffi::rocksdb_get_updates_since(
+                self.inner.as_ptr(),
+                seq_number,
+                ptr::null(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            let inner = NonNull::new(iter_ptr)
+                .ok_or_else(|| Error::new("Failed to create WAL iterator"))?;
+            Ok(WalIterator::new(inner))
+        }
+    }
+
+    /// Flush buffered WAL entries and fsync the log file
+    ///
+    /// The C API has no separate `SyncWAL` entry point, so this is
+    /// `flush_wal(true)`: any entries still buffered by manual WAL flush are
+    /// written out before the sync.
+    pub fn sync_wal(&self) -> Result<()> {
+        self.flush_wal(true)
+    }
+
+    /// Make everything written so far durable in one call
+    ///
+    /// Flushes the default column family's memtable, waiting for the flush to
+    /// finish, then fsyncs the WAL so writes made concurrently with the flush
+    /// are covered too. This is the point to acknowledge a state machine
+    /// checkpoint (for example a raft snapshot). Other column families are
+    /// durable through the WAL; flush them with [`DB::flush_cf`] first if
+    /// their data should also be in SST files.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// db.put(b"applied_index", &42u64.to_be_bytes()).unwrap();
+    /// db.sync().unwrap();
+    /// // Safe to acknowledge the snapshot now
+    /// ```
+    pub fn sync(&self) -> Result<()> {
+        let mut flush_opts = FlushOptions::default();
+        flush_opts.set_wait(true);
+        self.flush_opt(&flush_opts)?;
+        self.sync_wal()
+    }
+
+    /// Estimate the on-disk size of each key range `[start, end)` in the default column family
+    ///
+    /// Only SST files are considered; data still in memtables is not
+    /// counted. Use [`DB::approximate_sizes_cf`] to include it.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let sizes = db
+    ///     .approximate_sizes(&[(&b"a"[..], &b"m"[..]), (&b"m"[..], &b"z"[..])])
+    ///     .unwrap();
+    /// println!("a..m: {} bytes, m..z: {} bytes", sizes[0], sizes[1]);
+    /// ```
+    pub fn approximate_sizes(&self, ranges: &[(&[u8], &[u8])]) -> Result<Vec<u64>> {
+        let bounds = RangeBounds::new(ranges)?;
+        let mut sizes: Vec<u64> = vec![0; ranges.len()];
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_approximate_sizes(
+                self.inner.as_ptr(),
+                bounds.count,
+                bounds.start_ptrs.as_ptr(),
+                bounds.start_lens.as_ptr(),
+                bounds.end_ptrs.as_ptr(),
+                bounds.end_lens.as_ptr(),
+                sizes.as_mut_ptr(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+        }
+
+        Ok(sizes)
+    }
+
+    /// Estimate the size of each key range `[start, end)` in a column family
+    ///
+    /// `include_memtables` counts data not yet flushed and `include_files`
+    /// counts SST files; at least one must be set.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_opts = Options::default();
+    /// let cf_handle = db.create_column_family(&cf_opts, "users").unwrap();
+    ///
+    /// let sizes = db
+    ///     .approximate_sizes_cf(&cf_handle, &[(&b"user:"[..], &b"user;"[..])], true, true)
+    ///     .unwrap();
+    /// println!("users: {} bytes", sizes[0]);
+    /// ```
+    pub fn approximate_sizes_cf(
+        &self,
+        cf_handle: &ColumnFamilyHandle,
+        ranges: &[(&[u8], &[u8])],
+        include_memtables: bool,
+        include_files: bool,
+    ) -> Result<Vec<u64>> {
+        let mut flags: u8 = 0;
+        if include_memtables {
+            flags |= ffi::ROCKSDB_SIZE_APPROXIMATION_INCLUDE_MEMTABLE;
+        }
+        if include_files {
+            flags |= ffi::ROCKSDB_SIZE_APPROXIMATION_INCLUDE_FILES;
+        }
+        if flags == 0 {
+            return Err(Error::new(
+                "Size approximation must include memtables, files or both",
+            ));
+        }
+
+        let bounds = RangeBounds::new(ranges)?;
+        let mut sizes: Vec<u64> = vec![0; ranges.len()];
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_approximate_sizes_cf_with_flags(
+                self.inner.as_ptr(),
+                cf_handle.as_ptr(),
+                bounds.count,
+                bounds.start_ptrs.as_ptr(),
+                bounds.start_lens.as_ptr(),
+                bounds.end_ptrs.as_ptr(),
+                bounds.end_lens.as_ptr(),
+                flags,
+                sizes.as_mut_ptr(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+        }
+
+        Ok(sizes)
+    }
+
+    /// Estimate how many bytes of the range `[start, end)` are still in memtables
+    ///
+    /// Useful for deciding whether to [`DB::flush`] before a large range
+    /// scan. RocksDB's C API does not expose `GetApproximateMemTableStats`,
+    /// so only the size is available, not the entry count; the
+    /// `rocksdb.num-entries-active-mem-table` property gives the entry count
+    /// of the whole memtable.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let pending = db.approximate_memtable_size(b"log:", b"log;").unwrap();
+    /// if pending > 64 * 1024 * 1024 {
+    ///     db.flush().unwrap();
+    /// }
+    /// ```
+    pub fn approximate_memtable_size(&self, start: &[u8], end: &[u8]) -> Result<u64> {
+        let default_cf = self.default_cf_handle()?;
+        self.approximate_memtable_size_cf(&default_cf, start, end)
+    }
+
+    /// Estimate how many bytes of the range `[start, end)` of a column family are still in memtables
+    ///
+    /// See [`DB::approximate_memtable_size`].
+    pub fn approximate_memtable_size_cf(
+        &self,
+        cf_handle: &ColumnFamilyHandle,
+        start: &[u8],
+        end: &[u8],
+    ) -> Result<u64> {
+        let sizes = self.approximate_sizes_cf(cf_handle, &[(start, end)], true, false)?;
+        Ok(sizes[0])
+    }
+
+    /// Pick about `n` random keys from the range `[start, end)` of the default column family
+    ///
+    /// Meant for estimating selectivity and key distribution without a full
+    /// scan. Small ranges are scanned once and sampled exactly. Larger ranges
+    /// are split into buckets weighted by [`DB::approximate_sizes_cf`]; each
+    /// sample seeks into a bucket and skips ahead by a random number of keys,
+    /// so the sample is only approximately uniform and may contain
+    /// duplicates. `None` leaves the range unbounded on that side.
+    ///
+    /// Keys are returned in sorted order; fewer than `n` are returned if the
+    /// range holds fewer keys.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let sample = db.sample_keys(100, Some(&b"user:"[..]), Some(&b"user;"[..])).unwrap();
+    /// let active = sample.iter().filter(|key| key.ends_with(b":active")).count();
+    /// println!("~{}% of users are active", active * 100 / sample.len().max(1));
+    /// ```
+    pub fn sample_keys(
+        &self,
+        n: usize,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+    ) -> Result<Vec<Vec<u8>>> {
+        /// Ranges with at most this many keys are scanned instead of sampled
+        const EXACT_SCAN_KEYS: u64 = 10_000;
+        /// The most keys a single sample skips over within its bucket
+        const MAX_SKIP: u64 = 10_000;
+
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut iter = self.raw_iterator();
+        let mut rng = SplitMix64::from_time();
+
+        // Find the first and last keys actually in the range
+        match start {
+            Some(start) => iter.seek(start),
+            None => iter.seek_to_first(),
+        }
+        let first = match iter.key() {
+            Some(key) if end.is_none_or(|end| key < end) => key.to_vec(),
+            _ => {
+                iter.status()?;
+                return Ok(Vec::new());
+            }
+        };
+        match end {
+            Some(end) => {
+                iter.seek_for_prev(end);
+                if iter.key() == Some(end) {
+                    iter.prev();
+                }
+            }
+            None => iter.seek_to_last(),
+        }
+        let last = match iter.key() {
+            Some(key) if key >= first.as_slice() => key.to_vec(),
+            _ => {
+                iter.status()?;
+                return Ok(Vec::new());
+            }
+        };
+        let mut last_exclusive = last.clone();
+        last_exclusive.push(0);
+
+        // Scale the database-wide key estimate by the share of bytes in the range
+        let default_cf = self.default_cf_handle()?;
+        let total_keys = self
+            .property_int_value("rocksdb.estimate-num-keys")?
+            .unwrap_or(0);
+        iter.seek_to_first();
+        let db_first = iter.key().map(<[u8]>::to_vec).unwrap_or_default();
+        let db_size =
+            self.approximate_sizes_cf(&default_cf, &[(&db_first, &last_exclusive)], true, true)?[0];
+        let range_size =
+            self.approximate_sizes_cf(&default_cf, &[(&first, &last_exclusive)], true, true)?[0];
+        let range_keys = if db_size == 0 {
+            0
+        } else {
+            (total_keys as u128 * range_size as u128 / db_size as u128) as u64
+        };
+
+        if range_keys <= EXACT_SCAN_KEYS.max(4 * n as u64) {
+            return sample_by_scan(iter, n, &first, &last, &mut rng);
+        }
+
+        // Split the range so each bucket holds no more keys than a sample may skip
+        let bucket_count = (range_keys / MAX_SKIP).clamp(64, 1024) as usize;
+        let starts = split_range(&first, &last, bucket_count);
+        let ranges: Vec<(&[u8], &[u8])> = (0..starts.len())
+            .map(|i| {
+                let end = starts.get(i + 1).unwrap_or(&last_exclusive);
+                (starts[i].as_slice(), end.as_slice())
+            })
+            .collect();
+        let sizes = self.approximate_sizes_cf(&default_cf, &ranges, true, true)?;
+        let sampled_size: u64 = sizes.iter().sum();
+        if sampled_size == 0 {
+            return sample_by_scan(iter, n, &first, &last, &mut rng);
+        }
+
+        let mut sample = Vec::with_capacity(n);
+        for _ in 0..n {
+            // Pick a bucket with probability proportional to its size
+            let mut target = rng.below(sampled_size);
+            let mut bucket = 0;
+            while target >= sizes[bucket] {
+                target -= sizes[bucket];
+                bucket += 1;
+            }
+            let (bucket_start, bucket_end) = ranges[bucket];
+            let bucket_keys = range_keys as u128 * sizes[bucket] as u128 / sampled_size as u128;
+            let skip = rng.below((bucket_keys as u64).clamp(1, MAX_SKIP));
+
+            iter.seek(bucket_start);
+            let mut picked = match iter.key() {
+                Some(key) if key <= last.as_slice() => key.to_vec(),
+                // Size estimates can credit bytes to a bucket with no live keys
+                _ => first.clone(),
+            };
+            for _ in 0..skip {
+                iter.next();
+                match iter.key() {
+                    Some(key) if key < bucket_end => {
+                        picked.clear();
+                        picked.extend_from_slice(key);
+                    }
+                    _ => break,
+                }
+            }
+            sample.push(picked);
+        }
+        iter.status()?;
+
+        sample.sort();
+        Ok(sample)
+    }
+
+    /// Manually compact the key range `[start, end]` of the default column family
+    ///
+    /// `None` for `start` or `end` means the range is unbounded on that side,
+    /// so `compact_range(None, None)` compacts the whole column family.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// db.compact_range(Some(&b"a"[..]), Some(&b"m"[..]));
+    /// ```
+    pub fn compact_range(&self, start: Option<&[u8]>, end: Option<&[u8]>) {
+        let (start_ptr, start_len) = range_bound(start);
+        let (end_ptr, end_len) = range_bound(end);
+
+        unsafe {
+            ffi::rocksdb_compact_range(self.inner.as_ptr(), start_ptr, start_len, end_ptr, end_len);
+        }
+    }
+
+    /// Manually compact a key range of the default column family with explicit options
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{BottommostLevelCompaction, CompactRangeOptions, DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let mut compact_opts = CompactRangeOptions::default();
+    /// compact_opts.set_bottommost_level_compaction(BottommostLevelCompaction::Force);
+    /// db.compact_range_opt(None, None, &compact_opts);
+    /// ```
+    pub fn compact_range_opt(
+        &self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        compact_opts: &CompactRangeOptions,
+    ) {
+        let (start_ptr, start_len) = range_bound(start);
+        let (end_ptr, end_len) = range_bound(end);
+
+        unsafe {
+            ffi::rocksdb_compact_range_opt(
+                self.inner.as_ptr(),
+                compact_opts.as_ptr(),
+                start_ptr,
+                start_len,
+                end_ptr,
+                end_len,
+            );
+        }
+    }
+
+    /// Manually compact the key range `[start, end]` of a column family
+    ///
+    /// `None` bounds behave as in [`DB::compact_range`].
     ///
     /// # Example
     ///
@@ -719,29 +3991,109 @@ impl DB {
     ///
     /// let cf_opts = Options::default();
     /// let cf_handle = db.create_column_family(&cf_opts, "users").unwrap();
+    /// db.compact_range_cf(&cf_handle, None, None);
+    /// ```
+    pub fn compact_range_cf(
+        &self,
+        cf_handle: &ColumnFamilyHandle,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+    ) {
+        let (start_ptr, start_len) = range_bound(start);
+        let (end_ptr, end_len) = range_bound(end);
+
+        unsafe {
+            ffi::rocksdb_compact_range_cf(
+                self.inner.as_ptr(),
+                cf_handle.as_ptr(),
+                start_ptr,
+                start_len,
+                end_ptr,
+                end_len,
+            );
+        }
+    }
+
+    /// Manually compact a key range of a column family with explicit options
     ///
-    /// db.put_cf(&cf_handle, b"user:1", b"Alice").unwrap();
-    /// let value = db.get_cf(&cf_handle, b"user:1").unwrap();
-    /// assert_eq!(value.as_deref(), Some(&b"Alice"[..]));
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{CompactRangeOptions, DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_opts = Options::default();
+    /// let cf_handle = db.create_column_family(&cf_opts, "users").unwrap();
+    ///
+    /// let mut compact_opts = CompactRangeOptions::default();
+    /// compact_opts.set_change_level(true).set_target_level(1);
+    /// db.compact_range_cf_opt(&cf_handle, None, None, &compact_opts);
     /// ```
-    pub fn get_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8]) -> Result<Option<Vec<u8>>> {
-        debug_assert!(
-            key.len() < isize::MAX as usize,
-            "Key length exceeds maximum safe size"
-        );
+    pub fn compact_range_cf_opt(
+        &self,
+        cf_handle: &ColumnFamilyHandle,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        compact_opts: &CompactRangeOptions,
+    ) {
+        let (start_ptr, start_len) = range_bound(start);
+        let (end_ptr, end_len) = range_bound(end);
+
+        unsafe {
+            ffi::rocksdb_compact_range_cf_opt(
+                self.inner.as_ptr(),
+                cf_handle.as_ptr(),
+                compact_opts.as_ptr(),
+                start_ptr,
+                start_len,
+                end_ptr,
+                end_len,
+            );
+        }
+    }
 
-        let read_opts = ReadOptionsGuard::new()?;
+    /// Ask background compactions to rewrite the files of a column family
+    /// overlapping a key range
+    ///
+    /// Unlike [`DB::compact_range_cf`], returns at once: the files are only
+    /// marked, and compacted when the background threads get to them, at
+    /// the pace of regular compactions. `None` leaves that end of the range
+    /// open.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open_cf(&opts, "/tmp/my_db", ["sessions"]).unwrap();
+    ///
+    /// // After expiring many sessions
+    /// let sessions = db.cf_handle("sessions").unwrap();
+    /// db.suggest_compact_range_cf(&sessions, None, None).unwrap();
+    /// ```
+    pub fn suggest_compact_range_cf(
+        &self,
+        cf_handle: &ColumnFamilyHandle,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+    ) -> Result<()> {
+        let (start_ptr, start_len) = range_bound(start);
+        let (end_ptr, end_len) = range_bound(end);
 
         unsafe {
-            let mut val_len: usize = 0;
             let mut err: *mut i8 = ptr::null_mut();
-            let val_ptr = ffi::rocksdb_get_cf(
+            ffi::rocksdb_suggest_compact_range_cf(
                 self.inner.as_ptr(),
-                read_opts.as_ptr(),
                 cf_handle.as_ptr(),
-                key.as_ptr() as *const i8,
-                key.len(),
-                &mut val_len,
+                start_ptr,
+                start_len,
+                end_ptr,
+                end_len,
                 &mut err,
             );
 
@@ -749,16 +4101,96 @@ impl DB {
                 return Err(Error::from_c_string(err));
             }
 
-            Ok(OwnedRocksDBBytes::from_raw(val_ptr, val_len).map(|bytes| bytes.to_vec()))
+            Ok(())
         }
     }
 
-    /// Delete a key from a specific column family
+    /// Compact the full key range of every column family, one at a time
     ///
-    /// # Arguments
+    /// The usual maintenance after deleting much of the data: compaction drops
+    /// the tombstones and the values they shadow and gives the space back.
+    /// The default column family goes first, then the others by name.
+    /// `progress` is called before and after each column family.
     ///
-    /// * `cf_handle` - Handle to the column family
-    /// * `key` - The key to delete
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{CompactionProgress, DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open_cf(&opts, "/tmp/my_db", ["users", "posts"]).unwrap();
+    ///
+    /// // After a bulk delete
+    /// db.compact_all(|progress| {
+    ///     if let CompactionProgress::Finished { column_family, completed, total, elapsed } = progress {
+    ///         println!("{}/{} {} took {:?}", completed, total, column_family, elapsed);
+    ///     }
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn compact_all<F>(&self, progress: F) -> Result<()>
+    where
+        F: Fn(&CompactionProgress) + Sync,
+    {
+        self.compact_all_parallel(1, progress)
+    }
+
+    /// Compact the full key range of every column family, several at a time
+    ///
+    /// Like [`DB::compact_all`], with up to `max_parallel` column families
+    /// compacted at once on scoped threads, so `progress` may be called from
+    /// any of them. The compactions are not exclusive, so other manual
+    /// compactions can run alongside them.
+    pub fn compact_all_parallel<F>(&self, max_parallel: usize, progress: F) -> Result<()>
+    where
+        F: Fn(&CompactionProgress) + Sync,
+    {
+        let handles = self.all_cf_handles()?;
+        let total = handles.len();
+        let next = AtomicUsize::new(0);
+        let completed = AtomicUsize::new(0);
+        let workers = max_parallel.clamp(1, total.max(1));
+
+        let worker = || {
+            // Exclusive manual compactions would wait for each other
+            let mut compact_opts = CompactRangeOptions::default();
+            compact_opts.set_exclusive_manual_compaction(workers == 1);
+
+            while let Some(handle) = handles.get(next.fetch_add(1, Ordering::Relaxed)) {
+                progress(&CompactionProgress::Started {
+                    column_family: handle.name().to_string(),
+                    completed: completed.load(Ordering::Relaxed),
+                    total,
+                });
+                let started = Instant::now();
+                self.compact_range_cf_opt(handle, None, None, &compact_opts);
+                progress(&CompactionProgress::Finished {
+                    column_family: handle.name().to_string(),
+                    completed: completed.fetch_add(1, Ordering::Relaxed) + 1,
+                    total,
+                    elapsed: started.elapsed(),
+                });
+            }
+        };
+
+        if workers == 1 {
+            worker();
+        } else {
+            thread::scope(|scope| {
+                for _ in 0..workers {
+                    scope.spawn(worker);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// List the live SST files of every column family with their properties
+    ///
+    /// Includes the entry and tombstone counts of each file, so tooling can
+    /// find the files full of tombstones and compact just their key ranges
+    /// with [`DB::suggest_compact_range_cf`] or [`DB::compact_range_cf`].
     ///
     /// # Example
     ///
@@ -769,38 +4201,137 @@ impl DB {
     /// opts.create_if_missing(true);
     /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
     ///
-    /// let cf_opts = Options::default();
-    /// let cf_handle = db.create_column_family(&cf_opts, "users").unwrap();
-    ///
-    /// db.put_cf(&cf_handle, b"user:1", b"Alice").unwrap();
-    /// db.delete_cf(&cf_handle, b"user:1").unwrap();
-    /// assert_eq!(db.get_cf(&cf_handle, b"user:1").unwrap(), None);
+    /// for file in db.table_properties().unwrap() {
+    ///     if file.tombstone_ratio() > 0.5 {
+    ///         let cf = db.cf_handle(&file.column_family).unwrap();
+    ///         db.suggest_compact_range_cf(&cf, Some(&file.smallest_key), Some(&file.largest_key))
+    ///             .unwrap();
+    ///     }
+    /// }
     /// ```
-    pub fn delete_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8]) -> Result<()> {
-        debug_assert!(
-            key.len() < isize::MAX as usize,
-            "Key length exceeds maximum safe size"
-        );
+    pub fn table_properties(&self) -> Result<Vec<TableProperties>> {
+        unsafe {
+            let files = ffi::rocksdb_livefiles(self.inner.as_ptr());
+            if files.is_null() {
+                return Err(Error::new("Failed to list live files"));
+            }
+            Ok(read_live_files(files))
+        }
+    }
 
-        let write_opts = WriteOptionsGuard::new()?;
+    /// Describe the SST files of the default column family, level by level
+    pub fn column_family_metadata(&self) -> ColumnFamilyMetadata {
+        unsafe {
+            ColumnFamilyMetadata::from_raw(ffi::rocksdb_get_column_family_metadata(
+                self.inner.as_ptr(),
+            ))
+        }
+    }
 
+    /// Describe the SST files of a column family, level by level
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_handle = db.create_column_family(&Options::default(), "users").unwrap();
+    /// for level in db.column_family_metadata_cf(&cf_handle).levels {
+    ///     println!("L{}: {} files, {} bytes", level.level, level.files.len(), level.size);
+    /// }
+    /// ```
+    pub fn column_family_metadata_cf(
+        &self,
+        cf_handle: &ColumnFamilyHandle,
+    ) -> ColumnFamilyMetadata {
         unsafe {
-            let mut err: *mut i8 = ptr::null_mut();
-            ffi::rocksdb_delete_cf(
+            ColumnFamilyMetadata::from_raw(ffi::rocksdb_get_column_family_metadata_cf(
                 self.inner.as_ptr(),
-                write_opts.as_ptr(),
                 cf_handle.as_ptr(),
-                key.as_ptr() as *const i8,
-                key.len(),
-                &mut err,
-            );
+            ))
+        }
+    }
 
-            if !err.is_null() {
-                return Err(Error::from_c_string(err));
-            }
+    /// Get the file count and size of each level of the default column family
+    ///
+    /// The result has one entry per configured level, empty ones included.
+    pub fn level_info(&self) -> Vec<LevelInfo> {
+        self.column_family_metadata().level_info()
+    }
 
-            Ok(())
+    /// Get the file count and size of each level of a column family
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_handle = db.create_column_family(&Options::default(), "users").unwrap();
+    /// let levels = db.level_info_cf(&cf_handle);
+    /// println!("{} levels", levels.len());
+    /// if levels.first().is_some_and(|level0| level0.files >= 8) {
+    ///     db.compact_level0(&cf_handle);
+    /// }
+    /// ```
+    pub fn level_info_cf(&self, cf_handle: &ColumnFamilyHandle) -> Vec<LevelInfo> {
+        self.column_family_metadata_cf(cf_handle).level_info()
+    }
+
+    /// Compact the key range spanned by the level 0 files of a column family
+    ///
+    /// Level 0 files overlap each other, so every read has to check all of
+    /// them; compacting just their key range fixes read amplification after
+    /// a burst of flushes without rewriting the rest of the column family.
+    /// RocksDB compacts that range through every level it overlaps.
+    ///
+    /// Returns the number of level 0 files found; with none, nothing is
+    /// compacted.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_handle = db.cf_handle("default").unwrap();
+    /// let files = db.compact_level0(&cf_handle);
+    /// println!("Compacted {} level 0 files", files);
+    /// ```
+    pub fn compact_level0(&self, cf_handle: &ColumnFamilyHandle) -> usize {
+        let metadata = self.column_family_metadata_cf(cf_handle);
+        let files = metadata.level0_files();
+        if let Some((start, end)) = covering_range(files) {
+            self.compact_range_cf(cf_handle, Some(start), Some(end));
+        }
+        files.len()
+    }
+
+    /// Compact the files, in any level, whose key range contains `key`
+    ///
+    /// The range compacted is the one spanned by those files, so a hotspot
+    /// full of tombstones or overwritten versions is cleaned up without a
+    /// whole-column-family compaction.
+    ///
+    /// Returns the number of files found to contain the key; with none,
+    /// nothing is compacted.
+    pub fn compact_file_overlaps(&self, cf_handle: &ColumnFamilyHandle, key: &[u8]) -> usize {
+        let metadata = self.column_family_metadata_cf(cf_handle);
+        let files: Vec<&SstFileMetadata> = metadata.files_containing(key).collect();
+        if let Some((start, end)) = covering_range(files.iter().copied()) {
+            self.compact_range_cf(cf_handle, Some(start), Some(end));
         }
+        files.len()
     }
 
     /// Create a raw iterator with more control
@@ -826,14 +4357,203 @@ impl DB {
         use iterator::DBIterator;
 
         unsafe {
-            let read_opts = ReadOptionsGuard::new().expect("Failed to create read options");
+            let read_opts = self.cached_options.read(ReadMode::Cached);
             let iter_ptr = ffi::rocksdb_create_iterator(self.inner.as_ptr(), read_opts.as_ptr());
-            // read_opts is automatically destroyed here
+            let iter_non_null = NonNull::new(iter_ptr).expect("Failed to create iterator");
+            DBIterator::new(iter_non_null)
+        }
+    }
+
+    /// Create a raw iterator over a column family
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_opts = Options::default();
+    /// let cf_handle = db.create_column_family(&cf_opts, "users").unwrap();
+    ///
+    /// let mut iter = db.raw_iterator_cf(&cf_handle);
+    /// iter.seek_to_first();
+    /// while iter.valid() {
+    ///     println!("Found key: {:?}", iter.key());
+    ///     iter.next();
+    /// }
+    /// ```
+    pub fn raw_iterator_cf<'a>(
+        &'a self,
+        cf_handle: &'a ColumnFamilyHandle,
+    ) -> iterator::DBIterator<'a> {
+        use iterator::DBIterator;
 
+        unsafe {
+            let read_opts = self.cached_options.read(ReadMode::Cached);
+            let iter_ptr = ffi::rocksdb_create_iterator_cf(
+                self.inner.as_ptr(),
+                read_opts.as_ptr(),
+                cf_handle.as_ptr(),
+            );
             let iter_non_null = NonNull::new(iter_ptr).expect("Failed to create iterator");
             DBIterator::new(iter_non_null)
         }
     }
+
+    /// Create a raw iterator with the given read options
+    ///
+    /// The iterator takes ownership of the options, including the buffers
+    /// holding any iterator bounds, so the bounds can't change or be freed
+    /// while it is in use.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options, ReadOptions};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// // Stops at "order:2000" instead of reading on into the next key space
+    /// let mut read_opts = ReadOptions::default();
+    /// read_opts
+    ///     .set_iterate_lower_bound(b"order:1000")
+    ///     .set_iterate_upper_bound(b"order:2000");
+    /// let mut iter = db.raw_iterator_opt(read_opts);
+    /// iter.seek_to_first();
+    /// while iter.valid() {
+    ///     println!("Found key: {:?}", iter.key());
+    ///     iter.next();
+    /// }
+    /// ```
+    pub fn raw_iterator_opt(&self, read_opts: ReadOptions) -> iterator::DBIterator<'_> {
+        self.create_iterator(None, read_opts)
+    }
+
+    /// Create a raw iterator over a column family with the given read options
+    ///
+    /// See [`DB::raw_iterator_opt`].
+    pub fn raw_iterator_cf_opt<'a>(
+        &'a self,
+        cf_handle: &'a ColumnFamilyHandle,
+        read_opts: ReadOptions,
+    ) -> iterator::DBIterator<'a> {
+        self.create_iterator(Some(cf_handle), read_opts)
+    }
+
+    /// Create a raw iterator that sees keys written after it was created
+    ///
+    /// Suited to consuming the database as a queue: read up to the end,
+    /// wait, then seek past the last key seen to pick up what was written
+    /// since, reusing the same iterator and its cached blocks. See
+    /// [`ReadOptions::set_tailing`] for its limits; use
+    /// [`DB::raw_iterator_opt`] to combine tailing with other options.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    /// use std::time::Duration;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let mut iter = db.tailing_iterator();
+    /// let mut last_key: Option<Vec<u8>> = None;
+    /// loop {
+    ///     match &last_key {
+    ///         Some(key) => {
+    ///             iter.seek(key);
+    ///             if iter.key() == Some(key.as_slice()) {
+    ///                 iter.next();
+    ///             }
+    ///         }
+    ///         None => iter.seek_to_first(),
+    ///     }
+    ///     while let Some((key, value)) = iter.item() {
+    ///         println!("Dequeued {:?} = {:?}", key, value);
+    ///         last_key = Some(key.to_vec());
+    ///         iter.next();
+    ///     }
+    ///     std::thread::sleep(Duration::from_millis(100));
+    /// }
+    /// ```
+    pub fn tailing_iterator(&self) -> iterator::DBIterator<'_> {
+        let mut read_opts = ReadOptions::new();
+        read_opts.set_tailing(true);
+        self.create_iterator(None, read_opts)
+    }
+
+    /// Create a raw iterator over a column family that sees later writes
+    ///
+    /// See [`DB::tailing_iterator`].
+    pub fn tailing_iterator_cf<'a>(
+        &'a self,
+        cf_handle: &'a ColumnFamilyHandle,
+    ) -> iterator::DBIterator<'a> {
+        let mut read_opts = ReadOptions::new();
+        read_opts.set_tailing(true);
+        self.create_iterator(Some(cf_handle), read_opts)
+    }
+
+    /// Create an iterator with the given read options
+    ///
+    /// Starts at the lower bound when iterating forward and at the last key
+    /// below the upper bound when iterating in reverse.
+    pub fn iter_opt(
+        &self,
+        direction: iterator::Direction,
+        read_opts: ReadOptions,
+    ) -> iterator::DBIteratorAdapter<'_> {
+        let mut db_iter = self.create_iterator(None, read_opts);
+        match direction {
+            iterator::Direction::Forward => db_iter.seek_to_first(),
+            iterator::Direction::Reverse => db_iter.seek_to_last(),
+        }
+        iterator::DBIteratorAdapter::new(db_iter, direction)
+    }
+
+    /// Scan several column families at once, merged in key order
+    ///
+    /// Yields `(cf_id, key, value)` where `cf_id` is the [`ColumnFamilyHandle::id`]
+    /// of the column family the entry came from. Keys are compared bytewise.
+    /// When the same key exists in more than one column family, every copy is
+    /// yielded, in the order the handles were passed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let cf_opts = Options::default();
+    /// let jan = db.create_column_family(&cf_opts, "events_2024_01").unwrap();
+    /// let feb = db.create_column_family(&cf_opts, "events_2024_02").unwrap();
+    ///
+    /// for item in db.merge_iter(&[&jan, &feb]) {
+    ///     let (cf_id, key, value) = item.unwrap();
+    ///     println!("[{}] {:?} => {:?}", cf_id, key, value);
+    /// }
+    /// ```
+    pub fn merge_iter<'a>(
+        &'a self,
+        cf_handles: &[&'a ColumnFamilyHandle],
+    ) -> iterator::MergeIterator<'a> {
+        let sources = cf_handles
+            .iter()
+            .map(|cf_handle| (cf_handle.id(), self.raw_iterator_cf(cf_handle)))
+            .collect();
+
+        iterator::MergeIterator::new(sources)
+    }
 }
 
 impl Drop for DB {
@@ -841,6 +4561,15 @@ impl Drop for DB {
         // Catch panics to prevent double-panic during unwinding
         // SAFETY: self.inner is always valid during the lifetime of DB
         let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            // Snapshots and column family handles must be released before the database closes
+            self.snapshot_registry
+                .get_mut()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .clear();
+            self.cf_handles
+                .get_mut()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .clear();
             ffi::rocksdb_close(self.inner.as_ptr());
         }));
     }
@@ -0,0 +1,219 @@
+//! Environment controlling background threads and OS interaction
+
+use crate::ffi;
+use std::os::raw::c_void;
+use std::ptr::NonNull;
+use std::sync::Arc;
+
+/// Owner of the underlying `rocksdb_env_t` pointer
+struct EnvInner {
+    ptr: NonNull<ffi::rocksdb_env_t>,
+}
+
+impl Drop for EnvInner {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_env_destroy(self.ptr.as_ptr());
+        }));
+    }
+}
+
+// The RocksDB Env is internally synchronized
+unsafe impl Send for EnvInner {}
+unsafe impl Sync for EnvInner {}
+
+/// A RocksDB environment
+///
+/// The environment owns the background thread pools that run flushes and
+/// compactions. It is attached to a database with [`Options::set_env`](crate::Options::set_env).
+///
+/// `Env` is cheaply cloneable; clones refer to the same environment. RocksDB
+/// only stores a raw pointer to the environment, so `Options` and `DB` keep a
+/// clone alive for as long as they may use it.
+///
+/// The default environment is shared by the whole process: changes made
+/// through one `Env::new()` value apply to every database using the default
+/// environment.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{Env, Options};
+///
+/// // Keep compactions from competing with request-handling threads
+/// let env = Env::new();
+/// env.lower_thread_pool_cpu_priority();
+/// env.lower_thread_pool_io_priority();
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true).set_env(&env);
+/// ```
+#[derive(Clone)]
+pub struct Env {
+    inner: Arc<EnvInner>,
+}
+
+impl Env {
+    /// Get a handle to the default (process-wide) environment
+    pub fn new() -> Self {
+        unsafe {
+            let ptr = ffi::rocksdb_create_default_env();
+            Env {
+                inner: Arc::new(EnvInner {
+                    ptr: NonNull::new(ptr).expect("Failed to create environment"),
+                }),
+            }
+        }
+    }
+
+    /// Take ownership of a `rocksdb_env_t*` created outside this crate
+    ///
+    /// This is the extension point for environments the C API can't build,
+    /// most notably RocksDB's encrypted environment: `librocksdb.a` ships
+    /// `rocksdb::NewEncryptedEnv` and the CTR encryption provider, but the
+    /// block cipher (and key management) has to come from the application,
+    /// in C++. A small shim compiled into the binary (e.g. with the `cc`
+    /// crate) builds the environment and hands it over, and every SST, WAL
+    /// and MANIFEST file written through it is then encrypted.
+    ///
+    /// `rocksdb_env_t` is private to RocksDB's `db/c.cc`, so the shim has to
+    /// repeat its definition, which is `{ rocksdb::Env* rep; bool is_default; }`
+    /// as of RocksDB 10.9. Check it again when upgrading RocksDB.
+    ///
+    /// ```cpp
+    /// #include <rocksdb/env_encryption.h>
+    ///
+    /// struct rocksdb_env_t { rocksdb::Env* rep; bool is_default; };
+    ///
+    /// extern "C" rocksdb_env_t* myapp_create_encrypted_env(const char* key, size_t key_len) {
+    ///     auto cipher = std::make_shared<MyAesCipher>(key, key_len);  // a rocksdb::BlockCipher
+    ///     auto provider = std::make_shared<rocksdb::CTREncryptionProvider>(cipher);
+    ///     return new rocksdb_env_t{rocksdb::NewEncryptedEnv(rocksdb::Env::Default(), provider), false};
+    /// }
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a valid `rocksdb_env_t*` that nothing else owns or
+    /// destroys: the returned `Env` destroys it with `rocksdb_env_destroy`
+    /// when the last clone is dropped. It must be laid out as `db/c.cc` of
+    /// the linked RocksDB expects.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ptr` is null.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Env, Options};
+    /// use std::os::raw::c_void;
+    ///
+    /// unsafe extern "C" {
+    ///     // Provided by the C++ shim above
+    ///     fn myapp_create_encrypted_env(key: *const u8, key_len: usize) -> *mut c_void;
+    /// }
+    ///
+    /// let key = [0u8; 32];
+    /// let env = unsafe { Env::from_raw(myapp_create_encrypted_env(key.as_ptr(), key.len())) };
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true).set_env(&env);
+    /// let db = DB::open(&opts, "/tmp/my_encrypted_db").unwrap();
+    /// ```
+    pub unsafe fn from_raw(ptr: *mut c_void) -> Self {
+        Env {
+            inner: Arc::new(EnvInner {
+                ptr: NonNull::new(ptr as *mut ffi::rocksdb_env_t)
+                    .expect("Env::from_raw called with a null pointer"),
+            }),
+        }
+    }
+
+    /// Lower the CPU priority (niceness) of the low-priority pool threads
+    ///
+    /// Compactions run in the low-priority pool, so this keeps compaction CPU
+    /// from competing with foreground work on shared machines.
+    pub fn lower_thread_pool_cpu_priority(&self) {
+        unsafe {
+            ffi::rocksdb_env_lower_thread_pool_cpu_priority(self.as_ptr());
+        }
+    }
+
+    /// Lower the I/O priority of the low-priority pool threads (Linux only)
+    pub fn lower_thread_pool_io_priority(&self) {
+        unsafe {
+            ffi::rocksdb_env_lower_thread_pool_io_priority(self.as_ptr());
+        }
+    }
+
+    /// Lower the CPU priority of the high-priority pool threads (used for flushes)
+    pub fn lower_high_priority_thread_pool_cpu_priority(&self) {
+        unsafe {
+            ffi::rocksdb_env_lower_high_priority_thread_pool_cpu_priority(self.as_ptr());
+        }
+    }
+
+    /// Lower the I/O priority of the high-priority pool threads (Linux only)
+    pub fn lower_high_priority_thread_pool_io_priority(&self) {
+        unsafe {
+            ffi::rocksdb_env_lower_high_priority_thread_pool_io_priority(self.as_ptr());
+        }
+    }
+
+    /// Set the number of threads in the low-priority pool, which runs compactions
+    ///
+    /// The pool only grows; RocksDB never runs more background jobs than
+    /// [`Options::set_max_background_jobs`](crate::Options::set_max_background_jobs)
+    /// allows, so raise that too.
+    pub fn set_background_threads(&self, n: i32) {
+        unsafe {
+            ffi::rocksdb_env_set_background_threads(self.as_ptr(), n);
+        }
+    }
+
+    /// Get the number of threads in the low-priority pool
+    pub fn background_threads(&self) -> i32 {
+        unsafe { ffi::rocksdb_env_get_background_threads(self.as_ptr()) }
+    }
+
+    /// Set the number of threads in the high-priority pool, which runs flushes
+    pub fn set_high_priority_background_threads(&self, n: i32) {
+        unsafe {
+            ffi::rocksdb_env_set_high_priority_background_threads(self.as_ptr(), n);
+        }
+    }
+
+    /// Get the number of threads in the high-priority pool
+    pub fn high_priority_background_threads(&self) -> i32 {
+        unsafe { ffi::rocksdb_env_get_high_priority_background_threads(self.as_ptr()) }
+    }
+
+    /// Set the number of threads in the bottom-priority pool
+    ///
+    /// When the pool has threads, compactions into the bottommost level run
+    /// there instead of the low-priority pool, so large bottommost
+    /// compactions cannot hold up the smaller ones above them.
+    pub fn set_bottom_priority_background_threads(&self, n: i32) {
+        unsafe {
+            ffi::rocksdb_env_set_bottom_priority_background_threads(self.as_ptr(), n);
+        }
+    }
+
+    /// Get the number of threads in the bottom-priority pool
+    pub fn bottom_priority_background_threads(&self) -> i32 {
+        unsafe { ffi::rocksdb_env_get_bottom_priority_background_threads(self.as_ptr()) }
+    }
+
+    /// Get the raw pointer for FFI calls
+    pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_env_t {
+        self.inner.ptr.as_ptr()
+    }
+}
+
+impl Default for Env {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -8,10 +8,242 @@ use std::os::raw::c_char;
 /// Result type alias for RocksDB operations
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Broad category of an [`Error`]
+///
+/// Derived from the code of the RocksDB status that produced the error, so
+/// callers can retry [`ErrorKind::Busy`] and [`ErrorKind::TryAgain`] or
+/// alert on [`ErrorKind::Corruption`] without matching on messages.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, ErrorKind, Options};
+///
+/// let db = DB::open(&Options::default(), "/tmp/my_db").unwrap();
+/// match db.put(b"key", b"value") {
+///     Ok(()) => {}
+///     Err(e) if matches!(e.kind(), ErrorKind::Busy | ErrorKind::TryAgain) => { /* retry */ }
+///     Err(e) if e.kind() == ErrorKind::Corruption => eprintln!("alert: {}", e),
+///     Err(e) => eprintln!("write failed: {}", e),
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A requested entity, such as a file or column family, does not exist
+    NotFound,
+    /// Data on disk failed validation (e.g. a checksum mismatch); retrying will not help
+    Corruption,
+    /// The operation is not supported by this build or configuration
+    NotSupported,
+    /// An argument or option was invalid, e.g. a key or value exceeded a
+    /// size limit set on the [`Options`](crate::Options)
+    InvalidArgument,
+    /// An I/O operation failed; often transient
+    IOError,
+    /// A merge operation could not be resolved yet
+    MergeInProgress,
+    /// The result is incomplete, e.g. a read that would have needed disk I/O
+    Incomplete,
+    /// The database is shutting down
+    ShutdownInProgress,
+    /// The operation timed out, e.g. waiting for a lock
+    TimedOut,
+    /// The operation was aborted
+    Aborted,
+    /// A resource was busy, e.g. a write conflict in an optimistic transaction; worth retrying
+    Busy,
+    /// The operation expired, e.g. a transaction past its deadline
+    Expired,
+    /// The operation failed transiently; worth retrying
+    TryAgain,
+    /// A compaction grew too large to run
+    CompactionTooLarge,
+    /// The column family was dropped while the operation was using it
+    ColumnFamilyDropped,
+    /// The operation was stopped through a [`CancellationToken`](crate::CancellationToken)
+    Cancelled,
+    /// A write was refused because it would take a column family over a
+    /// quota set on a [`QuotaWriter`](crate::QuotaWriter); worth retrying
+    /// once the quota frees up
+    QuotaExceeded,
+    /// Any other error
+    Other,
+}
+
+impl ErrorKind {
+    /// Prefixes RocksDB writes before the messages of each status code,
+    /// in the order of the codes (starting at 1)
+    const PREFIXES: [(&'static str, ErrorKind); 15] = [
+        ("NotFound", ErrorKind::NotFound),
+        ("Corruption", ErrorKind::Corruption),
+        ("Not implemented", ErrorKind::NotSupported),
+        ("Invalid argument", ErrorKind::InvalidArgument),
+        ("IO error", ErrorKind::IOError),
+        ("Merge in progress", ErrorKind::MergeInProgress),
+        ("Result incomplete", ErrorKind::Incomplete),
+        ("Shutdown in progress", ErrorKind::ShutdownInProgress),
+        ("Operation timed out", ErrorKind::TimedOut),
+        ("Operation aborted", ErrorKind::Aborted),
+        ("Resource busy", ErrorKind::Busy),
+        ("Operation expired", ErrorKind::Expired),
+        ("Operation failed. Try again.", ErrorKind::TryAgain),
+        ("Compaction too large", ErrorKind::CompactionTooLarge),
+        ("Column family dropped", ErrorKind::ColumnFamilyDropped),
+    ];
+}
+
+/// Detail RocksDB attaches to some status codes
+///
+/// Narrows down an [`ErrorKind`], e.g. an [`ErrorKind::IOError`] that ran
+/// out of disk space or hit an [`SstFileManager`](crate::SstFileManager)
+/// space limit. Read it with [`Error::subcode`]; [`ErrorSubcode::as_raw`]
+/// gives RocksDB's own `Status::SubCode` value.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, ErrorSubcode, Options};
+///
+/// let db = DB::open(&Options::default(), "/tmp/my_db").unwrap();
+/// if let Err(e) = db.put(b"key", b"value") {
+///     match e.subcode() {
+///         Some(ErrorSubcode::NoSpace | ErrorSubcode::SpaceLimit) => eprintln!("page: disk full"),
+///         _ => eprintln!("write failed: {}", e),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+#[repr(u8)]
+pub enum ErrorSubcode {
+    /// Timed out acquiring a mutex
+    MutexTimeout = 1,
+    /// Timed out waiting to lock a key in a transaction
+    LockTimeout = 2,
+    /// A transaction hit its limit on the number of locked keys
+    LockLimit = 3,
+    /// The device ran out of space
+    NoSpace = 4,
+    /// A transaction deadlock was detected
+    Deadlock = 5,
+    /// A file handle went stale
+    StaleFile = 6,
+    /// A memory limit was reached, e.g. the memtables are full
+    MemoryLimit = 7,
+    /// The space limit of an [`SstFileManager`](crate::SstFileManager) was reached
+    SpaceLimit = 8,
+    /// A file or directory does not exist
+    PathNotFound = 9,
+    /// The buffer for merge operands was too small
+    MergeOperandsInsufficientCapacity = 10,
+    /// A manual compaction was paused
+    ManualCompactionPaused = 11,
+    /// The status overwrote an earlier one
+    Overwritten = 12,
+    /// A transaction was used before being prepared
+    TxnNotPrepared = 13,
+    /// I/O was fenced off, e.g. after another instance took over the files
+    IOFenced = 14,
+    /// The merge operator failed
+    MergeOperatorFailed = 15,
+    /// A merge exceeded the threshold on the number of operands
+    MergeOperandThresholdExceeded = 16,
+    /// A multi-range scan reached its limit on prefetched files
+    PrefetchLimitReached = 17,
+    /// RocksDB reached code it did not expect to run
+    ///
+    /// RocksDB writes no message for this subcode, so it is never parsed
+    /// from an error; it is listed to keep the raw values complete.
+    NotExpectedCodePath = 18,
+}
+
+impl ErrorSubcode {
+    /// Messages RocksDB writes for each subcode that has one
+    const MESSAGES: [(&'static str, ErrorSubcode); 17] = [
+        ("Timeout Acquiring Mutex", ErrorSubcode::MutexTimeout),
+        ("Timeout waiting to lock key", ErrorSubcode::LockTimeout),
+        (
+            "Failed to acquire lock due to max_num_locks limit",
+            ErrorSubcode::LockLimit,
+        ),
+        ("No space left on device", ErrorSubcode::NoSpace),
+        ("Deadlock", ErrorSubcode::Deadlock),
+        ("Stale file handle", ErrorSubcode::StaleFile),
+        ("Memory limit reached", ErrorSubcode::MemoryLimit),
+        ("Space limit reached", ErrorSubcode::SpaceLimit),
+        ("No such file or directory", ErrorSubcode::PathNotFound),
+        (
+            "Insufficient capacity for merge operands",
+            ErrorSubcode::MergeOperandsInsufficientCapacity,
+        ),
+        (
+            "Manual compaction paused",
+            ErrorSubcode::ManualCompactionPaused,
+        ),
+        (" (overwritten)", ErrorSubcode::Overwritten),
+        ("Txn not prepared", ErrorSubcode::TxnNotPrepared),
+        ("IO fenced off", ErrorSubcode::IOFenced),
+        ("Merge operator failed", ErrorSubcode::MergeOperatorFailed),
+        (
+            "Number of operands merged exceeded threshold",
+            ErrorSubcode::MergeOperandThresholdExceeded,
+        ),
+        (
+            "MultiScan reached file prefetch limit",
+            ErrorSubcode::PrefetchLimitReached,
+        ),
+    ];
+
+    /// Get the raw value of the subcode in RocksDB's `Status::SubCode`
+    pub fn as_raw(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Code, subcode and kind parsed from a RocksDB status message
+struct Status {
+    kind: ErrorKind,
+    code: Option<u8>,
+    subcode: Option<ErrorSubcode>,
+}
+
+impl Status {
+    /// Parse a status message, laid out as `<code prefix>: [<subcode message>][: ]<state>`
+    fn parse(message: &str) -> Self {
+        let Some((index, rest)) = ErrorKind::PREFIXES
+            .iter()
+            .enumerate()
+            .find_map(|(index, (prefix, _))| Some((index, message.strip_prefix(prefix)?)))
+        else {
+            return Status {
+                kind: ErrorKind::Other,
+                code: None,
+                subcode: None,
+            };
+        };
+        let detail = rest.strip_prefix(": ").unwrap_or(rest);
+        let subcode = ErrorSubcode::MESSAGES
+            .iter()
+            .find(|(text, _)| detail.starts_with(text))
+            .map(|&(_, subcode)| subcode);
+        Status {
+            kind: ErrorKind::PREFIXES[index].1,
+            code: Some(index as u8 + 1),
+            subcode,
+        }
+    }
+}
+
 /// Error type for RocksDB operations
 #[derive(Debug, Clone)]
 pub struct Error {
     message: String,
+    kind: ErrorKind,
+    // Raw status code and subcode, set for errors reported by RocksDB
+    code: Option<u8>,
+    subcode: Option<ErrorSubcode>,
+    last_valid_key: Option<Vec<u8>>,
 }
 
 impl Error {
@@ -22,9 +254,7 @@ impl Error {
     /// This function will free the pointer using rocksdb_free.
     pub(crate) unsafe fn from_c_string(ptr: *mut c_char) -> Self {
         if ptr.is_null() {
-            return Error {
-                message: "Unknown error".to_string(),
-            };
+            return Error::new("Unknown error");
         }
 
         let c_str = unsafe { CStr::from_ptr(ptr) };
@@ -33,15 +263,88 @@ impl Error {
         // Free the C string allocated by RocksDB
         unsafe { crate::ffi::rocksdb_free(ptr as *mut std::ffi::c_void) };
 
-        Error { message }
+        let status = Status::parse(&message);
+        Error {
+            message,
+            kind: status.kind,
+            code: status.code,
+            subcode: status.subcode,
+            last_valid_key: None,
+        }
     }
 
     /// Create a new error from a string
     pub fn new(message: impl Into<String>) -> Self {
         Error {
             message: message.into(),
+            kind: ErrorKind::Other,
+            code: None,
+            subcode: None,
+            last_valid_key: None,
         }
     }
+
+    /// Create a new error of a specific kind
+    pub(crate) fn with_kind(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Error {
+            message: message.into(),
+            kind,
+            code: None,
+            subcode: None,
+            last_valid_key: None,
+        }
+    }
+
+    /// Get the category of the error
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Get the raw code of the RocksDB status that produced the error
+    ///
+    /// The value of RocksDB's `Status::Code` (e.g. 5 for an I/O error), for
+    /// tooling that classifies errors by the exact code. `None` for errors
+    /// raised by the crate itself.
+    pub fn code(&self) -> Option<u8> {
+        self.code
+    }
+
+    /// Get the subcode of the RocksDB status that produced the error
+    ///
+    /// `None` if RocksDB attached no subcode, or for errors raised by the
+    /// crate itself.
+    pub fn subcode(&self) -> Option<ErrorSubcode> {
+        self.subcode
+    }
+
+    /// Get the message exactly as RocksDB reported it
+    ///
+    /// Unlike the `Display` output, carries no `RocksDB error:` prefix.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Get the last key an iterator returned successfully before failing
+    ///
+    /// Set only for errors reported by iterators, and only if at least one
+    /// entry was read. A scan can resume after this key.
+    pub fn last_valid_key(&self) -> Option<&[u8]> {
+        self.last_valid_key.as_deref()
+    }
+
+    /// Attach the last key an iterator returned before this error
+    pub(crate) fn with_last_valid_key(mut self, key: Option<&[u8]>) -> Self {
+        self.last_valid_key = key.map(<[u8]>::to_vec);
+        self
+    }
+
+    /// Check whether the error is a transient conflict worth retrying
+    ///
+    /// RocksDB reports these as `Busy` (e.g. an optimistic transaction write
+    /// conflict) or `TryAgain` statuses.
+    pub(crate) fn is_retryable(&self) -> bool {
+        matches!(self.kind, ErrorKind::Busy | ErrorKind::TryAgain)
+    }
 }
 
 impl fmt::Display for Error {
@@ -3,8 +3,14 @@
 //! This module contains unsafe bindings to the RocksDB C library.
 //! These are low-level and should not be used directly - use the safe
 //! wrappers in the parent module instead.
+//!
+//! The module is public only with the `unsafe-ffi` feature, for downstream
+//! crates extending the binding before a safe wrapper lands here. Combine it
+//! with the `as_raw()` accessors, casting their result to the matching type
+//! (e.g. `db.as_raw() as *mut ffi::rocksdb_t`). Bindings are added as the
+//! safe wrappers need them, so this is not the complete C API.
 
-use libc::{c_char, c_int, c_void, size_t};
+use libc::{c_char, c_int, c_uint, c_void, size_t};
 
 // Opaque types from RocksDB C API
 #[repr(C)]
@@ -32,11 +38,181 @@ pub struct rocksdb_iterator_t {
     _private: [u8; 0],
 }
 
+#[repr(C)]
+pub struct rocksdb_snapshot_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_slicetransform_t {
+    _private: [u8; 0],
+}
+
 #[repr(C)]
 pub struct rocksdb_column_family_handle_t {
     _private: [u8; 0],
 }
 
+#[repr(C)]
+pub struct rocksdb_checkpoint_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_optimistictransactiondb_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_optimistictransaction_options_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_transaction_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_env_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_compactoptions_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_flushoptions_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_pinnableslice_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_writebatch_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_writebatch_wi_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_wal_iterator_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_wal_readoptions_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_block_based_table_options_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_filterpolicy_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_perfcontext_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_ratelimiter_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_write_buffer_manager_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_sst_file_manager_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_compactionfilter_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_compactionfiltercontext_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_compactionfilterfactory_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_cache_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_envoptions_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_sstfilewriter_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_ingestexternalfileoptions_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_logger_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_column_family_metadata_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_level_metadata_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_sst_file_metadata_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_livefiles_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_universal_compaction_options_t {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct rocksdb_fifo_compaction_options_t {
+    _private: [u8; 0],
+}
+
 // Compile-time assertions to ensure opaque types are zero-sized
 // This verifies that the types are truly opaque and don't accidentally grow
 const _: () = {
@@ -49,7 +225,41 @@ const _: () = {
     assert_zero_sized::<rocksdb_readoptions_t>();
     assert_zero_sized::<rocksdb_writeoptions_t>();
     assert_zero_sized::<rocksdb_iterator_t>();
+    assert_zero_sized::<rocksdb_snapshot_t>();
+    assert_zero_sized::<rocksdb_slicetransform_t>();
     assert_zero_sized::<rocksdb_column_family_handle_t>();
+    assert_zero_sized::<rocksdb_checkpoint_t>();
+    assert_zero_sized::<rocksdb_optimistictransactiondb_t>();
+    assert_zero_sized::<rocksdb_optimistictransaction_options_t>();
+    assert_zero_sized::<rocksdb_transaction_t>();
+    assert_zero_sized::<rocksdb_env_t>();
+    assert_zero_sized::<rocksdb_compactoptions_t>();
+    assert_zero_sized::<rocksdb_flushoptions_t>();
+    assert_zero_sized::<rocksdb_pinnableslice_t>();
+    assert_zero_sized::<rocksdb_writebatch_t>();
+    assert_zero_sized::<rocksdb_writebatch_wi_t>();
+    assert_zero_sized::<rocksdb_wal_iterator_t>();
+    assert_zero_sized::<rocksdb_wal_readoptions_t>();
+    assert_zero_sized::<rocksdb_block_based_table_options_t>();
+    assert_zero_sized::<rocksdb_filterpolicy_t>();
+    assert_zero_sized::<rocksdb_perfcontext_t>();
+    assert_zero_sized::<rocksdb_ratelimiter_t>();
+    assert_zero_sized::<rocksdb_write_buffer_manager_t>();
+    assert_zero_sized::<rocksdb_sst_file_manager_t>();
+    assert_zero_sized::<rocksdb_compactionfilter_t>();
+    assert_zero_sized::<rocksdb_compactionfiltercontext_t>();
+    assert_zero_sized::<rocksdb_compactionfilterfactory_t>();
+    assert_zero_sized::<rocksdb_cache_t>();
+    assert_zero_sized::<rocksdb_envoptions_t>();
+    assert_zero_sized::<rocksdb_sstfilewriter_t>();
+    assert_zero_sized::<rocksdb_ingestexternalfileoptions_t>();
+    assert_zero_sized::<rocksdb_logger_t>();
+    assert_zero_sized::<rocksdb_column_family_metadata_t>();
+    assert_zero_sized::<rocksdb_level_metadata_t>();
+    assert_zero_sized::<rocksdb_sst_file_metadata_t>();
+    assert_zero_sized::<rocksdb_livefiles_t>();
+    assert_zero_sized::<rocksdb_universal_compaction_options_t>();
+    assert_zero_sized::<rocksdb_fifo_compaction_options_t>();
 };
 
 // External functions from RocksDB C API
@@ -61,6 +271,45 @@ unsafe extern "C" {
         errptr: *mut *mut c_char,
     ) -> *mut rocksdb_t;
 
+    pub fn rocksdb_destroy_db(
+        options: *const rocksdb_options_t,
+        name: *const c_char,
+        errptr: *mut *mut c_char,
+    );
+
+    pub fn rocksdb_repair_db(
+        options: *const rocksdb_options_t,
+        name: *const c_char,
+        errptr: *mut *mut c_char,
+    );
+
+    pub fn rocksdb_open_as_secondary(
+        options: *const rocksdb_options_t,
+        name: *const c_char,
+        secondary_path: *const c_char,
+        errptr: *mut *mut c_char,
+    ) -> *mut rocksdb_t;
+
+    pub fn rocksdb_try_catch_up_with_primary(db: *mut rocksdb_t, errptr: *mut *mut c_char);
+
+    pub fn rocksdb_open_with_ttl(
+        options: *const rocksdb_options_t,
+        name: *const c_char,
+        ttl: c_int,
+        errptr: *mut *mut c_char,
+    ) -> *mut rocksdb_t;
+
+    pub fn rocksdb_open_column_families_with_ttl(
+        options: *const rocksdb_options_t,
+        name: *const c_char,
+        num_column_families: c_int,
+        column_family_names: *const *const c_char,
+        column_family_options: *const *const rocksdb_options_t,
+        column_family_handles: *mut *mut rocksdb_column_family_handle_t,
+        ttls: *const c_int,
+        errptr: *mut *mut c_char,
+    ) -> *mut rocksdb_t;
+
     pub fn rocksdb_open_for_read_only(
         options: *const rocksdb_options_t,
         name: *const c_char,
@@ -100,17 +349,185 @@ unsafe extern "C" {
     // Options
     pub fn rocksdb_options_create() -> *mut rocksdb_options_t;
     pub fn rocksdb_options_destroy(options: *mut rocksdb_options_t);
+    pub fn rocksdb_options_create_copy(options: *mut rocksdb_options_t) -> *mut rocksdb_options_t;
     pub fn rocksdb_options_set_create_if_missing(options: *mut rocksdb_options_t, value: c_int);
+    pub fn rocksdb_options_set_create_missing_column_families(
+        options: *mut rocksdb_options_t,
+        value: u8,
+    );
+    pub fn rocksdb_options_set_prefix_extractor(
+        opt: *mut rocksdb_options_t,
+        prefix_extractor: *mut rocksdb_slicetransform_t,
+    );
+    pub fn rocksdb_slicetransform_create_fixed_prefix(len: size_t)
+    -> *mut rocksdb_slicetransform_t;
     pub fn rocksdb_options_set_error_if_exists(options: *mut rocksdb_options_t, value: c_int);
+    pub fn rocksdb_options_get_create_if_missing(options: *mut rocksdb_options_t) -> u8;
+    pub fn rocksdb_options_get_error_if_exists(options: *mut rocksdb_options_t) -> u8;
+    pub fn rocksdb_options_set_compression(options: *mut rocksdb_options_t, value: c_int);
+    pub fn rocksdb_options_get_compression(options: *mut rocksdb_options_t) -> c_int;
+    pub fn rocksdb_options_set_bottommost_compression(
+        options: *mut rocksdb_options_t,
+        value: c_int,
+    );
+    pub fn rocksdb_options_get_bottommost_compression(options: *mut rocksdb_options_t) -> c_int;
+    pub fn rocksdb_options_set_compression_options(
+        options: *mut rocksdb_options_t,
+        w_bits: c_int,
+        level: c_int,
+        strategy: c_int,
+        max_dict_bytes: c_int,
+    );
+    pub fn rocksdb_options_set_compression_options_zstd_max_train_bytes(
+        options: *mut rocksdb_options_t,
+        zstd_max_train_bytes: c_int,
+    );
+    pub fn rocksdb_options_set_compression_options_use_zstd_dict_trainer(
+        options: *mut rocksdb_options_t,
+        use_zstd_dict_trainer: u8,
+    );
+    pub fn rocksdb_options_set_compression_options_max_dict_buffer_bytes(
+        options: *mut rocksdb_options_t,
+        max_dict_buffer_bytes: u64,
+    );
+    pub fn rocksdb_options_set_bottommost_compression_options(
+        options: *mut rocksdb_options_t,
+        w_bits: c_int,
+        level: c_int,
+        strategy: c_int,
+        max_dict_bytes: c_int,
+        enabled: u8,
+    );
+    pub fn rocksdb_options_set_bottommost_compression_options_zstd_max_train_bytes(
+        options: *mut rocksdb_options_t,
+        zstd_max_train_bytes: c_int,
+        enabled: u8,
+    );
+
+    pub fn rocksdb_options_set_env(options: *mut rocksdb_options_t, env: *mut rocksdb_env_t);
+    pub fn rocksdb_options_set_max_open_files(opt: *mut rocksdb_options_t, n: c_int);
+    pub fn rocksdb_options_set_max_background_jobs(opt: *mut rocksdb_options_t, n: c_int);
+    pub fn rocksdb_options_increase_parallelism(opt: *mut rocksdb_options_t, total_threads: c_int);
+    pub fn rocksdb_options_optimize_for_point_lookup(
+        opt: *mut rocksdb_options_t,
+        block_cache_size_mb: u64,
+    );
+    pub fn rocksdb_options_optimize_level_style_compaction(
+        opt: *mut rocksdb_options_t,
+        memtable_memory_budget: u64,
+    );
+    pub fn rocksdb_options_optimize_universal_style_compaction(
+        opt: *mut rocksdb_options_t,
+        memtable_memory_budget: u64,
+    );
+    pub fn rocksdb_options_set_max_subcompactions(options: *mut rocksdb_options_t, value: u32);
+    pub fn rocksdb_options_set_compaction_style(opt: *mut rocksdb_options_t, style: c_int);
+    pub fn rocksdb_options_set_universal_compaction_options(
+        opt: *mut rocksdb_options_t,
+        uco: *mut rocksdb_universal_compaction_options_t,
+    );
+
+    // Universal compaction options
+    pub fn rocksdb_universal_compaction_options_create()
+    -> *mut rocksdb_universal_compaction_options_t;
+    pub fn rocksdb_universal_compaction_options_set_size_ratio(
+        uco: *mut rocksdb_universal_compaction_options_t,
+        ratio: c_int,
+    );
+    pub fn rocksdb_universal_compaction_options_set_min_merge_width(
+        uco: *mut rocksdb_universal_compaction_options_t,
+        w: c_int,
+    );
+    pub fn rocksdb_universal_compaction_options_set_max_merge_width(
+        uco: *mut rocksdb_universal_compaction_options_t,
+        w: c_int,
+    );
+    pub fn rocksdb_universal_compaction_options_set_max_size_amplification_percent(
+        uco: *mut rocksdb_universal_compaction_options_t,
+        p: c_int,
+    );
+    pub fn rocksdb_universal_compaction_options_destroy(
+        uco: *mut rocksdb_universal_compaction_options_t,
+    );
+    pub fn rocksdb_options_set_fifo_compaction_options(
+        opt: *mut rocksdb_options_t,
+        fifo: *mut rocksdb_fifo_compaction_options_t,
+    );
+    pub fn rocksdb_options_set_ttl(opt: *mut rocksdb_options_t, seconds: u64);
+
+    // FIFO compaction options
+    pub fn rocksdb_fifo_compaction_options_create() -> *mut rocksdb_fifo_compaction_options_t;
+    pub fn rocksdb_fifo_compaction_options_set_max_table_files_size(
+        fifo_opts: *mut rocksdb_fifo_compaction_options_t,
+        size: u64,
+    );
+    pub fn rocksdb_fifo_compaction_options_set_allow_compaction(
+        fifo_opts: *mut rocksdb_fifo_compaction_options_t,
+        allow_compaction: u8,
+    );
+    pub fn rocksdb_fifo_compaction_options_destroy(
+        fifo_opts: *mut rocksdb_fifo_compaction_options_t,
+    );
+    pub fn rocksdb_options_set_manual_wal_flush(options: *mut rocksdb_options_t, value: u8);
+    pub fn rocksdb_options_set_WAL_ttl_seconds(options: *mut rocksdb_options_t, ttl: u64);
+    pub fn rocksdb_options_set_WAL_size_limit_MB(options: *mut rocksdb_options_t, limit: u64);
+
+    // Compact range options
+    pub fn rocksdb_compactoptions_create() -> *mut rocksdb_compactoptions_t;
+    pub fn rocksdb_compactoptions_destroy(opt: *mut rocksdb_compactoptions_t);
+    pub fn rocksdb_compactoptions_set_exclusive_manual_compaction(
+        opt: *mut rocksdb_compactoptions_t,
+        value: u8,
+    );
+    pub fn rocksdb_compactoptions_set_bottommost_level_compaction(
+        opt: *mut rocksdb_compactoptions_t,
+        value: u8,
+    );
+    pub fn rocksdb_compactoptions_set_change_level(opt: *mut rocksdb_compactoptions_t, value: u8);
+    pub fn rocksdb_compactoptions_set_target_level(
+        opt: *mut rocksdb_compactoptions_t,
+        value: c_int,
+    );
+
+    // Flush options
+    pub fn rocksdb_flushoptions_create() -> *mut rocksdb_flushoptions_t;
+    pub fn rocksdb_flushoptions_destroy(opt: *mut rocksdb_flushoptions_t);
+    pub fn rocksdb_flushoptions_set_wait(opt: *mut rocksdb_flushoptions_t, value: u8);
 
     // Read options
     pub fn rocksdb_readoptions_create() -> *mut rocksdb_readoptions_t;
     pub fn rocksdb_readoptions_destroy(options: *mut rocksdb_readoptions_t);
+    pub fn rocksdb_readoptions_set_fill_cache(options: *mut rocksdb_readoptions_t, value: u8);
+    pub fn rocksdb_readoptions_set_prefix_same_as_start(
+        options: *mut rocksdb_readoptions_t,
+        value: u8,
+    );
+    pub fn rocksdb_readoptions_set_tailing(options: *mut rocksdb_readoptions_t, value: u8);
+    pub fn rocksdb_readoptions_set_snapshot(
+        options: *mut rocksdb_readoptions_t,
+        snapshot: *const rocksdb_snapshot_t,
+    );
+    pub fn rocksdb_readoptions_set_iterate_lower_bound(
+        options: *mut rocksdb_readoptions_t,
+        key: *const c_char,
+        keylen: usize,
+    );
+    pub fn rocksdb_readoptions_set_iterate_upper_bound(
+        options: *mut rocksdb_readoptions_t,
+        key: *const c_char,
+        keylen: usize,
+    );
+
+    // Snapshots
+    pub fn rocksdb_create_snapshot(db: *mut rocksdb_t) -> *const rocksdb_snapshot_t;
+    pub fn rocksdb_release_snapshot(db: *mut rocksdb_t, snapshot: *const rocksdb_snapshot_t);
+    pub fn rocksdb_snapshot_get_sequence_number(snapshot: *const rocksdb_snapshot_t) -> u64;
 
     // Write options
     pub fn rocksdb_writeoptions_create() -> *mut rocksdb_writeoptions_t;
     pub fn rocksdb_writeoptions_destroy(options: *mut rocksdb_writeoptions_t);
     pub fn rocksdb_writeoptions_set_sync(options: *mut rocksdb_writeoptions_t, value: c_int);
+    pub fn rocksdb_writeoptions_disable_WAL(options: *mut rocksdb_writeoptions_t, disable: c_int);
 
     // Iterator operations
     pub fn rocksdb_create_iterator(
@@ -133,6 +550,7 @@ unsafe extern "C" {
     pub fn rocksdb_iter_key(iter: *const rocksdb_iterator_t, klen: *mut size_t) -> *const c_char;
     pub fn rocksdb_iter_value(iter: *const rocksdb_iterator_t, vlen: *mut size_t) -> *const c_char;
     pub fn rocksdb_iter_get_error(iter: *const rocksdb_iterator_t, errptr: *mut *mut c_char);
+    pub fn rocksdb_iter_refresh(iter: *const rocksdb_iterator_t, errptr: *mut *mut c_char);
 
     // Memory management
     pub fn rocksdb_free(ptr: *mut c_void);
@@ -144,6 +562,15 @@ unsafe extern "C" {
         column_family_name: *const c_char,
         errptr: *mut *mut c_char,
     ) -> *mut rocksdb_column_family_handle_t;
+    pub fn rocksdb_create_column_families(
+        db: *mut rocksdb_t,
+        column_family_options: *const rocksdb_options_t,
+        num_column_families: c_int,
+        column_family_names: *const *const c_char,
+        lencfs: *mut size_t,
+        errptr: *mut *mut c_char,
+    ) -> *mut *mut rocksdb_column_family_handle_t;
+    pub fn rocksdb_create_column_families_destroy(list: *mut *mut rocksdb_column_family_handle_t);
 
     pub fn rocksdb_drop_column_family(
         db: *mut rocksdb_t,
@@ -201,4 +628,918 @@ unsafe extern "C" {
         lencf: *mut size_t,
         errptr: *mut *mut c_char,
     ) -> *mut *mut c_char;
-}
+
+    pub fn rocksdb_list_column_families_destroy(list: *mut *mut c_char, len: size_t);
+
+    pub fn rocksdb_load_latest_options(
+        db_path: *const c_char,
+        env: *mut rocksdb_env_t,
+        ignore_unknown_options: bool,
+        cache: *mut rocksdb_cache_t,
+        db_options: *mut *mut rocksdb_options_t,
+        num_column_families: *mut size_t,
+        column_family_names: *mut *mut *mut c_char,
+        column_family_options: *mut *mut *mut rocksdb_options_t,
+        errptr: *mut *mut c_char,
+    );
+
+    pub fn rocksdb_load_latest_options_destroy(
+        db_options: *mut rocksdb_options_t,
+        list_column_family_names: *mut *mut c_char,
+        list_column_family_options: *mut *mut rocksdb_options_t,
+        len: size_t,
+    );
+
+    // Environment
+    pub fn rocksdb_create_default_env() -> *mut rocksdb_env_t;
+    pub fn rocksdb_env_destroy(env: *mut rocksdb_env_t);
+    pub fn rocksdb_env_lower_thread_pool_io_priority(env: *mut rocksdb_env_t);
+    pub fn rocksdb_env_lower_high_priority_thread_pool_io_priority(env: *mut rocksdb_env_t);
+    pub fn rocksdb_env_lower_thread_pool_cpu_priority(env: *mut rocksdb_env_t);
+    pub fn rocksdb_env_lower_high_priority_thread_pool_cpu_priority(env: *mut rocksdb_env_t);
+    pub fn rocksdb_env_set_background_threads(env: *mut rocksdb_env_t, n: c_int);
+    pub fn rocksdb_env_get_background_threads(env: *mut rocksdb_env_t) -> c_int;
+    pub fn rocksdb_env_set_high_priority_background_threads(env: *mut rocksdb_env_t, n: c_int);
+    pub fn rocksdb_env_get_high_priority_background_threads(env: *mut rocksdb_env_t) -> c_int;
+    pub fn rocksdb_env_set_bottom_priority_background_threads(env: *mut rocksdb_env_t, n: c_int);
+    pub fn rocksdb_env_get_bottom_priority_background_threads(env: *mut rocksdb_env_t) -> c_int;
+
+    // SST file writer
+    pub fn rocksdb_envoptions_create() -> *mut rocksdb_envoptions_t;
+    pub fn rocksdb_envoptions_destroy(opt: *mut rocksdb_envoptions_t);
+
+    pub fn rocksdb_sstfilewriter_create(
+        env: *const rocksdb_envoptions_t,
+        io_options: *const rocksdb_options_t,
+    ) -> *mut rocksdb_sstfilewriter_t;
+
+    pub fn rocksdb_sstfilewriter_open(
+        writer: *mut rocksdb_sstfilewriter_t,
+        name: *const c_char,
+        errptr: *mut *mut c_char,
+    );
+
+    pub fn rocksdb_sstfilewriter_put(
+        writer: *mut rocksdb_sstfilewriter_t,
+        key: *const c_char,
+        keylen: size_t,
+        val: *const c_char,
+        vallen: size_t,
+        errptr: *mut *mut c_char,
+    );
+
+    pub fn rocksdb_sstfilewriter_delete(
+        writer: *mut rocksdb_sstfilewriter_t,
+        key: *const c_char,
+        keylen: size_t,
+        errptr: *mut *mut c_char,
+    );
+
+    pub fn rocksdb_sstfilewriter_delete_range(
+        writer: *mut rocksdb_sstfilewriter_t,
+        begin_key: *const c_char,
+        begin_keylen: size_t,
+        end_key: *const c_char,
+        end_keylen: size_t,
+        errptr: *mut *mut c_char,
+    );
+
+    pub fn rocksdb_sstfilewriter_finish(
+        writer: *mut rocksdb_sstfilewriter_t,
+        errptr: *mut *mut c_char,
+    );
+
+    pub fn rocksdb_sstfilewriter_file_size(
+        writer: *mut rocksdb_sstfilewriter_t,
+        file_size: *mut u64,
+    );
+
+    pub fn rocksdb_sstfilewriter_destroy(writer: *mut rocksdb_sstfilewriter_t);
+
+    // External file ingestion
+    pub fn rocksdb_ingestexternalfileoptions_create() -> *mut rocksdb_ingestexternalfileoptions_t;
+    pub fn rocksdb_ingestexternalfileoptions_destroy(opt: *mut rocksdb_ingestexternalfileoptions_t);
+
+    pub fn rocksdb_ingest_external_file(
+        db: *mut rocksdb_t,
+        file_list: *const *const c_char,
+        list_len: size_t,
+        opt: *const rocksdb_ingestexternalfileoptions_t,
+        errptr: *mut *mut c_char,
+    );
+
+    pub fn rocksdb_ingest_external_file_cf(
+        db: *mut rocksdb_t,
+        handle: *mut rocksdb_column_family_handle_t,
+        file_list: *const *const c_char,
+        list_len: size_t,
+        opt: *const rocksdb_ingestexternalfileoptions_t,
+        errptr: *mut *mut c_char,
+    );
+
+    // Checkpoints
+    pub fn rocksdb_checkpoint_object_create(
+        db: *mut rocksdb_t,
+        errptr: *mut *mut c_char,
+    ) -> *mut rocksdb_checkpoint_t;
+
+    pub fn rocksdb_checkpoint_create(
+        checkpoint: *mut rocksdb_checkpoint_t,
+        checkpoint_dir: *const c_char,
+        log_size_for_flush: u64,
+        errptr: *mut *mut c_char,
+    );
+
+    pub fn rocksdb_checkpoint_object_destroy(checkpoint: *mut rocksdb_checkpoint_t);
+
+    // Optimistic transactions
+    pub fn rocksdb_optimistictransactiondb_open(
+        options: *const rocksdb_options_t,
+        name: *const c_char,
+        errptr: *mut *mut c_char,
+    ) -> *mut rocksdb_optimistictransactiondb_t;
+
+    pub fn rocksdb_optimistictransactiondb_get_base_db(
+        otxn_db: *mut rocksdb_optimistictransactiondb_t,
+    ) -> *mut rocksdb_t;
+
+    pub fn rocksdb_optimistictransactiondb_close_base_db(base_db: *mut rocksdb_t);
+    pub fn rocksdb_optimistictransactiondb_close(otxn_db: *mut rocksdb_optimistictransactiondb_t);
+
+    pub fn rocksdb_optimistictransaction_options_create()
+    -> *mut rocksdb_optimistictransaction_options_t;
+    pub fn rocksdb_optimistictransaction_options_destroy(
+        opt: *mut rocksdb_optimistictransaction_options_t,
+    );
+
+    pub fn rocksdb_optimistictransaction_begin(
+        otxn_db: *mut rocksdb_optimistictransactiondb_t,
+        write_options: *const rocksdb_writeoptions_t,
+        otxn_options: *const rocksdb_optimistictransaction_options_t,
+        old_txn: *mut rocksdb_transaction_t,
+    ) -> *mut rocksdb_transaction_t;
+
+    pub fn rocksdb_transaction_commit(txn: *mut rocksdb_transaction_t, errptr: *mut *mut c_char);
+    pub fn rocksdb_transaction_rollback(txn: *mut rocksdb_transaction_t, errptr: *mut *mut c_char);
+    pub fn rocksdb_transaction_destroy(txn: *mut rocksdb_transaction_t);
+
+    pub fn rocksdb_transaction_get(
+        txn: *mut rocksdb_transaction_t,
+        options: *const rocksdb_readoptions_t,
+        key: *const c_char,
+        klen: size_t,
+        vlen: *mut size_t,
+        errptr: *mut *mut c_char,
+    ) -> *mut c_char;
+
+    pub fn rocksdb_transaction_get_for_update(
+        txn: *mut rocksdb_transaction_t,
+        options: *const rocksdb_readoptions_t,
+        key: *const c_char,
+        klen: size_t,
+        vlen: *mut size_t,
+        exclusive: u8,
+        errptr: *mut *mut c_char,
+    ) -> *mut c_char;
+
+    pub fn rocksdb_transaction_put(
+        txn: *mut rocksdb_transaction_t,
+        key: *const c_char,
+        klen: size_t,
+        val: *const c_char,
+        vlen: size_t,
+        errptr: *mut *mut c_char,
+    );
+
+    pub fn rocksdb_transaction_delete(
+        txn: *mut rocksdb_transaction_t,
+        key: *const c_char,
+        klen: size_t,
+        errptr: *mut *mut c_char,
+    );
+
+    // Manual compaction
+    pub fn rocksdb_compact_range(
+        db: *mut rocksdb_t,
+        start_key: *const c_char,
+        start_key_len: size_t,
+        limit_key: *const c_char,
+        limit_key_len: size_t,
+    );
+
+    pub fn rocksdb_compact_range_cf(
+        db: *mut rocksdb_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+        start_key: *const c_char,
+        start_key_len: size_t,
+        limit_key: *const c_char,
+        limit_key_len: size_t,
+    );
+
+    pub fn rocksdb_suggest_compact_range_cf(
+        db: *mut rocksdb_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+        start_key: *const c_char,
+        start_key_len: size_t,
+        limit_key: *const c_char,
+        limit_key_len: size_t,
+        errptr: *mut *mut c_char,
+    );
+
+    pub fn rocksdb_compact_range_opt(
+        db: *mut rocksdb_t,
+        opt: *mut rocksdb_compactoptions_t,
+        start_key: *const c_char,
+        start_key_len: size_t,
+        limit_key: *const c_char,
+        limit_key_len: size_t,
+    );
+
+    pub fn rocksdb_compact_range_cf_opt(
+        db: *mut rocksdb_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+        opt: *mut rocksdb_compactoptions_t,
+        start_key: *const c_char,
+        start_key_len: size_t,
+        limit_key: *const c_char,
+        limit_key_len: size_t,
+    );
+
+    // Flush
+    pub fn rocksdb_flush(
+        db: *mut rocksdb_t,
+        options: *const rocksdb_flushoptions_t,
+        errptr: *mut *mut c_char,
+    );
+
+    pub fn rocksdb_flush_cf(
+        db: *mut rocksdb_t,
+        options: *const rocksdb_flushoptions_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+        errptr: *mut *mut c_char,
+    );
+
+    // Pinned reads
+    pub fn rocksdb_get_pinned(
+        db: *mut rocksdb_t,
+        options: *const rocksdb_readoptions_t,
+        key: *const c_char,
+        keylen: size_t,
+        errptr: *mut *mut c_char,
+    ) -> *mut rocksdb_pinnableslice_t;
+
+    pub fn rocksdb_get_pinned_cf(
+        db: *mut rocksdb_t,
+        options: *const rocksdb_readoptions_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+        key: *const c_char,
+        keylen: size_t,
+        errptr: *mut *mut c_char,
+    ) -> *mut rocksdb_pinnableslice_t;
+
+    pub fn rocksdb_pinnableslice_destroy(v: *mut rocksdb_pinnableslice_t);
+    pub fn rocksdb_pinnableslice_value(
+        t: *const rocksdb_pinnableslice_t,
+        vlen: *mut size_t,
+    ) -> *const c_char;
+
+    // Column family iteration
+    pub fn rocksdb_create_iterator_cf(
+        db: *mut rocksdb_t,
+        options: *const rocksdb_readoptions_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+    ) -> *mut rocksdb_iterator_t;
+
+    pub fn rocksdb_column_family_handle_get_id(handle: *mut rocksdb_column_family_handle_t) -> u32;
+
+    pub fn rocksdb_get_default_column_family_handle(
+        db: *mut rocksdb_t,
+    ) -> *mut rocksdb_column_family_handle_t;
+
+    // WAL control
+    pub fn rocksdb_flush_wal(db: *mut rocksdb_t, sync: u8, errptr: *mut *mut c_char);
+
+    // Write batch
+    pub fn rocksdb_writebatch_create() -> *mut rocksdb_writebatch_t;
+    pub fn rocksdb_writebatch_create_from(
+        rep: *const c_char,
+        size: size_t,
+    ) -> *mut rocksdb_writebatch_t;
+    pub fn rocksdb_writebatch_destroy(batch: *mut rocksdb_writebatch_t);
+    pub fn rocksdb_writebatch_clear(batch: *mut rocksdb_writebatch_t);
+    pub fn rocksdb_writebatch_count(batch: *mut rocksdb_writebatch_t) -> c_int;
+
+    pub fn rocksdb_writebatch_put(
+        batch: *mut rocksdb_writebatch_t,
+        key: *const c_char,
+        klen: size_t,
+        val: *const c_char,
+        vlen: size_t,
+    );
+
+    pub fn rocksdb_writebatch_put_cf(
+        batch: *mut rocksdb_writebatch_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+        key: *const c_char,
+        klen: size_t,
+        val: *const c_char,
+        vlen: size_t,
+    );
+
+    pub fn rocksdb_writebatch_delete(
+        batch: *mut rocksdb_writebatch_t,
+        key: *const c_char,
+        klen: size_t,
+    );
+
+    pub fn rocksdb_writebatch_delete_cf(
+        batch: *mut rocksdb_writebatch_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+        key: *const c_char,
+        klen: size_t,
+    );
+
+    pub fn rocksdb_writebatch_delete_range(
+        batch: *mut rocksdb_writebatch_t,
+        start_key: *const c_char,
+        start_key_len: size_t,
+        end_key: *const c_char,
+        end_key_len: size_t,
+    );
+
+    pub fn rocksdb_writebatch_delete_range_cf(
+        batch: *mut rocksdb_writebatch_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+        start_key: *const c_char,
+        start_key_len: size_t,
+        end_key: *const c_char,
+        end_key_len: size_t,
+    );
+
+    pub fn rocksdb_writebatch_data(
+        batch: *mut rocksdb_writebatch_t,
+        size: *mut size_t,
+    ) -> *const c_char;
+
+    pub fn rocksdb_write(
+        db: *mut rocksdb_t,
+        options: *const rocksdb_writeoptions_t,
+        batch: *mut rocksdb_writebatch_t,
+        errptr: *mut *mut c_char,
+    );
+
+    // Write batch with index
+    pub fn rocksdb_writebatch_wi_create(
+        reserved_bytes: size_t,
+        overwrite_keys: u8,
+    ) -> *mut rocksdb_writebatch_wi_t;
+    pub fn rocksdb_writebatch_wi_destroy(batch: *mut rocksdb_writebatch_wi_t);
+    pub fn rocksdb_writebatch_wi_clear(batch: *mut rocksdb_writebatch_wi_t);
+    pub fn rocksdb_writebatch_wi_count(batch: *mut rocksdb_writebatch_wi_t) -> c_int;
+
+    pub fn rocksdb_writebatch_wi_put(
+        batch: *mut rocksdb_writebatch_wi_t,
+        key: *const c_char,
+        klen: size_t,
+        val: *const c_char,
+        vlen: size_t,
+    );
+
+    pub fn rocksdb_writebatch_wi_put_cf(
+        batch: *mut rocksdb_writebatch_wi_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+        key: *const c_char,
+        klen: size_t,
+        val: *const c_char,
+        vlen: size_t,
+    );
+
+    pub fn rocksdb_writebatch_wi_delete(
+        batch: *mut rocksdb_writebatch_wi_t,
+        key: *const c_char,
+        klen: size_t,
+    );
+
+    pub fn rocksdb_writebatch_wi_delete_cf(
+        batch: *mut rocksdb_writebatch_wi_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+        key: *const c_char,
+        klen: size_t,
+    );
+
+    pub fn rocksdb_writebatch_wi_data(
+        batch: *mut rocksdb_writebatch_wi_t,
+        size: *mut size_t,
+    ) -> *const c_char;
+
+    pub fn rocksdb_writebatch_wi_get_from_batch(
+        batch: *mut rocksdb_writebatch_wi_t,
+        options: *const rocksdb_options_t,
+        key: *const c_char,
+        keylen: size_t,
+        vallen: *mut size_t,
+        errptr: *mut *mut c_char,
+    ) -> *mut c_char;
+
+    pub fn rocksdb_writebatch_wi_get_from_batch_and_db(
+        batch: *mut rocksdb_writebatch_wi_t,
+        db: *mut rocksdb_t,
+        options: *const rocksdb_readoptions_t,
+        key: *const c_char,
+        keylen: size_t,
+        vallen: *mut size_t,
+        errptr: *mut *mut c_char,
+    ) -> *mut c_char;
+
+    // WAL iteration
+    pub fn rocksdb_get_updates_since(
+        db: *mut rocksdb_t,
+        seq_number: u64,
+        options: *const rocksdb_wal_readoptions_t,
+        errptr: *mut *mut c_char,
+    ) -> *mut rocksdb_wal_iterator_t;
+
+    pub fn rocksdb_wal_iter_next(iter: *mut rocksdb_wal_iterator_t);
+    pub fn rocksdb_wal_iter_valid(iter: *const rocksdb_wal_iterator_t) -> u8;
+    pub fn rocksdb_wal_iter_status(iter: *const rocksdb_wal_iterator_t, errptr: *mut *mut c_char);
+    pub fn rocksdb_wal_iter_get_batch(
+        iter: *const rocksdb_wal_iterator_t,
+        seq: *mut u64,
+    ) -> *mut rocksdb_writebatch_t;
+    pub fn rocksdb_wal_iter_destroy(iter: *const rocksdb_wal_iterator_t);
+
+    pub fn rocksdb_get_latest_sequence_number(db: *mut rocksdb_t) -> u64;
+
+    // Dynamic options
+    pub fn rocksdb_set_options_cf(
+        db: *mut rocksdb_t,
+        handle: *mut rocksdb_column_family_handle_t,
+        count: c_int,
+        keys: *const *const c_char,
+        values: *const *const c_char,
+        errptr: *mut *mut c_char,
+    );
+
+    // Properties
+    pub fn rocksdb_property_value(db: *mut rocksdb_t, propname: *const c_char) -> *mut c_char;
+
+    pub fn rocksdb_property_value_cf(
+        db: *mut rocksdb_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+        propname: *const c_char,
+    ) -> *mut c_char;
+
+    pub fn rocksdb_property_int(
+        db: *mut rocksdb_t,
+        propname: *const c_char,
+        out_val: *mut u64,
+    ) -> c_int;
+
+    pub fn rocksdb_property_int_cf(
+        db: *mut rocksdb_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+        propname: *const c_char,
+        out_val: *mut u64,
+    ) -> c_int;
+
+    // Batched reads
+    pub fn rocksdb_multi_get(
+        db: *mut rocksdb_t,
+        options: *const rocksdb_readoptions_t,
+        num_keys: size_t,
+        keys_list: *const *const c_char,
+        keys_list_sizes: *const size_t,
+        values_list: *mut *mut c_char,
+        values_list_sizes: *mut size_t,
+        errs: *mut *mut c_char,
+    );
+
+    pub fn rocksdb_multi_get_cf(
+        db: *mut rocksdb_t,
+        options: *const rocksdb_readoptions_t,
+        column_families: *const *const rocksdb_column_family_handle_t,
+        num_keys: size_t,
+        keys_list: *const *const c_char,
+        keys_list_sizes: *const size_t,
+        values_list: *mut *mut c_char,
+        values_list_sizes: *mut size_t,
+        errs: *mut *mut c_char,
+    );
+
+    // Size approximation
+    pub fn rocksdb_approximate_sizes(
+        db: *mut rocksdb_t,
+        num_ranges: c_int,
+        range_start_key: *const *const c_char,
+        range_start_key_len: *const size_t,
+        range_limit_key: *const *const c_char,
+        range_limit_key_len: *const size_t,
+        sizes: *mut u64,
+        errptr: *mut *mut c_char,
+    );
+
+    pub fn rocksdb_approximate_sizes_cf_with_flags(
+        db: *mut rocksdb_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+        num_ranges: c_int,
+        range_start_key: *const *const c_char,
+        range_start_key_len: *const size_t,
+        range_limit_key: *const *const c_char,
+        range_limit_key_len: *const size_t,
+        include_flags: u8,
+        sizes: *mut u64,
+        errptr: *mut *mut c_char,
+    );
+
+    // Block-based table options
+    pub fn rocksdb_block_based_options_create() -> *mut rocksdb_block_based_table_options_t;
+
+    pub fn rocksdb_block_based_options_destroy(options: *mut rocksdb_block_based_table_options_t);
+
+    pub fn rocksdb_block_based_options_set_block_size(
+        options: *mut rocksdb_block_based_table_options_t,
+        block_size: size_t,
+    );
+
+    pub fn rocksdb_block_based_options_set_filter_policy(
+        options: *mut rocksdb_block_based_table_options_t,
+        filter_policy: *mut rocksdb_filterpolicy_t,
+    );
+
+    pub fn rocksdb_block_based_options_set_whole_key_filtering(
+        options: *mut rocksdb_block_based_table_options_t,
+        value: u8,
+    );
+
+    pub fn rocksdb_block_based_options_set_cache_index_and_filter_blocks(
+        options: *mut rocksdb_block_based_table_options_t,
+        value: u8,
+    );
+
+    pub fn rocksdb_block_based_options_set_data_block_index_type(
+        options: *mut rocksdb_block_based_table_options_t,
+        value: c_int,
+    );
+
+    pub fn rocksdb_get_options_from_string(
+        base_options: *const rocksdb_options_t,
+        opts_str: *const c_char,
+        new_options: *mut rocksdb_options_t,
+        errptr: *mut *mut c_char,
+    );
+
+    pub fn rocksdb_options_set_block_based_table_factory(
+        opt: *mut rocksdb_options_t,
+        table_options: *mut rocksdb_block_based_table_options_t,
+    );
+
+    pub fn rocksdb_filterpolicy_create_bloom_full(bits_per_key: f64)
+    -> *mut rocksdb_filterpolicy_t;
+
+    // Memtable and level sizing
+    pub fn rocksdb_options_set_write_buffer_size(opt: *mut rocksdb_options_t, value: size_t);
+
+    pub fn rocksdb_options_set_max_write_buffer_number(opt: *mut rocksdb_options_t, value: c_int);
+
+    pub fn rocksdb_options_set_min_write_buffer_number_to_merge(
+        opt: *mut rocksdb_options_t,
+        value: c_int,
+    );
+
+    pub fn rocksdb_options_set_level0_file_num_compaction_trigger(
+        opt: *mut rocksdb_options_t,
+        value: c_int,
+    );
+
+    pub fn rocksdb_options_set_level0_slowdown_writes_trigger(
+        opt: *mut rocksdb_options_t,
+        value: c_int,
+    );
+
+    pub fn rocksdb_options_set_level0_stop_writes_trigger(
+        opt: *mut rocksdb_options_t,
+        value: c_int,
+    );
+
+    pub fn rocksdb_options_set_target_file_size_base(opt: *mut rocksdb_options_t, value: u64);
+
+    pub fn rocksdb_options_set_max_bytes_for_level_base(opt: *mut rocksdb_options_t, value: u64);
+
+    pub fn rocksdb_options_set_level_compaction_dynamic_level_bytes(
+        opt: *mut rocksdb_options_t,
+        value: u8,
+    );
+
+    pub fn rocksdb_options_set_optimize_filters_for_hits(opt: *mut rocksdb_options_t, value: c_int);
+
+    // Blob files
+    pub fn rocksdb_options_set_enable_blob_files(opt: *mut rocksdb_options_t, value: u8);
+
+    pub fn rocksdb_options_get_enable_blob_files(opt: *mut rocksdb_options_t) -> u8;
+
+    pub fn rocksdb_options_set_min_blob_size(opt: *mut rocksdb_options_t, value: u64);
+
+    pub fn rocksdb_options_set_blob_file_size(opt: *mut rocksdb_options_t, value: u64);
+
+    pub fn rocksdb_options_set_blob_compression_type(opt: *mut rocksdb_options_t, value: c_int);
+
+    pub fn rocksdb_options_add_compact_on_deletion_collector_factory_del_ratio(
+        opt: *mut rocksdb_options_t,
+        window_size: size_t,
+        num_dels_trigger: size_t,
+        deletion_ratio: f64,
+    );
+
+    pub fn rocksdb_options_get_blob_compression_type(opt: *mut rocksdb_options_t) -> c_int;
+
+    pub fn rocksdb_options_set_enable_blob_gc(opt: *mut rocksdb_options_t, value: u8);
+
+    // Statistics
+    pub fn rocksdb_options_enable_statistics(opt: *mut rocksdb_options_t);
+
+    pub fn rocksdb_options_statistics_get_string(opt: *mut rocksdb_options_t) -> *mut c_char;
+
+    pub fn rocksdb_options_statistics_get_ticker_count(
+        opt: *mut rocksdb_options_t,
+        ticker_type: u32,
+    ) -> u64;
+
+    // Perf context
+    pub fn rocksdb_set_perf_level(level: c_int);
+
+    pub fn rocksdb_perfcontext_create() -> *mut rocksdb_perfcontext_t;
+
+    pub fn rocksdb_perfcontext_reset(context: *mut rocksdb_perfcontext_t);
+
+    pub fn rocksdb_perfcontext_report(
+        context: *mut rocksdb_perfcontext_t,
+        exclude_zero_counters: u8,
+    ) -> *mut c_char;
+
+    pub fn rocksdb_perfcontext_metric(context: *mut rocksdb_perfcontext_t, metric: c_int) -> u64;
+
+    pub fn rocksdb_perfcontext_destroy(context: *mut rocksdb_perfcontext_t);
+
+    // Rate limiter
+    pub fn rocksdb_ratelimiter_create_with_mode(
+        rate_bytes_per_sec: i64,
+        refill_period_us: i64,
+        fairness: i32,
+        mode: c_int,
+        auto_tuned: bool,
+    ) -> *mut rocksdb_ratelimiter_t;
+
+    pub fn rocksdb_ratelimiter_destroy(limiter: *mut rocksdb_ratelimiter_t);
+
+    pub fn rocksdb_options_set_ratelimiter(
+        opt: *mut rocksdb_options_t,
+        limiter: *mut rocksdb_ratelimiter_t,
+    );
+
+    // Write buffer manager
+    pub fn rocksdb_write_buffer_manager_create(
+        buffer_size: size_t,
+        allow_stall: bool,
+    ) -> *mut rocksdb_write_buffer_manager_t;
+
+    pub fn rocksdb_write_buffer_manager_destroy(wbm: *mut rocksdb_write_buffer_manager_t);
+
+    pub fn rocksdb_write_buffer_manager_memory_usage(
+        wbm: *mut rocksdb_write_buffer_manager_t,
+    ) -> size_t;
+
+    pub fn rocksdb_write_buffer_manager_mutable_memtable_memory_usage(
+        wbm: *mut rocksdb_write_buffer_manager_t,
+    ) -> size_t;
+
+    pub fn rocksdb_write_buffer_manager_buffer_size(
+        wbm: *mut rocksdb_write_buffer_manager_t,
+    ) -> size_t;
+
+    pub fn rocksdb_write_buffer_manager_set_buffer_size(
+        wbm: *mut rocksdb_write_buffer_manager_t,
+        new_size: size_t,
+    );
+
+    pub fn rocksdb_write_buffer_manager_set_allow_stall(
+        wbm: *mut rocksdb_write_buffer_manager_t,
+        new_allow_stall: bool,
+    );
+
+    pub fn rocksdb_options_set_write_buffer_manager(
+        opt: *mut rocksdb_options_t,
+        wbm: *mut rocksdb_write_buffer_manager_t,
+    );
+
+    // SST file manager
+    pub fn rocksdb_sst_file_manager_create(
+        env: *mut rocksdb_env_t,
+    ) -> *mut rocksdb_sst_file_manager_t;
+
+    pub fn rocksdb_sst_file_manager_destroy(sfm: *mut rocksdb_sst_file_manager_t);
+
+    pub fn rocksdb_sst_file_manager_set_max_allowed_space_usage(
+        sfm: *mut rocksdb_sst_file_manager_t,
+        max_allowed_space: u64,
+    );
+
+    pub fn rocksdb_sst_file_manager_set_compaction_buffer_size(
+        sfm: *mut rocksdb_sst_file_manager_t,
+        compaction_buffer_size: u64,
+    );
+
+    pub fn rocksdb_sst_file_manager_is_max_allowed_space_reached(
+        sfm: *mut rocksdb_sst_file_manager_t,
+    ) -> bool;
+
+    pub fn rocksdb_sst_file_manager_is_max_allowed_space_reached_including_compactions(
+        sfm: *mut rocksdb_sst_file_manager_t,
+    ) -> bool;
+
+    pub fn rocksdb_sst_file_manager_get_total_size(sfm: *mut rocksdb_sst_file_manager_t) -> u64;
+
+    pub fn rocksdb_sst_file_manager_get_delete_rate_bytes_per_second(
+        sfm: *mut rocksdb_sst_file_manager_t,
+    ) -> i64;
+
+    pub fn rocksdb_sst_file_manager_set_delete_rate_bytes_per_second(
+        sfm: *mut rocksdb_sst_file_manager_t,
+        delete_rate: i64,
+    );
+
+    pub fn rocksdb_sst_file_manager_get_max_trash_db_ratio(
+        sfm: *mut rocksdb_sst_file_manager_t,
+    ) -> f64;
+
+    pub fn rocksdb_sst_file_manager_set_max_trash_db_ratio(
+        sfm: *mut rocksdb_sst_file_manager_t,
+        ratio: f64,
+    );
+
+    pub fn rocksdb_sst_file_manager_get_total_trash_size(
+        sfm: *mut rocksdb_sst_file_manager_t,
+    ) -> u64;
+
+    pub fn rocksdb_options_set_sst_file_manager(
+        opt: *mut rocksdb_options_t,
+        sfm: *mut rocksdb_sst_file_manager_t,
+    );
+
+    // Compaction filters
+    pub fn rocksdb_compactionfilter_create(
+        state: *mut c_void,
+        destructor: unsafe extern "C" fn(state: *mut c_void),
+        filter: unsafe extern "C" fn(
+            state: *mut c_void,
+            level: c_int,
+            key: *const c_char,
+            key_length: size_t,
+            existing_value: *const c_char,
+            value_length: size_t,
+            new_value: *mut *mut c_char,
+            new_value_length: *mut size_t,
+            value_changed: *mut u8,
+        ) -> u8,
+        name: unsafe extern "C" fn(state: *mut c_void) -> *const c_char,
+    ) -> *mut rocksdb_compactionfilter_t;
+
+    pub fn rocksdb_compactionfilterfactory_create(
+        state: *mut c_void,
+        destructor: unsafe extern "C" fn(state: *mut c_void),
+        create_compaction_filter: unsafe extern "C" fn(
+            state: *mut c_void,
+            context: *mut rocksdb_compactionfiltercontext_t,
+        ) -> *mut rocksdb_compactionfilter_t,
+        name: unsafe extern "C" fn(state: *mut c_void) -> *const c_char,
+    ) -> *mut rocksdb_compactionfilterfactory_t;
+
+    pub fn rocksdb_options_set_compaction_filter_factory(
+        opt: *mut rocksdb_options_t,
+        factory: *mut rocksdb_compactionfilterfactory_t,
+    );
+
+    // Caches
+    pub fn rocksdb_cache_create_lru(capacity: size_t) -> *mut rocksdb_cache_t;
+    pub fn rocksdb_cache_destroy(cache: *mut rocksdb_cache_t);
+    pub fn rocksdb_cache_set_capacity(cache: *mut rocksdb_cache_t, capacity: size_t);
+    pub fn rocksdb_cache_get_capacity(cache: *const rocksdb_cache_t) -> size_t;
+    pub fn rocksdb_cache_get_usage(cache: *const rocksdb_cache_t) -> size_t;
+    pub fn rocksdb_cache_get_pinned_usage(cache: *const rocksdb_cache_t) -> size_t;
+
+    pub fn rocksdb_block_based_options_set_block_cache(
+        options: *mut rocksdb_block_based_table_options_t,
+        block_cache: *mut rocksdb_cache_t,
+    );
+
+    pub fn rocksdb_options_set_row_cache(opt: *mut rocksdb_options_t, cache: *mut rocksdb_cache_t);
+
+    // Loggers
+    pub fn rocksdb_logger_create_callback_logger(
+        log_level: c_int,
+        callback: unsafe extern "C" fn(
+            priv_: *mut c_void,
+            level: c_uint,
+            msg: *mut c_char,
+            len: size_t,
+        ),
+        priv_: *mut c_void,
+    ) -> *mut rocksdb_logger_t;
+    pub fn rocksdb_logger_destroy(logger: *mut rocksdb_logger_t);
+    pub fn rocksdb_options_set_info_log(opt: *mut rocksdb_options_t, logger: *mut rocksdb_logger_t);
+    pub fn rocksdb_options_get_info_log_level(opt: *mut rocksdb_options_t) -> c_int;
+    pub fn rocksdb_options_set_max_log_file_size(opt: *mut rocksdb_options_t, v: size_t);
+    pub fn rocksdb_options_get_max_log_file_size(opt: *mut rocksdb_options_t) -> size_t;
+    pub fn rocksdb_options_get_log_file_time_to_roll(opt: *mut rocksdb_options_t) -> size_t;
+    pub fn rocksdb_options_set_keep_log_file_num(opt: *mut rocksdb_options_t, v: size_t);
+    pub fn rocksdb_options_get_keep_log_file_num(opt: *mut rocksdb_options_t) -> size_t;
+    pub fn rocksdb_options_set_db_log_dir(opt: *mut rocksdb_options_t, dir: *const c_char);
+    pub fn rocksdb_options_set_wal_dir(opt: *mut rocksdb_options_t, dir: *const c_char);
+
+    // Column family metadata
+    pub fn rocksdb_get_column_family_metadata(
+        db: *mut rocksdb_t,
+    ) -> *mut rocksdb_column_family_metadata_t;
+    pub fn rocksdb_get_column_family_metadata_cf(
+        db: *mut rocksdb_t,
+        column_family: *mut rocksdb_column_family_handle_t,
+    ) -> *mut rocksdb_column_family_metadata_t;
+    pub fn rocksdb_column_family_metadata_destroy(cf_meta: *mut rocksdb_column_family_metadata_t);
+
+    // Live files
+    pub fn rocksdb_livefiles(db: *mut rocksdb_t) -> *const rocksdb_livefiles_t;
+    pub fn rocksdb_livefiles_count(files: *const rocksdb_livefiles_t) -> c_int;
+    pub fn rocksdb_livefiles_column_family_name(
+        files: *const rocksdb_livefiles_t,
+        index: c_int,
+    ) -> *const c_char;
+    pub fn rocksdb_livefiles_name(files: *const rocksdb_livefiles_t, index: c_int)
+    -> *const c_char;
+    pub fn rocksdb_livefiles_directory(
+        files: *const rocksdb_livefiles_t,
+        index: c_int,
+    ) -> *const c_char;
+    pub fn rocksdb_livefiles_level(files: *const rocksdb_livefiles_t, index: c_int) -> c_int;
+    pub fn rocksdb_livefiles_size(files: *const rocksdb_livefiles_t, index: c_int) -> size_t;
+    pub fn rocksdb_livefiles_smallestkey(
+        files: *const rocksdb_livefiles_t,
+        index: c_int,
+        size: *mut size_t,
+    ) -> *const c_char;
+    pub fn rocksdb_livefiles_largestkey(
+        files: *const rocksdb_livefiles_t,
+        index: c_int,
+        size: *mut size_t,
+    ) -> *const c_char;
+    pub fn rocksdb_livefiles_smallest_seqno(files: *const rocksdb_livefiles_t, index: c_int)
+    -> u64;
+    pub fn rocksdb_livefiles_largest_seqno(files: *const rocksdb_livefiles_t, index: c_int) -> u64;
+    pub fn rocksdb_livefiles_entries(files: *const rocksdb_livefiles_t, index: c_int) -> u64;
+    pub fn rocksdb_livefiles_deletions(files: *const rocksdb_livefiles_t, index: c_int) -> u64;
+    pub fn rocksdb_livefiles_destroy(files: *const rocksdb_livefiles_t);
+    pub fn rocksdb_column_family_metadata_get_size(
+        cf_meta: *mut rocksdb_column_family_metadata_t,
+    ) -> u64;
+    pub fn rocksdb_column_family_metadata_get_file_count(
+        cf_meta: *mut rocksdb_column_family_metadata_t,
+    ) -> size_t;
+    pub fn rocksdb_column_family_metadata_get_name(
+        cf_meta: *mut rocksdb_column_family_metadata_t,
+    ) -> *mut c_char;
+    pub fn rocksdb_column_family_metadata_get_level_count(
+        cf_meta: *mut rocksdb_column_family_metadata_t,
+    ) -> size_t;
+    pub fn rocksdb_column_family_metadata_get_level_metadata(
+        cf_meta: *mut rocksdb_column_family_metadata_t,
+        i: size_t,
+    ) -> *mut rocksdb_level_metadata_t;
+    pub fn rocksdb_level_metadata_destroy(level_meta: *mut rocksdb_level_metadata_t);
+    pub fn rocksdb_level_metadata_get_level(level_meta: *mut rocksdb_level_metadata_t) -> c_int;
+    pub fn rocksdb_level_metadata_get_size(level_meta: *mut rocksdb_level_metadata_t) -> u64;
+    pub fn rocksdb_level_metadata_get_file_count(
+        level_meta: *mut rocksdb_level_metadata_t,
+    ) -> size_t;
+    pub fn rocksdb_level_metadata_get_sst_file_metadata(
+        level_meta: *mut rocksdb_level_metadata_t,
+        i: size_t,
+    ) -> *mut rocksdb_sst_file_metadata_t;
+    pub fn rocksdb_sst_file_metadata_destroy(file_meta: *mut rocksdb_sst_file_metadata_t);
+    pub fn rocksdb_sst_file_metadata_get_relative_filename(
+        file_meta: *mut rocksdb_sst_file_metadata_t,
+    ) -> *mut c_char;
+    pub fn rocksdb_sst_file_metadata_get_directory(
+        file_meta: *mut rocksdb_sst_file_metadata_t,
+    ) -> *mut c_char;
+    pub fn rocksdb_sst_file_metadata_get_size(file_meta: *mut rocksdb_sst_file_metadata_t) -> u64;
+    pub fn rocksdb_sst_file_metadata_get_smallestkey(
+        file_meta: *mut rocksdb_sst_file_metadata_t,
+        len: *mut size_t,
+    ) -> *mut c_char;
+    pub fn rocksdb_sst_file_metadata_get_largestkey(
+        file_meta: *mut rocksdb_sst_file_metadata_t,
+        len: *mut size_t,
+    ) -> *mut c_char;
+}
+
+// Flags for rocksdb_approximate_sizes_cf_with_flags
+pub const ROCKSDB_SIZE_APPROXIMATION_INCLUDE_MEMTABLE: u8 = 1 << 0;
+pub const ROCKSDB_SIZE_APPROXIMATION_INCLUDE_FILES: u8 = 1 << 1;
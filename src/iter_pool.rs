@@ -0,0 +1,190 @@
+//! Reuse of iterators across many short scans
+//!
+//! Creating a RocksDB iterator allocates its merging structure and takes a
+//! reference on the current version of every memtable and SST file; for a
+//! scan that reads a handful of keys that setup dominates. An
+//! [`IteratorPool`] keeps finished iterators and refreshes them for the next
+//! scan instead.
+
+use crate::db::{ColumnFamilyHandle, DB};
+use crate::error::Result;
+use crate::iterator::DBIterator;
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+use std::thread::{self, ThreadId};
+
+/// A bounded, per-thread pool of reusable iterators
+///
+/// [`IteratorPool::get`] hands out an iterator that goes back to the pool
+/// when dropped. Iterators are only reused by the thread that returned them,
+/// and each thread keeps at most `max_per_thread` idle iterators; extras are
+/// destroyed. A reused iterator is refreshed first, so it sees every write
+/// made before [`IteratorPool::get`] returned, exactly like a new one.
+///
+/// Idle iterators keep the files they were refreshed against alive, so a
+/// pool should be sized for the number of scans in flight rather than left
+/// large. Iterators left idle by threads that have exited are only released
+/// by [`IteratorPool::clear`] or by dropping the pool.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, IteratorPool, Options};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let pool = IteratorPool::new(&db, 4);
+///
+/// for user in ["alice", "bob"] {
+///     let prefix = format!("session:{}:", user);
+///     let mut iter = pool.get().unwrap();
+///     iter.seek(&prefix);
+///     while let Some(key) = iter.key() {
+///         if !key.starts_with(prefix.as_bytes()) {
+///             break;
+///         }
+///         println!("{:?}", key);
+///         iter.next();
+///     }
+/// }
+/// ```
+pub struct IteratorPool<'a> {
+    db: &'a DB,
+    cf_handle: Option<&'a ColumnFamilyHandle>,
+    max_per_thread: usize,
+    idle: Mutex<HashMap<ThreadId, Vec<IdleIterator<'a>>>>,
+}
+
+/// An iterator waiting in a pool for the thread that released it
+struct IdleIterator<'a>(DBIterator<'a>);
+
+// Idle iterators are only created by the pool, over a `&DB` (which is
+// `Sync`), and nothing uses them while they wait. A RocksDB iterator may be
+// used and destroyed from any thread, one at a time (as for
+// `OwnedIterator`), so `clear` and dropping the pool may destroy the idle
+// iterators of other threads. This is what makes the pool `Sync`.
+unsafe impl Send for IdleIterator<'_> {}
+
+impl<'a> IteratorPool<'a> {
+    /// Create a pool of iterators over the default column family
+    pub fn new(db: &'a DB, max_per_thread: usize) -> Self {
+        IteratorPool {
+            db,
+            cf_handle: None,
+            max_per_thread,
+            idle: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create a pool of iterators over a column family
+    pub fn with_cf(db: &'a DB, cf_handle: &'a ColumnFamilyHandle, max_per_thread: usize) -> Self {
+        IteratorPool {
+            db,
+            cf_handle: Some(cf_handle),
+            max_per_thread,
+            idle: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get an unpositioned iterator, reusing an idle one from this thread if possible
+    pub fn get(&self) -> Result<PooledIterator<'_, 'a>> {
+        let reused = self
+            .idle
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get_mut(&thread::current().id())
+            .and_then(Vec::pop)
+            .map(|idle| idle.0);
+
+        let iter = match reused {
+            Some(mut iter) => {
+                iter.refresh()?;
+                iter
+            }
+            None => match self.cf_handle {
+                Some(cf_handle) => self.db.raw_iterator_cf(cf_handle),
+                None => self.db.raw_iterator(),
+            },
+        };
+
+        Ok(PooledIterator {
+            pool: self,
+            iter: Some(iter),
+        })
+    }
+
+    /// Get the number of idle iterators held for the calling thread
+    pub fn idle_count(&self) -> usize {
+        self.idle
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(&thread::current().id())
+            .map_or(0, Vec::len)
+    }
+
+    /// Destroy every idle iterator, releasing the files they keep alive
+    pub fn clear(&self) {
+        self.idle
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clear();
+    }
+
+    /// Take back an iterator returned on the calling thread
+    fn release(&self, iter: DBIterator<'a>) {
+        // Failed iterators are not worth keeping
+        if iter.status().is_err() {
+            return;
+        }
+
+        let mut idle = self
+            .idle
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let per_thread = idle.entry(thread::current().id()).or_default();
+        if per_thread.len() < self.max_per_thread {
+            per_thread.push(IdleIterator(iter));
+        }
+    }
+}
+
+/// An iterator borrowed from an [`IteratorPool`]
+///
+/// Dereferences to [`DBIterator`] and goes back to the pool when dropped.
+#[must_use = "Iterators are lazy and do nothing unless consumed"]
+pub struct PooledIterator<'p, 'a> {
+    pool: &'p IteratorPool<'a>,
+    iter: Option<DBIterator<'a>>,
+}
+
+impl<'a> Deref for PooledIterator<'_, 'a> {
+    type Target = DBIterator<'a>;
+
+    fn deref(&self) -> &DBIterator<'a> {
+        self.iter
+            .as_ref()
+            .expect("Pooled iterator already released")
+    }
+}
+
+impl<'a> DerefMut for PooledIterator<'_, 'a> {
+    fn deref_mut(&mut self) -> &mut DBIterator<'a> {
+        self.iter
+            .as_mut()
+            .expect("Pooled iterator already released")
+    }
+}
+
+impl Drop for PooledIterator<'_, '_> {
+    fn drop(&mut self) {
+        if let Some(iter) = self.iter.take() {
+            // Catch panics to prevent double-panic during unwinding
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                self.pool.release(iter);
+            }));
+        }
+    }
+}
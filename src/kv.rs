@@ -0,0 +1,286 @@
+//! Traits over the key-value surface shared by databases and transactions
+//!
+//! [`KvRead`] and [`KvWrite`] cover the point reads and writes that
+//! [`DB`], [`OptimisticTransactionDB`], [`Transaction`],
+//! [`WriteBatchWithIndex`], [`Snapshot`] and [`BoundColumnFamily`] all offer, so application code can be written once
+//! against the traits and run on any of them. [`MemKv`] implements them in
+//! memory, for unit tests that shouldn't touch the disk.
+
+use crate::batch::WriteBatchWithIndex;
+use crate::bound_cf::BoundColumnFamily;
+use crate::db::DB;
+use crate::error::Result;
+use crate::snapshot::Snapshot;
+use crate::transaction::{OptimisticTransactionDB, Transaction};
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+/// Point reads from a key-value store
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, KvRead, Options, Result};
+///
+/// fn user_name(store: &impl KvRead, id: u32) -> Result<Option<Vec<u8>>> {
+///     store.get(format!("user:{}", id).as_bytes())
+/// }
+///
+/// let db = DB::open(&Options::default(), "/tmp/my_db").unwrap();
+/// let live = user_name(&db, 1).unwrap();
+/// let snapshot = db.snapshot().unwrap();
+/// let frozen = user_name(&snapshot, 1).unwrap();
+/// ```
+pub trait KvRead {
+    /// Get the value of a key, or `None` if it doesn't exist
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    /// Check whether a key exists
+    fn contains_key(&self, key: &[u8]) -> Result<bool> {
+        Ok(self.get(key)?.is_some())
+    }
+}
+
+/// Point writes to a key-value store
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{KvWrite, OptimisticTransactionDB, Options, Result};
+///
+/// fn register(store: &impl KvWrite, id: u32, name: &[u8]) -> Result<()> {
+///     store.put(format!("user:{}", id).as_bytes(), name)
+/// }
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = OptimisticTransactionDB::open(&opts, "/tmp/my_txn_db").unwrap();
+/// let txn = db.transaction().unwrap();
+/// register(&txn, 1, b"Alice").unwrap();
+/// txn.commit().unwrap();
+/// ```
+pub trait KvWrite {
+    /// Set the value of a key, replacing any existing value
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<()>;
+
+    /// Delete a key; deleting a missing key is not an error
+    fn delete(&self, key: &[u8]) -> Result<()>;
+}
+
+impl KvRead for DB {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        DB::get(self, key)
+    }
+
+    fn contains_key(&self, key: &[u8]) -> Result<bool> {
+        DB::contains_key(self, key)
+    }
+}
+
+impl KvWrite for DB {
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        DB::put(self, key, value)
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        DB::delete(self, key)
+    }
+}
+
+impl KvRead for OptimisticTransactionDB {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        OptimisticTransactionDB::get(self, key)
+    }
+}
+
+impl KvWrite for OptimisticTransactionDB {
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        OptimisticTransactionDB::put(self, key, value)
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        OptimisticTransactionDB::delete(self, key)
+    }
+}
+
+/// Reads see the transaction's own uncommitted writes
+impl KvRead for Transaction<'_> {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Transaction::get(self, key)
+    }
+}
+
+/// Writes become visible to others once the transaction commits
+impl KvWrite for Transaction<'_> {
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        Transaction::put(self, key, value)
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        Transaction::delete(self, key)
+    }
+}
+
+/// Reads see only the batch's own writes
+impl KvRead for WriteBatchWithIndex {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        WriteBatchWithIndex::get_from_batch(self, key)
+    }
+}
+
+/// Writes are applied once the batch is written with [`DB::write_indexed`]
+impl KvWrite for WriteBatchWithIndex {
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        WriteBatchWithIndex::put(self, key, value);
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        WriteBatchWithIndex::delete(self, key);
+        Ok(())
+    }
+}
+
+/// Reads see the database as of the snapshot
+impl KvRead for Snapshot<'_> {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Snapshot::get(self, key)
+    }
+}
+
+impl KvRead for BoundColumnFamily {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        BoundColumnFamily::get(self, key)
+    }
+
+    fn contains_key(&self, key: &[u8]) -> Result<bool> {
+        BoundColumnFamily::contains_key(self, key)
+    }
+}
+
+impl KvWrite for BoundColumnFamily {
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        BoundColumnFamily::put(self, key, value)
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        BoundColumnFamily::delete(self, key)
+    }
+}
+
+/// Key-value store held in memory, implementing [`KvRead`] and [`KvWrite`]
+///
+/// Keys are kept sorted in a `BTreeMap`. Snapshots share the map until the
+/// next write, which copies it, so taking one is cheap but writing after
+/// one costs a copy of the whole store. Meant for tests of code written
+/// against the traits, not for production data.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{KvRead, KvWrite, MemKv};
+///
+/// let kv = MemKv::new();
+/// kv.put(b"key", b"old").unwrap();
+/// let snapshot = kv.snapshot();
+/// kv.put(b"key", b"new").unwrap();
+///
+/// assert_eq!(kv.get(b"key").unwrap(), Some(b"new".to_vec()));
+/// assert_eq!(snapshot.get(b"key").unwrap(), Some(b"old".to_vec()));
+/// ```
+#[derive(Debug, Default)]
+pub struct MemKv {
+    map: RwLock<Arc<BTreeMap<Vec<u8>, Vec<u8>>>>,
+}
+
+impl MemKv {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take a read-only view of the current contents
+    pub fn snapshot(&self) -> MemKvSnapshot {
+        MemKvSnapshot {
+            map: Arc::clone(
+                &self
+                    .map
+                    .read()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()),
+            ),
+        }
+    }
+
+    /// Get the number of keys
+    pub fn len(&self) -> usize {
+        self.map
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .len()
+    }
+
+    /// Check whether the store holds no keys
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Copy out all entries in key order
+    pub fn entries(&self) -> Vec<(Vec<u8>, Vec<u8>)> {
+        self.snapshot().entries()
+    }
+}
+
+impl KvRead for MemKv {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .map
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(key)
+            .cloned())
+    }
+}
+
+impl KvWrite for MemKv {
+    fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        let mut map = self
+            .map
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        Arc::make_mut(&mut map).insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        let mut map = self
+            .map
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if map.contains_key(key) {
+            Arc::make_mut(&mut map).remove(key);
+        }
+        Ok(())
+    }
+}
+
+/// Read-only view of a [`MemKv`] as of [`MemKv::snapshot`]
+#[derive(Debug, Clone)]
+pub struct MemKvSnapshot {
+    map: Arc<BTreeMap<Vec<u8>, Vec<u8>>>,
+}
+
+impl MemKvSnapshot {
+    /// Copy out all entries in key order
+    pub fn entries(&self) -> Vec<(Vec<u8>, Vec<u8>)> {
+        self.map
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+impl KvRead for MemKvSnapshot {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.map.get(key).cloned())
+    }
+}
@@ -19,13 +19,107 @@
 //! db.delete(b"key").unwrap();
 //! ```
 
+mod backup;
+mod batch;
+mod bloom;
+mod bound_cf;
+mod cache;
+mod cancel;
+mod checkpoint;
+mod checksum;
+mod chunked;
+mod compaction;
 mod db;
+mod env;
 mod error;
+#[cfg(feature = "unsafe-ffi")]
+pub mod ffi;
+#[cfg(not(feature = "unsafe-ffi"))]
 mod ffi;
+mod iter_pool;
 mod iterator;
+mod kv;
+mod manager;
+mod metadata;
+mod migrate;
+mod open_progress;
 mod options;
+mod owned_iter;
+mod partition;
+mod perf;
+mod pinned;
+mod probe;
+mod quota;
+mod raft;
+mod rate_limiter;
+mod read_amp;
+mod rename;
+mod resources;
+mod sampling;
+mod session;
+mod snapshot;
+mod sst_file_manager;
+mod sst_file_writer;
+mod statistics;
+#[cfg(feature = "async")]
+mod stream;
+mod throttle;
+mod transaction;
+mod typed;
+mod wal;
+mod write_buffer_manager;
+mod write_gate;
 
-pub use db::{ColumnFamilyHandle, DB};
-pub use error::{Error, Result};
-pub use iterator::{DBIterator, DBIteratorAdapter, Direction};
-pub use options::Options;
+pub use backup::{BackupInfo, BackupRestore, BackupSink, BackupSource, DirectorySink, HotBackup};
+pub use batch::{WriteBatch, WriteBatchOp, WriteBatchWithIndex};
+pub use bloom::BloomFilterReport;
+pub use bound_cf::BoundColumnFamily;
+pub use cache::{Cache, CacheStats, CacheUsage};
+pub use cancel::CancellationToken;
+pub use checkpoint::Checkpoint;
+pub use checksum::ChecksummedDB;
+pub use chunked::ChunkedWriter;
+pub use compaction::{CompactionAction, CompactionProgress, CompactionScheduler};
+pub use db::{ColumnFamilyDescriptor, ColumnFamilyHandle, DB, GetResult};
+pub use env::Env;
+pub use error::{Error, ErrorKind, ErrorSubcode, Result};
+pub use iter_pool::{IteratorPool, PooledIterator};
+pub use iterator::{DBIterator, DBIteratorAdapter, Direction, KeyRange, MergeIterator};
+pub use kv::{KvRead, KvWrite, MemKv, MemKvSnapshot};
+pub use manager::DbManager;
+pub use metadata::{
+    ColumnFamilyMetadata, LevelInfo, LevelMetadata, SstFileMetadata, TableProperties,
+};
+pub use migrate::{MigrationProgress, Migrator};
+pub use open_progress::OpenProgress;
+pub use options::{
+    BlockBasedOptions, BottommostLevelCompaction, CfPreset, CompactRangeOptions, CompactionStyle,
+    CompressionType, FifoCompactionOptions, FlushOptions, Options, ReadMode, ReadOptions,
+    UniversalCompactionOptions, WriteMode,
+};
+pub use owned_iter::OwnedIterator;
+pub use partition::{PartitionPeriod, Partitioner};
+pub use perf::{PerfContext, PerfLevel, PerfMetric};
+pub use pinned::PinnedSlice;
+pub use probe::{ProbeAction, ProbeReport, ProbeStatus};
+pub use quota::QuotaWriter;
+pub use raft::RaftApplier;
+pub use rate_limiter::{RateLimiter, RateLimiterMode};
+pub use read_amp::ReadAmpReport;
+pub use rename::{PrefixRename, RenameToken};
+pub use resources::SharedResources;
+pub use session::Session;
+pub use snapshot::{Snapshot, SnapshotInfo};
+pub use sst_file_manager::SstFileManager;
+pub use sst_file_writer::SstFileWriter;
+pub use statistics::{StatisticsSnapshot, Ticker};
+#[cfg(feature = "async")]
+pub use stream::DbStream;
+pub use throttle::ThrottledWriter;
+pub use transaction::{OptimisticTransactionDB, Transaction};
+#[cfg(feature = "serde")]
+pub use typed::JsonCodec;
+pub use typed::{Codec, OrderedCodec, TypedCf, TypedDb, TypedIter};
+pub use wal::WalIterator;
+pub use write_buffer_manager::WriteBufferManager;
+pub use write_gate::{BackOff, CompactionDebt, WriteGate};
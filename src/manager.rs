@@ -0,0 +1,300 @@
+//! Management of many databases under one directory
+//!
+//! Services that keep one database per tenant can have far more databases
+//! than they can keep open at once, as each open database holds file
+//! descriptors, memtables and background state. [`DbManager`] opens them on
+//! demand and closes the least recently used ones to stay within a bound.
+
+use crate::db::DB;
+use crate::error::{Error, ErrorKind, Result};
+use crate::options::Options;
+use crate::resources::SharedResources;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+/// An open database and when it was last handed out
+struct OpenDb {
+    db: Arc<DB>,
+    last_used: u64,
+}
+
+struct ManagerState {
+    options: Options,
+    open: HashMap<String, OpenDb>,
+    // Databases being opened or closed outside the lock
+    busy: HashSet<String>,
+    // Incremented on every access; orders databases by recency
+    clock: u64,
+}
+
+/// Opens and tracks the databases in the subdirectories of a root directory
+///
+/// [`DbManager::get`] returns the named database, opening (and creating, if
+/// the options allow it) `<root>/<name>` on first use. At most
+/// `max_open_dbs` databases are kept open: opening another closes the least
+/// recently used database that no caller is still holding. Databases in use
+/// are never closed from under their users, so the bound can be exceeded
+/// while more than `max_open_dbs` are in use at the same time; they are
+/// closed once released.
+///
+/// Every database is opened with a copy of the same options, so statistics,
+/// caches and rate limiters set on them are shared. Dropping the manager
+/// closes every database it holds.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DbManager, Options};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true).set_max_open_files(64);
+///
+/// // At most 100 tenant databases open at once
+/// let manager = DbManager::new("/var/lib/app/tenants", &opts, 100).unwrap();
+///
+/// let db = manager.get("tenant-42").unwrap();
+/// db.put(b"setting", b"value").unwrap();
+/// ```
+pub struct DbManager {
+    root: PathBuf,
+    max_open_dbs: usize,
+    state: Mutex<ManagerState>,
+    // Signalled when a database stops being busy
+    idle: Condvar,
+    // Dropped after the databases using them
+    resources: Option<SharedResources>,
+}
+
+impl DbManager {
+    /// Create a manager for the databases under `root`, creating the directory if needed
+    ///
+    /// `max_open_dbs` must be at least 1.
+    pub fn new<P: AsRef<Path>>(root: P, options: &Options, max_open_dbs: usize) -> Result<Self> {
+        Self::create(root.as_ref(), options.clone(), max_open_dbs, None)
+    }
+
+    /// Create a manager whose databases all use the same [`SharedResources`]
+    ///
+    /// The resources are applied to a copy of `options` (see
+    /// [`SharedResources::apply`]) and kept by the manager, so their usage
+    /// can be checked through [`DbManager::resources`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DbManager, Options, SharedResources, WriteBufferManager};
+    ///
+    /// let mut resources = SharedResources::new();
+    /// resources.set_write_buffer_manager(WriteBufferManager::new(1 << 30, true).unwrap());
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let manager = DbManager::with_resources("/var/lib/app/tenants", &opts, 100, resources).unwrap();
+    ///
+    /// let used = manager.resources().unwrap().write_buffer_manager().unwrap().memory_usage();
+    /// println!("memtables across all tenants: {} bytes", used);
+    /// ```
+    pub fn with_resources<P: AsRef<Path>>(
+        root: P,
+        options: &Options,
+        max_open_dbs: usize,
+        resources: SharedResources,
+    ) -> Result<Self> {
+        let mut options = options.clone();
+        resources.apply(&mut options);
+        Self::create(root.as_ref(), options, max_open_dbs, Some(resources))
+    }
+
+    /// Create the root directory and the manager
+    fn create(
+        root: &Path,
+        options: Options,
+        max_open_dbs: usize,
+        resources: Option<SharedResources>,
+    ) -> Result<Self> {
+        if max_open_dbs == 0 {
+            return Err(Error::with_kind(
+                ErrorKind::InvalidArgument,
+                "A manager must allow at least one open database",
+            ));
+        }
+
+        fs::create_dir_all(root).map_err(|e| {
+            Error::with_kind(
+                ErrorKind::IOError,
+                format!("Failed to create manager root {}: {}", root.display(), e),
+            )
+        })?;
+
+        Ok(DbManager {
+            root: root.to_path_buf(),
+            max_open_dbs,
+            state: Mutex::new(ManagerState {
+                options,
+                open: HashMap::new(),
+                busy: HashSet::new(),
+                clock: 0,
+            }),
+            idle: Condvar::new(),
+            resources,
+        })
+    }
+
+    /// Get the resources shared by the managed databases, if any
+    pub fn resources(&self) -> Option<&SharedResources> {
+        self.resources.as_ref()
+    }
+
+    /// Get the root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Get the named database, opening it if needed
+    ///
+    /// Names must be a single path component: empty names, `.`, `..` and
+    /// names containing a path separator are rejected. Databases are opened
+    /// and closed without holding up callers that want other databases.
+    pub fn get(&self, name: &str) -> Result<Arc<DB>> {
+        validate_name(name)?;
+
+        let mut state = self.lock();
+        // RocksDB allows only one open instance, so wait for another
+        // thread opening or closing this one
+        while state.busy.contains(name) {
+            state = self
+                .idle
+                .wait(state)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        state.clock += 1;
+        let now = state.clock;
+
+        if let Some(open) = state.open.get_mut(name) {
+            open.last_used = now;
+            return Ok(Arc::clone(&open.db));
+        }
+
+        // Make room before opening, so the bound holds while the new one opens
+        let mut evicted = Vec::new();
+        while state.open.len() + state.busy.len() >= self.max_open_dbs {
+            let idle = state
+                .open
+                .iter()
+                .filter(|(_, open)| Arc::strong_count(&open.db) == 1)
+                .min_by_key(|(_, open)| open.last_used)
+                .map(|(name, _)| name.clone());
+            let Some(idle) = idle else {
+                break;
+            };
+            let open = state.open.remove(&idle);
+            state.busy.insert(idle.clone());
+            evicted.push((idle, open));
+        }
+        state.busy.insert(name.to_string());
+        let options = state.options.clone();
+        drop(state);
+
+        let mut names = vec![name.to_string()];
+        for (idle, open) in evicted {
+            drop(open);
+            names.push(idle);
+        }
+        let result = DB::open(&options, self.root.join(name)).map(Arc::new);
+
+        let mut state = self.lock();
+        for name in &names {
+            state.busy.remove(name);
+        }
+        if let Ok(db) = &result {
+            state.open.insert(
+                name.to_string(),
+                OpenDb {
+                    db: Arc::clone(db),
+                    last_used: now,
+                },
+            );
+        }
+        drop(state);
+        self.idle.notify_all();
+        result
+    }
+
+    /// Check whether the named database is currently open
+    pub fn is_open(&self, name: &str) -> bool {
+        self.lock().open.contains_key(name)
+    }
+
+    /// Get the number of databases currently open
+    pub fn open_count(&self) -> usize {
+        self.lock().open.len()
+    }
+
+    /// Stop tracking the named database so it closes once released
+    ///
+    /// If no caller holds the database it is closed before this returns;
+    /// otherwise [`DbManager::get`] fails for this name until the last
+    /// holder releases it, as RocksDB allows only one open instance. Returns
+    /// `false` if it was not open.
+    pub fn close(&self, name: &str) -> bool {
+        // Take it out of the map first, so it is not dropped under the lock
+        let removed = self.lock().open.remove(name);
+        removed.is_some()
+    }
+
+    /// Stop tracking every database, closing those no caller holds
+    pub fn close_all(&self) {
+        let removed: Vec<OpenDb> = self.lock().open.drain().map(|(_, open)| open).collect();
+        drop(removed);
+    }
+
+    /// List the names of the databases on disk under the root, sorted
+    ///
+    /// A subdirectory counts as a database if it contains a `CURRENT` file.
+    pub fn list(&self) -> Result<Vec<String>> {
+        let list_error = |e| {
+            Error::with_kind(
+                ErrorKind::IOError,
+                format!("Failed to list {}: {}", self.root.display(), e),
+            )
+        };
+        let entries = fs::read_dir(&self.root).map_err(list_error)?;
+
+        let mut names = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(list_error)?;
+            if entry.path().join("CURRENT").is_file() {
+                names.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+
+        names.sort();
+        Ok(names)
+    }
+
+    /// Lock the manager state, recovering from a panic in another thread
+    fn lock(&self) -> MutexGuard<'_, ManagerState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Reject names that would escape the root directory or are not directory names
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || name == "."
+        || name == ".."
+        || name.contains('/')
+        || name.contains(std::path::MAIN_SEPARATOR)
+        || name.contains('\0')
+    {
+        return Err(Error::with_kind(
+            ErrorKind::InvalidArgument,
+            format!("Invalid database name: {:?}", name),
+        ));
+    }
+    Ok(())
+}
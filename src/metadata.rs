@@ -0,0 +1,302 @@
+//! The LSM tree layout of a column family
+//!
+//! [`DB::column_family_metadata`](crate::DB::column_family_metadata) lists
+//! the SST files of each level with their key ranges, which is what it takes
+//! to compact just the files behind a hotspot instead of a whole column
+//! family. [`DB::table_properties`](crate::DB::table_properties) adds the
+//! entry counts of every file, for finding the ones full of tombstones.
+
+use crate::db::{OwnedRocksDBBytes, take_c_string};
+use crate::ffi;
+use std::ffi::CStr;
+use std::fs;
+use std::os::raw::c_char;
+use std::path::Path;
+use std::slice;
+use std::time::SystemTime;
+
+/// One SST file, as described by [`LevelMetadata::files`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SstFileMetadata {
+    /// File name relative to `directory`, e.g. `000012.sst`
+    pub name: String,
+    /// Directory holding the file
+    pub directory: String,
+    /// File size in bytes
+    pub size: u64,
+    /// Smallest user key in the file
+    pub smallest_key: Vec<u8>,
+    /// Largest user key in the file
+    pub largest_key: Vec<u8>,
+}
+
+impl SstFileMetadata {
+    /// Check whether `key` falls within the file's key range
+    pub fn contains(&self, key: &[u8]) -> bool {
+        self.smallest_key.as_slice() <= key && key <= self.largest_key.as_slice()
+    }
+}
+
+/// Properties of one live SST file, from [`DB::table_properties`](crate::DB::table_properties)
+///
+/// RocksDB's C API exposes the entry counts of each file but not the raw
+/// key and value sizes; the per-level totals of those are available
+/// through [`DB::property_value`](crate::DB::property_value) with
+/// `rocksdb.aggregated-table-properties-at-level<N>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableProperties {
+    /// Name of the column family holding the file
+    pub column_family: String,
+    /// File name relative to `directory`, e.g. `000012.sst`
+    pub name: String,
+    /// Directory holding the file
+    pub directory: String,
+    /// Level of the file
+    pub level: u32,
+    /// File size in bytes
+    pub size: u64,
+    /// Smallest user key in the file
+    pub smallest_key: Vec<u8>,
+    /// Largest user key in the file
+    pub largest_key: Vec<u8>,
+    /// Smallest sequence number of the entries in the file
+    pub smallest_seqno: u64,
+    /// Largest sequence number of the entries in the file
+    pub largest_seqno: u64,
+    /// Entries in the file, deletions included
+    pub num_entries: u64,
+    /// Deletion tombstones in the file
+    pub num_deletions: u64,
+    /// When the file was written, from its modification time (SST files are
+    /// never modified), or `None` if the file system doesn't say
+    pub created: Option<SystemTime>,
+}
+
+impl TableProperties {
+    /// Get the share of the file's entries that are deletion tombstones,
+    /// between 0 and 1
+    pub fn tombstone_ratio(&self) -> f64 {
+        if self.num_entries == 0 {
+            0.0
+        } else {
+            self.num_deletions as f64 / self.num_entries as f64
+        }
+    }
+}
+
+/// One level of the LSM tree, as described by [`ColumnFamilyMetadata::levels`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevelMetadata {
+    /// Level number; 0 holds freshly flushed files with overlapping ranges
+    pub level: u32,
+    /// Total size of the level's files in bytes
+    pub size: u64,
+    /// The files in the level
+    pub files: Vec<SstFileMetadata>,
+}
+
+/// File count and size of one level, from [`DB::level_info`](crate::DB::level_info)
+///
+/// A summary of [`LevelMetadata`] without the per-file details, cheap to
+/// keep around for dashboards or for deciding when a level needs a manual
+/// compaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LevelInfo {
+    /// Level number; 0 holds freshly flushed files with overlapping ranges
+    pub level: u32,
+    /// Number of SST files in the level
+    pub files: usize,
+    /// Total size of the level's files in bytes
+    pub bytes: u64,
+}
+
+/// The files of a column family, level by level
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnFamilyMetadata {
+    /// Column family name
+    pub name: String,
+    /// Total size of the SST files in bytes
+    pub size: u64,
+    /// Total number of SST files
+    pub file_count: usize,
+    /// Every configured level, including the empty ones
+    pub levels: Vec<LevelMetadata>,
+}
+
+impl ColumnFamilyMetadata {
+    /// Read and free metadata returned by `rocksdb_get_column_family_metadata*`
+    ///
+    /// # Safety
+    /// `ptr` must be a valid metadata pointer; it is destroyed by this call.
+    pub(crate) unsafe fn from_raw(ptr: *mut ffi::rocksdb_column_family_metadata_t) -> Self {
+        unsafe {
+            let level_count = ffi::rocksdb_column_family_metadata_get_level_count(ptr);
+            let levels = (0..level_count)
+                .filter_map(|i| {
+                    let level = ffi::rocksdb_column_family_metadata_get_level_metadata(ptr, i);
+                    (!level.is_null()).then(|| read_level(level))
+                })
+                .collect();
+
+            let metadata = ColumnFamilyMetadata {
+                name: take_c_string(ffi::rocksdb_column_family_metadata_get_name(ptr))
+                    .unwrap_or_default(),
+                size: ffi::rocksdb_column_family_metadata_get_size(ptr),
+                file_count: ffi::rocksdb_column_family_metadata_get_file_count(ptr),
+                levels,
+            };
+            ffi::rocksdb_column_family_metadata_destroy(ptr);
+            metadata
+        }
+    }
+
+    /// Get the file count and size of every level, in level order
+    pub fn level_info(&self) -> Vec<LevelInfo> {
+        self.levels
+            .iter()
+            .map(|level| LevelInfo {
+                level: level.level,
+                files: level.files.len(),
+                bytes: level.size,
+            })
+            .collect()
+    }
+
+    /// Get the files in level 0
+    pub fn level0_files(&self) -> &[SstFileMetadata] {
+        self.levels
+            .iter()
+            .find(|level| level.level == 0)
+            .map_or(&[], |level| level.files.as_slice())
+    }
+
+    /// Get every file, in any level, whose key range contains `key`
+    pub fn files_containing<'m>(
+        &'m self,
+        key: &'m [u8],
+    ) -> impl Iterator<Item = &'m SstFileMetadata> + 'm {
+        self.levels
+            .iter()
+            .flat_map(|level| level.files.iter())
+            .filter(move |file| file.contains(key))
+    }
+}
+
+/// Read and free the metadata of one level
+unsafe fn read_level(ptr: *mut ffi::rocksdb_level_metadata_t) -> LevelMetadata {
+    unsafe {
+        let file_count = ffi::rocksdb_level_metadata_get_file_count(ptr);
+        let files = (0..file_count)
+            .filter_map(|i| {
+                let file = ffi::rocksdb_level_metadata_get_sst_file_metadata(ptr, i);
+                (!file.is_null()).then(|| read_file(file))
+            })
+            .collect();
+
+        let level = LevelMetadata {
+            level: ffi::rocksdb_level_metadata_get_level(ptr) as u32,
+            size: ffi::rocksdb_level_metadata_get_size(ptr),
+            files,
+        };
+        ffi::rocksdb_level_metadata_destroy(ptr);
+        level
+    }
+}
+
+/// Read and free the metadata of one SST file
+unsafe fn read_file(ptr: *mut ffi::rocksdb_sst_file_metadata_t) -> SstFileMetadata {
+    unsafe {
+        let mut smallest_len: usize = 0;
+        let smallest = ffi::rocksdb_sst_file_metadata_get_smallestkey(ptr, &mut smallest_len);
+        let mut largest_len: usize = 0;
+        let largest = ffi::rocksdb_sst_file_metadata_get_largestkey(ptr, &mut largest_len);
+
+        let file = SstFileMetadata {
+            name: take_c_string(ffi::rocksdb_sst_file_metadata_get_relative_filename(ptr))
+                .unwrap_or_default(),
+            directory: take_c_string(ffi::rocksdb_sst_file_metadata_get_directory(ptr))
+                .unwrap_or_default(),
+            size: ffi::rocksdb_sst_file_metadata_get_size(ptr),
+            smallest_key: OwnedRocksDBBytes::from_raw(smallest, smallest_len)
+                .map(|key| key.to_vec())
+                .unwrap_or_default(),
+            largest_key: OwnedRocksDBBytes::from_raw(largest, largest_len)
+                .map(|key| key.to_vec())
+                .unwrap_or_default(),
+        };
+        ffi::rocksdb_sst_file_metadata_destroy(ptr);
+        file
+    }
+}
+
+/// Read and free the file list returned by `rocksdb_livefiles`
+///
+/// # Safety
+/// `ptr` must be a valid live files pointer; it is destroyed by this call.
+pub(crate) unsafe fn read_live_files(ptr: *const ffi::rocksdb_livefiles_t) -> Vec<TableProperties> {
+    unsafe {
+        let string = |ptr: *const c_char| {
+            if ptr.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(ptr).to_string_lossy().into_owned()
+            }
+        };
+        // Keys are borrowed from the list, so copy them
+        let bytes = |ptr: *const c_char, len: usize| {
+            if ptr.is_null() {
+                Vec::new()
+            } else {
+                slice::from_raw_parts(ptr as *const u8, len).to_vec()
+            }
+        };
+
+        let files = (0..ffi::rocksdb_livefiles_count(ptr))
+            .map(|i| {
+                let mut smallest_len: usize = 0;
+                let smallest = ffi::rocksdb_livefiles_smallestkey(ptr, i, &mut smallest_len);
+                let mut largest_len: usize = 0;
+                let largest = ffi::rocksdb_livefiles_largestkey(ptr, i, &mut largest_len);
+
+                // RocksDB reports names with a leading separator
+                let name = string(ffi::rocksdb_livefiles_name(ptr, i))
+                    .trim_start_matches('/')
+                    .to_string();
+                let directory = string(ffi::rocksdb_livefiles_directory(ptr, i));
+                let created = fs::metadata(Path::new(&directory).join(&name))
+                    .and_then(|metadata| metadata.modified())
+                    .ok();
+                TableProperties {
+                    column_family: string(ffi::rocksdb_livefiles_column_family_name(ptr, i)),
+                    name,
+                    directory,
+                    level: ffi::rocksdb_livefiles_level(ptr, i) as u32,
+                    size: ffi::rocksdb_livefiles_size(ptr, i) as u64,
+                    smallest_key: bytes(smallest, smallest_len),
+                    largest_key: bytes(largest, largest_len),
+                    smallest_seqno: ffi::rocksdb_livefiles_smallest_seqno(ptr, i),
+                    largest_seqno: ffi::rocksdb_livefiles_largest_seqno(ptr, i),
+                    num_entries: ffi::rocksdb_livefiles_entries(ptr, i),
+                    num_deletions: ffi::rocksdb_livefiles_deletions(ptr, i),
+                    created,
+                }
+            })
+            .collect();
+        ffi::rocksdb_livefiles_destroy(ptr);
+        files
+    }
+}
+
+/// Get the smallest range covering every file in `files`, or `None` if there are none
+pub(crate) fn covering_range<'m>(
+    files: impl IntoIterator<Item = &'m SstFileMetadata>,
+) -> Option<(&'m [u8], &'m [u8])> {
+    files.into_iter().fold(None, |range, file| {
+        let (start, end) =
+            range.unwrap_or((file.smallest_key.as_slice(), file.largest_key.as_slice()));
+        Some((
+            start.min(file.smallest_key.as_slice()),
+            end.max(file.largest_key.as_slice()),
+        ))
+    })
+}
@@ -0,0 +1,287 @@
+//! Lazy value format migrations
+//!
+//! A [`Migrator`] upgrades values from an old format to a new one without a
+//! stop-the-world rewrite: compactions rewrite the values they come across,
+//! and reads upgrade values that haven't been compacted yet. Old and new
+//! formats must be distinguishable from the value bytes alone (a version
+//! byte is the usual way).
+
+use crate::db::{ColumnFamilyHandle, DB};
+use crate::error::{Error, Result};
+use crate::ffi;
+use libc::{c_char, c_int, c_void, size_t};
+use std::ffi::CString;
+use std::slice;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+type UpgradeFn = dyn Fn(&[u8]) -> Option<Vec<u8>> + Send + Sync;
+
+/// State shared by a migrator and the compaction filters it creates
+struct MigratorInner {
+    name: CString,
+    upgrade: Box<UpgradeFn>,
+    migrated: AtomicU64,
+    unchanged: AtomicU64,
+    failed: AtomicU64,
+    upgraded_on_read: AtomicU64,
+}
+
+impl MigratorInner {
+    /// Upgrade a value, counting the outcome (`None` if it is already current)
+    ///
+    /// Only compactions pass `unchanged`: values read many times would
+    /// otherwise inflate it.
+    fn upgrade(
+        &self,
+        value: &[u8],
+        upgraded: &AtomicU64,
+        unchanged: Option<&AtomicU64>,
+    ) -> Option<Vec<u8>> {
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| (self.upgrade)(value))) {
+            Ok(Some(value)) => {
+                upgraded.fetch_add(1, Ordering::Relaxed);
+                Some(value)
+            }
+            Ok(None) => {
+                if let Some(unchanged) = unchanged {
+                    unchanged.fetch_add(1, Ordering::Relaxed);
+                }
+                None
+            }
+            // A panicking upgrade leaves the value as it is
+            Err(_) => {
+                self.failed.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+}
+
+/// How far a [`Migrator`] has got
+///
+/// The counters cover the lifetime of the migrator, not of the database, so
+/// they restart from zero when the process does. Compactions may see the same
+/// key more than once, so `migrated + unchanged` can exceed the number of keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationProgress {
+    /// Values rewritten to the new format by compactions
+    pub migrated: u64,
+    /// Values compactions found already in the new format
+    pub unchanged: u64,
+    /// Values whose upgrade panicked; they are kept in the old format
+    pub failed: u64,
+    /// Values upgraded in memory by reads (not written back)
+    pub upgraded_on_read: u64,
+    /// RocksDB's estimate of the number of keys (`rocksdb.estimate-num-keys`)
+    pub estimated_keys: u64,
+}
+
+/// A value format upgrade applied during compaction and on read
+///
+/// The upgrade function returns the new encoding of an old-format value, or
+/// `None` for values that are already current; it must accept its own output
+/// and return `None` for it. Install the migrator on the options of every
+/// column family holding migrated values with
+/// [`Options::set_migrator`](crate::Options::set_migrator), and read through
+/// [`Migrator::get`] so values not yet compacted come back upgraded.
+///
+/// Compactions happen on their own as data is written; to finish a migration
+/// sooner, force a full compaction with [`DB::compact_range_opt`] and
+/// [`BottommostLevelCompaction::Force`](crate::BottommostLevelCompaction::Force).
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Migrator, Options};
+///
+/// // v1 values are raw strings, v2 values carry a leading version byte
+/// let migrator = Migrator::new("users-v1-to-v2", |value| {
+///     if value.first() == Some(&2) {
+///         return None;
+///     }
+///     let mut v2 = vec![2];
+///     v2.extend_from_slice(value);
+///     Some(v2)
+/// })
+/// .unwrap();
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true).set_migrator(&migrator);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let value = migrator.get(&db, b"user:1").unwrap();
+///
+/// let progress = migrator.progress(&db).unwrap();
+/// println!("{} of ~{} migrated", progress.migrated, progress.estimated_keys);
+/// ```
+#[derive(Clone)]
+pub struct Migrator {
+    inner: Arc<MigratorInner>,
+}
+
+impl Migrator {
+    /// Create a migrator
+    ///
+    /// * `name` - Identifies the migration in RocksDB's logs
+    /// * `upgrade` - Converts an old-format value, or returns `None` if it is current
+    pub fn new<F>(name: &str, upgrade: F) -> Result<Self>
+    where
+        F: Fn(&[u8]) -> Option<Vec<u8>> + Send + Sync + 'static,
+    {
+        let name =
+            CString::new(name).map_err(|_| Error::new("Migrator name contains null byte"))?;
+
+        Ok(Migrator {
+            inner: Arc::new(MigratorInner {
+                name,
+                upgrade: Box::new(upgrade),
+                migrated: AtomicU64::new(0),
+                unchanged: AtomicU64::new(0),
+                failed: AtomicU64::new(0),
+                upgraded_on_read: AtomicU64::new(0),
+            }),
+        })
+    }
+
+    /// Upgrade a value read from the database, if it is in the old format
+    pub fn upgrade(&self, value: Vec<u8>) -> Vec<u8> {
+        self.inner
+            .upgrade(&value, &self.inner.upgraded_on_read, None)
+            .unwrap_or(value)
+    }
+
+    /// Read a value from the default column family, upgrading it if needed
+    pub fn get(&self, db: &DB, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(db.get(key)?.map(|value| self.upgrade(value)))
+    }
+
+    /// Read a value from a column family, upgrading it if needed
+    pub fn get_cf(
+        &self,
+        db: &DB,
+        cf_handle: &ColumnFamilyHandle,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        Ok(db.get_cf(cf_handle, key)?.map(|value| self.upgrade(value)))
+    }
+
+    /// Get the migration counters along with the key estimate of `db`
+    pub fn progress(&self, db: &DB) -> Result<MigrationProgress> {
+        let estimated_keys = db
+            .property_int_value("rocksdb.estimate-num-keys")?
+            .unwrap_or(0);
+
+        Ok(MigrationProgress {
+            migrated: self.inner.migrated.load(Ordering::Relaxed),
+            unchanged: self.inner.unchanged.load(Ordering::Relaxed),
+            failed: self.inner.failed.load(Ordering::Relaxed),
+            upgraded_on_read: self.inner.upgraded_on_read.load(Ordering::Relaxed),
+            estimated_keys,
+        })
+    }
+
+    /// Create a compaction filter factory running this migration
+    ///
+    /// The returned factory owns a reference to the migrator's state and is
+    /// meant to be handed to `rocksdb_options_set_compaction_filter_factory`,
+    /// which takes ownership of it.
+    pub(crate) fn create_factory(&self) -> *mut ffi::rocksdb_compactionfilterfactory_t {
+        let state = Box::into_raw(Box::new(self.inner.clone()));
+
+        unsafe {
+            ffi::rocksdb_compactionfilterfactory_create(
+                state as *mut c_void,
+                destroy_factory_state,
+                create_filter,
+                migrator_name,
+            )
+        }
+    }
+}
+
+/// State of one compaction filter, used by a single compaction at a time
+struct FilterState {
+    inner: Arc<MigratorInner>,
+    // RocksDB copies the upgraded value out of this buffer after each call
+    buffer: Vec<u8>,
+}
+
+unsafe extern "C" fn destroy_factory_state(state: *mut c_void) {
+    unsafe {
+        drop(Box::from_raw(state as *mut Arc<MigratorInner>));
+    }
+}
+
+unsafe extern "C" fn destroy_filter_state(state: *mut c_void) {
+    unsafe {
+        drop(Box::from_raw(state as *mut FilterState));
+    }
+}
+
+unsafe extern "C" fn migrator_name(state: *mut c_void) -> *const c_char {
+    unsafe {
+        let inner = &*(state as *const Arc<MigratorInner>);
+        inner.name.as_ptr()
+    }
+}
+
+unsafe extern "C" fn filter_name(state: *mut c_void) -> *const c_char {
+    unsafe {
+        let filter = &*(state as *const FilterState);
+        filter.inner.name.as_ptr()
+    }
+}
+
+unsafe extern "C" fn create_filter(
+    state: *mut c_void,
+    _context: *mut ffi::rocksdb_compactionfiltercontext_t,
+) -> *mut ffi::rocksdb_compactionfilter_t {
+    unsafe {
+        let inner = Arc::clone(&*(state as *const Arc<MigratorInner>));
+        let filter_state = Box::into_raw(Box::new(FilterState {
+            inner,
+            buffer: Vec::new(),
+        }));
+
+        ffi::rocksdb_compactionfilter_create(
+            filter_state as *mut c_void,
+            destroy_filter_state,
+            filter_value,
+            filter_name,
+        )
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "C" fn filter_value(
+    state: *mut c_void,
+    _level: c_int,
+    _key: *const c_char,
+    _key_length: size_t,
+    existing_value: *const c_char,
+    value_length: size_t,
+    new_value: *mut *mut c_char,
+    new_value_length: *mut size_t,
+    value_changed: *mut u8,
+) -> u8 {
+    unsafe {
+        let filter = &mut *(state as *mut FilterState);
+        let value = if value_length == 0 {
+            &[][..]
+        } else {
+            slice::from_raw_parts(existing_value as *const u8, value_length)
+        };
+
+        let inner = &filter.inner;
+        if let Some(upgraded) = inner.upgrade(value, &inner.migrated, Some(&inner.unchanged)) {
+            filter.buffer = upgraded;
+            *new_value = filter.buffer.as_mut_ptr() as *mut c_char;
+            *new_value_length = filter.buffer.len();
+            *value_changed = 1;
+        }
+    }
+
+    // Never remove the entry
+    0
+}
@@ -0,0 +1,387 @@
+//! Progress reports while a database is opened
+//!
+//! Opening a large database can take minutes: every table file is opened and
+//! every WAL written since the last flush is replayed into memtables. RocksDB
+//! has no progress API, but it logs each step of recovery. With a callback
+//! set by [`Options::set_open_progress`](crate::Options::set_open_progress),
+//! those log messages are routed through this module, which turns them into
+//! [`OpenProgress`] events and still writes them to the info log, honoring
+//! the options RocksDB's own logger would: the log level, the log
+//! directory, and the size and age limits at which a new file is started.
+
+use crate::ffi;
+use crate::options::{InfoLogSettings, Options};
+use libc::{c_char, c_uint, c_void, size_t};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::slice;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub(crate) type OpenProgressFn = dyn Fn(&OpenProgress) + Send + Sync;
+
+/// RocksDB's `InfoLogLevel::INFO_LEVEL`, the level recovery steps are logged at
+const INFO_LEVEL: i32 = 1;
+
+/// RocksDB's `InfoLogLevel::WARN_LEVEL`; messages at or above it are flushed at once
+const WARN_LEVEL: u32 = 2;
+
+/// Longest time a message stays buffered before the next one flushes it,
+/// as in RocksDB's own logger
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A step of opening a database, reported to the callback set with
+/// [`Options::set_open_progress`](crate::Options::set_open_progress)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OpenProgress {
+    /// Opening has started; the counts describe the work recovery has ahead of it
+    Started {
+        /// Table files in the database directory, each opened during recovery
+        table_files: usize,
+        /// WAL files that may have to be replayed
+        wal_files: usize,
+        /// Total size of those WAL files
+        wal_bytes: u64,
+    },
+    /// The MANIFEST has been read and the table files it lists opened
+    ManifestRecovered,
+    /// Replay of one WAL file has started
+    ReplayingWal {
+        /// The number in the WAL's file name
+        number: u64,
+        /// Bytes of WAL replayed before this file
+        bytes_replayed: u64,
+        /// Total size of the WAL files (as in [`OpenProgress::Started`])
+        wal_bytes: u64,
+    },
+    /// The database is open
+    Finished {
+        /// Time taken since [`OpenProgress::Started`]
+        elapsed: Duration,
+    },
+}
+
+/// The info log the progress logger writes in place of RocksDB's
+struct LogFile {
+    dir: PathBuf,
+    // "LOG", or the database's flattened path plus "_LOG" in a shared log directory
+    name: String,
+    settings: InfoLogSettings,
+    // Not opened yet: the database directory may not exist until RocksDB creates it
+    current: Option<CurrentLog>,
+}
+
+struct CurrentLog {
+    file: BufWriter<File>,
+    size: u64,
+    opened: Instant,
+    flushed: Instant,
+}
+
+impl LogFile {
+    fn new(options: &Options, db_path: &Path) -> Self {
+        let (dir, name) = match options.db_log_dir() {
+            Some(log_dir) => {
+                // RocksDB creates the log directory rather than failing the open
+                let _ = fs::create_dir_all(log_dir);
+                let absolute = std::path::absolute(db_path).unwrap_or_else(|_| db_path.into());
+                (log_dir.to_path_buf(), shared_log_name(&absolute))
+            }
+            None => (db_path.to_path_buf(), "LOG".to_string()),
+        };
+        LogFile {
+            dir,
+            name,
+            settings: options.info_log_settings(),
+            current: None,
+        }
+    }
+
+    fn write(&mut self, level: u32, msg: &str) {
+        if (level as i32) < self.settings.level {
+            return;
+        }
+        if self
+            .current
+            .as_ref()
+            .is_none_or(|current| self.due_to_roll(current))
+        {
+            // Close the old file before it is renamed
+            self.current = None;
+            self.current = self.start_file();
+        }
+        let Some(current) = &mut self.current else {
+            return;
+        };
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let line = format!(
+            "{}.{:06} {}\n",
+            now.as_secs(),
+            now.subsec_micros(),
+            msg.trim_end()
+        );
+        if current.file.write_all(line.as_bytes()).is_ok() {
+            current.size += line.len() as u64;
+        }
+        if level >= WARN_LEVEL || current.flushed.elapsed() >= FLUSH_INTERVAL {
+            let _ = current.file.flush();
+            current.flushed = Instant::now();
+        }
+    }
+
+    /// Check whether the current file has reached the size or age limit
+    fn due_to_roll(&self, current: &CurrentLog) -> bool {
+        let settings = &self.settings;
+        (settings.max_size > 0 && current.size >= settings.max_size)
+            || (settings.time_to_roll_secs > 0
+                && current.opened.elapsed() >= Duration::from_secs(settings.time_to_roll_secs))
+    }
+
+    /// Move the previous log aside the way RocksDB does, then start a new one
+    fn start_file(&self) -> Option<CurrentLog> {
+        if !self.dir.is_dir() {
+            return None;
+        }
+
+        let path = self.dir.join(&self.name);
+        if path.exists() {
+            let micros = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_micros();
+            let _ = fs::rename(
+                &path,
+                self.dir.join(format!("{}.old.{}", self.name, micros)),
+            );
+            self.trim_old_files();
+        }
+        let now = Instant::now();
+        File::create(path).ok().map(|file| CurrentLog {
+            file: BufWriter::new(file),
+            size: 0,
+            opened: now,
+            flushed: now,
+        })
+    }
+
+    /// Delete the oldest logs beyond the number kept, counting the one about to start
+    fn trim_old_files(&self) {
+        let prefix = format!("{}.old.", self.name);
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut old: Vec<(u128, PathBuf)> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let micros = name.strip_prefix(&prefix)?.parse().ok()?;
+                Some((micros, entry.path()))
+            })
+            .collect();
+        old.sort();
+        let keep = self.settings.keep_files.saturating_sub(1);
+        for (_, path) in old.iter().take(old.len().saturating_sub(keep)) {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// RocksDB's name for the log of the database at `path` in a shared log directory
+///
+/// Every character other than ASCII letters, digits, `-`, `.` and `_` becomes
+/// `_`, except a leading one, which is dropped (`/data/db` -> `data_db_LOG`).
+fn shared_log_name(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    let mut name: String = path
+        .chars()
+        .enumerate()
+        .filter_map(|(i, c)| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '.' | '_' => Some(c),
+            _ if i > 0 => Some('_'),
+            _ => None,
+        })
+        .take(255)
+        .collect();
+    name.push_str("_LOG");
+    name
+}
+
+/// State behind the RocksDB callback logger
+///
+/// Kept alive by every [`Options`] the logger is set on, since RocksDB keeps
+/// calling it for as long as the database is open.
+pub(crate) struct ProgressLogger {
+    callback: Arc<OpenProgressFn>,
+    // Cleared once the open call returns; later messages are only logged
+    opening: AtomicBool,
+    // WAL number -> bytes of the WALs before it
+    wal_offsets: HashMap<u64, u64>,
+    wal_bytes: u64,
+    log: Mutex<LogFile>,
+}
+
+impl ProgressLogger {
+    fn report(&self, progress: &OpenProgress) {
+        // A panicking callback must not unwind into RocksDB
+        let _ =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| (self.callback)(progress)));
+    }
+
+    /// Handle one message logged by RocksDB
+    fn log(&self, level: u32, msg: &str) {
+        if self.opening.load(Ordering::Acquire) {
+            if msg.contains("Recovered from manifest file") {
+                self.report(&OpenProgress::ManifestRecovered);
+            } else if let Some(number) = wal_being_recovered(msg) {
+                self.report(&OpenProgress::ReplayingWal {
+                    number,
+                    bytes_replayed: self.wal_offsets.get(&number).copied().unwrap_or(0),
+                    wal_bytes: self.wal_bytes,
+                });
+            }
+        }
+
+        self.log
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .write(level, msg);
+    }
+}
+
+/// Parse the WAL number out of RocksDB's "Recovering log #N mode M" message
+fn wal_being_recovered(msg: &str) -> Option<u64> {
+    let rest = &msg[msg.find("Recovering log #")? + "Recovering log #".len()..];
+    let digits = rest.split(|c: char| !c.is_ascii_digit()).next()?;
+    digits.parse().ok()
+}
+
+unsafe extern "C" fn log_callback(
+    priv_: *mut c_void,
+    level: c_uint,
+    msg: *mut c_char,
+    len: size_t,
+) {
+    let logger = unsafe { &*(priv_ as *const ProgressLogger) };
+    let msg = unsafe { slice::from_raw_parts(msg as *const u8, len) };
+    logger.log(level, &String::from_utf8_lossy(msg));
+}
+
+/// Reports the progress of one open call
+///
+/// Without a callback on the options it does nothing and the options are
+/// used as they are.
+pub(crate) struct OpenReporter {
+    options: Option<Options>,
+    logger: Option<Arc<ProgressLogger>>,
+    started: Instant,
+}
+
+impl OpenReporter {
+    /// Report [`OpenProgress::Started`] for the database at `db_path`
+    ///
+    /// `log_path` is the directory RocksDB would write the info log to
+    /// without a log directory set: `db_path`, except for a secondary,
+    /// which logs to its own directory.
+    pub(crate) fn start(options: &Options, db_path: &Path, log_path: &Path) -> Self {
+        let started = Instant::now();
+        let Some(callback) = options.open_progress() else {
+            return OpenReporter {
+                options: None,
+                logger: None,
+                started,
+            };
+        };
+
+        let file_names = |dir: &Path| -> Vec<fs::DirEntry> {
+            fs::read_dir(dir)
+                .map(|entries| entries.filter_map(|entry| entry.ok()).collect())
+                .unwrap_or_default()
+        };
+        let table_files = file_names(db_path)
+            .iter()
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".sst"))
+            .count();
+        let mut wals: Vec<(u64, u64)> = file_names(options.wal_dir().unwrap_or(db_path))
+            .iter()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let number = name.strip_suffix(".log")?.parse().ok()?;
+                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                Some((number, size))
+            })
+            .collect();
+        // WALs are replayed oldest first
+        wals.sort();
+
+        let mut wal_offsets = HashMap::new();
+        let mut wal_bytes = 0;
+        for (number, size) in &wals {
+            wal_offsets.insert(*number, wal_bytes);
+            wal_bytes += size;
+        }
+
+        let logger = Arc::new(ProgressLogger {
+            callback,
+            opening: AtomicBool::new(true),
+            wal_offsets,
+            wal_bytes,
+            log: Mutex::new(LogFile::new(options, log_path)),
+        });
+        logger.report(&OpenProgress::Started {
+            table_files,
+            wal_files: wals.len(),
+            wal_bytes,
+        });
+
+        let mut reporting_options = options.clone();
+        unsafe {
+            // Recovery steps are logged at INFO, so those messages are
+            // received even when the log file only takes warnings
+            let c_logger = ffi::rocksdb_logger_create_callback_logger(
+                options.info_log_settings().level.min(INFO_LEVEL),
+                log_callback,
+                Arc::as_ptr(&logger) as *mut c_void,
+            );
+            reporting_options.set_progress_logger(c_logger, logger.clone());
+            // The options keep their own reference to the logger
+            ffi::rocksdb_logger_destroy(c_logger);
+        }
+
+        OpenReporter {
+            options: Some(reporting_options),
+            logger: Some(logger),
+            started,
+        }
+    }
+
+    /// Get the options to open the database with: `options` with the logger set
+    pub(crate) fn options<'o>(&'o self, options: &'o Options) -> &'o Options {
+        self.options.as_ref().unwrap_or(options)
+    }
+
+    /// Report [`OpenProgress::Finished`]
+    pub(crate) fn finish(&self) {
+        if let Some(logger) = &self.logger {
+            logger.opening.store(false, Ordering::Release);
+            logger.report(&OpenProgress::Finished {
+                elapsed: self.started.elapsed(),
+            });
+        }
+    }
+}
+
+impl Drop for OpenReporter {
+    fn drop(&mut self) {
+        // A failed open reports nothing more
+        if let Some(logger) = &self.logger {
+            logger.opening.store(false, Ordering::Release);
+        }
+    }
+}
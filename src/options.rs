@@ -1,61 +1,2202 @@
 //! Options for configuring RocksDB
 
+use crate::cache::Cache;
+use crate::db::{ColumnFamilyDescriptor, take_c_string};
+use crate::env::Env;
+use crate::error::{Error, ErrorKind, Result};
 use crate::ffi;
-use std::ptr::NonNull;
+use crate::migrate::Migrator;
+use crate::open_progress::{OpenProgress, OpenProgressFn, ProgressLogger};
+use crate::rate_limiter::RateLimiter;
+use crate::snapshot::SnapshotInner;
+use crate::sst_file_manager::SstFileManager;
+use crate::statistics::{StatisticsSnapshot, Ticker};
+use crate::write_buffer_manager::WriteBufferManager;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::fs;
+use std::io;
+use std::os::raw::{c_char, c_int, c_void};
+use std::path::{Path, PathBuf};
+use std::ptr::{self, NonNull};
+use std::sync::Arc;
+
+const MB: u64 = 1024 * 1024;
+
+/// Compression algorithm applied to SST data blocks
+///
+/// A compression library must be linked into `librocksdb.a` for its type to be
+/// usable; opening a database with an unavailable type fails. The default
+/// size-optimized build ships without any compression libraries (see
+/// `BUILD_ROCKSDB.md` for rebuilding with them).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionType {
+    /// No compression
+    None,
+    /// Snappy compression
+    Snappy,
+    /// Zlib compression
+    Zlib,
+    /// Bzip2 compression
+    Bz2,
+    /// LZ4 compression
+    Lz4,
+    /// LZ4 high-compression mode
+    Lz4hc,
+    /// Zstandard compression (supports dictionary compression)
+    Zstd,
+}
+
+impl CompressionType {
+    /// Get the value used by the RocksDB C API
+    fn as_raw(self) -> c_int {
+        match self {
+            CompressionType::None => 0,
+            CompressionType::Snappy => 1,
+            CompressionType::Zlib => 2,
+            CompressionType::Bz2 => 3,
+            CompressionType::Lz4 => 4,
+            CompressionType::Lz4hc => 5,
+            CompressionType::Zstd => 7,
+        }
+    }
+
+    /// Convert a value from the RocksDB C API, if it is a type listed here
+    fn from_raw(value: c_int) -> Option<Self> {
+        match value {
+            0 => Some(CompressionType::None),
+            1 => Some(CompressionType::Snappy),
+            2 => Some(CompressionType::Zlib),
+            3 => Some(CompressionType::Bz2),
+            4 => Some(CompressionType::Lz4),
+            5 => Some(CompressionType::Lz4hc),
+            7 => Some(CompressionType::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// How SST files are merged as they accumulate
+///
+/// Set with [`Options::set_compaction_style`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CompactionStyle {
+    /// Sorted levels of growing size, each merged into the next (default)
+    ///
+    /// Keeps space and read amplification low at the cost of rewriting
+    /// data once per level.
+    #[default]
+    Level,
+    /// Sorted runs of similar size merged together, tuned with
+    /// [`UniversalCompactionOptions`]
+    ///
+    /// Rewrites data fewer times than [`CompactionStyle::Level`], suiting
+    /// write-heavy workloads, but a full merge can temporarily need up to
+    /// twice the space of the data.
+    Universal,
+    /// Files kept in creation order and the oldest deleted once the total
+    /// size exceeds a budget or files outlive a TTL, tuned with
+    /// [`FifoCompactionOptions`] and [`Options::set_ttl`]
+    ///
+    /// Data is never merged, so overwritten and deleted keys only go away
+    /// with the file holding them. Suits logs and caches where losing the
+    /// oldest entries is the point.
+    Fifo,
+}
+
+impl CompactionStyle {
+    /// Get the value used by the RocksDB C API
+    fn as_raw(self) -> c_int {
+        match self {
+            CompactionStyle::Level => 0,
+            CompactionStyle::Universal => 1,
+            CompactionStyle::Fifo => 2,
+        }
+    }
+}
+
+/// A named bundle of column family settings for a common workload
+///
+/// Applied with [`Options::apply_preset`]. The presets give reasonable
+/// defaults for teams without a RocksDB expert at hand; they are not a
+/// substitute for tuning a hot column family against real traffic.
+///
+/// All presets enable [`CompressionType::Lz4`] (and `TimeSeries` also
+/// [`CompressionType::Zstd`] for the bottommost level), which must be linked
+/// into `librocksdb.a`. With a build lacking them, override the compression
+/// after applying the preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CfPreset {
+    /// Random `get`s of individual keys
+    ///
+    /// 10 bits/key bloom filters cached alongside the index, hash indexes
+    /// inside data blocks, and no filters on the last level (lookups are
+    /// expected to mostly find their key).
+    PointLookup,
+    /// Sustained high write rates, such as ingestion or logs
+    ///
+    /// Larger and more memtables merged before flushing, and more L0 files
+    /// tolerated before compaction and write stalls kick in.
+    WriteHeavy,
+    /// Large values (4 KiB and up), such as documents or images
+    ///
+    /// Values are stored in separate blob files so compaction only rewrites
+    /// keys and small values; blob garbage collection reclaims overwritten
+    /// values.
+    Blob,
+    /// Append-mostly keys scanned in order, such as metrics or events
+    ///
+    /// Larger blocks for sequential reads, cheaper compression above a
+    /// stronger bottommost level, and dynamic level sizing to limit space
+    /// amplification.
+    TimeSeries,
+}
 
 /// Options for opening a RocksDB database
 #[must_use = "Options must be used to open a database"]
 pub struct Options {
     inner: NonNull<ffi::rocksdb_options_t>,
+    // RocksDB only stores a raw pointer to the Env, so keep it alive here
+    env: Option<Env>,
+    // Kept so databases opened with these options can report its usage
+    row_cache: Option<Cache>,
+    limits: SizeLimits,
+    // Length of the fixed prefix extractor, if one is set
+    prefix_len: Option<usize>,
+    // Whether the table factory's filter holds prefixes only
+    prefix_only_filter: bool,
+    // Directories set with set_wal_dir and set_db_log_dir, which RocksDB has no getters for
+    wal_dir: Option<PathBuf>,
+    db_log_dir: Option<PathBuf>,
+    // Failure to apply table options the C API has no setter for, reported by validate
+    table_options_error: Option<Error>,
+    open_progress: Option<Arc<OpenProgressFn>>,
+    // RocksDB calls the logger through a raw pointer to this state
+    progress_logger: Option<Arc<ProgressLogger>>,
+}
+
+/// Info log settings honored by the open progress logger
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct InfoLogSettings {
+    /// RocksDB's `InfoLogLevel`: messages below it are not written
+    pub(crate) level: i32,
+    /// Size at which a new log file is started (0: no limit)
+    pub(crate) max_size: u64,
+    /// Age in seconds at which a new log file is started (0: no limit)
+    pub(crate) time_to_roll_secs: u64,
+    /// Log files kept, the current one included
+    pub(crate) keep_files: usize,
+}
+
+/// Key and value size limits enforced by the wrappers before calling RocksDB
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct SizeLimits {
+    max_key_size: Option<usize>,
+    max_value_size: Option<usize>,
+}
+
+impl SizeLimits {
+    /// Check whether any limit is set
+    pub(crate) fn is_set(&self) -> bool {
+        self.max_key_size.is_some() || self.max_value_size.is_some()
+    }
+
+    /// Reject a key longer than the key limit
+    pub(crate) fn check_key(&self, key: &[u8]) -> Result<()> {
+        match self.max_key_size {
+            Some(max) if key.len() > max => Err(Error::with_kind(
+                ErrorKind::InvalidArgument,
+                format!("Key size {} exceeds the limit of {} bytes", key.len(), max),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reject a value longer than the value limit
+    pub(crate) fn check_value(&self, value: &[u8]) -> Result<()> {
+        match self.max_value_size {
+            Some(max) if value.len() > max => Err(Error::with_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Value size {} exceeds the limit of {} bytes",
+                    value.len(),
+                    max
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check a key-value pair against both limits
+    pub(crate) fn check(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.check_key(key)?;
+        self.check_value(value)
+    }
+}
+
+impl Options {
+    /// Create a new Options instance with default settings
+    pub fn new() -> Self {
+        unsafe { Self::from_raw(ffi::rocksdb_options_create()) }
+    }
+
+    /// Take ownership of options created through the C API
+    unsafe fn from_raw(ptr: *mut ffi::rocksdb_options_t) -> Self {
+        Options {
+            inner: NonNull::new(ptr).expect("Failed to create options"),
+            env: None,
+            row_cache: None,
+            limits: SizeLimits::default(),
+            prefix_len: None,
+            prefix_only_filter: false,
+            wal_dir: None,
+            db_log_dir: None,
+            table_options_error: None,
+            open_progress: None,
+            progress_logger: None,
+        }
+    }
+
+    /// Set whether to create the database if it doesn't exist
+    pub fn create_if_missing(&mut self, value: bool) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_create_if_missing(self.inner.as_ptr(), value as i32);
+        }
+        self
+    }
+
+    /// Set whether opening with column families creates the ones that don't exist yet
+    pub fn create_missing_column_families(&mut self, value: bool) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_create_missing_column_families(
+                self.inner.as_ptr(),
+                value as u8,
+            );
+        }
+        self
+    }
+
+    /// Set whether to error if the database already exists
+    pub fn error_if_exists(&mut self, value: bool) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_error_if_exists(self.inner.as_ptr(), value as i32);
+        }
+        self
+    }
+
+    /// Treat the first `len` bytes of every key as its prefix
+    ///
+    /// Lets memtables and bloom filters index keys by prefix, and lets
+    /// [`DB::prefix_iter`](crate::DB::prefix_iter) skip data with other
+    /// prefixes entirely. Keys shorter than `len` have no prefix and are
+    /// only found by full scans.
+    pub fn set_fixed_prefix_extractor(&mut self, len: usize) -> &mut Self {
+        unsafe {
+            // RocksDB takes ownership of the transform
+            let transform = ffi::rocksdb_slicetransform_create_fixed_prefix(len);
+            ffi::rocksdb_options_set_prefix_extractor(self.inner.as_ptr(), transform);
+        }
+        self.prefix_len = Some(len);
+        self
+    }
+
+    /// Set the compression algorithm used for SST files
+    pub fn set_compression_type(&mut self, compression: CompressionType) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_compression(self.inner.as_ptr(), compression.as_raw());
+        }
+        self
+    }
+
+    /// Set the compression algorithm used for the bottommost level
+    ///
+    /// The bottommost level usually holds most of the data, so a stronger
+    /// algorithm here saves the most space.
+    pub fn set_bottommost_compression_type(&mut self, compression: CompressionType) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_bottommost_compression(
+                self.inner.as_ptr(),
+                compression.as_raw(),
+            );
+        }
+        self
+    }
+
+    /// Get the compression algorithm for all but the bottommost level
+    ///
+    /// `None` for a type this crate has no [`CompressionType`] for.
+    pub fn compression_type(&self) -> Option<CompressionType> {
+        CompressionType::from_raw(unsafe {
+            ffi::rocksdb_options_get_compression(self.inner.as_ptr())
+        })
+    }
+
+    /// Get the compression algorithm for the bottommost level
+    ///
+    /// `None` if none is set, in which case the level uses
+    /// [`Options::compression_type`].
+    pub fn bottommost_compression_type(&self) -> Option<CompressionType> {
+        CompressionType::from_raw(unsafe {
+            ffi::rocksdb_options_get_bottommost_compression(self.inner.as_ptr())
+        })
+    }
+
+    /// Set the parameters passed to the compression library
+    ///
+    /// * `window_bits` - Zlib window size (RocksDB default: -14)
+    /// * `level` - Compression level (RocksDB default: 32767, the library's own default)
+    /// * `strategy` - Zlib strategy (RocksDB default: 0)
+    /// * `max_dict_bytes` - Maximum size of the compression dictionary built for
+    ///   each SST file; 0 disables dictionary compression
+    ///
+    /// Dictionary compression pays off for many small, similar values (for
+    /// example JSON documents) that compress poorly on their own.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{CompressionType, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_compression_type(CompressionType::Zstd)
+    ///     .set_compression_options(-14, 3, 0, 16 * 1024)
+    ///     .set_zstd_max_train_bytes(100 * 16 * 1024);
+    /// ```
+    pub fn set_compression_options(
+        &mut self,
+        window_bits: i32,
+        level: i32,
+        strategy: i32,
+        max_dict_bytes: i32,
+    ) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_compression_options(
+                self.inner.as_ptr(),
+                window_bits,
+                level,
+                strategy,
+                max_dict_bytes,
+            );
+        }
+        self
+    }
+
+    /// Set how many bytes of samples zstd may use to train the dictionary
+    ///
+    /// Only used when `max_dict_bytes` is non-zero. A value of 0 uses the raw
+    /// samples as the dictionary without training; around 100x
+    /// `max_dict_bytes` is the usual recommendation. Opening the database
+    /// fails with [`ErrorKind::InvalidArgument`](crate::ErrorKind::InvalidArgument)
+    /// if this trains a dictionary but zstd isn't linked.
+    pub fn set_zstd_max_train_bytes(&mut self, value: i32) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_compression_options_zstd_max_train_bytes(
+                self.inner.as_ptr(),
+                value,
+            );
+        }
+        self
+    }
+
+    /// Set whether zstd's dictionary trainer is used (default: true)
+    ///
+    /// When false, RocksDB uses zstd's faster `finalizeDictionary` API instead.
+    pub fn set_use_zstd_dict_trainer(&mut self, value: bool) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_compression_options_use_zstd_dict_trainer(
+                self.inner.as_ptr(),
+                value as u8,
+            );
+        }
+        self
+    }
+
+    /// Limit the data buffered per SST file while collecting dictionary samples
+    ///
+    /// 0 means no limit.
+    pub fn set_max_dict_buffer_bytes(&mut self, value: u64) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_compression_options_max_dict_buffer_bytes(
+                self.inner.as_ptr(),
+                value,
+            );
+        }
+        self
+    }
+
+    /// Set compression parameters for the bottommost level
+    ///
+    /// Takes the same parameters as [`Options::set_compression_options`] and
+    /// overrides them for the bottommost level only.
+    pub fn set_bottommost_compression_options(
+        &mut self,
+        window_bits: i32,
+        level: i32,
+        strategy: i32,
+        max_dict_bytes: i32,
+    ) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_bottommost_compression_options(
+                self.inner.as_ptr(),
+                window_bits,
+                level,
+                strategy,
+                max_dict_bytes,
+                1,
+            );
+        }
+        self
+    }
+
+    /// Set the zstd dictionary training budget for the bottommost level
+    pub fn set_bottommost_zstd_max_train_bytes(&mut self, value: i32) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_bottommost_compression_options_zstd_max_train_bytes(
+                self.inner.as_ptr(),
+                value,
+                1,
+            );
+        }
+        self
+    }
+
+    /// Use the given environment for background threads and file access
+    ///
+    /// The options (and any database opened with them) keep a reference to
+    /// the environment, so it stays alive as long as it is needed.
+    pub fn set_env(&mut self, env: &Env) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_env(self.inner.as_ptr(), env.as_ptr());
+        }
+        self.env = Some(env.clone());
+        self
+    }
+
+    /// Set how many table files may be kept open (-1 keeps all open; default: -1)
+    pub fn set_max_open_files(&mut self, value: i32) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_max_open_files(self.inner.as_ptr(), value);
+        }
+        self
+    }
+
+    /// Set the maximum number of concurrent flush and compaction jobs (default: 2)
+    pub fn set_max_background_jobs(&mut self, value: i32) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_max_background_jobs(self.inner.as_ptr(), value);
+        }
+        self
+    }
+
+    /// Use up to `total_threads` background threads for flushes and compactions
+    ///
+    /// Sets the maximum number of background jobs and sizes the low-priority
+    /// pool of the options' environment to match; a reasonable value is the
+    /// number of cores. Call it after [`Options::set_env`], since it resizes
+    /// whichever environment is set at the time.
+    pub fn increase_parallelism(&mut self, total_threads: i32) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_increase_parallelism(self.inner.as_ptr(), total_threads);
+        }
+        self
+    }
+
+    /// Tune for point lookups with no range scans, using RocksDB's preset
+    ///
+    /// Installs a block-based table factory with a bloom filter, a hash
+    /// index inside data blocks and a block cache of `block_cache_size_mb`
+    /// megabytes, replacing any table factory set before, and a bloom
+    /// filter for memtables. Iterating over the column family still works
+    /// but gets no help from these settings.
+    pub fn optimize_for_point_lookup(&mut self, block_cache_size_mb: u64) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_optimize_for_point_lookup(
+                self.inner.as_ptr(),
+                block_cache_size_mb,
+            );
+        }
+        self.prefix_only_filter = false;
+        self
+    }
+
+    /// Tune for level-style compaction within a memtable memory budget,
+    /// using RocksDB's preset
+    ///
+    /// Sizes the memtables and level targets from `memtable_memory_budget`
+    /// bytes (512 MiB is a common choice), and compresses all but the first
+    /// two levels with the fastest compression linked in, if any.
+    pub fn optimize_level_style_compaction(&mut self, memtable_memory_budget: u64) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_optimize_level_style_compaction(
+                self.inner.as_ptr(),
+                memtable_memory_budget,
+            );
+        }
+        self
+    }
+
+    /// Tune for universal-style compaction within a memtable memory budget,
+    /// using RocksDB's preset
+    ///
+    /// Switches the compaction style to universal, which trades higher
+    /// space amplification for lower write amplification, and sizes the
+    /// memtables from `memtable_memory_budget` bytes.
+    pub fn optimize_universal_style_compaction(
+        &mut self,
+        memtable_memory_budget: u64,
+    ) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_optimize_universal_style_compaction(
+                self.inner.as_ptr(),
+                memtable_memory_budget,
+            );
+        }
+        self
+    }
+
+    /// Set how SST files are merged as they accumulate
+    ///
+    /// Changing the style of an existing column family only affects
+    /// compactions from then on; files already written stay where they are.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{CompactionStyle, Options, UniversalCompactionOptions};
+    ///
+    /// let mut universal = UniversalCompactionOptions::default();
+    /// universal
+    ///     .set_size_ratio(10)
+    ///     .set_max_size_amplification_percent(150);
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true)
+    ///     .set_compaction_style(CompactionStyle::Universal)
+    ///     .set_universal_compaction_options(&universal);
+    /// ```
+    pub fn set_compaction_style(&mut self, style: CompactionStyle) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_compaction_style(self.inner.as_ptr(), style.as_raw());
+        }
+        self
+    }
+
+    /// Set how universal compaction picks the sorted runs to merge
+    ///
+    /// Only used with [`CompactionStyle::Universal`]. The settings are
+    /// copied, so `universal_options` can be dropped or reused afterwards.
+    pub fn set_universal_compaction_options(
+        &mut self,
+        universal_options: &UniversalCompactionOptions,
+    ) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_universal_compaction_options(
+                self.inner.as_ptr(),
+                universal_options.as_ptr(),
+            );
+        }
+        self
+    }
+
+    /// Set how FIFO compaction bounds the total size of SST files
+    ///
+    /// Only used with [`CompactionStyle::Fifo`]. The settings are copied,
+    /// so `fifo_options` can be dropped or reused afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{CompactionStyle, FifoCompactionOptions, Options};
+    ///
+    /// // Keep about a gigabyte of events, and none older than a week
+    /// let mut fifo = FifoCompactionOptions::default();
+    /// fifo.set_max_table_files_size(1024 * 1024 * 1024);
+    ///
+    /// let mut events = Options::default();
+    /// events
+    ///     .set_compaction_style(CompactionStyle::Fifo)
+    ///     .set_fifo_compaction_options(&fifo)
+    ///     .set_ttl(7 * 24 * 60 * 60);
+    /// ```
+    pub fn set_fifo_compaction_options(
+        &mut self,
+        fifo_options: &FifoCompactionOptions,
+    ) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_fifo_compaction_options(
+                self.inner.as_ptr(),
+                fifo_options.as_ptr(),
+            );
+        }
+        self
+    }
+
+    /// Set the age, in seconds, after which SST files are compacted away
+    /// (default: 30 days; 0 turns it off)
+    ///
+    /// With [`CompactionStyle::Fifo`], files whose newest entry is older
+    /// than `seconds` are deleted. With [`CompactionStyle::Level`], files
+    /// this old are rewritten so deletions in them eventually take effect.
+    pub fn set_ttl(&mut self, seconds: u64) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_ttl(self.inner.as_ptr(), seconds);
+        }
+        self
+    }
+
+    /// Set the maximum number of threads a single compaction job may use
+    ///
+    /// Values above 1 split large compactions into parallel subcompactions,
+    /// which mainly speeds up L0 -> L1 and manual compactions (default: 1).
+    pub fn set_max_subcompactions(&mut self, value: u32) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_max_subcompactions(self.inner.as_ptr(), value);
+        }
+        self
+    }
+
+    /// Set whether WAL writes are buffered until flushed explicitly (default: false)
+    ///
+    /// When enabled, writes append to an in-memory WAL buffer and reach the
+    /// log file only when [`DB::flush_wal`](crate::DB::flush_wal) is called,
+    /// letting the application group-commit many writes with one flush.
+    /// Writes not yet flushed are lost if the process crashes.
+    pub fn set_manual_wal_flush(&mut self, value: bool) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_manual_wal_flush(self.inner.as_ptr(), value as u8);
+        }
+        self
+    }
+
+    /// Set how long archived WAL files are kept, in seconds (default: 0)
+    ///
+    /// Archived WAL files are what [`DB::get_updates_since`](crate::DB::get_updates_since)
+    /// reads once their data has been flushed. With both this and
+    /// [`Options::set_wal_size_limit_mb`] at 0, archived files are deleted
+    /// right away.
+    pub fn set_wal_ttl_seconds(&mut self, value: u64) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_WAL_ttl_seconds(self.inner.as_ptr(), value);
+        }
+        self
+    }
+
+    /// Set the total size of archived WAL files to keep, in megabytes (default: 0)
+    pub fn set_wal_size_limit_mb(&mut self, value: u64) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_WAL_size_limit_MB(self.inner.as_ptr(), value);
+        }
+        self
+    }
+
+    /// Keep the WAL files in `path` instead of the database directory
+    ///
+    /// Lets the WAL live on a separate (e.g. faster or replicated) device.
+    /// The database must always be reopened with the same directory.
+    pub fn set_wal_dir<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        let path = path.as_ref();
+        if let Ok(c_path) = CString::new(path.to_string_lossy().as_bytes()) {
+            unsafe {
+                ffi::rocksdb_options_set_wal_dir(self.inner.as_ptr(), c_path.as_ptr());
+            }
+        }
+        self.wal_dir = Some(path.to_path_buf());
+        self
+    }
+
+    /// Write the info log to `path` instead of the database directory
+    ///
+    /// As in RocksDB, the log file is named after the database's absolute
+    /// path, so databases can share the directory.
+    pub fn set_db_log_dir<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        let path = path.as_ref();
+        if let Ok(c_path) = CString::new(path.to_string_lossy().as_bytes()) {
+            unsafe {
+                ffi::rocksdb_options_set_db_log_dir(self.inner.as_ptr(), c_path.as_ptr());
+            }
+        }
+        self.db_log_dir = Some(path.to_path_buf());
+        self
+    }
+
+    /// Start a new info log once the current one reaches `value` bytes
+    /// (default: 0, no limit)
+    pub fn set_max_log_file_size(&mut self, value: usize) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_max_log_file_size(self.inner.as_ptr(), value);
+        }
+        self
+    }
+
+    /// Set how many info log files are kept, the current one included (default: 1000)
+    pub fn set_keep_log_file_num(&mut self, value: usize) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_keep_log_file_num(self.inner.as_ptr(), value);
+        }
+        self
+    }
+
+    /// Set the compression algorithm used for blob files
+    ///
+    /// Only relevant when values are stored in blob files, as with
+    /// [`CfPreset::Blob`].
+    pub fn set_blob_compression_type(&mut self, compression: CompressionType) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_blob_compression_type(
+                self.inner.as_ptr(),
+                compression.as_raw(),
+            );
+        }
+        self
+    }
+
+    /// Get the compression algorithm for blob files
+    pub fn blob_compression_type(&self) -> Option<CompressionType> {
+        CompressionType::from_raw(unsafe {
+            ffi::rocksdb_options_get_blob_compression_type(self.inner.as_ptr())
+        })
+    }
+
+    /// Store SST files in the block-based table format with the given settings
+    ///
+    /// The table options are copied, so `table_options` can be dropped or
+    /// reused afterwards. Settings RocksDB rejects are reported by
+    /// [`Options::validate`] and when opening.
+    pub fn set_block_based_table_factory(
+        &mut self,
+        table_options: &BlockBasedOptions,
+    ) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_block_based_table_factory(
+                self.inner.as_ptr(),
+                table_options.as_ptr(),
+            );
+        }
+        self.prefix_only_filter = table_options.has_filter && !table_options.whole_key_filtering;
+        self.table_options_error = table_options.read_amp_bytes_per_bit.and_then(|bits| {
+            // Parsed on top of the table factory just set, keeping its other settings
+            self.apply_option_string(&format!(
+                "block_based_table_factory={{read_amp_bytes_per_bit={}}}",
+                bits
+            ))
+            .err()
+        });
+        self
+    }
+
+    /// Replace the options with a copy that has `opts_str` applied
+    ///
+    /// The options are left unchanged if RocksDB can't parse `opts_str`.
+    fn apply_option_string(&mut self, opts_str: &str) -> Result<()> {
+        let c_opts = CString::new(opts_str).map_err(|_| {
+            invalid_options(&format!(
+                "Option string \"{}\" contains a nul byte",
+                opts_str
+            ))
+        })?;
+        let mut err: *mut c_char = ptr::null_mut();
+        unsafe {
+            let new_options = ffi::rocksdb_options_create();
+            ffi::rocksdb_get_options_from_string(
+                self.inner.as_ptr(),
+                c_opts.as_ptr(),
+                new_options,
+                &mut err,
+            );
+            if !err.is_null() {
+                ffi::rocksdb_options_destroy(new_options);
+                return Err(invalid_options(&format!(
+                    "Failed to apply \"{}\": {}",
+                    opts_str,
+                    Error::from_c_string(err)
+                )));
+            }
+            ffi::rocksdb_options_destroy(self.inner.as_ptr());
+            self.inner = NonNull::new(new_options).expect("Failed to create options");
+        }
+        Ok(())
+    }
+
+    /// Apply a bundle of table, compression and compaction settings tuned for a workload
+    ///
+    /// Settings applied afterwards override the preset's, so a preset can be
+    /// used as a starting point and adjusted. See [`CfPreset`] for what each
+    /// preset sets.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{CfPreset, DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// let mut session_opts = Options::default();
+    /// session_opts.apply_preset(CfPreset::PointLookup);
+    /// let sessions = db.create_column_family(&session_opts, "sessions").unwrap();
+    /// ```
+    pub fn apply_preset(&mut self, preset: CfPreset) -> &mut Self {
+        let opt = self.inner.as_ptr();
+        let mut table_options = BlockBasedOptions::new();
+
+        unsafe {
+            match preset {
+                CfPreset::PointLookup => {
+                    table_options
+                        .set_bloom_filter(10.0)
+                        .set_cache_index_and_filter_blocks(true)
+                        .set_data_block_hash_index(true);
+                    self.set_compression_type(CompressionType::Lz4);
+                    ffi::rocksdb_options_set_optimize_filters_for_hits(opt, 1);
+                }
+                CfPreset::WriteHeavy => {
+                    table_options.set_bloom_filter(10.0);
+                    self.set_compression_type(CompressionType::Lz4);
+                    ffi::rocksdb_options_set_write_buffer_size(opt, 128 * MB as usize);
+                    ffi::rocksdb_options_set_max_write_buffer_number(opt, 4);
+                    ffi::rocksdb_options_set_min_write_buffer_number_to_merge(opt, 2);
+                    ffi::rocksdb_options_set_level0_file_num_compaction_trigger(opt, 8);
+                    ffi::rocksdb_options_set_level0_slowdown_writes_trigger(opt, 32);
+                    ffi::rocksdb_options_set_level0_stop_writes_trigger(opt, 48);
+                    ffi::rocksdb_options_set_target_file_size_base(opt, 128 * MB);
+                    ffi::rocksdb_options_set_max_bytes_for_level_base(opt, 1024 * MB);
+                }
+                CfPreset::Blob => {
+                    table_options.set_bloom_filter(10.0);
+                    self.set_compression_type(CompressionType::Lz4);
+                    ffi::rocksdb_options_set_enable_blob_files(opt, 1);
+                    ffi::rocksdb_options_set_min_blob_size(opt, 4 * 1024);
+                    ffi::rocksdb_options_set_blob_file_size(opt, 256 * MB);
+                    self.set_blob_compression_type(CompressionType::Lz4);
+                    ffi::rocksdb_options_set_enable_blob_gc(opt, 1);
+                }
+                CfPreset::TimeSeries => {
+                    table_options.set_block_size(32 * 1024);
+                    self.set_compression_type(CompressionType::Lz4)
+                        .set_bottommost_compression_type(CompressionType::Zstd);
+                    ffi::rocksdb_options_set_level_compaction_dynamic_level_bytes(opt, 1);
+                    ffi::rocksdb_options_set_target_file_size_base(opt, 128 * MB);
+                }
+            }
+        }
+
+        self.set_block_based_table_factory(&table_options)
+    }
+
+    /// Limit the I/O bandwidth of flushes and compactions
+    ///
+    /// The options share ownership of the limiter, so `limiter` can be
+    /// dropped afterwards.
+    pub fn set_ratelimiter(&mut self, limiter: &RateLimiter) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_ratelimiter(self.inner.as_ptr(), limiter.as_ptr());
+        }
+        self
+    }
+
+    /// Count memtable memory against a budget shared with other databases
+    ///
+    /// The options share ownership of the manager, so `manager` can be
+    /// dropped afterwards.
+    pub fn set_write_buffer_manager(&mut self, manager: &WriteBufferManager) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_write_buffer_manager(self.inner.as_ptr(), manager.as_ptr());
+        }
+        self
+    }
+
+    /// Run a value format migration during compactions
+    ///
+    /// This replaces any compaction filter set previously. The options share
+    /// ownership of the migration state, so `migrator` can be dropped
+    /// afterwards (its clones keep reporting progress).
+    pub fn set_migrator(&mut self, migrator: &Migrator) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_compaction_filter_factory(
+                self.inner.as_ptr(),
+                migrator.create_factory(),
+            );
+        }
+        self
+    }
+
+    /// Cache whole key-value pairs read by point lookups
+    ///
+    /// A row cache hit skips the block cache and block decoding entirely,
+    /// which pays off for hot keys in large blocks. The options share
+    /// ownership of the cache.
+    pub fn set_row_cache(&mut self, cache: &Cache) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_row_cache(self.inner.as_ptr(), cache.as_ptr());
+        }
+        self.row_cache = Some(cache.clone());
+        self
+    }
+
+    /// Mark SST files dense with tombstones for compaction as they are written
+    ///
+    /// While a flush or compaction writes a file, RocksDB slides a window of
+    /// `window_size` entries over it; if any window holds at least
+    /// `num_dels_trigger` deletions, or the whole file has at least
+    /// `deletion_ratio` deletions among its entries (0 turns this check
+    /// off), the file is queued for compaction right away. Keeps scans fast
+    /// after mass deletes without a manual compaction.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// // 5000 deletions in any 10000 entries, or half the file
+    /// opts.add_compact_on_deletion_collector_factory(10_000, 5_000, 0.5);
+    /// ```
+    pub fn add_compact_on_deletion_collector_factory(
+        &mut self,
+        window_size: usize,
+        num_dels_trigger: usize,
+        deletion_ratio: f64,
+    ) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_add_compact_on_deletion_collector_factory_del_ratio(
+                self.inner.as_ptr(),
+                window_size,
+                num_dels_trigger,
+                deletion_ratio,
+            );
+        }
+        self
+    }
+
+    /// Track SST files with a manager enforcing space and deletion rate limits
+    ///
+    /// The options share ownership of the manager, so `manager` can be
+    /// dropped afterwards.
+    pub fn set_sst_file_manager(&mut self, manager: &SstFileManager) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_set_sst_file_manager(self.inner.as_ptr(), manager.as_ptr());
+        }
+        self
+    }
+
+    /// Report the progress of opening a database with these options
+    ///
+    /// `callback` is called on the opening thread with an
+    /// [`OpenProgress`] for each step: once before recovery starts, once
+    /// the MANIFEST is read, once per WAL file replayed, and once the
+    /// database is open. Only [`OpenProgress::Finished`] marks success; a
+    /// failed open just stops reporting.
+    ///
+    /// RocksDB has no progress API, so the steps are read from its info
+    /// log: for as long as the database is open, its log messages go
+    /// through this crate, which writes them on to the info log the way
+    /// RocksDB would, in the database directory or the one set with
+    /// [`Options::set_db_log_dir`], at the configured log level, starting a
+    /// new file at the size or age limit. Log lines are formatted more
+    /// tersely than RocksDB's.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, OpenProgress, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_open_progress(|progress| match progress {
+    ///     OpenProgress::ReplayingWal { bytes_replayed, wal_bytes, .. } => {
+    ///         println!("Replaying WAL: {}/{} bytes", bytes_replayed, wal_bytes);
+    ///     }
+    ///     other => println!("{:?}", other),
+    /// });
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    /// ```
+    pub fn set_open_progress<F>(&mut self, callback: F) -> &mut Self
+    where
+        F: Fn(&OpenProgress) + Send + Sync + 'static,
+    {
+        self.open_progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// Reject keys longer than `value` bytes in writes (default: no limit)
+    ///
+    /// The limit is a safeguard against misbehaving callers: very large keys
+    /// bloat index blocks and slow down every comparison. It is checked by
+    /// this crate before calling RocksDB, applies to every write through a
+    /// [`DB`](crate::DB) opened with these options, and fails with
+    /// [`ErrorKind::InvalidArgument`].
+    pub fn set_max_key_size(&mut self, value: usize) -> &mut Self {
+        self.limits.max_key_size = Some(value);
+        self
+    }
+
+    /// Reject values longer than `value` bytes in writes (default: no limit)
+    ///
+    /// Works like [`Options::set_max_key_size`].
+    pub fn set_max_value_size(&mut self, value: usize) -> &mut Self {
+        self.limits.max_value_size = Some(value);
+        self
+    }
+
+    /// Collect statistics for databases opened with these options
+    ///
+    /// Must be called before opening. The statistics object is shared by
+    /// these options and every database opened with them, so the counters
+    /// can be read back through these options while the databases run.
+    /// Collecting statistics costs a few percent of throughput.
+    pub fn enable_statistics(&mut self) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_options_enable_statistics(self.inner.as_ptr());
+        }
+        self
+    }
+
+    /// Get the current value of a statistics ticker
+    ///
+    /// Returns 0 if statistics are not enabled.
+    pub fn ticker_count(&self, ticker: Ticker) -> u64 {
+        unsafe {
+            ffi::rocksdb_options_statistics_get_ticker_count(self.inner.as_ptr(), ticker.as_raw())
+        }
+    }
+
+    /// Capture the current value of every [`Ticker`]
+    ///
+    /// RocksDB statistics cannot be reset through the C API; compare two
+    /// snapshots with [`StatisticsSnapshot::delta`] to measure one phase.
+    pub fn statistics_snapshot(&self) -> StatisticsSnapshot {
+        StatisticsSnapshot::capture(|ticker| self.ticker_count(ticker))
+    }
+
+    /// Get a human-readable dump of all tickers and histograms
+    ///
+    /// Returns `None` if statistics are not enabled.
+    pub fn statistics_string(&self) -> Option<String> {
+        unsafe {
+            take_c_string(ffi::rocksdb_options_statistics_get_string(
+                self.inner.as_ptr(),
+            ))
+        }
+    }
+
+    /// Check for settings that conflict with each other
+    ///
+    /// Catches combinations that make RocksDB fail to open with a terse
+    /// message, or silently ignore a setting, and describes the problem
+    /// instead. The `DB` open functions run it first (read-only and
+    /// secondary instances, and
+    /// [`DB::create_column_family`](crate::DB::create_column_family), only
+    /// the per-column-family checks), so calling it directly is only needed
+    /// to check options early, e.g. when loading them from a configuration
+    /// file. Fails with [`ErrorKind::InvalidArgument`].
+    ///
+    /// Checks that:
+    /// * `error_if_exists` is only set together with `create_if_missing`,
+    ///   since otherwise neither a missing nor an existing database can be opened
+    /// * a fixed prefix extractor has a non-zero length
+    /// * a bloom filter without whole-key filtering has a prefix extractor
+    ///   to fill it
+    /// * blob compression is only configured with blob files enabled
+    /// * RocksDB accepted the settings of the last
+    ///   [`Options::set_block_based_table_factory`]
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::Options;
+    ///
+    /// let mut opts = Options::default();
+    /// opts.error_if_exists(true);
+    /// assert!(opts.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<()> {
+        for (name, dir) in [("WAL", &self.wal_dir), ("info log", &self.db_log_dir)] {
+            if let Some(dir) = dir
+                && dir.to_string_lossy().contains('\0')
+            {
+                return Err(invalid_options(&format!(
+                    "The {} directory {:?} contains a nul byte",
+                    name, dir
+                )));
+            }
+        }
+
+        let opt = self.inner.as_ptr();
+        unsafe {
+            if ffi::rocksdb_options_get_error_if_exists(opt) != 0
+                && ffi::rocksdb_options_get_create_if_missing(opt) == 0
+            {
+                return Err(invalid_options(
+                    "error_if_exists is set without create_if_missing, so no database can be opened",
+                ));
+            }
+        }
+        self.validate_column_family()
+    }
+
+    /// Check the settings that apply per column family (internal use only)
+    pub(crate) fn validate_column_family(&self) -> Result<()> {
+        if let Some(err) = &self.table_options_error {
+            return Err(err.clone());
+        }
+        if self.prefix_len == Some(0) {
+            return Err(invalid_options(
+                "The fixed prefix extractor must take at least 1 byte",
+            ));
+        }
+        if self.prefix_only_filter && self.prefix_len.is_none() {
+            return Err(invalid_options(
+                "The bloom filter has whole-key filtering off but no prefix extractor is set, \
+                 so it can't rule out any file",
+            ));
+        }
+
+        let opt = self.inner.as_ptr();
+        unsafe {
+            if ffi::rocksdb_options_get_blob_compression_type(opt) != CompressionType::None.as_raw()
+                && ffi::rocksdb_options_get_enable_blob_files(opt) == 0
+            {
+                return Err(invalid_options(
+                    "Blob compression is set but blob files are not enabled",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Load the options a database was last opened with from its OPTIONS file
+    ///
+    /// RocksDB writes an `OPTIONS-<number>` file to the database directory
+    /// whenever the database is opened or its options change. This reads
+    /// the latest one back as the database options plus a descriptor for
+    /// every column family, ready for
+    /// [`DB::open_cf_descriptors`](crate::DB::open_cf_descriptors), so a
+    /// tuned database is not reopened with defaults by mistake. `path` may
+    /// also be a directory an OPTIONS file was saved to with
+    /// [`DB::save_options`](crate::DB::save_options).
+    ///
+    /// `env` and `block_cache` are attached to the loaded options, since
+    /// neither can be stored in the file; without a cache every column
+    /// family gets a default block cache of its own. A fixed prefix
+    /// extractor and a prefix-only bloom filter are read back along with
+    /// the rest, so [`Options::prefix_len`] and
+    /// [`DB::prefix_iter`](crate::DB::prefix_iter) see them. Settings this
+    /// crate enforces itself, such as size limits and progress reporting,
+    /// are not in the file and have to be set again. Options written by a
+    /// newer RocksDB with settings unknown to this one fail to load.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let (opts, cf_descriptors) = Options::load_latest("/tmp/my_db", None, None).unwrap();
+    /// let db = DB::open_cf_descriptors(&opts, "/tmp/my_db", &cf_descriptors).unwrap();
+    /// ```
+    pub fn load_latest<P: AsRef<Path>>(
+        path: P,
+        env: Option<&Env>,
+        block_cache: Option<&Cache>,
+    ) -> Result<(Options, Vec<ColumnFamilyDescriptor>)> {
+        let c_path = CString::new(path.as_ref().to_string_lossy().as_bytes())
+            .map_err(|_| Error::new("Invalid path"))?;
+        // RocksDB reads the file through the Env, so one is required
+        let env = env.cloned().unwrap_or_default();
+
+        unsafe {
+            let mut db_ptr: *mut ffi::rocksdb_options_t = ptr::null_mut();
+            let mut len: usize = 0;
+            let mut names: *mut *mut c_char = ptr::null_mut();
+            let mut cf_ptrs: *mut *mut ffi::rocksdb_options_t = ptr::null_mut();
+            let mut err: *mut c_char = ptr::null_mut();
+            ffi::rocksdb_load_latest_options(
+                c_path.as_ptr(),
+                env.as_ptr(),
+                false,
+                block_cache.map_or(ptr::null_mut(), Cache::as_ptr),
+                &mut db_ptr,
+                &mut len,
+                &mut names,
+                &mut cf_ptrs,
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            // The loaded options are freed as a group, so keep copies
+            let mut options = Options::from_raw(ffi::rocksdb_options_create_copy(db_ptr));
+            options.env = Some(env);
+            let mut descriptors: Vec<ColumnFamilyDescriptor> = (0..len)
+                .map(|i| {
+                    ColumnFamilyDescriptor::new(
+                        CStr::from_ptr(*names.add(i)).to_string_lossy(),
+                        Options::from_raw(ffi::rocksdb_options_create_copy(*cf_ptrs.add(i))),
+                    )
+                })
+                .collect();
+            ffi::rocksdb_load_latest_options_destroy(db_ptr, names, cf_ptrs, len);
+
+            restore_prefix_settings(path.as_ref(), &mut descriptors)?;
+            Ok((options, descriptors))
+        }
+    }
+
+    /// Get the environment set with [`Options::set_env`], if any
+    pub(crate) fn env(&self) -> Option<&Env> {
+        self.env.as_ref()
+    }
+
+    /// Get the row cache set with [`Options::set_row_cache`], if any
+    pub(crate) fn row_cache(&self) -> Option<&Cache> {
+        self.row_cache.as_ref()
+    }
+
+    /// Get the callback set with [`Options::set_open_progress`], if any
+    pub(crate) fn open_progress(&self) -> Option<Arc<OpenProgressFn>> {
+        self.open_progress.clone()
+    }
+
+    /// Get the directory set with [`Options::set_wal_dir`], if any
+    pub(crate) fn wal_dir(&self) -> Option<&Path> {
+        self.wal_dir.as_deref()
+    }
+
+    /// Get the directory set with [`Options::set_db_log_dir`], if any
+    pub(crate) fn db_log_dir(&self) -> Option<&Path> {
+        self.db_log_dir.as_deref()
+    }
+
+    /// Get the info log settings: level, size and age limits, and files kept
+    pub(crate) fn info_log_settings(&self) -> InfoLogSettings {
+        let opt = self.inner.as_ptr();
+        unsafe {
+            InfoLogSettings {
+                level: ffi::rocksdb_options_get_info_log_level(opt),
+                max_size: ffi::rocksdb_options_get_max_log_file_size(opt) as u64,
+                time_to_roll_secs: ffi::rocksdb_options_get_log_file_time_to_roll(opt) as u64,
+                keep_files: ffi::rocksdb_options_get_keep_log_file_num(opt),
+            }
+        }
+    }
+
+    /// Send the info log to `c_logger`, keeping `logger`, the state it calls into, alive
+    pub(crate) unsafe fn set_progress_logger(
+        &mut self,
+        c_logger: *mut ffi::rocksdb_logger_t,
+        logger: Arc<ProgressLogger>,
+    ) {
+        unsafe {
+            ffi::rocksdb_options_set_info_log(self.inner.as_ptr(), c_logger);
+        }
+        self.progress_logger = Some(logger);
+    }
+
+    /// Get the length set with [`Options::set_fixed_prefix_extractor`], if any
+    ///
+    /// Also reports the prefix extractor of options read back with
+    /// [`Options::load_latest`].
+    pub fn prefix_len(&self) -> Option<usize> {
+        self.prefix_len
+    }
+
+    /// Get the size limits set with [`Options::set_max_key_size`] and
+    /// [`Options::set_max_value_size`]
+    pub(crate) fn size_limits(&self) -> SizeLimits {
+        self.limits
+    }
+
+    /// Get the underlying `rocksdb_options_t*` for calling C API functions
+    /// this crate does not wrap, such as setters for options without a method
+    ///
+    /// # Safety
+    ///
+    /// The pointer is only valid while these options are alive and must not
+    /// be passed to `rocksdb_options_destroy`. Objects attached through it
+    /// that RocksDB only borrows (such as an Env) must outlive every database
+    /// opened with the options.
+    pub unsafe fn as_raw(&self) -> *mut c_void {
+        self.inner.as_ptr() as *mut c_void
+    }
+
+    /// Get the raw pointer for FFI calls
+    pub(crate) fn as_ptr(&self) -> *const ffi::rocksdb_options_t {
+        self.inner.as_ptr()
+    }
+}
+
+/// Describe options that failed [`Options::validate`]
+fn invalid_options(message: &str) -> Error {
+    Error::with_kind(
+        ErrorKind::InvalidArgument,
+        format!("Invalid options: {}", message),
+    )
+}
+
+/// Read back the prefix settings this crate tracks from the OPTIONS file
+/// [`Options::load_latest`] loaded the column families from
+///
+/// RocksDB restores the prefix extractor and filter themselves but has no
+/// getters for them, so they are parsed from the `[CFOptions "<name>"]` and
+/// `[TableOptions/BlockBasedTable "<name>"]` sections.
+fn restore_prefix_settings(dir: &Path, descriptors: &mut [ColumnFamilyDescriptor]) -> Result<()> {
+    let io_error =
+        |err: io::Error| Error::with_kind(ErrorKind::IOError, format!("Load options: {}", err));
+
+    // RocksDB loads the OPTIONS file with the highest number
+    let latest = fs::read_dir(dir)
+        .map_err(io_error)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let number = name.strip_prefix("OPTIONS-")?.parse::<u64>().ok()?;
+            Some((number, name))
+        })
+        .max();
+    let Some((_, latest)) = latest else {
+        return Ok(());
+    };
+    let contents = fs::read_to_string(dir.join(latest)).map_err(io_error)?;
+
+    // Filter settings per column family: (has a filter, whole-key filtering)
+    let mut filters: HashMap<String, (bool, bool)> = HashMap::new();
+    let mut section: Option<(&str, String)> = None;
+    for line in contents.lines().map(str::trim) {
+        if let Some(header) = line.strip_prefix('[').and_then(|h| h.strip_suffix(']')) {
+            section = header
+                .split_once(' ')
+                .map(|(kind, name)| (kind, unescape_option_string(name.trim_matches('"'))));
+            continue;
+        }
+        let (Some((kind, name)), Some((key, value))) = (&section, line.split_once('=')) else {
+            continue;
+        };
+        let value = value.trim();
+        match (*kind, key.trim()) {
+            ("CFOptions", "prefix_extractor") => {
+                if let Some(descriptor) = descriptors.iter_mut().find(|d| &d.name == name) {
+                    descriptor.options.prefix_len = value
+                        .strip_prefix("rocksdb.FixedPrefix.")
+                        .and_then(|len| len.parse().ok());
+                }
+            }
+            ("TableOptions/BlockBasedTable", "filter_policy") => {
+                filters.entry(name.clone()).or_insert((false, true)).0 = value != "nullptr";
+            }
+            ("TableOptions/BlockBasedTable", "whole_key_filtering") => {
+                filters.entry(name.clone()).or_insert((false, true)).1 = value == "true";
+            }
+            _ => {}
+        }
+    }
+
+    for descriptor in descriptors.iter_mut() {
+        if let Some(&(has_filter, whole_key_filtering)) = filters.get(&descriptor.name) {
+            descriptor.options.prefix_only_filter = has_filter && !whole_key_filtering;
+        }
+    }
+    Ok(())
+}
+
+/// Undo the escaping RocksDB applies to column family names in OPTIONS files
+fn unescape_option_string(escaped: &str) -> String {
+    let mut unescaped = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Copies every setting into independent options
+///
+/// Changing the copy leaves the original untouched, so per-column-family
+/// options can start from one base configuration. Objects attached to the
+/// options, such as the [`Env`], caches, rate limiter and managers, are
+/// shared by the copies rather than duplicated.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{CompressionType, Options};
+///
+/// let mut base = Options::default();
+/// base.set_max_key_size(1024);
+///
+/// let mut logs = base.clone();
+/// logs.set_compression_type(CompressionType::Zstd);
+/// let mut users = base.clone();
+/// users.set_fixed_prefix_extractor(8);
+/// ```
+impl Clone for Options {
+    fn clone(&self) -> Self {
+        unsafe {
+            let ptr = ffi::rocksdb_options_create_copy(self.inner.as_ptr());
+            Options {
+                inner: NonNull::new(ptr).expect("Failed to copy options"),
+                env: self.env.clone(),
+                row_cache: self.row_cache.clone(),
+                limits: self.limits,
+                prefix_len: self.prefix_len,
+                prefix_only_filter: self.prefix_only_filter,
+                wal_dir: self.wal_dir.clone(),
+                db_log_dir: self.db_log_dir.clone(),
+                table_options_error: self.table_options_error.clone(),
+                open_progress: self.open_progress.clone(),
+                progress_logger: self.progress_logger.clone(),
+            }
+        }
+    }
+}
+
+impl Drop for Options {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_options_destroy(self.inner.as_ptr());
+        }));
+    }
+}
+
+// Options is safe to send between threads
+unsafe impl Send for Options {}
+
+/// Durability of a write made with [`DB::put_opt`](crate::DB::put_opt) and friends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteMode {
+    /// Return once the write is in the OS page cache (default)
+    ///
+    /// Survives a process crash but not a machine crash.
+    #[default]
+    Buffered,
+    /// fsync the WAL before returning
+    ///
+    /// Survives a machine crash, at the cost of a disk flush per write.
+    Sync,
+}
+
+/// Caching behaviour of a read made with [`DB::get_opt`](crate::DB::get_opt) and friends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReadMode {
+    /// Add the blocks read to the block cache (default)
+    #[default]
+    Cached,
+    /// Leave the block cache as it is
+    ///
+    /// For one-off reads, such as scans for a backup or an export, that
+    /// would otherwise evict the hot working set.
+    NoFillCache,
+}
+
+/// How manual compaction treats the bottommost level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BottommostLevelCompaction {
+    /// Skip the bottommost level
+    Skip,
+    /// Compact the bottommost level only if a compaction filter is configured (default)
+    IfHaveCompactionFilter,
+    /// Always compact the bottommost level
+    Force,
+    /// Always compact the bottommost level, but skip files created by this compaction
+    ForceOptimized,
+}
+
+impl BottommostLevelCompaction {
+    /// Get the value used by the RocksDB C API
+    fn as_raw(self) -> u8 {
+        match self {
+            BottommostLevelCompaction::Skip => 0,
+            BottommostLevelCompaction::IfHaveCompactionFilter => 1,
+            BottommostLevelCompaction::Force => 2,
+            BottommostLevelCompaction::ForceOptimized => 3,
+        }
+    }
+}
+
+/// Options for manual compaction with [`DB::compact_range_opt`](crate::DB::compact_range_opt)
+/// and [`DB::compact_range_cf_opt`](crate::DB::compact_range_cf_opt)
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{BottommostLevelCompaction, CompactRangeOptions};
+///
+/// // Push everything down to the bottom level
+/// let mut compact_opts = CompactRangeOptions::default();
+/// compact_opts
+///     .set_exclusive_manual_compaction(true)
+///     .set_bottommost_level_compaction(BottommostLevelCompaction::Force);
+/// ```
+#[must_use = "CompactRangeOptions must be passed to a compaction call"]
+pub struct CompactRangeOptions {
+    inner: NonNull<ffi::rocksdb_compactoptions_t>,
+}
+
+impl CompactRangeOptions {
+    /// Create compaction options with RocksDB's defaults
+    pub fn new() -> Self {
+        unsafe {
+            let ptr = ffi::rocksdb_compactoptions_create();
+            CompactRangeOptions {
+                inner: NonNull::new(ptr).expect("Failed to create compact range options"),
+            }
+        }
+    }
+
+    /// Set whether this compaction blocks other manual compactions (default: true)
+    pub fn set_exclusive_manual_compaction(&mut self, value: bool) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_compactoptions_set_exclusive_manual_compaction(
+                self.inner.as_ptr(),
+                value as u8,
+            );
+        }
+        self
+    }
+
+    /// Set how the bottommost level is compacted
+    pub fn set_bottommost_level_compaction(
+        &mut self,
+        value: BottommostLevelCompaction,
+    ) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_compactoptions_set_bottommost_level_compaction(
+                self.inner.as_ptr(),
+                value.as_raw(),
+            );
+        }
+        self
+    }
+
+    /// Set whether compacted files are moved to the level given by
+    /// [`CompactRangeOptions::set_target_level`] (default: false)
+    pub fn set_change_level(&mut self, value: bool) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_compactoptions_set_change_level(self.inner.as_ptr(), value as u8);
+        }
+        self
+    }
+
+    /// Set the level files are moved to when change_level is enabled
+    ///
+    /// A negative value means the lowest level that can hold the data.
+    pub fn set_target_level(&mut self, value: i32) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_compactoptions_set_target_level(self.inner.as_ptr(), value);
+        }
+        self
+    }
+
+    /// Get the raw pointer for FFI calls
+    pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_compactoptions_t {
+        self.inner.as_ptr()
+    }
 }
 
-impl Options {
-    /// Create a new Options instance with default settings
+impl Default for CompactRangeOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for CompactRangeOptions {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_compactoptions_destroy(self.inner.as_ptr());
+        }));
+    }
+}
+
+// CompactRangeOptions is safe to send between threads
+unsafe impl Send for CompactRangeOptions {}
+
+/// Options for [`DB::flush_opt`](crate::DB::flush_opt) and
+/// [`DB::flush_cf_opt`](crate::DB::flush_cf_opt)
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::FlushOptions;
+///
+/// // Start the flush in the background and return immediately
+/// let mut flush_opts = FlushOptions::default();
+/// flush_opts.set_wait(false);
+/// ```
+#[must_use = "FlushOptions must be passed to a flush call"]
+pub struct FlushOptions {
+    inner: NonNull<ffi::rocksdb_flushoptions_t>,
+}
+
+impl FlushOptions {
+    /// Create flush options with RocksDB's defaults
     pub fn new() -> Self {
         unsafe {
-            let ptr = ffi::rocksdb_options_create();
-            Options {
-                inner: NonNull::new(ptr).expect("Failed to create options"),
+            let ptr = ffi::rocksdb_flushoptions_create();
+            FlushOptions {
+                inner: NonNull::new(ptr).expect("Failed to create flush options"),
             }
         }
     }
 
-    /// Set whether to create the database if it doesn't exist
-    pub fn create_if_missing(&mut self, value: bool) -> &mut Self {
+    /// Set whether the flush call blocks until the flush completes (default: true)
+    pub fn set_wait(&mut self, value: bool) -> &mut Self {
         unsafe {
-            ffi::rocksdb_options_set_create_if_missing(self.inner.as_ptr(), value as i32);
+            ffi::rocksdb_flushoptions_set_wait(self.inner.as_ptr(), value as u8);
         }
         self
     }
 
-    /// Set whether to error if the database already exists
-    pub fn error_if_exists(&mut self, value: bool) -> &mut Self {
+    /// Get the raw pointer for FFI calls
+    pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_flushoptions_t {
+        self.inner.as_ptr()
+    }
+}
+
+impl Default for FlushOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for FlushOptions {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_flushoptions_destroy(self.inner.as_ptr());
+        }));
+    }
+}
+
+// FlushOptions is safe to send between threads
+unsafe impl Send for FlushOptions {}
+
+/// Options for reads, including iterator bounds
+///
+/// Passed by value to [`DB::raw_iterator_opt`](crate::DB::raw_iterator_opt)
+/// and the other `_opt` iterator constructors.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::ReadOptions;
+///
+/// // A one-off scan of one key space that shouldn't evict hot blocks
+/// let mut read_opts = ReadOptions::default();
+/// read_opts
+///     .set_iterate_lower_bound(b"log:2024-01")
+///     .set_iterate_upper_bound(b"log:2024-02")
+///     .set_fill_cache(false);
+/// ```
+///
+/// # Bound lifetimes
+///
+/// RocksDB doesn't copy iterator bounds: the iterator reads them through a
+/// pointer every time it moves. The setters therefore copy the key into a
+/// buffer owned by the `ReadOptions`, and the iterator constructors take the
+/// `ReadOptions` by value and free it only after the iterator. The caller's
+/// buffer can be dropped or reused right away, and the bounds of an iterator
+/// can't be changed while it is in use:
+///
+/// ```compile_fail
+/// # use rust_small_rocksdb::{DB, Options, ReadOptions};
+/// # let db = DB::open(&Options::default(), "/tmp/my_db").unwrap();
+/// let mut read_opts = ReadOptions::default();
+/// read_opts.set_iterate_upper_bound(b"m");
+/// let iter = db.raw_iterator_opt(read_opts);
+/// read_opts.set_iterate_upper_bound(b"z"); // error: `read_opts` was moved
+/// ```
+#[must_use = "ReadOptions must be passed to a read call"]
+pub struct ReadOptions {
+    inner: NonNull<ffi::rocksdb_readoptions_t>,
+    // RocksDB points into these rather than copying them
+    lower_bound: Option<Vec<u8>>,
+    upper_bound: Option<Vec<u8>>,
+    // Kept from being released while RocksDB reads at it
+    snapshot: Option<Arc<SnapshotInner>>,
+}
+
+impl ReadOptions {
+    /// Create read options with RocksDB's defaults
+    pub fn new() -> Self {
         unsafe {
-            ffi::rocksdb_options_set_error_if_exists(self.inner.as_ptr(), value as i32);
+            let ptr = ffi::rocksdb_readoptions_create();
+            ReadOptions {
+                inner: NonNull::new(ptr).expect("Failed to create read options"),
+                lower_bound: None,
+                upper_bound: None,
+                snapshot: None,
+            }
+        }
+    }
+
+    /// Start iteration at `key`: keys before it are never returned
+    ///
+    /// The bound is inclusive. Unlike seeking, it also stops reverse
+    /// iteration and lets RocksDB skip files entirely below it.
+    pub fn set_iterate_lower_bound(&mut self, key: impl Into<Vec<u8>>) -> &mut Self {
+        let bound = self.lower_bound.insert(key.into());
+        unsafe {
+            ffi::rocksdb_readoptions_set_iterate_lower_bound(
+                self.inner.as_ptr(),
+                bound.as_ptr() as *const i8,
+                bound.len(),
+            );
+        }
+        self
+    }
+
+    /// Stop iteration before `key`: it and the keys after it are never returned
+    ///
+    /// The bound is exclusive. Iteration ends as soon as it is reached, so
+    /// deleted keys beyond it are not read.
+    pub fn set_iterate_upper_bound(&mut self, key: impl Into<Vec<u8>>) -> &mut Self {
+        let bound = self.upper_bound.insert(key.into());
+        unsafe {
+            ffi::rocksdb_readoptions_set_iterate_upper_bound(
+                self.inner.as_ptr(),
+                bound.as_ptr() as *const i8,
+                bound.len(),
+            );
+        }
+        self
+    }
+
+    /// Get the lower bound set with [`ReadOptions::set_iterate_lower_bound`]
+    pub fn iterate_lower_bound(&self) -> Option<&[u8]> {
+        self.lower_bound.as_deref()
+    }
+
+    /// Get the upper bound set with [`ReadOptions::set_iterate_upper_bound`]
+    pub fn iterate_upper_bound(&self) -> Option<&[u8]> {
+        self.upper_bound.as_deref()
+    }
+
+    /// Set whether blocks read are added to the block cache (default: true)
+    pub fn set_fill_cache(&mut self, value: bool) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_readoptions_set_fill_cache(self.inner.as_ptr(), value as u8);
+        }
+        self
+    }
+
+    /// Stop iteration at the first key whose prefix differs from the seek key's
+    ///
+    /// Needs a prefix extractor (see [`Options::set_fixed_prefix_extractor`]).
+    pub fn set_prefix_same_as_start(&mut self, value: bool) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_readoptions_set_prefix_same_as_start(self.inner.as_ptr(), value as u8);
+        }
+        self
+    }
+
+    /// Make iterators see keys written after they were created (default: false)
+    ///
+    /// A tailing iterator that has run off the end picks up new writes the
+    /// next time it is positioned with `seek`; it never needs recreating.
+    /// It doesn't take an implicit snapshot, so iteration isn't a
+    /// consistent view and `seek_to_last` and reverse iteration are not
+    /// supported.
+    pub fn set_tailing(&mut self, value: bool) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_readoptions_set_tailing(self.inner.as_ptr(), value as u8);
+        }
+        self
+    }
+
+    /// Read at `snapshot`, keeping it alive as long as these options (internal use only)
+    ///
+    /// Not public because the options don't borrow the database: only
+    /// iterators, which do, may hold the snapshot.
+    pub(crate) fn pin_snapshot(&mut self, snapshot: Arc<SnapshotInner>) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_readoptions_set_snapshot(self.inner.as_ptr(), snapshot.as_ptr());
         }
+        self.snapshot = Some(snapshot);
         self
     }
 
     /// Get the raw pointer for FFI calls
-    pub(crate) fn as_ptr(&self) -> *const ffi::rocksdb_options_t {
+    pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_readoptions_t {
         self.inner.as_ptr()
     }
 }
 
-impl Default for Options {
+impl Default for ReadOptions {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl Drop for Options {
+impl Drop for ReadOptions {
     fn drop(&mut self) {
         // Catch panics to prevent double-panic during unwinding
         let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
-            ffi::rocksdb_options_destroy(self.inner.as_ptr());
+            ffi::rocksdb_readoptions_destroy(self.inner.as_ptr());
         }));
     }
 }
 
-// Options is safe to send between threads
-unsafe impl Send for Options {}
+// ReadOptions is safe to send between threads
+unsafe impl Send for ReadOptions {}
+
+/// Settings for the block-based SST table format
+///
+/// Applied with [`Options::set_block_based_table_factory`].
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{BlockBasedOptions, Options};
+///
+/// let mut table_opts = BlockBasedOptions::default();
+/// table_opts.set_block_size(16 * 1024).set_bloom_filter(10.0);
+///
+/// let mut opts = Options::default();
+/// opts.set_block_based_table_factory(&table_opts);
+/// ```
+#[must_use = "BlockBasedOptions must be applied to Options"]
+pub struct BlockBasedOptions {
+    inner: NonNull<ffi::rocksdb_block_based_table_options_t>,
+    // Not settable through the C API, so applied as an option string
+    read_amp_bytes_per_bit: Option<u32>,
+    // Mirrored for Options::validate, which can't read them back
+    has_filter: bool,
+    whole_key_filtering: bool,
+}
+
+impl BlockBasedOptions {
+    /// Create block-based table options with RocksDB's defaults
+    pub fn new() -> Self {
+        unsafe {
+            let ptr = ffi::rocksdb_block_based_options_create();
+            BlockBasedOptions {
+                inner: NonNull::new(ptr).expect("Failed to create block-based table options"),
+                read_amp_bytes_per_bit: None,
+                has_filter: false,
+                whole_key_filtering: true,
+            }
+        }
+    }
+
+    /// Set the approximate size of uncompressed data per block (default: 4 KiB)
+    ///
+    /// Larger blocks compress better and suit scans; smaller blocks read less
+    /// per point lookup.
+    pub fn set_block_size(&mut self, value: usize) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_block_based_options_set_block_size(self.inner.as_ptr(), value);
+        }
+        self
+    }
+
+    /// Add a bloom filter with the given bits per key to each SST file
+    ///
+    /// 10 bits per key gives a false positive rate of about 1%.
+    pub fn set_bloom_filter(&mut self, bits_per_key: f64) -> &mut Self {
+        unsafe {
+            // The table options take ownership of the policy
+            let policy = ffi::rocksdb_filterpolicy_create_bloom_full(bits_per_key);
+            ffi::rocksdb_block_based_options_set_filter_policy(self.inner.as_ptr(), policy);
+        }
+        self.has_filter = true;
+        self
+    }
+
+    /// Set whether whole keys are added to the filter (default: true)
+    pub fn set_whole_key_filtering(&mut self, value: bool) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_block_based_options_set_whole_key_filtering(
+                self.inner.as_ptr(),
+                value as u8,
+            );
+        }
+        self.whole_key_filtering = value;
+        self
+    }
+
+    /// Set whether index and filter blocks live in the block cache (default: false)
+    ///
+    /// When false they are held in memory for every open SST file, outside
+    /// any memory limit.
+    pub fn set_cache_index_and_filter_blocks(&mut self, value: bool) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_block_based_options_set_cache_index_and_filter_blocks(
+                self.inner.as_ptr(),
+                value as u8,
+            );
+        }
+        self
+    }
+
+    /// Set whether data blocks carry a hash index for point lookups (default: false)
+    ///
+    /// Saves CPU on `get`s at the cost of slightly larger blocks.
+    pub fn set_data_block_hash_index(&mut self, value: bool) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_block_based_options_set_data_block_index_type(
+                self.inner.as_ptr(),
+                value as c_int,
+            );
+        }
+        self
+    }
+
+    /// Track how much of each data block read is actually used (default: 0, off)
+    ///
+    /// Every data block loaded gets a bitmap with one bit per `value` bytes,
+    /// so 32 costs about 0.4% of the block memory. This feeds the
+    /// `ReadAmpTotalReadBytes` and `ReadAmpEstimateUsefulBytes` tickers and
+    /// [`ReadAmpReport::block_read_amplification`](crate::ReadAmpReport::block_read_amplification),
+    /// which stay at 0 without it. RocksDB rounds `value` down to a power of 2.
+    pub fn set_read_amp_bytes_per_bit(&mut self, value: u32) -> &mut Self {
+        self.read_amp_bytes_per_bit = Some(value);
+        self
+    }
+
+    /// Use `cache` as the block cache instead of a private default-sized one
+    ///
+    /// The table options share ownership of the cache.
+    pub fn set_block_cache(&mut self, cache: &Cache) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_block_based_options_set_block_cache(self.inner.as_ptr(), cache.as_ptr());
+        }
+        self
+    }
+
+    /// Get the raw pointer for FFI calls
+    pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_block_based_table_options_t {
+        self.inner.as_ptr()
+    }
+}
+
+impl Default for BlockBasedOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for BlockBasedOptions {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_block_based_options_destroy(self.inner.as_ptr());
+        }));
+    }
+}
+
+// BlockBasedOptions is safe to send between threads
+unsafe impl Send for BlockBasedOptions {}
+
+/// Settings for [`CompactionStyle::Universal`], applied with
+/// [`Options::set_universal_compaction_options`]
+///
+/// Universal compaction merges adjacent sorted runs of similar size. Runs
+/// are picked newest first: a candidate joins the merge while the runs
+/// picked so far total at least its size less `size_ratio` percent.
+/// RocksDB stores the settings as C `int`s, so values above `i32::MAX` are
+/// capped to it.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::UniversalCompactionOptions;
+///
+/// // Merge fewer, larger batches and tolerate more space overhead
+/// let mut universal = UniversalCompactionOptions::default();
+/// universal
+///     .set_min_merge_width(4)
+///     .set_max_merge_width(16)
+///     .set_max_size_amplification_percent(300);
+/// ```
+#[must_use = "UniversalCompactionOptions must be applied to Options"]
+pub struct UniversalCompactionOptions {
+    inner: NonNull<ffi::rocksdb_universal_compaction_options_t>,
+}
+
+impl UniversalCompactionOptions {
+    /// Create universal compaction options with RocksDB's defaults
+    pub fn new() -> Self {
+        unsafe {
+            let ptr = ffi::rocksdb_universal_compaction_options_create();
+            UniversalCompactionOptions {
+                inner: NonNull::new(ptr).expect("Failed to create universal compaction options"),
+            }
+        }
+    }
+
+    /// Set how much smaller, in percent, a run may be than the runs already
+    /// picked and still join the merge (default: 1)
+    pub fn set_size_ratio(&mut self, value: u32) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_universal_compaction_options_set_size_ratio(
+                self.inner.as_ptr(),
+                clamp_c_int(value),
+            );
+        }
+        self
+    }
+
+    /// Set the fewest sorted runs merged in one compaction (default: 2)
+    pub fn set_min_merge_width(&mut self, value: u32) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_universal_compaction_options_set_min_merge_width(
+                self.inner.as_ptr(),
+                clamp_c_int(value),
+            );
+        }
+        self
+    }
+
+    /// Set the most sorted runs merged in one compaction (default: unlimited)
+    pub fn set_max_merge_width(&mut self, value: u32) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_universal_compaction_options_set_max_merge_width(
+                self.inner.as_ptr(),
+                clamp_c_int(value),
+            );
+        }
+        self
+    }
+
+    /// Set the extra space, in percent of the oldest run, that newer runs
+    /// may take before everything is merged into one run (default: 200)
+    ///
+    /// With the default, 100 bytes of fully compacted data can take up to
+    /// 300 bytes on disk. Lower values reclaim space from overwritten and
+    /// deleted keys sooner at the cost of more full merges.
+    pub fn set_max_size_amplification_percent(&mut self, value: u32) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_universal_compaction_options_set_max_size_amplification_percent(
+                self.inner.as_ptr(),
+                clamp_c_int(value),
+            );
+        }
+        self
+    }
+
+    /// Get the raw pointer for FFI calls
+    pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_universal_compaction_options_t {
+        self.inner.as_ptr()
+    }
+}
+
+/// Convert a setting to a C `int`, capping it at `c_int::MAX`
+fn clamp_c_int(value: u32) -> c_int {
+    value.min(c_int::MAX as u32) as c_int
+}
+
+impl Default for UniversalCompactionOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for UniversalCompactionOptions {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_universal_compaction_options_destroy(self.inner.as_ptr());
+        }));
+    }
+}
+
+// UniversalCompactionOptions is safe to send between threads
+unsafe impl Send for UniversalCompactionOptions {}
+
+/// Settings for [`CompactionStyle::Fifo`], applied with
+/// [`Options::set_fifo_compaction_options`]
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::FifoCompactionOptions;
+///
+/// let mut fifo = FifoCompactionOptions::default();
+/// fifo.set_max_table_files_size(256 * 1024 * 1024)
+///     .set_allow_compaction(true);
+/// ```
+#[must_use = "FifoCompactionOptions must be applied to Options"]
+pub struct FifoCompactionOptions {
+    inner: NonNull<ffi::rocksdb_fifo_compaction_options_t>,
+}
+
+impl FifoCompactionOptions {
+    /// Create FIFO compaction options with RocksDB's defaults
+    pub fn new() -> Self {
+        unsafe {
+            let ptr = ffi::rocksdb_fifo_compaction_options_create();
+            FifoCompactionOptions {
+                inner: NonNull::new(ptr).expect("Failed to create FIFO compaction options"),
+            }
+        }
+    }
+
+    /// Set the total size of SST files above which the oldest files are
+    /// deleted, in bytes (default: 1 GiB)
+    ///
+    /// Data still in memtables doesn't count, so the column family can
+    /// briefly hold up to a memtable's worth more.
+    pub fn set_max_table_files_size(&mut self, value: u64) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_fifo_compaction_options_set_max_table_files_size(
+                self.inner.as_ptr(),
+                value,
+            );
+        }
+        self
+    }
+
+    /// Set whether small L0 files are merged into larger ones (default: false)
+    ///
+    /// Fewer files make reads cheaper, at the cost of rewriting the data
+    /// once. Deletion still happens a whole file at a time.
+    pub fn set_allow_compaction(&mut self, value: bool) -> &mut Self {
+        unsafe {
+            ffi::rocksdb_fifo_compaction_options_set_allow_compaction(
+                self.inner.as_ptr(),
+                value as u8,
+            );
+        }
+        self
+    }
+
+    /// Get the raw pointer for FFI calls
+    pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_fifo_compaction_options_t {
+        self.inner.as_ptr()
+    }
+}
+
+impl Default for FifoCompactionOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for FifoCompactionOptions {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_fifo_compaction_options_destroy(self.inner.as_ptr());
+        }));
+    }
+}
+
+// FifoCompactionOptions is safe to send between threads
+unsafe impl Send for FifoCompactionOptions {}
@@ -0,0 +1,135 @@
+//! Iterators that keep their database open
+
+use crate::cancel::CancellationToken;
+use crate::db::{ColumnFamilyHandle, DB};
+use crate::error::Result;
+use crate::iterator::{DBIteratorAdapter, Direction, Entry, KeyRange};
+use std::sync::Arc;
+
+/// A scan that owns a shared reference to its database
+///
+/// A [`DBIteratorAdapter`] borrows its database, which ties the scan to the
+/// stack frame holding the reference. An `OwnedIterator` holds an `Arc<DB>`
+/// instead, so it is `'static` and `Send` and can be moved into a worker
+/// thread or an async task; the database stays open until it is dropped. It
+/// is not `Sync`: a RocksDB iterator may move between threads but not be
+/// used from two at once.
+///
+/// Yields the same items as [`DBIteratorAdapter`]. Reads block, so in an
+/// async runtime run the scan on a blocking thread.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options, OwnedIterator};
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = Arc::new(DB::open(&opts, "/tmp/my_db").unwrap());
+///
+/// let users = OwnedIterator::prefix(&db, b"user:");
+/// let count = thread::spawn(move || users.count()).join().unwrap();
+/// ```
+#[must_use = "Iterators are lazy and do nothing unless consumed"]
+pub struct OwnedIterator {
+    // Declared first so the iterator is destroyed before what it reads from
+    inner: DBIteratorAdapter<'static>,
+    _cf_handle: Option<ColumnFamilyHandle>,
+    _db: Arc<DB>,
+}
+
+impl OwnedIterator {
+    /// Iterate over every key of the default column family in `direction`
+    ///
+    /// See [`DB::iter`].
+    pub fn new(db: &Arc<DB>, direction: Direction) -> Self {
+        let inner = db.iter(direction);
+        // SAFETY: the iterator is destroyed before the database it borrows
+        unsafe { Self::from_parts(db, None, inner) }
+    }
+
+    /// Iterate forward over the keys in `range` of the default column family
+    ///
+    /// See [`DB::range`].
+    pub fn range(db: &Arc<DB>, range: impl KeyRange) -> Self {
+        let inner = db.range(range);
+        // SAFETY: the iterator is destroyed before the database it borrows
+        unsafe { Self::from_parts(db, None, inner) }
+    }
+
+    /// Iterate forward over the keys in `range` of a column family
+    ///
+    /// See [`DB::range_cf`].
+    pub fn range_cf(db: &Arc<DB>, cf_handle: &ColumnFamilyHandle, range: impl KeyRange) -> Self {
+        let inner = db.range_cf(cf_handle, range);
+        // SAFETY: the iterator is destroyed before the handle and database it borrows
+        unsafe { Self::from_parts(db, Some(cf_handle), inner) }
+    }
+
+    /// Iterate forward over the keys of the default column family starting with `prefix`
+    ///
+    /// See [`DB::prefix_iter`].
+    pub fn prefix(db: &Arc<DB>, prefix: &[u8]) -> Self {
+        let inner = db.prefix_iter(prefix);
+        // SAFETY: the iterator is destroyed before the database it borrows
+        unsafe { Self::from_parts(db, None, inner) }
+    }
+
+    /// Keep `db` and `cf_handle` alive for as long as an iterator over them
+    ///
+    /// # Safety
+    ///
+    /// `inner` must borrow nothing but `db` and `cf_handle`.
+    pub(crate) unsafe fn from_parts(
+        db: &Arc<DB>,
+        cf_handle: Option<&ColumnFamilyHandle>,
+        inner: DBIteratorAdapter<'_>,
+    ) -> Self {
+        OwnedIterator {
+            // The borrowed database and handle are kept alive by the fields below
+            inner: unsafe {
+                std::mem::transmute::<DBIteratorAdapter<'_>, DBIteratorAdapter<'static>>(inner)
+            },
+            _cf_handle: cf_handle.cloned(),
+            _db: Arc::clone(db),
+        }
+    }
+
+    /// Stop the scan once `token` is cancelled
+    ///
+    /// See [`DBIteratorAdapter::with_cancellation`].
+    pub fn with_cancellation(mut self, token: &CancellationToken) -> Self {
+        self.inner = self.inner.with_cancellation(token);
+        self
+    }
+
+    /// Read `entries` entries at a time into an internal buffer
+    ///
+    /// See [`DBIteratorAdapter::with_prefetch`].
+    pub fn with_prefetch(mut self, entries: usize) -> Self {
+        self.inner = self.inner.with_prefetch(entries);
+        self
+    }
+
+    /// Turn the scan into a `Send` stream read in chunks as it is polled
+    ///
+    /// See [`DbStream`](crate::DbStream). Requires the `async` feature.
+    #[cfg(feature = "async")]
+    pub fn into_stream(self) -> crate::stream::DbStream<OwnedIterator> {
+        crate::stream::DbStream::new_owned(self)
+    }
+}
+
+impl Iterator for OwnedIterator {
+    type Item = Result<Entry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+// A RocksDB iterator may be used from any thread, one at a time, and the
+// database and handle it reads from are shared and thread-safe
+unsafe impl Send for OwnedIterator {}
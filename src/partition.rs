@@ -0,0 +1,293 @@
+//! Time-partitioned column families
+//!
+//! A [`Partitioner`] stores each time window (an hour or a day) in its own
+//! column family. Expiring old data is then a matter of dropping whole column
+//! families, which is far cheaper than deleting keys one by one.
+
+use crate::db::{ColumnFamilyHandle, DB};
+use crate::error::{Error, ErrorKind, Result};
+use crate::iterator::MergeIterator;
+use crate::options::Options;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Mutex, RwLock};
+
+/// Length of the time window stored in one partition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartitionPeriod {
+    /// One partition per hour
+    Hourly,
+    /// One partition per day (UTC)
+    Daily,
+}
+
+impl PartitionPeriod {
+    /// Get the length of the period in seconds
+    pub fn seconds(self) -> u64 {
+        match self {
+            PartitionPeriod::Hourly => 60 * 60,
+            PartitionPeriod::Daily => 24 * 60 * 60,
+        }
+    }
+
+    /// Get the start of the period containing `timestamp`
+    fn start_of(self, timestamp: u64) -> u64 {
+        timestamp - timestamp % self.seconds()
+    }
+}
+
+/// A database whose data is split into time-partitioned column families
+///
+/// Each partition is a column family named `<prefix><start>`, where `<start>`
+/// is the first second (since the UNIX epoch) covered by the partition. Writes
+/// are routed to the partition containing their timestamp, creating it on
+/// first use. [`Partitioner::roll`] drops partitions that fell out of the
+/// retention window and should be called periodically (for example from a
+/// timer once per period).
+///
+/// Existing partitions are discovered when the database is opened. Column
+/// families that do not match the naming scheme are opened but not exposed.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{Options, PartitionPeriod, Partitioner};
+/// use std::time::{SystemTime, UNIX_EPOCH};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+///
+/// // Keep the last 7 days of events
+/// let events = Partitioner::open(opts, "/tmp/events", "events_", PartitionPeriod::Daily, 7)
+///     .unwrap();
+///
+/// let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+/// events.put(now, b"event:1", b"login").unwrap();
+///
+/// // Drop partitions older than 7 days
+/// let dropped = events.roll(now).unwrap();
+/// println!("Dropped partitions: {:?}", dropped);
+/// ```
+#[must_use = "Database handle must be stored or the database will be immediately closed"]
+pub struct Partitioner {
+    // Declared before `db` so the handles are destroyed before the database closes
+    partitions: RwLock<BTreeMap<u64, ColumnFamilyHandle>>,
+    db: DB,
+    // Options aren't Sync; only used to create partitions, which holds the write lock anyway
+    options: Mutex<Options>,
+    prefix: String,
+    period: PartitionPeriod,
+    retention: usize,
+}
+
+impl Partitioner {
+    /// Open (or create) a partitioned database
+    ///
+    /// `options` are used both to open the database and to create new
+    /// partitions. `retention` is the number of partitions kept by
+    /// [`Partitioner::roll`], including the current one.
+    pub fn open<P: AsRef<Path>>(
+        options: Options,
+        path: P,
+        prefix: &str,
+        period: PartitionPeriod,
+        retention: usize,
+    ) -> Result<Self> {
+        if retention == 0 {
+            return Err(Error::with_kind(
+                ErrorKind::InvalidArgument,
+                "Retention must keep at least one partition",
+            ));
+        }
+
+        let path = path.as_ref();
+
+        // An existing database must be opened with all of its column families
+        let mut names = vec!["default".to_string()];
+        if path.join("CURRENT").exists() {
+            names.extend(
+                DB::list_column_families(&options, path)?
+                    .into_iter()
+                    .filter(|name| name != "default"),
+            );
+        }
+
+        let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+        let cf_options: Vec<Options> = names.iter().map(|_| options.clone()).collect();
+        let (db, handles) = DB::open_with_column_families(&options, path, &name_refs, &cf_options)?;
+
+        let mut partitions = BTreeMap::new();
+        for (name, handle) in names.iter().zip(handles) {
+            if let Some(start) = parse_partition_name(prefix, period, name) {
+                partitions.insert(start, handle);
+            }
+        }
+
+        Ok(Partitioner {
+            partitions: RwLock::new(partitions),
+            db,
+            options: Mutex::new(options),
+            prefix: prefix.to_string(),
+            period,
+            retention,
+        })
+    }
+
+    /// Get the underlying database
+    pub fn db(&self) -> &DB {
+        &self.db
+    }
+
+    /// Get the column family name of the partition containing `timestamp`
+    pub fn partition_name(&self, timestamp: u64) -> String {
+        format!("{}{}", self.prefix, self.period.start_of(timestamp))
+    }
+
+    /// Get the start timestamps of all current partitions, oldest first
+    pub fn partitions(&self) -> Vec<u64> {
+        self.read_partitions().keys().copied().collect()
+    }
+
+    /// Write a key-value pair to the partition containing `timestamp`
+    ///
+    /// The partition is created if it does not exist yet. Writes older than
+    /// the retention window (relative to the newest partition) are rejected
+    /// so that expired partitions are not recreated.
+    pub fn put(&self, timestamp: u64, key: &[u8], value: &[u8]) -> Result<()> {
+        let start = self.period.start_of(timestamp);
+
+        if let Some(handle) = self.read_partitions().get(&start) {
+            return self.db.put_cf(handle, key, value);
+        }
+
+        let mut partitions = self.write_partitions();
+        // Another writer may have created the partition in the meantime
+        if let Some(handle) = partitions.get(&start) {
+            return self.db.put_cf(handle, key, value);
+        }
+
+        if let Some(&newest) = partitions.keys().next_back()
+            && start < self.retention_cutoff(newest)
+        {
+            return Err(Error::with_kind(
+                ErrorKind::InvalidArgument,
+                "Timestamp is older than the retention window",
+            ));
+        }
+
+        let handle = self
+            .db
+            .create_column_family(&self.lock_options(), &self.partition_name(start))?;
+        let result = self.db.put_cf(&handle, key, value);
+        partitions.insert(start, handle);
+        result
+    }
+
+    /// Read a key from the partition containing `timestamp`
+    ///
+    /// Returns `None` if the key or the partition does not exist.
+    pub fn get(&self, timestamp: u64, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let start = self.period.start_of(timestamp);
+
+        match self.read_partitions().get(&start) {
+            Some(handle) => self.db.get_cf(handle, key),
+            None => Ok(None),
+        }
+    }
+
+    /// Advance the schedule to `now` and drop expired partitions
+    ///
+    /// Creates the partition for `now` ahead of the first write, then drops
+    /// every partition outside the newest `retention` periods. Returns the
+    /// names of the dropped column families.
+    pub fn roll(&self, now: u64) -> Result<Vec<String>> {
+        let current = self.period.start_of(now);
+        let mut partitions = self.write_partitions();
+
+        let newest = partitions
+            .keys()
+            .next_back()
+            .map_or(current, |&newest| newest.max(current));
+        let cutoff = self.retention_cutoff(newest);
+
+        if current >= cutoff && !partitions.contains_key(&current) {
+            let handle = self
+                .db
+                .create_column_family(&self.lock_options(), &self.partition_name(current))?;
+            partitions.insert(current, handle);
+        }
+
+        let expired: Vec<u64> = partitions
+            .range(..cutoff)
+            .map(|(&start, _)| start)
+            .collect();
+        let mut dropped = Vec::with_capacity(expired.len());
+        for start in expired {
+            // Keep the partition if the drop fails, so a later roll retries it
+            if let Some(handle) = partitions.get(&start) {
+                self.db.drop_column_family(handle.clone())?;
+                partitions.remove(&start);
+                dropped.push(self.partition_name(start));
+            }
+        }
+
+        Ok(dropped)
+    }
+
+    /// Scan all partitions as one key-ordered view
+    ///
+    /// The closure receives a [`MergeIterator`] over every current partition.
+    /// Partitions cannot be created or dropped while the scan runs.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use rust_small_rocksdb::{Options, PartitionPeriod, Partitioner};
+    /// # let mut opts = Options::default();
+    /// # opts.create_if_missing(true);
+    /// # let events =
+    /// #     Partitioner::open(opts, "/tmp/events", "events_", PartitionPeriod::Daily, 7).unwrap();
+    /// let count = events.scan(|iter| iter.filter_map(|item| item.ok()).count());
+    /// println!("{} events retained", count);
+    /// ```
+    pub fn scan<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(MergeIterator<'_>) -> R,
+    {
+        let partitions = self.read_partitions();
+        let handles: Vec<&ColumnFamilyHandle> = partitions.values().collect();
+        f(self.db.merge_iter(&handles))
+    }
+
+    /// Get the start of the oldest partition retained alongside `newest`
+    fn retention_cutoff(&self, newest: u64) -> u64 {
+        newest.saturating_sub((self.retention as u64 - 1) * self.period.seconds())
+    }
+
+    fn read_partitions(&self) -> std::sync::RwLockReadGuard<'_, BTreeMap<u64, ColumnFamilyHandle>> {
+        // A panic while holding the lock cannot leave the map half-updated
+        self.partitions
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write_partitions(
+        &self,
+    ) -> std::sync::RwLockWriteGuard<'_, BTreeMap<u64, ColumnFamilyHandle>> {
+        self.partitions
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_options(&self) -> std::sync::MutexGuard<'_, Options> {
+        self.options
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Parse a column family name of the form `<prefix><start>` into its start timestamp
+fn parse_partition_name(prefix: &str, period: PartitionPeriod, name: &str) -> Option<u64> {
+    let start: u64 = name.strip_prefix(prefix)?.parse().ok()?;
+    start.is_multiple_of(period.seconds()).then_some(start)
+}
@@ -0,0 +1,262 @@
+//! Per-operation profiling with RocksDB's perf context
+//!
+//! The perf context counts what individual operations did internally (block
+//! reads, bloom filter checks, memtable lookups, time spent in each step),
+//! which is what attributes a slow request to its cause. Counting is off by
+//! default and enabled per thread with [`PerfContext::set_level`].
+//!
+//! RocksDB's IOStatsContext (per-thread file I/O counters) is not exposed by
+//! the C API; [`PerfMetric::BlockReadByte`] and [`PerfMetric::BlockReadTime`]
+//! cover the SST reads that usually matter.
+//!
+//! RocksDB builds compiled with `NPERF_CONTEXT` leave the perf context out
+//! entirely; [`PerfContext::new`] then fails with
+//! [`ErrorKind::NotSupported`] rather than handing out counters that never
+//! move.
+
+use crate::db::take_c_string;
+use crate::error::{Error, ErrorKind, Result};
+use crate::ffi;
+use std::marker::PhantomData;
+use std::os::raw::c_int;
+use std::ptr::NonNull;
+
+/// How much detail the perf context collects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerfLevel {
+    /// Collect nothing (default)
+    Disable,
+    /// Collect counters only
+    EnableCount,
+    /// Collect counters and timings, except timings that need a mutex
+    EnableTimeExceptForMutex,
+    /// Collect counters and all timings
+    EnableTime,
+}
+
+impl PerfLevel {
+    /// Get the value used by the RocksDB C API
+    fn as_raw(self) -> c_int {
+        match self {
+            PerfLevel::Disable => 1,
+            PerfLevel::EnableCount => 2,
+            PerfLevel::EnableTimeExceptForMutex => 3,
+            PerfLevel::EnableTime => 4,
+        }
+    }
+}
+
+/// A counter recorded by the perf context
+///
+/// Times are in nanoseconds and are only collected with
+/// [`PerfLevel::EnableTimeExceptForMutex`] or higher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PerfMetric {
+    /// Key comparisons made by the comparator
+    UserKeyComparisonCount,
+    /// Blocks found in the block cache
+    BlockCacheHitCount,
+    /// Blocks read from SST files
+    BlockReadCount,
+    /// Bytes of blocks read from SST files
+    BlockReadByte,
+    /// Time spent reading blocks from SST files
+    BlockReadTime,
+    /// Time spent verifying block checksums
+    BlockChecksumTime,
+    /// Time spent decompressing blocks
+    BlockDecompressTime,
+    /// Bytes returned by gets
+    GetReadBytes,
+    /// Bytes returned by multi-gets
+    MultigetReadBytes,
+    /// Bytes returned by iterators
+    IterReadBytes,
+    /// Internal keys skipped over by iterators (old versions, hidden entries)
+    InternalKeySkippedCount,
+    /// Tombstones skipped over by iterators
+    InternalDeleteSkippedCount,
+    /// Time spent looking up keys in memtables
+    GetFromMemtableTime,
+    /// Memtables searched by gets
+    GetFromMemtableCount,
+    /// Time spent looking up keys in SST files
+    GetFromOutputFilesTime,
+    /// Time spent seeking in memtables
+    SeekOnMemtableTime,
+    /// Time spent seeking child iterators
+    SeekChildSeekTime,
+    /// Child iterator seeks
+    SeekChildSeekCount,
+    /// Time spent finding the next user key while iterating
+    FindNextUserEntryTime,
+    /// Time spent writing to the WAL
+    WriteWalTime,
+    /// Time spent writing to memtables
+    WriteMemtableTime,
+    /// Time writes were delayed by write stalls
+    WriteDelayTime,
+    /// Time spent waiting for the DB mutex
+    DbMutexLockNanos,
+    /// Time spent reading index blocks
+    ReadIndexBlockNanos,
+    /// Time spent reading filter blocks
+    ReadFilterBlockNanos,
+    /// Time spent finding and opening table readers
+    FindTableNanos,
+    /// Memtable bloom filter checks that passed
+    BloomMemtableHitCount,
+    /// Memtable bloom filter checks that ruled out the memtable
+    BloomMemtableMissCount,
+    /// SST bloom filter checks that passed
+    BloomSstHitCount,
+    /// SST bloom filter checks that ruled out the file
+    BloomSstMissCount,
+    /// Blob values read from blob files
+    BlobReadCount,
+    /// Bytes of blob values read from blob files
+    BlobReadByte,
+}
+
+impl PerfMetric {
+    /// Get the value used by the RocksDB C API
+    fn as_raw(self) -> c_int {
+        match self {
+            PerfMetric::UserKeyComparisonCount => 0,
+            PerfMetric::BlockCacheHitCount => 1,
+            PerfMetric::BlockReadCount => 2,
+            PerfMetric::BlockReadByte => 3,
+            PerfMetric::BlockReadTime => 4,
+            PerfMetric::BlockChecksumTime => 5,
+            PerfMetric::BlockDecompressTime => 6,
+            PerfMetric::GetReadBytes => 7,
+            PerfMetric::MultigetReadBytes => 8,
+            PerfMetric::IterReadBytes => 9,
+            PerfMetric::InternalKeySkippedCount => 10,
+            PerfMetric::InternalDeleteSkippedCount => 11,
+            PerfMetric::GetFromMemtableTime => 15,
+            PerfMetric::GetFromMemtableCount => 16,
+            PerfMetric::GetFromOutputFilesTime => 18,
+            PerfMetric::SeekOnMemtableTime => 19,
+            PerfMetric::SeekChildSeekTime => 23,
+            PerfMetric::SeekChildSeekCount => 24,
+            PerfMetric::FindNextUserEntryTime => 28,
+            PerfMetric::WriteWalTime => 29,
+            PerfMetric::WriteMemtableTime => 30,
+            PerfMetric::WriteDelayTime => 31,
+            PerfMetric::DbMutexLockNanos => 33,
+            PerfMetric::ReadIndexBlockNanos => 36,
+            PerfMetric::ReadFilterBlockNanos => 37,
+            PerfMetric::FindTableNanos => 41,
+            PerfMetric::BloomMemtableHitCount => 42,
+            PerfMetric::BloomMemtableMissCount => 43,
+            PerfMetric::BloomSstHitCount => 44,
+            PerfMetric::BloomSstMissCount => 45,
+            PerfMetric::BlobReadCount => 70,
+            PerfMetric::BlobReadByte => 71,
+        }
+    }
+}
+
+/// The calling thread's perf context
+///
+/// RocksDB keeps one perf context per thread, recording the operations
+/// that thread performs; this handle reads the context of the thread that
+/// created it and therefore cannot be sent to another thread.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options, PerfContext, PerfLevel, PerfMetric};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// PerfContext::set_level(PerfLevel::EnableTimeExceptForMutex);
+/// let mut perf = PerfContext::new().unwrap();
+///
+/// perf.reset();
+/// db.get(b"key").unwrap();
+/// println!(
+///     "blocks read: {}, bloom misses: {}",
+///     perf.metric(PerfMetric::BlockReadCount),
+///     perf.metric(PerfMetric::BloomSstMissCount)
+/// );
+/// println!("{}", perf.report(true));
+///
+/// PerfContext::set_level(PerfLevel::Disable);
+/// ```
+pub struct PerfContext {
+    inner: NonNull<ffi::rocksdb_perfcontext_t>,
+    // The context is thread-local, so the handle must stay on its thread
+    _not_send: PhantomData<*const ()>,
+}
+
+impl PerfContext {
+    /// Set how much the calling thread's perf context collects
+    ///
+    /// The level is per thread and applies until changed.
+    pub fn set_level(level: PerfLevel) {
+        unsafe {
+            ffi::rocksdb_set_perf_level(level.as_raw());
+        }
+    }
+
+    /// Get a handle to the calling thread's perf context
+    ///
+    /// Fails with [`ErrorKind::NotSupported`] if the linked RocksDB was built
+    /// without the perf context.
+    pub fn new() -> Result<Self> {
+        let perf = unsafe {
+            let ptr = ffi::rocksdb_perfcontext_create();
+            PerfContext {
+                inner: NonNull::new(ptr)
+                    .ok_or_else(|| Error::new("Failed to create perf context"))?,
+                _not_send: PhantomData,
+            }
+        };
+        // A full report lists every counter, even at zero, unless the
+        // perf context was compiled out, in which case it is empty
+        if perf.report(false).is_empty() {
+            return Err(Error::with_kind(
+                ErrorKind::NotSupported,
+                "The linked RocksDB was built without the perf context",
+            ));
+        }
+        Ok(perf)
+    }
+
+    /// Zero all counters, typically right before the operation to profile
+    pub fn reset(&mut self) {
+        unsafe {
+            ffi::rocksdb_perfcontext_reset(self.inner.as_ptr());
+        }
+    }
+
+    /// Get the value of one counter since the last reset
+    pub fn metric(&self, metric: PerfMetric) -> u64 {
+        unsafe { ffi::rocksdb_perfcontext_metric(self.inner.as_ptr(), metric.as_raw()) }
+    }
+
+    /// Get a human-readable dump of every counter since the last reset
+    ///
+    /// With `exclude_zero_counters` only the counters that moved are listed.
+    pub fn report(&self, exclude_zero_counters: bool) -> String {
+        unsafe {
+            let ptr =
+                ffi::rocksdb_perfcontext_report(self.inner.as_ptr(), exclude_zero_counters as u8);
+            take_c_string(ptr).unwrap_or_default()
+        }
+    }
+}
+
+impl Drop for PerfContext {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_perfcontext_destroy(self.inner.as_ptr());
+        }));
+    }
+}
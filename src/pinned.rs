@@ -0,0 +1,74 @@
+//! Values read without copying them out of RocksDB
+
+use crate::ffi;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+use std::slice;
+
+/// A value returned by [`DB::get_pinned`](crate::DB::get_pinned), read in place
+///
+/// The bytes stay where RocksDB found them (a block cache entry or a
+/// memtable), which are pinned until the slice is dropped. Dereferences to
+/// `[u8]`. Drop it promptly: a pinned block can't be evicted from the cache.
+pub struct PinnedSlice<'a> {
+    inner: NonNull<ffi::rocksdb_pinnableslice_t>,
+    _phantom: PhantomData<&'a ()>,
+}
+
+impl<'a> PinnedSlice<'a> {
+    /// Wrap a slice returned by `rocksdb_get_pinned*` (internal use only)
+    pub(crate) unsafe fn new(inner: NonNull<ffi::rocksdb_pinnableslice_t>) -> Self {
+        PinnedSlice {
+            inner,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a> std::ops::Deref for PinnedSlice<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe {
+            let mut len: usize = 0;
+            let ptr = ffi::rocksdb_pinnableslice_value(self.inner.as_ptr(), &mut len);
+            if ptr.is_null() {
+                return &[];
+            }
+            // SAFETY: the value stays pinned until the slice is destroyed
+            slice::from_raw_parts(ptr as *const u8, len)
+        }
+    }
+}
+
+impl<'a> AsRef<[u8]> for PinnedSlice<'a> {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<'a> PartialEq<[u8]> for PinnedSlice<'a> {
+    fn eq(&self, other: &[u8]) -> bool {
+        **self == *other
+    }
+}
+
+impl<'a> fmt::Debug for PinnedSlice<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PinnedSlice").field(&&**self).finish()
+    }
+}
+
+impl<'a> Drop for PinnedSlice<'a> {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_pinnableslice_destroy(self.inner.as_ptr());
+        }));
+    }
+}
+
+// The pinned value is immutable, and RocksDB may release the pin from any thread
+unsafe impl<'a> Send for PinnedSlice<'a> {}
+unsafe impl<'a> Sync for PinnedSlice<'a> {}
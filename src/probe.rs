@@ -0,0 +1,347 @@
+//! Offline inspection of a database directory before opening it
+//!
+//! A supervisor restarting a crashed process has to decide whether to open
+//! the database, repair it or restore it from a backup. [`DB::probe`](crate::DB::probe)
+//! looks at the files that decide this (the `LOCK` file, `CURRENT`, the
+//! MANIFEST it names and the WAL files) without opening the database.
+
+use crate::checksum::crc32c;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What [`DB::probe`](crate::DB::probe) found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProbeStatus {
+    /// Everything checked is in order
+    Healthy,
+    /// There is no database at the path
+    NotFound,
+    /// Another open instance holds the database lock
+    Locked,
+    /// Database files exist but `CURRENT` does not
+    MissingCurrent,
+    /// `CURRENT` does not name a MANIFEST file
+    InvalidCurrent,
+    /// The MANIFEST named by `CURRENT` does not exist
+    MissingManifest,
+    /// The newest WAL ends in a partially written record, as left by a crash
+    TornWal,
+    /// A WAL record failed its checksum or is malformed before the end of the log
+    CorruptWal,
+}
+
+/// What to do about a [`ProbeStatus`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeAction {
+    /// Open the database normally
+    Open,
+    /// Create a new database (or restore one, if one was expected)
+    Create,
+    /// Wait for the running instance to close the database
+    Wait,
+    /// Run [`DB::repair`](crate::DB::repair) before opening
+    Repair,
+    /// Restore from a backup; too little is left to repair
+    Restore,
+}
+
+/// The result of probing a database directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeReport {
+    /// What was found
+    pub status: ProbeStatus,
+    /// The suggested next step
+    pub action: ProbeAction,
+    /// A human-readable explanation, naming the file involved
+    pub detail: String,
+}
+
+impl ProbeReport {
+    fn new(status: ProbeStatus, action: ProbeAction, detail: impl Into<String>) -> Self {
+        ProbeReport {
+            status,
+            action,
+            detail: detail.into(),
+        }
+    }
+
+    /// Check whether the database can be opened as is
+    pub fn is_healthy(&self) -> bool {
+        self.status == ProbeStatus::Healthy
+    }
+}
+
+/// Inspect the database directory at `path`
+pub(crate) fn probe(path: &Path) -> ProbeReport {
+    let names: Vec<String> = match fs::read_dir(path) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect(),
+        Err(_) => {
+            return ProbeReport::new(
+                ProbeStatus::NotFound,
+                ProbeAction::Create,
+                format!("{} does not exist or cannot be read", path.display()),
+            );
+        }
+    };
+
+    let has_tables = names.iter().any(|name| name.ends_with(".sst"));
+    let has_manifests = names.iter().any(|name| name.starts_with("MANIFEST-"));
+    // Repair rebuilds the MANIFEST from the table files, so it needs some
+    let damaged_action = if has_tables {
+        ProbeAction::Repair
+    } else {
+        ProbeAction::Restore
+    };
+
+    if is_locked(&path.join("LOCK")) {
+        return ProbeReport::new(
+            ProbeStatus::Locked,
+            ProbeAction::Wait,
+            format!(
+                "{} is locked by an open instance",
+                path.join("LOCK").display()
+            ),
+        );
+    }
+
+    let current = match fs::read_to_string(path.join("CURRENT")) {
+        Ok(current) => current,
+        Err(_) if !has_tables && !has_manifests => {
+            return ProbeReport::new(
+                ProbeStatus::NotFound,
+                ProbeAction::Create,
+                format!("{} holds no database", path.display()),
+            );
+        }
+        Err(_) => {
+            return ProbeReport::new(
+                ProbeStatus::MissingCurrent,
+                damaged_action,
+                format!("{} is missing", path.join("CURRENT").display()),
+            );
+        }
+    };
+
+    let manifest = current.trim_end_matches('\n');
+    if !manifest.starts_with("MANIFEST-") || manifest.contains(['/', '\n']) {
+        return ProbeReport::new(
+            ProbeStatus::InvalidCurrent,
+            damaged_action,
+            format!("CURRENT names {:?}, which is not a MANIFEST", manifest),
+        );
+    }
+    if !path.join(manifest).is_file() {
+        return ProbeReport::new(
+            ProbeStatus::MissingManifest,
+            damaged_action,
+            format!(
+                "{} named by CURRENT is missing",
+                path.join(manifest).display()
+            ),
+        );
+    }
+
+    // WAL files are numbered; only the newest may legitimately be torn
+    let mut wals: Vec<(u64, PathBuf)> = names
+        .iter()
+        .filter_map(|name| {
+            let number = name.strip_suffix(".log")?.parse().ok()?;
+            Some((number, path.join(name)))
+        })
+        .collect();
+    wals.sort();
+
+    let newest = wals.last().map(|(number, _)| *number);
+    for (number, wal) in &wals {
+        let data = match fs::read(wal) {
+            Ok(data) => data,
+            Err(e) => {
+                return ProbeReport::new(
+                    ProbeStatus::CorruptWal,
+                    ProbeAction::Repair,
+                    format!("{} cannot be read: {}", wal.display(), e),
+                );
+            }
+        };
+
+        match check_wal(&data) {
+            WalState::Intact => {}
+            WalState::Torn(offset) if Some(*number) == newest => {
+                return ProbeReport::new(
+                    ProbeStatus::TornWal,
+                    ProbeAction::Open,
+                    format!(
+                        "{} ends in a partial record at offset {}; recovery drops it",
+                        wal.display(),
+                        offset
+                    ),
+                );
+            }
+            WalState::Torn(offset) | WalState::Corrupt(offset) => {
+                return ProbeReport::new(
+                    ProbeStatus::CorruptWal,
+                    ProbeAction::Repair,
+                    format!("{} has a bad record at offset {}", wal.display(), offset),
+                );
+            }
+        }
+    }
+
+    ProbeReport::new(
+        ProbeStatus::Healthy,
+        ProbeAction::Open,
+        format!("{} looks consistent", path.display()),
+    )
+}
+
+/// Check whether another open instance holds the lock file
+///
+/// Reads `/proc/locks` rather than testing the lock itself: opening and
+/// closing the file would release a lock held by this very process.
+#[cfg(target_os = "linux")]
+fn is_locked(lock_path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    let Ok(metadata) = fs::metadata(lock_path) else {
+        return false;
+    };
+    let Ok(locks) = fs::read_to_string("/proc/locks") else {
+        return false;
+    };
+
+    let id = format!(
+        "{:02x}:{:02x}:{}",
+        libc::major(metadata.dev()),
+        libc::minor(metadata.dev()),
+        metadata.ino()
+    );
+    locks
+        .lines()
+        .any(|line| line.split_whitespace().any(|field| field == id))
+}
+
+/// Lock detection needs `/proc/locks`; elsewhere databases are never reported locked
+#[cfg(not(target_os = "linux"))]
+fn is_locked(_lock_path: &Path) -> bool {
+    false
+}
+
+/// The WAL is written in blocks of this size; records never span blocks
+const WAL_BLOCK_SIZE: usize = 32 * 1024;
+/// Record header: checksum (4), length (2), type (1)
+const WAL_HEADER_SIZE: usize = 7;
+/// Recyclable record header: the above plus the log number (4)
+const WAL_RECYCLABLE_HEADER_SIZE: usize = 11;
+
+/// Record types, from RocksDB's `log_format.h`
+const WAL_FULL: u8 = 1;
+const WAL_FIRST: u8 = 2;
+const WAL_MIDDLE: u8 = 3;
+const WAL_LAST: u8 = 4;
+const WAL_RECYCLABLE_FULL: u8 = 5;
+const WAL_RECYCLABLE_FIRST: u8 = 6;
+const WAL_RECYCLABLE_MIDDLE: u8 = 7;
+const WAL_RECYCLABLE_LAST: u8 = 8;
+const WAL_RECYCLABLE_TIMESTAMP_SIZE: u8 = 11;
+
+/// The state of one WAL file
+enum WalState {
+    /// Every record is complete and passes its checksum
+    Intact,
+    /// The log ends in an incomplete record starting at this offset
+    Torn(usize),
+    /// A record before the end of the log is bad
+    Corrupt(usize),
+}
+
+/// Walk the records of a WAL file, verifying their checksums
+fn check_wal(data: &[u8]) -> WalState {
+    let mut pos = 0;
+    // Start of the logical record being assembled from fragments
+    let mut fragment_start: Option<usize> = None;
+
+    while pos < data.len() {
+        let block_left = WAL_BLOCK_SIZE - pos % WAL_BLOCK_SIZE;
+        if block_left < WAL_HEADER_SIZE {
+            // Block trailers too small for a header are zero padding
+            pos += block_left;
+            continue;
+        }
+
+        let rest = &data[pos..];
+        if rest.len() < WAL_HEADER_SIZE {
+            return if rest.iter().all(|&b| b == 0) {
+                fragment_start.map_or(WalState::Intact, WalState::Torn)
+            } else {
+                WalState::Torn(fragment_start.unwrap_or(pos))
+            };
+        }
+
+        let stored_crc = u32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]]);
+        let length = u16::from_le_bytes([rest[4], rest[5]]) as usize;
+        let record_type = rest[6];
+
+        // Preallocated space past the last write is all zeros
+        if stored_crc == 0 && length == 0 && record_type == 0 {
+            return if rest.iter().all(|&b| b == 0) {
+                fragment_start.map_or(WalState::Intact, WalState::Torn)
+            } else {
+                WalState::Corrupt(pos)
+            };
+        }
+
+        let header_size = match record_type {
+            WAL_RECYCLABLE_FULL..=WAL_RECYCLABLE_LAST | WAL_RECYCLABLE_TIMESTAMP_SIZE => {
+                WAL_RECYCLABLE_HEADER_SIZE
+            }
+            _ => WAL_HEADER_SIZE,
+        };
+        let record_len = header_size + length;
+
+        if rest.len() < record_len {
+            return WalState::Torn(fragment_start.unwrap_or(pos));
+        }
+        if record_len > block_left {
+            return WalState::Corrupt(pos);
+        }
+
+        // The checksum covers the type, any log number, and the payload
+        let actual_crc = mask_crc(crc32c(&rest[WAL_HEADER_SIZE - 1..record_len]));
+        if actual_crc != stored_crc {
+            // A bad final record is a torn write; anything earlier is damage
+            return if rest[record_len..].iter().all(|&b| b == 0) {
+                WalState::Torn(fragment_start.unwrap_or(pos))
+            } else {
+                WalState::Corrupt(pos)
+            };
+        }
+
+        match record_type {
+            WAL_FIRST | WAL_RECYCLABLE_FIRST => fragment_start = Some(pos),
+            WAL_MIDDLE | WAL_RECYCLABLE_MIDDLE if fragment_start.is_none() => {
+                return WalState::Corrupt(pos);
+            }
+            WAL_LAST | WAL_RECYCLABLE_LAST if fragment_start.is_none() => {
+                return WalState::Corrupt(pos);
+            }
+            WAL_LAST | WAL_RECYCLABLE_LAST => fragment_start = None,
+            WAL_FULL | WAL_RECYCLABLE_FULL if fragment_start.is_some() => {
+                return WalState::Corrupt(pos);
+            }
+            _ => {}
+        }
+
+        pos += record_len;
+    }
+
+    fragment_start.map_or(WalState::Intact, WalState::Torn)
+}
+
+/// Mask a CRC the way RocksDB stores it in log records
+fn mask_crc(crc: u32) -> u32 {
+    crc.rotate_right(15).wrapping_add(0xa282_ead8)
+}
@@ -0,0 +1,240 @@
+//! Per-column-family write quotas
+//!
+//! With one column family per tenant, RocksDB hands out write bandwidth and
+//! disk space first come, first served, so one busy tenant can slow down or
+//! crowd out all the others. A [`QuotaWriter`] charges every write to the
+//! column families it touches, reading batches operation by operation, and
+//! refuses writes that would take a column family over its quota.
+
+use crate::batch::{WriteBatch, WriteBatchOp};
+use crate::db::{ColumnFamilyHandle, DB};
+use crate::error::{Error, ErrorKind, Result};
+use crate::throttle::TokenBucket;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, MutexGuard};
+
+/// Limits set on one column family
+struct CfQuota {
+    cf_handle: ColumnFamilyHandle,
+    bytes_per_sec: Option<(u64, Mutex<TokenBucket>)>,
+    max_size: Option<u64>,
+}
+
+/// A writer that enforces write throughput and size quotas per column family
+///
+/// Writes are charged the length of their keys and values to the column
+/// family they go to; a batch is split up by column family and charged to
+/// each. A write that would exceed any quota is refused as a whole with an
+/// error of kind [`ErrorKind::QuotaExceeded`], and is neither written nor
+/// charged. Unlike [`ThrottledWriter`](crate::ThrottledWriter), the writer
+/// never blocks: the caller decides whether to retry later, shed the load
+/// or report the tenant. Column families without quotas are written
+/// without checks. The writer can be shared between threads, in which case
+/// the quotas apply to all of them together.
+///
+/// The size quota is compared with the column family's live SST files plus
+/// its memtables, so it counts data written by any means, not only through
+/// this writer. Range deletions are charged the length of their start and
+/// end keys, however much data they remove. A batch that can't be decoded
+/// in full (see [`WriteBatch::operations`]) is refused, since it can't be
+/// charged.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, ErrorKind, Options, QuotaWriter};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+/// let tenant = db.create_column_family(&Options::default(), "tenant_a").unwrap();
+///
+/// // 1 MiB per second and 10 GiB in total for this tenant
+/// let mut writer = QuotaWriter::new(&db);
+/// writer
+///     .set_bytes_per_sec(&tenant, 1024 * 1024)
+///     .set_max_size(&tenant, 10 * 1024 * 1024 * 1024);
+///
+/// match writer.put_cf(&tenant, b"key", b"value") {
+///     Ok(()) => {}
+///     Err(e) if e.kind() == ErrorKind::QuotaExceeded => eprintln!("throttled: {}", e),
+///     Err(e) => panic!("write failed: {}", e),
+/// }
+/// println!("tenant_a wrote {} bytes", writer.bytes_written(&tenant));
+/// ```
+pub struct QuotaWriter<'a> {
+    db: &'a DB,
+    quotas: HashMap<u32, CfQuota>,
+    // Bytes accepted so far, by column family ID
+    written: Mutex<HashMap<u32, u64>>,
+}
+
+impl<'a> QuotaWriter<'a> {
+    /// Create a writer for `db` with no quotas
+    pub fn new(db: &'a DB) -> Self {
+        QuotaWriter {
+            db,
+            quotas: HashMap::new(),
+            written: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Limit the bytes written to a column family per second (0 removes the limit)
+    ///
+    /// Up to one second's worth can be written in a burst.
+    pub fn set_bytes_per_sec(&mut self, cf_handle: &ColumnFamilyHandle, value: u64) -> &mut Self {
+        self.quota_mut(cf_handle).bytes_per_sec =
+            (value > 0).then(|| (value, Mutex::new(TokenBucket::new(value))));
+        self
+    }
+
+    /// Limit the total size of a column family, in bytes (0 removes the limit)
+    pub fn set_max_size(&mut self, cf_handle: &ColumnFamilyHandle, value: u64) -> &mut Self {
+        self.quota_mut(cf_handle).max_size = (value > 0).then_some(value);
+        self
+    }
+
+    /// Get the database this writer writes to
+    pub fn db(&self) -> &'a DB {
+        self.db
+    }
+
+    /// Get the bytes written to a column family through this writer
+    pub fn bytes_written(&self, cf_handle: &ColumnFamilyHandle) -> u64 {
+        self.written
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(&cf_handle.id())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Write a key-value pair to the default column family
+    pub fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.charge(&BTreeMap::from([(0, key.len() + value.len())]), || {
+            self.db.put(key, value)
+        })
+    }
+
+    /// Write a key-value pair to a column family
+    pub fn put_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8], value: &[u8]) -> Result<()> {
+        self.charge(
+            &BTreeMap::from([(cf_handle.id(), key.len() + value.len())]),
+            || self.db.put_cf(cf_handle, key, value),
+        )
+    }
+
+    /// Delete a key from the default column family
+    pub fn delete(&self, key: &[u8]) -> Result<()> {
+        self.charge(&BTreeMap::from([(0, key.len())]), || self.db.delete(key))
+    }
+
+    /// Delete a key from a column family
+    pub fn delete_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8]) -> Result<()> {
+        self.charge(&BTreeMap::from([(cf_handle.id(), key.len())]), || {
+            self.db.delete_cf(cf_handle, key)
+        })
+    }
+
+    /// Apply a write batch atomically
+    pub fn write(&self, batch: &WriteBatch) -> Result<()> {
+        let mut bytes: BTreeMap<u32, usize> = BTreeMap::new();
+        for op in batch.operations()? {
+            let (cf_id, len) = match op {
+                WriteBatchOp::Put { cf_id, key, value }
+                | WriteBatchOp::Merge { cf_id, key, value } => (cf_id, key.len() + value.len()),
+                WriteBatchOp::Delete { cf_id, key } => (cf_id, key.len()),
+                WriteBatchOp::DeleteRange { cf_id, start, end } => (cf_id, start.len() + end.len()),
+            };
+            *bytes.entry(cf_id).or_insert(0) += len;
+        }
+        self.charge(&bytes, || self.db.write(batch))
+    }
+
+    /// Run `write` if `bytes` (by column family ID) fit within every quota,
+    /// and charge them if it succeeds
+    fn charge(
+        &self,
+        bytes: &BTreeMap<u32, usize>,
+        write: impl FnOnce() -> Result<()>,
+    ) -> Result<()> {
+        // Buckets are locked in column family ID order, so concurrent
+        // writes can't deadlock
+        let mut buckets: Vec<(MutexGuard<'_, TokenBucket>, usize)> = Vec::new();
+        for (cf_id, &len) in bytes {
+            let Some(quota) = self.quotas.get(cf_id) else {
+                continue;
+            };
+            if let Some(max_size) = quota.max_size {
+                let size = self.size(&quota.cf_handle)?;
+                if size + len as u64 > max_size {
+                    return Err(Error::with_kind(
+                        ErrorKind::QuotaExceeded,
+                        format!(
+                            "Column family \"{}\" would exceed its size quota of {} bytes \
+                             ({} used, {} to write)",
+                            quota.cf_handle.name(),
+                            max_size,
+                            size,
+                            len
+                        ),
+                    ));
+                }
+            }
+            if let Some((limit, bucket)) = &quota.bytes_per_sec {
+                let mut bucket = bucket
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                if !bucket.can_take(len as f64) {
+                    return Err(Error::with_kind(
+                        ErrorKind::QuotaExceeded,
+                        format!(
+                            "Column family \"{}\" is over its write quota of {} bytes per second",
+                            quota.cf_handle.name(),
+                            limit
+                        ),
+                    ));
+                }
+                buckets.push((bucket, len));
+            }
+        }
+
+        // The buckets stay locked through the write, so a write that fails
+        // isn't charged and concurrent writes can't both fit in the same tokens
+        write()?;
+        for (bucket, len) in &mut buckets {
+            bucket.take(*len as f64);
+        }
+        drop(buckets);
+        let mut written = self
+            .written
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        for (cf_id, &len) in bytes {
+            *written.entry(*cf_id).or_insert(0) += len as u64;
+        }
+        Ok(())
+    }
+
+    /// Get the bytes a column family takes in live SST files and memtables
+    fn size(&self, cf_handle: &ColumnFamilyHandle) -> Result<u64> {
+        let sst = self
+            .db
+            .property_int_value_cf(cf_handle, "rocksdb.live-sst-files-size")?;
+        let memtables = self
+            .db
+            .property_int_value_cf(cf_handle, "rocksdb.cur-size-all-mem-tables")?;
+        Ok(sst.unwrap_or(0) + memtables.unwrap_or(0))
+    }
+
+    /// Get the quota entry of a column family, creating an empty one
+    fn quota_mut(&mut self, cf_handle: &ColumnFamilyHandle) -> &mut CfQuota {
+        self.quotas
+            .entry(cf_handle.id())
+            .or_insert_with(|| CfQuota {
+                cf_handle: cf_handle.clone(),
+                bytes_per_sec: None,
+                max_size: None,
+            })
+    }
+}
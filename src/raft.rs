@@ -0,0 +1,138 @@
+//! Helpers for replicated state machines (raft and similar protocols)
+//!
+//! A replicated state machine has to apply each committed log entry exactly
+//! once, even across crashes. The invariant that makes this work is that the
+//! last-applied index is written in the same atomic batch as the entry it
+//! describes: after a restart the stored index says precisely which entries
+//! are already reflected in the database.
+
+use crate::batch::WriteBatch;
+use crate::db::{ColumnFamilyHandle, DB};
+use crate::error::{Error, ErrorKind, Result};
+use std::sync::Mutex;
+
+/// Applies committed log entries to a database together with their index
+///
+/// Entries are serialized [`WriteBatch`]es (see [`WriteBatch::data`]), as
+/// produced by the leader. Each one is written along with the updated
+/// last-applied index in a single batch, and entries at or below the stored
+/// index are skipped, so replaying the log after a crash is harmless.
+///
+/// Writes use the default write options, as the log itself is the durable
+/// record; call [`DB::sync`] before acknowledging a snapshot.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options, RaftApplier, WriteBatch};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let applier = RaftApplier::new(&db);
+/// // On startup, resume the log after the last applied entry
+/// let next = applier.last_applied_index().unwrap() + 1;
+///
+/// // On the leader
+/// let mut entry = WriteBatch::new();
+/// entry.put(b"user:1", b"Alice");
+/// let payload = entry.data().to_vec();
+///
+/// // On every replica, once the entry is committed
+/// assert!(applier.apply(next, &payload).unwrap());
+/// // Replaying it is a no-op
+/// assert!(!applier.apply(next, &payload).unwrap());
+/// ```
+pub struct RaftApplier<'a> {
+    db: &'a DB,
+    cf_handle: Option<&'a ColumnFamilyHandle>,
+    index_key: Vec<u8>,
+    // Serializes the index check with the write that advances it
+    apply_lock: Mutex<()>,
+}
+
+impl<'a> RaftApplier<'a> {
+    /// The key the last-applied index is stored under unless changed
+    pub const DEFAULT_INDEX_KEY: &'static [u8] = b"raft:last_applied_index";
+
+    /// Create an applier storing the index in the default column family
+    pub fn new(db: &'a DB) -> Self {
+        RaftApplier {
+            db,
+            cf_handle: None,
+            index_key: Self::DEFAULT_INDEX_KEY.to_vec(),
+            apply_lock: Mutex::new(()),
+        }
+    }
+
+    /// Create an applier storing the index in a column family
+    ///
+    /// Keeping the index in a dedicated metadata column family keeps it out
+    /// of scans over the application's data.
+    pub fn with_cf(db: &'a DB, cf_handle: &'a ColumnFamilyHandle) -> Self {
+        RaftApplier {
+            cf_handle: Some(cf_handle),
+            ..Self::new(db)
+        }
+    }
+
+    /// Store the index under a different key (default: [`Self::DEFAULT_INDEX_KEY`])
+    pub fn set_index_key(&mut self, key: &[u8]) -> &mut Self {
+        self.index_key = key.to_vec();
+        self
+    }
+
+    /// Get the index of the last applied entry, or 0 if nothing has been applied
+    pub fn last_applied_index(&self) -> Result<u64> {
+        let stored = match self.cf_handle {
+            Some(cf_handle) => self.db.get_cf(cf_handle, &self.index_key)?,
+            None => self.db.get(&self.index_key)?,
+        };
+
+        match stored {
+            None => Ok(0),
+            Some(bytes) => {
+                let bytes: [u8; 8] = bytes.as_slice().try_into().map_err(|_| {
+                    Error::with_kind(
+                        ErrorKind::Corruption,
+                        format!("Stored applied index has {} bytes, expected 8", bytes.len()),
+                    )
+                })?;
+                Ok(u64::from_be_bytes(bytes))
+            }
+        }
+    }
+
+    /// Apply a serialized write batch as the entry at `index`
+    ///
+    /// Returns `false` without writing anything if an entry at `index` or
+    /// later was already applied. Fails with [`ErrorKind::Corruption`],
+    /// leaving the applied index where it was, if `data` is not a valid
+    /// serialized batch.
+    pub fn apply(&self, index: u64, data: &[u8]) -> Result<bool> {
+        self.apply_batch(index, WriteBatch::from_data(data)?)
+    }
+
+    /// Apply a write batch as the entry at `index`
+    ///
+    /// Returns `false` without writing anything if an entry at `index` or
+    /// later was already applied.
+    pub fn apply_batch(&self, index: u64, mut batch: WriteBatch) -> Result<bool> {
+        let _guard = self
+            .apply_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if index <= self.last_applied_index()? {
+            return Ok(false);
+        }
+
+        match self.cf_handle {
+            Some(cf_handle) => batch.put_cf(cf_handle, &self.index_key, &index.to_be_bytes()),
+            None => batch.put(&self.index_key, &index.to_be_bytes()),
+        }
+        self.db.write(&batch)?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,142 @@
+//! Rate limiting for background I/O
+
+use crate::error::{Error, Result};
+use crate::ffi;
+use std::os::raw::c_int;
+use std::ptr::NonNull;
+
+/// Which I/O a [`RateLimiter`] charges against its budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimiterMode {
+    /// Limit reads only
+    ReadsOnly,
+    /// Limit writes only (default)
+    WritesOnly,
+    /// Limit reads and writes together
+    AllIo,
+}
+
+impl RateLimiterMode {
+    /// Get the value used by the RocksDB C API
+    fn as_raw(self) -> c_int {
+        match self {
+            RateLimiterMode::ReadsOnly => 0,
+            RateLimiterMode::WritesOnly => 1,
+            RateLimiterMode::AllIo => 2,
+        }
+    }
+}
+
+/// A limit on the I/O bandwidth used by flushes and compactions
+///
+/// Attach it with [`Options::set_ratelimiter`](crate::Options::set_ratelimiter).
+/// Foreground reads and writes are not throttled (see
+/// [`ThrottledWriter`](crate::ThrottledWriter) for that); capping the
+/// background I/O keeps compactions from saturating the disk and starving
+/// them. The same limiter can be set on several options, in which case all
+/// databases opened with them share one budget.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{Options, RateLimiter};
+///
+/// // At most 64 MiB/s of flush and compaction writes
+/// let limiter = RateLimiter::new(64 * 1024 * 1024).unwrap();
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true).set_ratelimiter(&limiter);
+/// ```
+pub struct RateLimiter {
+    inner: NonNull<ffi::rocksdb_ratelimiter_t>,
+}
+
+impl RateLimiter {
+    /// RocksDB's default refill period, in microseconds
+    const DEFAULT_REFILL_PERIOD_US: i64 = 100 * 1000;
+    /// RocksDB's default fairness between high- and low-priority requests
+    const DEFAULT_FAIRNESS: i32 = 10;
+
+    /// Create a limiter allowing `rate_bytes_per_sec` bytes of background writes per second
+    pub fn new(rate_bytes_per_sec: i64) -> Result<Self> {
+        Self::with_mode(
+            rate_bytes_per_sec,
+            Self::DEFAULT_REFILL_PERIOD_US,
+            Self::DEFAULT_FAIRNESS,
+            RateLimiterMode::WritesOnly,
+            false,
+        )
+    }
+
+    /// Create a self-tuning limiter with `rate_bytes_per_sec` as its upper bound
+    ///
+    /// The effective rate adapts between 1/20 of the bound and the bound
+    /// itself based on recent demand, so bursts of compaction get through
+    /// while idle periods don't accumulate a large budget.
+    pub fn new_auto_tuned(rate_bytes_per_sec: i64) -> Result<Self> {
+        Self::with_mode(
+            rate_bytes_per_sec,
+            Self::DEFAULT_REFILL_PERIOD_US,
+            Self::DEFAULT_FAIRNESS,
+            RateLimiterMode::WritesOnly,
+            true,
+        )
+    }
+
+    /// Create a limiter with every parameter specified
+    ///
+    /// * `rate_bytes_per_sec` - Bandwidth budget (the upper bound when `auto_tuned`)
+    /// * `refill_period_us` - How often the budget is refilled; shorter periods
+    ///   smooth out bursts at some CPU cost (RocksDB default: 100ms)
+    /// * `fairness` - 1 in `fairness` requests of low priority goes ahead of
+    ///   waiting high-priority ones, so low priority is never starved
+    ///   (RocksDB default: 10)
+    /// * `mode` - Which I/O is charged
+    /// * `auto_tuned` - Whether the rate adapts to demand
+    pub fn with_mode(
+        rate_bytes_per_sec: i64,
+        refill_period_us: i64,
+        fairness: i32,
+        mode: RateLimiterMode,
+        auto_tuned: bool,
+    ) -> Result<Self> {
+        if rate_bytes_per_sec <= 0 {
+            return Err(Error::new("Rate limit must be positive"));
+        }
+        if refill_period_us <= 0 || fairness <= 0 {
+            return Err(Error::new("Refill period and fairness must be positive"));
+        }
+
+        unsafe {
+            let ptr = ffi::rocksdb_ratelimiter_create_with_mode(
+                rate_bytes_per_sec,
+                refill_period_us,
+                fairness,
+                mode.as_raw(),
+                auto_tuned,
+            );
+            Ok(RateLimiter {
+                inner: NonNull::new(ptr)
+                    .ok_or_else(|| Error::new("Failed to create rate limiter"))?,
+            })
+        }
+    }
+
+    /// Get the raw pointer for FFI calls
+    pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_ratelimiter_t {
+        self.inner.as_ptr()
+    }
+}
+
+impl Drop for RateLimiter {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_ratelimiter_destroy(self.inner.as_ptr());
+        }));
+    }
+}
+
+// The RocksDB rate limiter is internally synchronized
+unsafe impl Send for RateLimiter {}
+unsafe impl Sync for RateLimiter {}
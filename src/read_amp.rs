@@ -0,0 +1,80 @@
+//! Read amplification: bytes read from storage per byte returned
+//!
+//! RocksDB reads whole blocks to return single values, so a lookup pattern
+//! can read far more than it returns. [`ReadAmpReport`] puts the database
+//! tickers that measure this side by side, including the estimate of how
+//! much of each block read was actually used.
+
+use crate::options::Options;
+use crate::statistics::Ticker;
+
+/// Read amplification counters, from [`DB::read_amp_report`](crate::DB::read_amp_report)
+///
+/// The fields are cumulative database statistics: they are 0 unless
+/// [`Options::enable_statistics`](crate::Options::enable_statistics) was
+/// set, and `db_block_bytes_read` and `db_useful_block_bytes` also need
+/// [`BlockBasedOptions::set_read_amp_bytes_per_bit`](crate::BlockBasedOptions::set_read_amp_bytes_per_bit).
+/// Take a report before and after a query pattern and use
+/// [`ReadAmpReport::delta`] to measure just that pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ReadAmpReport {
+    /// Bytes returned by gets and iterators
+    pub db_bytes_returned: u64,
+    /// Bytes of data blocks read, from storage or the block cache
+    pub db_block_bytes_read: u64,
+    /// Estimated bytes of those blocks that reads actually used
+    pub db_useful_block_bytes: u64,
+    /// Bytes of blocks served from the block cache
+    pub db_block_cache_bytes_read: u64,
+    /// Block cache lookups that hit
+    pub db_block_cache_hits: u64,
+    /// Block cache lookups that missed
+    pub db_block_cache_misses: u64,
+}
+
+impl ReadAmpReport {
+    /// Read the statistics of `options`
+    pub(crate) fn capture(options: &Options) -> Self {
+        ReadAmpReport {
+            db_bytes_returned: options.ticker_count(Ticker::BytesRead)
+                + options.ticker_count(Ticker::IterBytesRead),
+            db_block_bytes_read: options.ticker_count(Ticker::ReadAmpTotalReadBytes),
+            db_useful_block_bytes: options.ticker_count(Ticker::ReadAmpEstimateUsefulBytes),
+            db_block_cache_bytes_read: options.ticker_count(Ticker::BlockCacheBytesRead),
+            db_block_cache_hits: options.ticker_count(Ticker::BlockCacheHit),
+            db_block_cache_misses: options.ticker_count(Ticker::BlockCacheMiss),
+        }
+    }
+
+    /// Get how much each counter grew since an earlier report
+    pub fn delta(&self, earlier: &ReadAmpReport) -> ReadAmpReport {
+        ReadAmpReport {
+            db_bytes_returned: self
+                .db_bytes_returned
+                .saturating_sub(earlier.db_bytes_returned),
+            db_block_bytes_read: self
+                .db_block_bytes_read
+                .saturating_sub(earlier.db_block_bytes_read),
+            db_useful_block_bytes: self
+                .db_useful_block_bytes
+                .saturating_sub(earlier.db_useful_block_bytes),
+            db_block_cache_bytes_read: self
+                .db_block_cache_bytes_read
+                .saturating_sub(earlier.db_block_cache_bytes_read),
+            db_block_cache_hits: self
+                .db_block_cache_hits
+                .saturating_sub(earlier.db_block_cache_hits),
+            db_block_cache_misses: self
+                .db_block_cache_misses
+                .saturating_sub(earlier.db_block_cache_misses),
+        }
+    }
+
+    /// Get the data block bytes read per byte used, database-wide
+    ///
+    /// `None` until blocks were read with read amplification tracking on.
+    pub fn block_read_amplification(&self) -> Option<f64> {
+        (self.db_useful_block_bytes > 0)
+            .then(|| self.db_block_bytes_read as f64 / self.db_useful_block_bytes as f64)
+    }
+}
@@ -0,0 +1,256 @@
+//! Moving every key under one prefix to another
+//!
+//! Re-keying data, such as moving a tenant to a new id, means copying each
+//! entry to its new key and deleting the old one. [`PrefixRename`] does this
+//! in batches, each copying a run of entries and range-deleting their old
+//! keys in one atomic write, so the data is never lost or duplicated at any
+//! point. A [`RenameToken`] records how far it got, to report progress or
+//! resume in another process.
+
+use crate::batch::WriteBatch;
+use crate::db::{ColumnFamilyHandle, DB};
+use crate::error::{Error, ErrorKind, Result};
+use crate::iterator::prefix_read_options;
+use crate::snapshot::SnapshotInner;
+use std::sync::Arc;
+
+/// How far a [`PrefixRename`] has got, from [`PrefixRename::token`]
+///
+/// Store it with [`RenameToken::to_bytes`] and pass it to
+/// [`PrefixRename::resume_from`] to carry on where a rename stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameToken {
+    old_prefix: Vec<u8>,
+    new_prefix: Vec<u8>,
+    // First old key not renamed yet
+    next_key: Vec<u8>,
+    renamed: u64,
+}
+
+impl RenameToken {
+    /// Get the number of entries renamed so far
+    pub fn renamed(&self) -> u64 {
+        self.renamed
+    }
+
+    /// Encode the token for storage
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for part in [&self.old_prefix, &self.new_prefix, &self.next_key] {
+            bytes.extend_from_slice(&(part.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(part);
+        }
+        bytes.extend_from_slice(&self.renamed.to_le_bytes());
+        bytes
+    }
+
+    /// Decode a token produced by [`RenameToken::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let invalid = || Error::with_kind(ErrorKind::InvalidArgument, "Invalid rename token");
+        let mut rest = bytes;
+        let mut take = |len: usize| -> Result<&[u8]> {
+            if rest.len() < len {
+                return Err(invalid());
+            }
+            let (part, tail) = rest.split_at(len);
+            rest = tail;
+            Ok(part)
+        };
+
+        let mut parts = Vec::with_capacity(3);
+        for _ in 0..3 {
+            let len = u32::from_le_bytes(take(4)?.try_into().map_err(|_| invalid())?);
+            parts.push(take(len as usize)?.to_vec());
+        }
+        let renamed = u64::from_le_bytes(take(8)?.try_into().map_err(|_| invalid())?);
+        if !rest.is_empty() {
+            return Err(invalid());
+        }
+
+        let next_key = parts.pop().unwrap_or_default();
+        let new_prefix = parts.pop().unwrap_or_default();
+        let old_prefix = parts.pop().unwrap_or_default();
+        Ok(RenameToken {
+            old_prefix,
+            new_prefix,
+            next_key,
+            renamed,
+        })
+    }
+}
+
+/// A rename of every key starting with one prefix to start with another
+///
+/// Created with [`DB::rename_prefix`] or [`DB::rename_prefix_cf`]. Entries
+/// are read from a snapshot taken at creation, in batches of
+/// [`PrefixRename::set_batch_size`] entries. Each batch is one atomic write
+/// that range-deletes the old keys it copied, so a rename stopped at any
+/// point leaves every entry under exactly one of the prefixes, and
+/// restarting it, from its token or from scratch, is safe.
+///
+/// Writes to the old prefix must be stopped for the duration: the range
+/// deletions also remove keys written there after the snapshot. The prefixes
+/// must not overlap, i.e. neither may be a prefix of the other.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let mut rename = db.rename_prefix(b"tenant:17/", b"tenant:42/").unwrap();
+/// rename.set_batch_size(10_000);
+/// while rename.step().unwrap() {
+///     // Persist the progress to resume after a restart
+///     db.put(b"meta:rename", &rename.token().to_bytes()).unwrap();
+/// }
+/// println!("renamed {} entries", rename.renamed());
+/// ```
+pub struct PrefixRename<'a> {
+    db: &'a DB,
+    cf_handle: Option<&'a ColumnFamilyHandle>,
+    snapshot: Arc<SnapshotInner>,
+    token: RenameToken,
+    batch_size: usize,
+    done: bool,
+}
+
+impl<'a> PrefixRename<'a> {
+    /// Default number of entries per atomic step
+    const DEFAULT_BATCH_SIZE: usize = 1000;
+
+    /// Start a rename at a snapshot of `db` (internal use only)
+    pub(crate) fn new(
+        db: &'a DB,
+        cf_handle: Option<&'a ColumnFamilyHandle>,
+        old_prefix: &[u8],
+        new_prefix: &[u8],
+    ) -> Result<Self> {
+        if old_prefix.starts_with(new_prefix) || new_prefix.starts_with(old_prefix) {
+            return Err(Error::with_kind(
+                ErrorKind::InvalidArgument,
+                "Prefixes of a rename must not be prefixes of each other",
+            ));
+        }
+
+        Ok(PrefixRename {
+            db,
+            cf_handle,
+            snapshot: Arc::new(SnapshotInner::new(db)?),
+            token: RenameToken {
+                old_prefix: old_prefix.to_vec(),
+                new_prefix: new_prefix.to_vec(),
+                next_key: old_prefix.to_vec(),
+                renamed: 0,
+            },
+            batch_size: Self::DEFAULT_BATCH_SIZE,
+            done: false,
+        })
+    }
+
+    /// Set the number of entries copied per atomic step (default: 1000)
+    pub fn set_batch_size(&mut self, value: usize) -> &mut Self {
+        self.batch_size = value.max(1);
+        self
+    }
+
+    /// Continue from a token of an earlier rename between the same prefixes
+    ///
+    /// Only the progress count depends on it: the keys already renamed are
+    /// gone from the old prefix either way.
+    pub fn resume_from(&mut self, token: &RenameToken) -> Result<&mut Self> {
+        if token.old_prefix != self.token.old_prefix || token.new_prefix != self.token.new_prefix {
+            return Err(Error::with_kind(
+                ErrorKind::InvalidArgument,
+                "Rename token is for different prefixes",
+            ));
+        }
+        self.token = token.clone();
+        Ok(self)
+    }
+
+    /// Get the progress so far, to persist or report
+    pub fn token(&self) -> RenameToken {
+        self.token.clone()
+    }
+
+    /// Get the number of entries renamed so far
+    pub fn renamed(&self) -> u64 {
+        self.token.renamed
+    }
+
+    /// Check whether every entry has been renamed
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Rename the next batch of entries in one atomic write
+    ///
+    /// Returns whether entries may remain.
+    pub fn step(&mut self) -> Result<bool> {
+        if self.done {
+            return Ok(false);
+        }
+
+        let mut read_opts = prefix_read_options(&self.token.old_prefix);
+        read_opts.pin_snapshot(self.snapshot.clone());
+        let mut iter = self.db.create_iterator(self.cf_handle, read_opts);
+        iter.seek(&self.token.next_key);
+
+        let old_len = self.token.old_prefix.len();
+        let mut entries = Vec::new();
+        let mut last_key = None;
+        while entries.len() < self.batch_size
+            && let Some((key, value)) = iter.item()
+        {
+            let mut new_key = self.token.new_prefix.clone();
+            new_key.extend_from_slice(&key[old_len..]);
+            entries.push((new_key, value.to_vec()));
+            last_key = Some(key.to_vec());
+            iter.next();
+        }
+        iter.status()?;
+
+        let Some(mut last_key) = last_key else {
+            self.done = true;
+            return Ok(false);
+        };
+        // The range ends at the first key left for the next step
+        let end = match iter.key() {
+            Some(key) => key.to_vec(),
+            None => {
+                self.done = true;
+                last_key.push(0);
+                last_key
+            }
+        };
+        drop(iter);
+
+        // The range deletion comes first so it can't cover the new keys
+        let mut batch = WriteBatch::new();
+        match self.cf_handle {
+            Some(cf_handle) => batch.delete_range_cf(cf_handle, &self.token.next_key, &end),
+            None => batch.delete_range(&self.token.next_key, &end),
+        }
+        for (new_key, value) in &entries {
+            match self.cf_handle {
+                Some(cf_handle) => batch.put_cf(cf_handle, new_key, value),
+                None => batch.put(new_key, value),
+            }
+        }
+        self.db.write(&batch)?;
+
+        self.token.next_key = end;
+        self.token.renamed += entries.len() as u64;
+        Ok(!self.done)
+    }
+
+    /// Rename every remaining entry, returning the total number renamed
+    pub fn run(&mut self) -> Result<u64> {
+        while self.step()? {}
+        Ok(self.token.renamed)
+    }
+}
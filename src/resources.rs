@@ -0,0 +1,137 @@
+//! Resources shared by several databases
+//!
+//! Each RocksDB instance normally gets its own background threads, block
+//! cache and memtable budget, so the process-wide footprint grows with the
+//! number of open databases. Sharing one set of resources between them keeps
+//! the totals fixed regardless of how many are open.
+
+use crate::cache::Cache;
+use crate::env::Env;
+use crate::options::{BlockBasedOptions, Options};
+use crate::rate_limiter::RateLimiter;
+use crate::write_buffer_manager::WriteBufferManager;
+
+/// An environment, block cache, memtable budget and I/O limit for several databases
+///
+/// Build it once, then [`SharedResources::apply`] it to the options of every
+/// database, or hand it to
+/// [`DbManager::with_resources`](crate::DbManager::with_resources). Each
+/// resource is optional; unset ones are left as configured on the options.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{
+///     Cache, DB, Env, Options, RateLimiter, SharedResources, WriteBufferManager,
+/// };
+///
+/// let env = Env::new();
+/// env.set_background_threads(4);
+///
+/// let mut resources = SharedResources::new();
+/// resources
+///     .set_env(env)
+///     .set_block_cache(Cache::new_lru(512 * 1024 * 1024))
+///     .set_write_buffer_manager(WriteBufferManager::new(256 * 1024 * 1024, true).unwrap())
+///     .set_rate_limiter(RateLimiter::new(100 * 1024 * 1024).unwrap());
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// resources.apply(&mut opts);
+///
+/// // Both databases use the same 4 threads, 512 MiB cache and 256 MiB of memtables
+/// let orders = DB::open(&opts, "/tmp/orders").unwrap();
+/// let users = DB::open(&opts, "/tmp/users").unwrap();
+/// ```
+#[derive(Default)]
+pub struct SharedResources {
+    env: Option<Env>,
+    block_cache: Option<Cache>,
+    write_buffer_manager: Option<WriteBufferManager>,
+    rate_limiter: Option<RateLimiter>,
+}
+
+impl SharedResources {
+    /// Create an empty set of resources
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Share an environment, and with it the background thread pools
+    pub fn set_env(&mut self, env: Env) -> &mut Self {
+        self.env = Some(env);
+        self
+    }
+
+    /// Share a block cache
+    pub fn set_block_cache(&mut self, cache: Cache) -> &mut Self {
+        self.block_cache = Some(cache);
+        self
+    }
+
+    /// Share a memtable memory budget
+    pub fn set_write_buffer_manager(&mut self, manager: WriteBufferManager) -> &mut Self {
+        self.write_buffer_manager = Some(manager);
+        self
+    }
+
+    /// Share a flush and compaction I/O limit
+    pub fn set_rate_limiter(&mut self, limiter: RateLimiter) -> &mut Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Get the shared environment, if set
+    pub fn env(&self) -> Option<&Env> {
+        self.env.as_ref()
+    }
+
+    /// Get the shared block cache, if set
+    pub fn block_cache(&self) -> Option<&Cache> {
+        self.block_cache.as_ref()
+    }
+
+    /// Get the shared memtable budget, if set
+    pub fn write_buffer_manager(&self) -> Option<&WriteBufferManager> {
+        self.write_buffer_manager.as_ref()
+    }
+
+    /// Get the shared I/O limit, if set
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.rate_limiter.as_ref()
+    }
+
+    /// Configure `options` to use these resources
+    ///
+    /// A shared block cache is installed with default block-based table
+    /// options, replacing any table factory set before. To keep custom table
+    /// options, use [`SharedResources::apply_with_table_options`] instead.
+    pub fn apply(&self, options: &mut Options) {
+        let mut table_options = BlockBasedOptions::default();
+        self.apply_with_table_options(options, &mut table_options);
+    }
+
+    /// Configure `options` to use these resources, with custom table options
+    ///
+    /// If a block cache is shared, it is set on `table_options`, which are
+    /// then installed on `options`; otherwise `table_options` are not used.
+    pub fn apply_with_table_options(
+        &self,
+        options: &mut Options,
+        table_options: &mut BlockBasedOptions,
+    ) {
+        if let Some(env) = &self.env {
+            options.set_env(env);
+        }
+        if let Some(cache) = &self.block_cache {
+            table_options.set_block_cache(cache);
+            options.set_block_based_table_factory(table_options);
+        }
+        if let Some(manager) = &self.write_buffer_manager {
+            options.set_write_buffer_manager(manager);
+        }
+        if let Some(limiter) = &self.rate_limiter {
+            options.set_ratelimiter(limiter);
+        }
+    }
+}
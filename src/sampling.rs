@@ -0,0 +1,106 @@
+//! Helpers for sampling keys without a full scan
+
+use crate::error::Result;
+use crate::iterator::DBIterator;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A small, fast pseudo-random generator (SplitMix64)
+///
+/// Sampling needs randomness but not cryptographic quality, and this keeps
+/// the crate free of a `rand` dependency.
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// Create a generator seeded from the clock
+    pub(crate) fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        SplitMix64 { state: nanos }
+    }
+
+    /// Get the next 64 random bits
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Get a random number in `0..bound` (`bound` must be positive)
+    pub(crate) fn below(&mut self, bound: u64) -> u64 {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+}
+
+/// Split the key range `[first, last]` into `buckets` roughly even pieces
+///
+/// Keys are treated as numbers: after their common prefix, the next 8 bytes
+/// of `first` and `last` are interpolated linearly. Returns the start key of
+/// each bucket, beginning with `first` itself; the last bucket runs to the
+/// end of the range. Fewer starts are returned if the keys are too close
+/// together to split.
+pub(crate) fn split_range(first: &[u8], last: &[u8], buckets: usize) -> Vec<Vec<u8>> {
+    let prefix_len = first.iter().zip(last).take_while(|(a, b)| a == b).count();
+    let prefix = &first[..prefix_len];
+    let lo = key_digits(&first[prefix_len..]) as u128;
+    let hi = key_digits(&last[prefix_len..]) as u128;
+
+    let mut starts = vec![first.to_vec()];
+    for i in 1..buckets as u128 {
+        let digits = lo + (hi - lo) * i / buckets as u128;
+        let mut start = prefix.to_vec();
+        start.extend_from_slice(&(digits as u64).to_be_bytes());
+
+        // Interpolated keys must stay sorted and inside the range
+        if start.as_slice() > starts.last().unwrap().as_slice() && start.as_slice() <= last {
+            starts.push(start);
+        }
+    }
+
+    starts
+}
+
+/// Read up to 8 bytes of a key suffix as a big-endian number, padding with zeros
+fn key_digits(suffix: &[u8]) -> u64 {
+    let mut digits = [0u8; 8];
+    let len = suffix.len().min(8);
+    digits[..len].copy_from_slice(&suffix[..len]);
+    u64::from_be_bytes(digits)
+}
+
+/// Sample `n` keys from `[first, last]` exactly by scanning them all (reservoir sampling)
+pub(crate) fn sample_by_scan(
+    mut iter: DBIterator<'_>,
+    n: usize,
+    first: &[u8],
+    last: &[u8],
+    rng: &mut SplitMix64,
+) -> Result<Vec<Vec<u8>>> {
+    let mut sample: Vec<Vec<u8>> = Vec::with_capacity(n);
+    let mut seen: u64 = 0;
+
+    iter.seek(first);
+    while let Some(key) = iter.key() {
+        if key > last {
+            break;
+        }
+        if sample.len() < n {
+            sample.push(key.to_vec());
+        } else {
+            let slot = rng.below(seen + 1) as usize;
+            if slot < n {
+                sample[slot] = key.to_vec();
+            }
+        }
+        seen += 1;
+        iter.next();
+    }
+    iter.status()?;
+
+    sample.sort();
+    Ok(sample)
+}
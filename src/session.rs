@@ -0,0 +1,150 @@
+//! Read-your-writes consistency across a primary and a read replica
+//!
+//! Reads from a secondary instance (see [`DB::open_as_secondary`]) lag the
+//! primary until it catches up, so a client that writes and then reads back
+//! from the replica may not see its own write. A [`Session`] remembers the
+//! sequence number of its latest write and only serves reads from the replica
+//! once the replica has reached it.
+
+use crate::batch::WriteBatch;
+use crate::db::DB;
+use crate::error::Result;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A client session with causally consistent reads from a replica
+///
+/// Writes go to the primary and advance the session's sequence number.
+/// A read is served by the replica when the replica's latest sequence number
+/// has reached the session's; otherwise the session asks the replica to
+/// catch up, retrying for up to the configured wait, and falls back to the
+/// primary if it is still behind. With the default wait of zero the session
+/// tries once and falls back straight away.
+///
+/// The sequence number can be passed between sessions with
+/// [`Session::last_write_sequence`] and [`Session::observe`], so causality
+/// carries across clients (for example in a request header).
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options, Session};
+/// use std::time::Duration;
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let primary = DB::open(&opts, "/tmp/my_db").unwrap();
+/// let replica = DB::open_as_secondary(&opts, "/tmp/my_db", "/tmp/my_db_replica").unwrap();
+///
+/// let mut session = Session::new(&primary, &replica);
+/// session.set_max_wait(Duration::from_millis(50));
+///
+/// session.put(b"user:1", b"Alice").unwrap();
+/// // Sees the write, from the replica if it caught up in time
+/// assert_eq!(session.get(b"user:1").unwrap().as_deref(), Some(&b"Alice"[..]));
+/// ```
+pub struct Session<'a> {
+    primary: &'a DB,
+    replica: &'a DB,
+    last_write_seq: AtomicU64,
+    max_wait: Duration,
+    poll_interval: Duration,
+}
+
+impl<'a> Session<'a> {
+    /// Create a session writing to `primary` and reading from `replica` where possible
+    pub fn new(primary: &'a DB, replica: &'a DB) -> Self {
+        Session {
+            primary,
+            replica,
+            last_write_seq: AtomicU64::new(0),
+            max_wait: Duration::ZERO,
+            poll_interval: Duration::from_millis(5),
+        }
+    }
+
+    /// Set how long a read waits for the replica before falling back to the primary
+    pub fn set_max_wait(&mut self, value: Duration) -> &mut Self {
+        self.max_wait = value;
+        self
+    }
+
+    /// Set how often the replica is asked to catch up while waiting (default: 5ms)
+    pub fn set_poll_interval(&mut self, value: Duration) -> &mut Self {
+        self.poll_interval = value;
+        self
+    }
+
+    /// Get the sequence number the replica must reach to reflect this session's writes
+    pub fn last_write_sequence(&self) -> u64 {
+        self.last_write_seq.load(Ordering::Acquire)
+    }
+
+    /// Require reads to reflect everything up to `seq`, e.g. another session's writes
+    pub fn observe(&self, seq: u64) {
+        self.last_write_seq.fetch_max(seq, Ordering::AcqRel);
+    }
+
+    /// Write a key-value pair to the primary
+    pub fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.primary.put(key, value)?;
+        self.record_write();
+        Ok(())
+    }
+
+    /// Delete a key on the primary
+    pub fn delete(&self, key: &[u8]) -> Result<()> {
+        self.primary.delete(key)?;
+        self.record_write();
+        Ok(())
+    }
+
+    /// Apply a write batch atomically on the primary
+    pub fn write(&self, batch: &WriteBatch) -> Result<()> {
+        self.primary.write(batch)?;
+        self.record_write();
+        Ok(())
+    }
+
+    /// Read a key, from the replica once it reflects this session's writes
+    pub fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        if self.wait_for_replica() {
+            self.replica.get(key)
+        } else {
+            self.primary.get(key)
+        }
+    }
+
+    /// Check whether the replica currently reflects this session's writes
+    pub fn replica_is_current(&self) -> bool {
+        self.replica.latest_sequence_number() >= self.last_write_sequence()
+    }
+
+    /// Record the primary's sequence number after a write
+    ///
+    /// Concurrent writers may have advanced it further, which only makes the
+    /// session wait for slightly more than strictly necessary.
+    fn record_write(&self) {
+        self.observe(self.primary.latest_sequence_number());
+    }
+
+    /// Catch the replica up until it is current or the wait runs out
+    fn wait_for_replica(&self) -> bool {
+        if self.replica_is_current() {
+            return true;
+        }
+
+        let deadline = Instant::now() + self.max_wait;
+        loop {
+            // A replica that cannot catch up is treated as lagging
+            if self.replica.try_catch_up_with_primary().is_ok() && self.replica_is_current() {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            thread::sleep(self.poll_interval);
+        }
+    }
+}
@@ -0,0 +1,328 @@
+//! Point-in-time read views and a registry of named ones
+//!
+//! A snapshot keeps every version of the data visible at the moment it was
+//! taken, so compactions can't discard those versions until it is released.
+//! A forgotten snapshot therefore makes the database grow without bound. The
+//! registry lets long-lived snapshots be found by name, listed with
+//! [`DB::snapshots`](crate::DB::snapshots) and released automatically once
+//! their TTL runs out.
+
+use crate::db::{ColumnFamilyHandle, DB, OwnedRocksDBBytes, ReadOptionsGuard};
+use crate::error::{Error, ErrorKind, Result};
+use crate::ffi;
+use crate::iterator::{DBIterator, DBIteratorAdapter, Direction};
+use crate::options::ReadOptions;
+use std::collections::HashMap;
+use std::ptr::{self, NonNull};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A RocksDB snapshot, released when the last reference goes away
+pub(crate) struct SnapshotInner {
+    db: NonNull<ffi::rocksdb_t>,
+    ptr: NonNull<ffi::rocksdb_snapshot_t>,
+}
+
+impl SnapshotInner {
+    /// Take a snapshot of the current state of `db`
+    pub(crate) fn new(db: &DB) -> Result<Self> {
+        unsafe {
+            let ptr = ffi::rocksdb_create_snapshot(db.as_ptr());
+            Ok(SnapshotInner {
+                db: NonNull::new(db.as_ptr()).ok_or_else(|| Error::new("Invalid database"))?,
+                ptr: NonNull::new(ptr as *mut _)
+                    .ok_or_else(|| Error::new("Failed to create snapshot"))?,
+            })
+        }
+    }
+}
+
+impl SnapshotInner {
+    /// Get the raw pointer for FFI calls
+    pub(crate) fn as_ptr(&self) -> *const ffi::rocksdb_snapshot_t {
+        self.ptr.as_ptr()
+    }
+}
+
+impl Drop for SnapshotInner {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_release_snapshot(self.db.as_ptr(), self.ptr.as_ptr());
+        }));
+    }
+}
+
+// RocksDB snapshots are immutable and may be used from any thread
+unsafe impl Send for SnapshotInner {}
+unsafe impl Sync for SnapshotInner {}
+
+/// A consistent, read-only view of the database at a point in time
+///
+/// Created with [`DB::snapshot`](crate::DB::snapshot), or looked up with
+/// [`DB::named_snapshot`](crate::DB::named_snapshot). Writes made after the
+/// snapshot was taken are not visible through it.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// db.put(b"balance", b"100").unwrap();
+/// let snapshot = db.snapshot().unwrap();
+/// db.put(b"balance", b"0").unwrap();
+///
+/// assert_eq!(snapshot.get(b"balance").unwrap(), Some(b"100".to_vec()));
+/// ```
+#[must_use = "Snapshot is released as soon as it is dropped"]
+pub struct Snapshot<'a> {
+    inner: Arc<SnapshotInner>,
+    db: &'a DB,
+}
+
+impl<'a> Snapshot<'a> {
+    /// Wrap a snapshot of `db` (internal use only)
+    pub(crate) fn new(db: &'a DB, inner: Arc<SnapshotInner>) -> Self {
+        Snapshot { inner, db }
+    }
+
+    /// Get the sequence number of the last write visible in the snapshot
+    pub fn sequence_number(&self) -> u64 {
+        unsafe { ffi::rocksdb_snapshot_get_sequence_number(self.inner.ptr.as_ptr()) }
+    }
+
+    /// Get a value as of the snapshot
+    pub fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.get_inner(None, key)
+    }
+
+    /// Get a value from a column family as of the snapshot
+    pub fn get_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.get_inner(Some(cf_handle), key)
+    }
+
+    /// Create read options that read at this snapshot
+    pub(crate) fn read_options(&self) -> Result<ReadOptionsGuard> {
+        let read_opts = ReadOptionsGuard::new()?;
+        unsafe {
+            ffi::rocksdb_readoptions_set_snapshot(read_opts.as_ptr(), self.inner.ptr.as_ptr());
+        }
+        Ok(read_opts)
+    }
+
+    /// Create a raw iterator that reads at the snapshot
+    ///
+    /// The iterator keeps the snapshot alive, so it can outlive this handle
+    /// (but not the database) and every pass over it sees the same data.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_small_rocksdb::{DB, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.create_if_missing(true);
+    /// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+    ///
+    /// // Two passes of an export see the same keys, whatever is written meanwhile
+    /// let snapshot = db.snapshot().unwrap();
+    /// let mut count = 0;
+    /// let mut iter = snapshot.raw_iterator();
+    /// iter.seek_to_first();
+    /// while iter.valid() {
+    ///     count += 1;
+    ///     iter.next();
+    /// }
+    /// for item in snapshot.iter(rust_small_rocksdb::Direction::Forward) {
+    ///     let (key, value) = item.unwrap();
+    ///     println!("{} keys; {:?} = {:?}", count, key, value);
+    /// }
+    /// ```
+    pub fn raw_iterator(&self) -> DBIterator<'a> {
+        self.raw_iterator_opt(ReadOptions::new())
+    }
+
+    /// Create a raw iterator that reads at the snapshot with the given read options
+    ///
+    /// See [`DB::raw_iterator_opt`](crate::DB::raw_iterator_opt).
+    pub fn raw_iterator_opt(&self, mut read_opts: ReadOptions) -> DBIterator<'a> {
+        read_opts.pin_snapshot(self.inner.clone());
+        self.db.create_iterator(None, read_opts)
+    }
+
+    /// Create a raw iterator over a column family that reads at the snapshot
+    pub fn raw_iterator_cf(&self, cf_handle: &'a ColumnFamilyHandle) -> DBIterator<'a> {
+        let mut read_opts = ReadOptions::new();
+        read_opts.pin_snapshot(self.inner.clone());
+        self.db.create_iterator(Some(cf_handle), read_opts)
+    }
+
+    /// Create an iterator over the snapshot, starting at the first or last key
+    pub fn iter(&self, direction: Direction) -> DBIteratorAdapter<'a> {
+        let mut db_iter = self.raw_iterator();
+        match direction {
+            Direction::Forward => db_iter.seek_to_first(),
+            Direction::Reverse => db_iter.seek_to_last(),
+        }
+        DBIteratorAdapter::new(db_iter, direction)
+    }
+
+    fn get_inner(
+        &self,
+        cf_handle: Option<&ColumnFamilyHandle>,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+
+        let read_opts = self.read_options()?;
+
+        unsafe {
+            let mut val_len: usize = 0;
+            let mut err: *mut i8 = ptr::null_mut();
+            let val_ptr = match cf_handle {
+                Some(cf_handle) => ffi::rocksdb_get_cf(
+                    self.db.as_ptr(),
+                    read_opts.as_ptr(),
+                    cf_handle.as_ptr(),
+                    key.as_ptr() as *const i8,
+                    key.len(),
+                    &mut val_len,
+                    &mut err,
+                ),
+                None => ffi::rocksdb_get(
+                    self.db.as_ptr(),
+                    read_opts.as_ptr(),
+                    key.as_ptr() as *const i8,
+                    key.len(),
+                    &mut val_len,
+                    &mut err,
+                ),
+            };
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(OwnedRocksDBBytes::from_raw(val_ptr, val_len).map(|bytes| bytes.to_vec()))
+        }
+    }
+}
+
+/// A registered snapshot, as listed by [`DB::snapshots`](crate::DB::snapshots)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotInfo {
+    /// The name it was registered under
+    pub name: String,
+    /// The sequence number of the last write visible in it
+    pub sequence_number: u64,
+    /// How long ago it was taken
+    pub age: Duration,
+    /// How long until it is released, or `None` if it has no TTL
+    pub expires_in: Option<Duration>,
+}
+
+/// A snapshot held by the registry
+struct NamedSnapshot {
+    inner: Arc<SnapshotInner>,
+    created: Instant,
+    ttl: Option<Duration>,
+}
+
+impl NamedSnapshot {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.ttl
+            .is_some_and(|ttl| now.duration_since(self.created) >= ttl)
+    }
+}
+
+/// Named snapshots of one database
+///
+/// Expired snapshots are released whenever the registry is used, so no
+/// background thread is needed.
+#[derive(Default)]
+pub(crate) struct SnapshotRegistry {
+    snapshots: HashMap<String, NamedSnapshot>,
+}
+
+impl SnapshotRegistry {
+    /// Add a snapshot under `name`, failing if the name is taken
+    pub(crate) fn register(
+        &mut self,
+        name: &str,
+        inner: Arc<SnapshotInner>,
+        ttl: Option<Duration>,
+    ) -> Result<()> {
+        self.release_expired();
+        if self.snapshots.contains_key(name) {
+            return Err(Error::with_kind(
+                ErrorKind::InvalidArgument,
+                format!("A snapshot named {:?} is already registered", name),
+            ));
+        }
+
+        self.snapshots.insert(
+            name.to_string(),
+            NamedSnapshot {
+                inner,
+                created: Instant::now(),
+                ttl,
+            },
+        );
+        Ok(())
+    }
+
+    /// Look up a live snapshot
+    pub(crate) fn get(&mut self, name: &str) -> Option<Arc<SnapshotInner>> {
+        self.release_expired();
+        self.snapshots.get(name).map(|named| named.inner.clone())
+    }
+
+    /// Remove a snapshot, returning whether it was registered
+    pub(crate) fn release(&mut self, name: &str) -> bool {
+        self.release_expired();
+        self.snapshots.remove(name).is_some()
+    }
+
+    /// Remove every snapshot whose TTL has run out, returning how many
+    pub(crate) fn release_expired(&mut self) -> usize {
+        let now = Instant::now();
+        let before = self.snapshots.len();
+        self.snapshots.retain(|_, named| !named.is_expired(now));
+        before - self.snapshots.len()
+    }
+
+    /// Describe the live snapshots, sorted by name
+    pub(crate) fn list(&mut self) -> Vec<SnapshotInfo> {
+        self.release_expired();
+        let now = Instant::now();
+        let mut infos: Vec<SnapshotInfo> = self
+            .snapshots
+            .iter()
+            .map(|(name, named)| {
+                let age = now.duration_since(named.created);
+                SnapshotInfo {
+                    name: name.clone(),
+                    sequence_number: unsafe {
+                        ffi::rocksdb_snapshot_get_sequence_number(named.inner.ptr.as_ptr())
+                    },
+                    age,
+                    expires_in: named.ttl.map(|ttl| ttl.saturating_sub(age)),
+                }
+            })
+            .collect();
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+        infos
+    }
+
+    /// Drop every snapshot
+    pub(crate) fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+}
@@ -0,0 +1,153 @@
+//! Tracking and limiting the disk space used by SST files
+
+use crate::env::Env;
+use crate::error::{Error, Result};
+use crate::ffi;
+use std::ptr::NonNull;
+
+/// Tracks the SST files of one or more databases and enforces space limits
+///
+/// Attach it with [`Options::set_sst_file_manager`](crate::Options::set_sst_file_manager).
+/// With a maximum allowed space set, flushes and compactions that would push
+/// the tracked files past it fail, and the database stops accepting writes
+/// (they fail with an [`ErrorKind::IOError`](crate::ErrorKind::IOError))
+/// before the disk fills up. Deleting files can also be rate limited, so
+/// dropping a large column family doesn't cause a burst of discard I/O.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options, SstFileManager};
+///
+/// let sfm = SstFileManager::new().unwrap();
+/// sfm.set_max_allowed_space_usage(8 * 1024 * 1024 * 1024);
+/// sfm.set_delete_rate_bytes_per_second(64 * 1024 * 1024);
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true).set_sst_file_manager(&sfm);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// if sfm.is_max_allowed_space_reached() {
+///     eprintln!("out of space: {} bytes of SST files", sfm.total_size());
+/// }
+/// ```
+pub struct SstFileManager {
+    inner: NonNull<ffi::rocksdb_sst_file_manager_t>,
+    // The manager only stores a raw pointer to the Env
+    _env: Env,
+}
+
+impl SstFileManager {
+    /// Create a manager using the default environment
+    pub fn new() -> Result<Self> {
+        let env = Env::new();
+
+        unsafe {
+            let ptr = ffi::rocksdb_sst_file_manager_create(env.as_ptr());
+            Ok(SstFileManager {
+                inner: NonNull::new(ptr)
+                    .ok_or_else(|| Error::new("Failed to create SST file manager"))?,
+                _env: env,
+            })
+        }
+    }
+
+    /// Set the maximum total size of tracked SST files, in bytes (0 means no limit)
+    pub fn set_max_allowed_space_usage(&self, max_allowed_space: u64) {
+        unsafe {
+            ffi::rocksdb_sst_file_manager_set_max_allowed_space_usage(
+                self.inner.as_ptr(),
+                max_allowed_space,
+            );
+        }
+    }
+
+    /// Set how much free space to keep for compaction outputs, in bytes
+    ///
+    /// Compactions are only started if their estimated output plus this
+    /// buffer fits below the maximum allowed space.
+    pub fn set_compaction_buffer_size(&self, compaction_buffer_size: u64) {
+        unsafe {
+            ffi::rocksdb_sst_file_manager_set_compaction_buffer_size(
+                self.inner.as_ptr(),
+                compaction_buffer_size,
+            );
+        }
+    }
+
+    /// Check whether the tracked files have reached the maximum allowed space
+    pub fn is_max_allowed_space_reached(&self) -> bool {
+        unsafe { ffi::rocksdb_sst_file_manager_is_max_allowed_space_reached(self.inner.as_ptr()) }
+    }
+
+    /// Check whether the tracked files plus running compaction outputs have
+    /// reached the maximum allowed space
+    pub fn is_max_allowed_space_reached_including_compactions(&self) -> bool {
+        unsafe {
+            ffi::rocksdb_sst_file_manager_is_max_allowed_space_reached_including_compactions(
+                self.inner.as_ptr(),
+            )
+        }
+    }
+
+    /// Get the total size of the tracked SST files, in bytes
+    pub fn total_size(&self) -> u64 {
+        unsafe { ffi::rocksdb_sst_file_manager_get_total_size(self.inner.as_ptr()) }
+    }
+
+    /// Get the file deletion rate limit in bytes per second (0 means unlimited)
+    pub fn delete_rate_bytes_per_second(&self) -> i64 {
+        unsafe {
+            ffi::rocksdb_sst_file_manager_get_delete_rate_bytes_per_second(self.inner.as_ptr())
+        }
+    }
+
+    /// Limit how fast obsolete files are deleted, in bytes per second (0 means unlimited)
+    ///
+    /// Rate-limited files are first renamed into a trash directory and then
+    /// deleted in the background.
+    pub fn set_delete_rate_bytes_per_second(&self, delete_rate: i64) {
+        unsafe {
+            ffi::rocksdb_sst_file_manager_set_delete_rate_bytes_per_second(
+                self.inner.as_ptr(),
+                delete_rate,
+            );
+        }
+    }
+
+    /// Get the trash-to-database size ratio above which trash is deleted immediately
+    pub fn max_trash_db_ratio(&self) -> f64 {
+        unsafe { ffi::rocksdb_sst_file_manager_get_max_trash_db_ratio(self.inner.as_ptr()) }
+    }
+
+    /// Set the trash-to-database size ratio above which trash is deleted
+    /// immediately, bypassing the rate limit (RocksDB default: 0.25)
+    pub fn set_max_trash_db_ratio(&self, ratio: f64) {
+        unsafe {
+            ffi::rocksdb_sst_file_manager_set_max_trash_db_ratio(self.inner.as_ptr(), ratio);
+        }
+    }
+
+    /// Get the total size of files waiting in the trash, in bytes
+    pub fn total_trash_size(&self) -> u64 {
+        unsafe { ffi::rocksdb_sst_file_manager_get_total_trash_size(self.inner.as_ptr()) }
+    }
+
+    /// Get the raw pointer for FFI calls
+    pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_sst_file_manager_t {
+        self.inner.as_ptr()
+    }
+}
+
+impl Drop for SstFileManager {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_sst_file_manager_destroy(self.inner.as_ptr());
+        }));
+    }
+}
+
+// The RocksDB SST file manager is internally synchronized
+unsafe impl Send for SstFileManager {}
+unsafe impl Sync for SstFileManager {}
@@ -0,0 +1,269 @@
+//! Writer for building SST files outside of a running database
+//!
+//! SST files produced here can be bulk-loaded with
+//! [`DB::ingest_external_file`](crate::DB::ingest_external_file) or
+//! [`DB::ingest_external_file_cf`](crate::DB::ingest_external_file_cf).
+//! The file format is determined by the options passed to the writer, so
+//! those options must describe the column family the file will end up in.
+
+use crate::batch::check_range;
+use crate::env::Env;
+use crate::error::{Error, ErrorKind, Result};
+use crate::ffi;
+use crate::options::Options;
+use std::ffi::CString;
+use std::path::Path;
+use std::ptr::{self, NonNull};
+
+/// A writer that produces a sorted SST file for external ingestion
+///
+/// The writer is built from the *target column family's* options. RocksDB
+/// takes the comparator, compression settings and table factory from those
+/// options, so a file created with the same `Options` that were used to
+/// create (or open) the column family will ingest cleanly into it. Creating
+/// the file with mismatched options (for example a different comparator) is
+/// only detected at ingest time, when RocksDB rejects the file.
+///
+/// Keys must be added in strictly increasing order according to that
+/// comparator. In debug builds the writer checks this itself and names the
+/// offending keys, rather than leaving it to RocksDB's terser error; see
+/// [`SstFileWriter::set_check_key_order`].
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options, SstFileWriter};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let cf_opts = Options::default();
+/// let cf = db.create_column_family(&cf_opts, "bulk").unwrap();
+///
+/// // Build the file with the same options as the target column family
+/// let mut writer = SstFileWriter::new(&cf_opts).unwrap();
+/// writer.open("/tmp/bulk.sst").unwrap();
+/// writer.put(b"a", b"1").unwrap();
+/// writer.put(b"b", b"2").unwrap();
+/// writer.finish().unwrap();
+///
+/// db.ingest_external_file_cf(&cf, &["/tmp/bulk.sst"]).unwrap();
+/// ```
+#[must_use = "SST file writer must be opened and finished to produce a file"]
+pub struct SstFileWriter {
+    inner: NonNull<ffi::rocksdb_sstfilewriter_t>,
+    // The writer's copy of the options points at this Env
+    _env: Option<Env>,
+    check_key_order: bool,
+    // Last point key added, tracked only while checking the order
+    last_key: Option<Vec<u8>>,
+}
+
+impl SstFileWriter {
+    /// Create a writer that produces files compatible with `options`
+    ///
+    /// Pass the options of the column family the file will be ingested into.
+    /// RocksDB copies what it needs, so `options` may be dropped afterwards.
+    pub fn new(options: &Options) -> Result<Self> {
+        unsafe {
+            let env_opts = ffi::rocksdb_envoptions_create();
+            if env_opts.is_null() {
+                return Err(Error::new("Failed to create env options"));
+            }
+
+            let ptr = ffi::rocksdb_sstfilewriter_create(env_opts, options.as_ptr());
+
+            // The writer keeps its own copy of the env options
+            ffi::rocksdb_envoptions_destroy(env_opts);
+
+            let inner =
+                NonNull::new(ptr).ok_or_else(|| Error::new("Failed to create SST file writer"))?;
+
+            Ok(SstFileWriter {
+                inner,
+                _env: options.env().cloned(),
+                check_key_order: cfg!(debug_assertions),
+                last_key: None,
+            })
+        }
+    }
+
+    /// Set whether keys are checked for order before reaching RocksDB
+    /// (default: on in debug builds)
+    ///
+    /// While on, [`SstFileWriter::put`] and [`SstFileWriter::delete`] fail
+    /// with [`ErrorKind::InvalidArgument`] naming both keys when a key does
+    /// not sort strictly after the previous one, and
+    /// [`SstFileWriter::delete_range`] fails on an empty or inverted range.
+    /// Keys are compared bytewise, the order of the default comparator.
+    /// Checking keeps a copy of the last key added.
+    pub fn set_check_key_order(&mut self, value: bool) -> &mut Self {
+        self.check_key_order = value;
+        if !value {
+            self.last_key = None;
+        }
+        self
+    }
+
+    /// Check that `key` sorts after the last key added, and remember it
+    fn check_order(&mut self, key: &[u8]) -> Result<()> {
+        if !self.check_key_order {
+            return Ok(());
+        }
+        if let Some(last) = &self.last_key
+            && key <= last.as_slice()
+        {
+            return Err(Error::with_kind(
+                ErrorKind::InvalidArgument,
+                format!(
+                    "Key {:?} added to SST file out of order: it must sort after {:?}",
+                    String::from_utf8_lossy(key),
+                    String::from_utf8_lossy(last)
+                ),
+            ));
+        }
+        self.last_key = Some(key.to_vec());
+        Ok(())
+    }
+
+    /// Create the SST file at `path` and prepare it for writing
+    pub fn open<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let c_path = CString::new(path.as_ref().to_string_lossy().as_bytes())
+            .map_err(|_| Error::new("Invalid path"))?;
+        self.last_key = None;
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_sstfilewriter_open(self.inner.as_ptr(), c_path.as_ptr(), &mut err);
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Add a key-value pair to the file
+    ///
+    /// The key must sort after every key previously added.
+    pub fn put(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+        debug_assert!(
+            value.len() < isize::MAX as usize,
+            "Value length exceeds maximum safe size"
+        );
+        self.check_order(key)?;
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_sstfilewriter_put(
+                self.inner.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+                value.as_ptr() as *const i8,
+                value.len(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Add a deletion tombstone for `key` to the file
+    pub fn delete(&mut self, key: &[u8]) -> Result<()> {
+        debug_assert!(
+            key.len() < isize::MAX as usize,
+            "Key length exceeds maximum safe size"
+        );
+        self.check_order(key)?;
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_sstfilewriter_delete(
+                self.inner.as_ptr(),
+                key.as_ptr() as *const i8,
+                key.len(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Add a range deletion covering `[begin_key, end_key)` to the file
+    ///
+    /// Range deletions may be added in any order relative to other keys.
+    pub fn delete_range(&mut self, begin_key: &[u8], end_key: &[u8]) -> Result<()> {
+        if self.check_key_order {
+            check_range(begin_key, end_key)?;
+        }
+
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_sstfilewriter_delete_range(
+                self.inner.as_ptr(),
+                begin_key.as_ptr() as *const i8,
+                begin_key.len(),
+                end_key.as_ptr() as *const i8,
+                end_key.len(),
+                &mut err,
+            );
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Finalize the file and flush it to disk
+    ///
+    /// The file cannot be ingested until this returns successfully.
+    pub fn finish(&mut self) -> Result<()> {
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_sstfilewriter_finish(self.inner.as_ptr(), &mut err);
+
+            if !err.is_null() {
+                return Err(Error::from_c_string(err));
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Get the current size of the file in bytes
+    pub fn file_size(&self) -> u64 {
+        let mut size: u64 = 0;
+        unsafe {
+            ffi::rocksdb_sstfilewriter_file_size(self.inner.as_ptr(), &mut size);
+        }
+        size
+    }
+}
+
+impl Drop for SstFileWriter {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_sstfilewriter_destroy(self.inner.as_ptr());
+        }));
+    }
+}
+
+// SstFileWriter is safe to send between threads
+unsafe impl Send for SstFileWriter {}
@@ -0,0 +1,246 @@
+//! Database statistics (tickers)
+//!
+//! Statistics are enabled with [`Options::enable_statistics`](crate::Options::enable_statistics)
+//! and collected in an object shared by those options and every database
+//! opened with them. The C API cannot reset the counters, so measuring a
+//! single phase of a test or benchmark is done by taking a
+//! [`StatisticsSnapshot`] before and after it and looking at the delta.
+
+use std::collections::HashMap;
+
+/// A cumulative counter maintained by RocksDB statistics
+///
+/// Only the most commonly used tickers are listed; their values match
+/// RocksDB's `Tickers` enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Ticker {
+    /// Block cache lookups that missed
+    BlockCacheMiss,
+    /// Block cache lookups that hit
+    BlockCacheHit,
+    /// Blocks added to the block cache
+    BlockCacheAdd,
+    /// Index block cache misses
+    BlockCacheIndexMiss,
+    /// Index block cache hits
+    BlockCacheIndexHit,
+    /// Filter block cache misses
+    BlockCacheFilterMiss,
+    /// Filter block cache hits
+    BlockCacheFilterHit,
+    /// Data block cache misses
+    BlockCacheDataMiss,
+    /// Data block cache hits
+    BlockCacheDataHit,
+    /// Bytes read from the block cache
+    BlockCacheBytesRead,
+    /// Bytes written into the block cache
+    BlockCacheBytesWrite,
+    /// Lookups where the bloom filter ruled out the file
+    BloomFilterUseful,
+    /// Lookups where the bloom filter did not rule out the file
+    BloomFilterFullPositive,
+    /// Bloom filter positives where the key really was in the file
+    BloomFilterFullTruePositive,
+    /// Prefix bloom filter checks
+    BloomFilterPrefixChecked,
+    /// Prefix bloom filter checks that ruled out the file
+    BloomFilterPrefixUseful,
+    /// Reads served from a memtable
+    MemtableHit,
+    /// Reads not found in any memtable
+    MemtableMiss,
+    /// Reads served from level 0
+    GetHitL0,
+    /// Reads served from level 1
+    GetHitL1,
+    /// Reads served from level 2 or deeper
+    GetHitL2AndUp,
+    /// Keys written by puts
+    NumberKeysWritten,
+    /// Keys read by gets
+    NumberKeysRead,
+    /// Keys updated in place
+    NumberKeysUpdated,
+    /// Bytes written by puts (keys and values)
+    BytesWritten,
+    /// Bytes returned by gets
+    BytesRead,
+    /// Iterator seeks
+    NumberDbSeek,
+    /// Iterator `next` calls
+    NumberDbNext,
+    /// Iterator `prev` calls
+    NumberDbPrev,
+    /// Bytes of keys and values returned by iterators
+    IterBytesRead,
+    /// Microseconds writers spent stalled
+    StallMicros,
+    /// WAL file syncs
+    WalFileSynced,
+    /// Bytes written to the WAL
+    WalFileBytes,
+    /// Bytes read by compactions
+    CompactReadBytes,
+    /// Bytes written by compactions
+    CompactWriteBytes,
+    /// Bytes written by flushes
+    FlushWriteBytes,
+    /// Row cache hits
+    RowCacheHit,
+    /// Row cache misses
+    RowCacheMiss,
+    /// Bytes of read blocks actually used (requires `read_amp_bytes_per_bit`)
+    ReadAmpEstimateUsefulBytes,
+    /// Total bytes of blocks read (requires `read_amp_bytes_per_bit`)
+    ReadAmpTotalReadBytes,
+}
+
+impl Ticker {
+    /// Every ticker in this enum
+    pub const ALL: &'static [Ticker] = &[
+        Ticker::BlockCacheMiss,
+        Ticker::BlockCacheHit,
+        Ticker::BlockCacheAdd,
+        Ticker::BlockCacheIndexMiss,
+        Ticker::BlockCacheIndexHit,
+        Ticker::BlockCacheFilterMiss,
+        Ticker::BlockCacheFilterHit,
+        Ticker::BlockCacheDataMiss,
+        Ticker::BlockCacheDataHit,
+        Ticker::BlockCacheBytesRead,
+        Ticker::BlockCacheBytesWrite,
+        Ticker::BloomFilterUseful,
+        Ticker::BloomFilterFullPositive,
+        Ticker::BloomFilterFullTruePositive,
+        Ticker::BloomFilterPrefixChecked,
+        Ticker::BloomFilterPrefixUseful,
+        Ticker::MemtableHit,
+        Ticker::MemtableMiss,
+        Ticker::GetHitL0,
+        Ticker::GetHitL1,
+        Ticker::GetHitL2AndUp,
+        Ticker::NumberKeysWritten,
+        Ticker::NumberKeysRead,
+        Ticker::NumberKeysUpdated,
+        Ticker::BytesWritten,
+        Ticker::BytesRead,
+        Ticker::NumberDbSeek,
+        Ticker::NumberDbNext,
+        Ticker::NumberDbPrev,
+        Ticker::IterBytesRead,
+        Ticker::StallMicros,
+        Ticker::WalFileSynced,
+        Ticker::WalFileBytes,
+        Ticker::CompactReadBytes,
+        Ticker::CompactWriteBytes,
+        Ticker::FlushWriteBytes,
+        Ticker::RowCacheHit,
+        Ticker::RowCacheMiss,
+        Ticker::ReadAmpEstimateUsefulBytes,
+        Ticker::ReadAmpTotalReadBytes,
+    ];
+
+    /// Get the value used by the RocksDB C API
+    pub(crate) fn as_raw(self) -> u32 {
+        match self {
+            Ticker::BlockCacheMiss => 0,
+            Ticker::BlockCacheHit => 1,
+            Ticker::BlockCacheAdd => 2,
+            Ticker::BlockCacheIndexMiss => 4,
+            Ticker::BlockCacheIndexHit => 5,
+            Ticker::BlockCacheFilterMiss => 8,
+            Ticker::BlockCacheFilterHit => 9,
+            Ticker::BlockCacheDataMiss => 12,
+            Ticker::BlockCacheDataHit => 13,
+            Ticker::BlockCacheBytesRead => 16,
+            Ticker::BlockCacheBytesWrite => 17,
+            Ticker::BloomFilterUseful => 35,
+            Ticker::BloomFilterFullPositive => 36,
+            Ticker::BloomFilterFullTruePositive => 37,
+            Ticker::BloomFilterPrefixChecked => 38,
+            Ticker::BloomFilterPrefixUseful => 39,
+            Ticker::MemtableHit => 45,
+            Ticker::MemtableMiss => 46,
+            Ticker::GetHitL0 => 47,
+            Ticker::GetHitL1 => 48,
+            Ticker::GetHitL2AndUp => 49,
+            Ticker::NumberKeysWritten => 57,
+            Ticker::NumberKeysRead => 58,
+            Ticker::NumberKeysUpdated => 59,
+            Ticker::BytesWritten => 60,
+            Ticker::BytesRead => 61,
+            Ticker::NumberDbSeek => 62,
+            Ticker::NumberDbNext => 63,
+            Ticker::NumberDbPrev => 64,
+            Ticker::IterBytesRead => 68,
+            Ticker::StallMicros => 75,
+            Ticker::WalFileSynced => 83,
+            Ticker::WalFileBytes => 84,
+            Ticker::CompactReadBytes => 88,
+            Ticker::CompactWriteBytes => 89,
+            Ticker::FlushWriteBytes => 90,
+            Ticker::RowCacheHit => 114,
+            Ticker::RowCacheMiss => 115,
+            Ticker::ReadAmpEstimateUsefulBytes => 116,
+            Ticker::ReadAmpTotalReadBytes => 117,
+        }
+    }
+}
+
+/// The values of every [`Ticker`] at one point in time
+///
+/// Taken with [`Options::statistics_snapshot`](crate::Options::statistics_snapshot).
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options, Ticker};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true).enable_statistics();
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// // Ignore whatever happened before the measured phase
+/// let before = opts.statistics_snapshot();
+/// for i in 0..1000u32 {
+///     db.put(&i.to_be_bytes(), b"value").unwrap();
+/// }
+/// let delta = opts.statistics_snapshot().delta(&before);
+/// assert_eq!(delta.get(Ticker::NumberKeysWritten), 1000);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatisticsSnapshot {
+    counts: HashMap<Ticker, u64>,
+}
+
+impl StatisticsSnapshot {
+    /// Build a snapshot from a ticker reader (internal use only)
+    pub(crate) fn capture<F: Fn(Ticker) -> u64>(read: F) -> Self {
+        StatisticsSnapshot {
+            counts: Ticker::ALL.iter().map(|&t| (t, read(t))).collect(),
+        }
+    }
+
+    /// Get the value of a ticker
+    pub fn get(&self, ticker: Ticker) -> u64 {
+        self.counts.get(&ticker).copied().unwrap_or(0)
+    }
+
+    /// Get how much each ticker grew since an earlier snapshot
+    pub fn delta(&self, earlier: &StatisticsSnapshot) -> StatisticsSnapshot {
+        StatisticsSnapshot {
+            counts: self
+                .counts
+                .iter()
+                .map(|(&t, &count)| (t, count.saturating_sub(earlier.get(t))))
+                .collect(),
+        }
+    }
+
+    /// Iterate over the tickers and their values, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (Ticker, u64)> + '_ {
+        self.counts.iter().map(|(&t, &count)| (t, count))
+    }
+}
@@ -0,0 +1,121 @@
+//! Asynchronous iteration for servers that page scans out to clients
+//!
+//! A [`DbStream`] reads entries only as they are polled for, in chunks, so a
+//! slow consumer (an HTTP client, say) holds back the scan instead of
+//! letting results pile up in memory. It yields to the executor between
+//! chunks, so a long scan doesn't starve the other tasks of its thread.
+//!
+//! Streams implement `futures_core::Stream`, so the `futures` combinators
+//! work on them. A stream over an [`OwnedIterator`] is `'static` and `Send`
+//! and can be moved into a spawned task.
+
+use crate::error::Result;
+use crate::iterator::{DBIteratorAdapter, Entry};
+use crate::owned_iter::OwnedIterator;
+use std::future::poll_fn;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Entries read per chunk unless set with [`DbStream::with_chunk_size`]
+const DEFAULT_CHUNK_SIZE: usize = 256;
+
+/// A key-ordered scan read chunk by chunk as it is polled
+///
+/// Created with [`DB::stream`](crate::DB::stream) or
+/// [`DB::stream_cf`](crate::DB::stream_cf), which borrow the database, or
+/// with [`OwnedIterator::into_stream`], which doesn't. Yields the same items
+/// as [`DBIteratorAdapter`]. RocksDB reads are blocking, so reading a chunk
+/// blocks the polling task briefly; keep chunks small enough for that to be
+/// harmless, or move the scan to a blocking thread.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options};
+///
+/// # async fn serve() {
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let mut stream = db.stream(&b"user:"[..]..&b"user;"[..]).with_chunk_size(100);
+/// while let Some(item) = stream.next().await {
+///     let (key, value) = item.unwrap();
+///     // Awaiting a slow send here holds back the scan
+///     println!("{:?} = {:?}", key, value);
+/// }
+/// # }
+/// ```
+#[must_use = "Streams are lazy and do nothing unless polled"]
+pub struct DbStream<I> {
+    inner: I,
+    chunk_size: usize,
+    // Entries returned since the stream last yielded to the executor
+    since_yield: usize,
+}
+
+impl<'a> DbStream<DBIteratorAdapter<'a>> {
+    /// Wrap a positioned iterator (internal use only)
+    pub(crate) fn new(inner: DBIteratorAdapter<'a>) -> Self {
+        DbStream {
+            inner: inner.with_prefetch(DEFAULT_CHUNK_SIZE),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            since_yield: 0,
+        }
+    }
+
+    /// Set the number of entries read per chunk (default: 256)
+    pub fn with_chunk_size(mut self, entries: usize) -> Self {
+        self.chunk_size = entries.max(1);
+        self.inner = self.inner.with_prefetch(self.chunk_size);
+        self
+    }
+}
+
+impl DbStream<OwnedIterator> {
+    /// Wrap an owned iterator (internal use only)
+    pub(crate) fn new_owned(inner: OwnedIterator) -> Self {
+        DbStream {
+            inner: inner.with_prefetch(DEFAULT_CHUNK_SIZE),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            since_yield: 0,
+        }
+    }
+
+    /// Set the number of entries read per chunk (default: 256)
+    pub fn with_chunk_size(mut self, entries: usize) -> Self {
+        self.chunk_size = entries.max(1);
+        self.inner = self.inner.with_prefetch(self.chunk_size);
+        self
+    }
+}
+
+impl<I: Iterator<Item = Result<Entry>> + Unpin> DbStream<I> {
+    /// Poll for the next entry, reading a new chunk if the current one is used up
+    pub fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Entry>>> {
+        if self.since_yield >= self.chunk_size {
+            // Give other tasks a turn before reading the next chunk
+            self.since_yield = 0;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        self.since_yield += 1;
+        Poll::Ready(self.inner.next())
+    }
+
+    /// Get the next entry
+    pub async fn next(&mut self) -> Option<Result<Entry>> {
+        poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+}
+
+impl<I: Iterator<Item = Result<Entry>> + Unpin> futures_core::Stream for DbStream<I> {
+    type Item = Result<Entry>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        DbStream::poll_next(self, cx)
+    }
+}
@@ -0,0 +1,184 @@
+//! Application-level write throttling
+//!
+//! RocksDB's own rate limiter only paces background flush and compaction
+//! I/O. [`ThrottledWriter`] paces the foreground writes themselves, so a bulk
+//! job sharing a database with latency-sensitive traffic cannot flood the
+//! memtables and trigger write stalls for everyone.
+
+use crate::batch::WriteBatch;
+use crate::db::{ColumnFamilyHandle, DB};
+use crate::error::Result;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A token bucket refilled continuously at a fixed rate
+pub(crate) struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Create a full bucket allowing `rate` tokens per second, with one second of burst
+    pub(crate) fn new(rate: u64) -> Self {
+        TokenBucket {
+            rate: rate as f64,
+            capacity: rate as f64,
+            tokens: rate as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Add the tokens accrued since the last refill
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Take `amount` tokens and return how long the caller must wait before using them
+    ///
+    /// The balance may go negative, which lets requests larger than the
+    /// bucket through while still charging them in full.
+    fn reserve(&mut self, amount: f64) -> Duration {
+        self.refill();
+        self.tokens -= amount;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+
+    /// Check whether `amount` tokens are available right now, without taking them
+    ///
+    /// A full bucket admits any amount, so requests larger than the bucket
+    /// are not refused forever.
+    pub(crate) fn can_take(&mut self, amount: f64) -> bool {
+        self.refill();
+        self.tokens >= amount || self.tokens >= self.capacity
+    }
+
+    /// Take `amount` tokens, letting the balance go negative
+    pub(crate) fn take(&mut self, amount: f64) {
+        self.refill();
+        self.tokens -= amount;
+    }
+}
+
+/// A writer that limits how fast it writes to a database
+///
+/// Limits are set in operations per second and/or bytes (keys plus values)
+/// per second; writes block until they fit within both. A writer with no
+/// limits set writes at full speed. The writer can be shared between threads,
+/// in which case the limits apply to all of them together.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options, ThrottledWriter};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// // Backfill at no more than 1000 writes and 4 MiB per second
+/// let mut writer = ThrottledWriter::new(&db);
+/// writer
+///     .set_ops_per_sec(1000)
+///     .set_bytes_per_sec(4 * 1024 * 1024);
+///
+/// for i in 0..10_000u32 {
+///     writer.put(&i.to_be_bytes(), b"value").unwrap();
+/// }
+/// ```
+pub struct ThrottledWriter<'a> {
+    db: &'a DB,
+    ops: Option<Mutex<TokenBucket>>,
+    bytes: Option<Mutex<TokenBucket>>,
+}
+
+impl<'a> ThrottledWriter<'a> {
+    /// Create a writer for `db` with no limits
+    pub fn new(db: &'a DB) -> Self {
+        ThrottledWriter {
+            db,
+            ops: None,
+            bytes: None,
+        }
+    }
+
+    /// Limit the number of operations written per second (0 removes the limit)
+    ///
+    /// Each operation in a [`WriteBatch`] counts separately.
+    pub fn set_ops_per_sec(&mut self, value: u64) -> &mut Self {
+        self.ops = (value > 0).then(|| Mutex::new(TokenBucket::new(value)));
+        self
+    }
+
+    /// Limit the number of bytes written per second (0 removes the limit)
+    ///
+    /// Single writes count their key and value length; batches count their
+    /// serialized size.
+    pub fn set_bytes_per_sec(&mut self, value: u64) -> &mut Self {
+        self.bytes = (value > 0).then(|| Mutex::new(TokenBucket::new(value)));
+        self
+    }
+
+    /// Get the database this writer writes to
+    pub fn db(&self) -> &'a DB {
+        self.db
+    }
+
+    /// Write a key-value pair to the default column family
+    pub fn put(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.throttle(1, key.len() + value.len());
+        self.db.put(key, value)
+    }
+
+    /// Write a key-value pair to a column family
+    pub fn put_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8], value: &[u8]) -> Result<()> {
+        self.throttle(1, key.len() + value.len());
+        self.db.put_cf(cf_handle, key, value)
+    }
+
+    /// Delete a key from the default column family
+    pub fn delete(&self, key: &[u8]) -> Result<()> {
+        self.throttle(1, key.len());
+        self.db.delete(key)
+    }
+
+    /// Delete a key from a column family
+    pub fn delete_cf(&self, cf_handle: &ColumnFamilyHandle, key: &[u8]) -> Result<()> {
+        self.throttle(1, key.len());
+        self.db.delete_cf(cf_handle, key)
+    }
+
+    /// Apply a write batch atomically
+    pub fn write(&self, batch: &WriteBatch) -> Result<()> {
+        self.throttle(batch.len(), batch.data().len());
+        self.db.write(batch)
+    }
+
+    /// Block until `ops` operations totalling `bytes` bytes fit within the limits
+    fn throttle(&self, ops: usize, bytes: usize) {
+        let wait = reserve(&self.ops, ops).max(reserve(&self.bytes, bytes));
+        if !wait.is_zero() {
+            thread::sleep(wait);
+        }
+    }
+}
+
+/// Reserve `amount` tokens from an optional bucket
+fn reserve(bucket: &Option<Mutex<TokenBucket>>, amount: usize) -> Duration {
+    match bucket {
+        Some(bucket) => bucket
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .reserve(amount as f64),
+        None => Duration::ZERO,
+    }
+}
@@ -0,0 +1,369 @@
+//! Typed keys and values on top of the byte-oriented API
+//!
+//! [`TypedDb`] and [`TypedCf`] encode keys and values with a [`Codec`] on
+//! the way in and decode them on the way out, so callers work with their own
+//! types instead of byte slices. [`OrderedCodec`] covers byte strings,
+//! strings and integers, and with the `serde` feature `JsonCodec` covers any
+//! serde type; any other format plugs in by implementing [`Codec`] for a
+//! marker type of its own.
+
+use crate::db::{ColumnFamilyHandle, DB};
+use crate::error::{Error, Result};
+use crate::iterator::DBIteratorAdapter;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+
+/// Converts values of type `T` to and from bytes
+///
+/// Implemented by marker types, so one codec can serve many value types.
+/// Key codecs decide the iteration order: RocksDB sorts keys by their
+/// encoded bytes.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{Codec, Error, Result};
+///
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// struct PointCodec;
+///
+/// impl Codec<Point> for PointCodec {
+///     fn encode(value: &Point) -> Vec<u8> {
+///         let mut bytes = value.x.to_le_bytes().to_vec();
+///         bytes.extend_from_slice(&value.y.to_le_bytes());
+///         bytes
+///     }
+///
+///     fn decode(bytes: &[u8]) -> Result<Point> {
+///         let bytes: [u8; 8] = bytes
+///             .try_into()
+///             .map_err(|_| Error::new("Point must be 8 bytes"))?;
+///         Ok(Point {
+///             x: i32::from_le_bytes(bytes[..4].try_into().unwrap()),
+///             y: i32::from_le_bytes(bytes[4..].try_into().unwrap()),
+///         })
+///     }
+/// }
+/// ```
+pub trait Codec<T> {
+    /// Encode a value
+    fn encode(value: &T) -> Vec<u8>;
+
+    /// Decode a value, failing if `bytes` were not produced by [`Codec::encode`]
+    fn decode(bytes: &[u8]) -> Result<T>;
+}
+
+/// Codec whose encoding sorts like the values themselves
+///
+/// Byte strings and strings are stored as they are. Integers are stored
+/// big-endian in their full width, with the sign bit of signed integers
+/// flipped, so negative numbers sort before positive ones.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OrderedCodec;
+
+impl Codec<Vec<u8>> for OrderedCodec {
+    fn encode(value: &Vec<u8>) -> Vec<u8> {
+        value.clone()
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Vec<u8>> {
+        Ok(bytes.to_vec())
+    }
+}
+
+impl Codec<String> for OrderedCodec {
+    fn encode(value: &String) -> Vec<u8> {
+        value.as_bytes().to_vec()
+    }
+
+    fn decode(bytes: &[u8]) -> Result<String> {
+        String::from_utf8(bytes.to_vec()).map_err(|_| Error::new("Stored string is not UTF-8"))
+    }
+}
+
+macro_rules! impl_ordered_int {
+    ($($int:ty => $flip:expr),*) => {
+        $(
+            impl Codec<$int> for OrderedCodec {
+                fn encode(value: &$int) -> Vec<u8> {
+                    (*value ^ $flip).to_be_bytes().to_vec()
+                }
+
+                fn decode(bytes: &[u8]) -> Result<$int> {
+                    let bytes = bytes.try_into().map_err(|_| {
+                        Error::new(format!(
+                            "Stored {} has {} bytes, expected {}",
+                            stringify!($int),
+                            bytes.len(),
+                            size_of::<$int>()
+                        ))
+                    })?;
+                    Ok(<$int>::from_be_bytes(bytes) ^ $flip)
+                }
+            }
+        )*
+    };
+}
+
+impl_ordered_int!(
+    u8 => 0,
+    u16 => 0,
+    u32 => 0,
+    u64 => 0,
+    u128 => 0,
+    i8 => i8::MIN,
+    i16 => i16::MIN,
+    i32 => i32::MIN,
+    i64 => i64::MIN,
+    i128 => i128::MIN
+);
+
+/// Codec storing serde types as JSON (needs the `serde` feature)
+///
+/// The encoding does not sort like the values, so it suits values, and keys
+/// that are only looked up exactly.
+///
+/// # Panics
+///
+/// Encoding panics if the value can't be represented as JSON, which happens
+/// for maps whose keys don't serialize as strings or numbers, and for
+/// `Serialize` implementations that fail.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, JsonCodec, OrderedCodec, Options, TypedDb};
+/// use std::collections::BTreeMap;
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let profiles = TypedDb::<u64, BTreeMap<String, String>, OrderedCodec, JsonCodec>::new(&db);
+/// let profile = BTreeMap::from([("name".to_string(), "Alice".to_string())]);
+/// profiles.put(&1, &profile).unwrap();
+/// assert_eq!(profiles.get(&1).unwrap(), Some(profile));
+/// ```
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonCodec;
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> Codec<T> for JsonCodec {
+    fn encode(value: &T) -> Vec<u8> {
+        serde_json::to_vec(value).expect("Failed to encode value as JSON")
+    }
+
+    fn decode(bytes: &[u8]) -> Result<T> {
+        serde_json::from_slice(bytes)
+            .map_err(|e| Error::new(format!("Failed to decode stored JSON: {}", e)))
+    }
+}
+
+/// Marks the key, value and codec types without holding any of them
+type Types<K, V, KC, VC> = PhantomData<fn() -> (K, V, KC, VC)>;
+
+/// Encode both ends of a typed range
+fn encode_range<K, KC: Codec<K>>(range: impl RangeBounds<K>) -> (Bound<Vec<u8>>, Bound<Vec<u8>>) {
+    let encode = |bound: Bound<&K>| match bound {
+        Bound::Included(key) => Bound::Included(KC::encode(key)),
+        Bound::Excluded(key) => Bound::Excluded(KC::encode(key)),
+        Bound::Unbounded => Bound::Unbounded,
+    };
+    (encode(range.start_bound()), encode(range.end_bound()))
+}
+
+/// The default column family of a database, with typed keys and values
+///
+/// Keys are encoded with `KC` and values with `VC`, both [`OrderedCodec`]
+/// unless given.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options, TypedDb};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let scores = TypedDb::<u64, String>::new(&db);
+/// scores.put(&7, &"seven".to_string()).unwrap();
+/// assert_eq!(scores.get(&7).unwrap(), Some("seven".to_string()));
+///
+/// for entry in scores.range(5..10) {
+///     let (id, name) = entry.unwrap();
+///     println!("{}: {}", id, name);
+/// }
+/// ```
+pub struct TypedDb<'a, K, V, KC = OrderedCodec, VC = OrderedCodec> {
+    db: &'a DB,
+    _types: Types<K, V, KC, VC>,
+}
+
+impl<'a, K, V, KC: Codec<K>, VC: Codec<V>> TypedDb<'a, K, V, KC, VC> {
+    /// Wrap the default column family of `db`
+    pub fn new(db: &'a DB) -> Self {
+        TypedDb {
+            db,
+            _types: PhantomData,
+        }
+    }
+
+    /// Get the database
+    pub fn db(&self) -> &'a DB {
+        self.db
+    }
+
+    /// Write a key-value pair
+    pub fn put(&self, key: &K, value: &V) -> Result<()> {
+        self.db.put(&KC::encode(key), &VC::encode(value))
+    }
+
+    /// Read the value of a key
+    pub fn get(&self, key: &K) -> Result<Option<V>> {
+        self.db
+            .get(&KC::encode(key))?
+            .map(|value| VC::decode(&value))
+            .transpose()
+    }
+
+    /// Check whether a key exists
+    pub fn contains_key(&self, key: &K) -> Result<bool> {
+        self.db.contains_key(&KC::encode(key))
+    }
+
+    /// Delete a key
+    pub fn delete(&self, key: &K) -> Result<()> {
+        self.db.delete(&KC::encode(key))
+    }
+
+    /// Iterate over every entry in key order
+    pub fn iter(&self) -> TypedIter<'a, K, V, KC, VC> {
+        TypedIter::new(self.db.range(..))
+    }
+
+    /// Iterate over the entries in a range of keys
+    ///
+    /// The range is applied to the encoded keys, so it selects the expected
+    /// keys only with an order-preserving key codec such as [`OrderedCodec`].
+    pub fn range(&self, range: impl RangeBounds<K>) -> TypedIter<'a, K, V, KC, VC> {
+        TypedIter::new(self.db.range(encode_range::<K, KC>(range)))
+    }
+}
+
+/// A column family with typed keys and values
+///
+/// The column family counterpart of [`TypedDb`].
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options, TypedCf};
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open_cf(&opts, "/tmp/my_db", ["balances"]).unwrap();
+/// let handle = db.cf_handle("balances").unwrap();
+///
+/// let balances = TypedCf::<String, i64>::new(&db, &handle);
+/// balances.put(&"alice".to_string(), &-20).unwrap();
+/// assert_eq!(balances.get(&"alice".to_string()).unwrap(), Some(-20));
+/// ```
+pub struct TypedCf<'a, K, V, KC = OrderedCodec, VC = OrderedCodec> {
+    db: &'a DB,
+    cf_handle: &'a ColumnFamilyHandle,
+    _types: Types<K, V, KC, VC>,
+}
+
+impl<'a, K, V, KC: Codec<K>, VC: Codec<V>> TypedCf<'a, K, V, KC, VC> {
+    /// Wrap a column family of `db`
+    pub fn new(db: &'a DB, cf_handle: &'a ColumnFamilyHandle) -> Self {
+        TypedCf {
+            db,
+            cf_handle,
+            _types: PhantomData,
+        }
+    }
+
+    /// Get the database
+    pub fn db(&self) -> &'a DB {
+        self.db
+    }
+
+    /// Get the column family handle
+    pub fn handle(&self) -> &'a ColumnFamilyHandle {
+        self.cf_handle
+    }
+
+    /// Write a key-value pair
+    pub fn put(&self, key: &K, value: &V) -> Result<()> {
+        self.db
+            .put_cf(self.cf_handle, &KC::encode(key), &VC::encode(value))
+    }
+
+    /// Read the value of a key
+    pub fn get(&self, key: &K) -> Result<Option<V>> {
+        self.db
+            .get_cf(self.cf_handle, &KC::encode(key))?
+            .map(|value| VC::decode(&value))
+            .transpose()
+    }
+
+    /// Check whether a key exists
+    pub fn contains_key(&self, key: &K) -> Result<bool> {
+        self.db.contains_key_cf(self.cf_handle, &KC::encode(key))
+    }
+
+    /// Delete a key
+    pub fn delete(&self, key: &K) -> Result<()> {
+        self.db.delete_cf(self.cf_handle, &KC::encode(key))
+    }
+
+    /// Iterate over every entry in key order
+    pub fn iter(&self) -> TypedIter<'a, K, V, KC, VC> {
+        TypedIter::new(self.db.range_cf(self.cf_handle, ..))
+    }
+
+    /// Iterate over the entries in a range of keys
+    ///
+    /// See [`TypedDb::range`].
+    pub fn range(&self, range: impl RangeBounds<K>) -> TypedIter<'a, K, V, KC, VC> {
+        TypedIter::new(
+            self.db
+                .range_cf(self.cf_handle, encode_range::<K, KC>(range)),
+        )
+    }
+}
+
+/// Iterator over decoded entries, from [`TypedDb::iter`] and friends
+///
+/// Yields `Result<(K, V)>`: an entry that fails to decode is returned as an
+/// error and iteration continues with the next one.
+#[must_use = "Iterators are lazy and do nothing unless consumed"]
+pub struct TypedIter<'a, K, V, KC = OrderedCodec, VC = OrderedCodec> {
+    inner: DBIteratorAdapter<'a>,
+    _types: Types<K, V, KC, VC>,
+}
+
+impl<'a, K, V, KC, VC> TypedIter<'a, K, V, KC, VC> {
+    fn new(inner: DBIteratorAdapter<'a>) -> Self {
+        TypedIter {
+            inner,
+            _types: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V, KC: Codec<K>, VC: Codec<V>> Iterator for TypedIter<'a, K, V, KC, VC> {
+    type Item = Result<(K, V)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.inner.next()?;
+        Some(entry.and_then(|(key, value)| Ok((KC::decode(&key)?, VC::decode(&value)?))))
+    }
+}
@@ -0,0 +1,95 @@
+//! Tailing the write-ahead log
+
+use crate::batch::WriteBatch;
+use crate::error::{Error, Result};
+use crate::ffi;
+use std::marker::PhantomData;
+use std::ptr::{self, NonNull};
+
+/// An iterator over the write batches recorded in the WAL
+///
+/// Created by [`DB::get_updates_since`](crate::DB::get_updates_since). Yields
+/// `(sequence_number, batch)` pairs in commit order, where the sequence number
+/// is that of the first operation in the batch. The batch after it therefore
+/// starts at `sequence_number + batch.len()`.
+///
+/// The iterator ends once it reaches the end of the WAL. To keep tailing,
+/// create a new iterator starting after the last batch seen.
+#[must_use = "Iterators are lazy and do nothing unless consumed"]
+pub struct WalIterator<'a> {
+    inner: NonNull<ffi::rocksdb_wal_iterator_t>,
+    started: bool,
+    done: bool,
+    _phantom: PhantomData<&'a ()>,
+}
+
+impl<'a> WalIterator<'a> {
+    /// Create a new WAL iterator (internal use only)
+    pub(crate) unsafe fn new(inner: NonNull<ffi::rocksdb_wal_iterator_t>) -> Self {
+        WalIterator {
+            inner,
+            started: false,
+            done: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Check for any error that occurred while reading the WAL
+    #[must_use = "Iterator errors should be checked to detect I/O failures"]
+    pub fn status(&self) -> Result<()> {
+        unsafe {
+            let mut err: *mut i8 = ptr::null_mut();
+            ffi::rocksdb_wal_iter_status(self.inner.as_ptr(), &mut err);
+
+            if err.is_null() {
+                Ok(())
+            } else {
+                Err(Error::from_c_string(err))
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for WalIterator<'a> {
+    type Item = Result<(u64, WriteBatch)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        // The iterator starts out positioned at the first batch
+        if self.started {
+            unsafe { ffi::rocksdb_wal_iter_next(self.inner.as_ptr()) };
+        }
+        self.started = true;
+
+        if unsafe { ffi::rocksdb_wal_iter_valid(self.inner.as_ptr()) } == 0 {
+            self.done = true;
+            return match self.status() {
+                Ok(()) => None,
+                Err(e) => Some(Err(e)),
+            };
+        }
+
+        let mut seq: u64 = 0;
+        let batch_ptr = unsafe { ffi::rocksdb_wal_iter_get_batch(self.inner.as_ptr(), &mut seq) };
+
+        match NonNull::new(batch_ptr) {
+            Some(batch) => Some(Ok((seq, WriteBatch::from_raw(batch)))),
+            None => {
+                self.done = true;
+                Some(Err(Error::new("Failed to read write batch from WAL")))
+            }
+        }
+    }
+}
+
+impl<'a> Drop for WalIterator<'a> {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_wal_iter_destroy(self.inner.as_ptr());
+        }));
+    }
+}
@@ -0,0 +1,110 @@
+//! Memtable memory budgets shared across databases
+
+use crate::error::{Error, Result};
+use crate::ffi;
+use std::ptr::NonNull;
+
+/// A memory budget for the memtables of one or more databases
+///
+/// Attach it with [`Options::set_write_buffer_manager`](crate::Options::set_write_buffer_manager).
+/// Every database (and column family) opened with options sharing the same
+/// manager counts its memtables against one budget; once the total passes
+/// the budget, the largest memtables are flushed early. This bounds memtable
+/// memory for a process running many databases, which would otherwise grow
+/// with the number of databases opened.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options, WriteBufferManager};
+///
+/// // 256 MiB of memtables across all tenants
+/// let wbm = WriteBufferManager::new(256 * 1024 * 1024, false).unwrap();
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true).set_write_buffer_manager(&wbm);
+///
+/// let tenants: Vec<DB> = (0..32)
+///     .map(|i| DB::open(&opts, format!("/tmp/tenant_{}", i)).unwrap())
+///     .collect();
+///
+/// println!("memtables use {} bytes", wbm.memory_usage());
+/// ```
+pub struct WriteBufferManager {
+    inner: NonNull<ffi::rocksdb_write_buffer_manager_t>,
+}
+
+impl WriteBufferManager {
+    /// Create a manager limiting memtables to `buffer_size` bytes in total
+    ///
+    /// With `allow_stall`, writes to every database sharing the manager stall
+    /// while memory usage exceeds the budget and flushes catch up, making the
+    /// budget a hard limit instead of a flush trigger.
+    pub fn new(buffer_size: usize, allow_stall: bool) -> Result<Self> {
+        if buffer_size == 0 {
+            return Err(Error::new("Write buffer budget must be positive"));
+        }
+
+        unsafe {
+            let ptr = ffi::rocksdb_write_buffer_manager_create(buffer_size, allow_stall);
+            Ok(WriteBufferManager {
+                inner: NonNull::new(ptr)
+                    .ok_or_else(|| Error::new("Failed to create write buffer manager"))?,
+            })
+        }
+    }
+
+    /// Get the memory used by all memtables counted against the budget, in bytes
+    pub fn memory_usage(&self) -> usize {
+        unsafe { ffi::rocksdb_write_buffer_manager_memory_usage(self.inner.as_ptr()) }
+    }
+
+    /// Get the memory used by memtables still accepting writes, in bytes
+    pub fn mutable_memtable_memory_usage(&self) -> usize {
+        unsafe {
+            ffi::rocksdb_write_buffer_manager_mutable_memtable_memory_usage(self.inner.as_ptr())
+        }
+    }
+
+    /// Get the budget in bytes
+    pub fn buffer_size(&self) -> usize {
+        unsafe { ffi::rocksdb_write_buffer_manager_buffer_size(self.inner.as_ptr()) }
+    }
+
+    /// Change the budget while databases are running
+    pub fn set_buffer_size(&self, buffer_size: usize) -> Result<()> {
+        if buffer_size == 0 {
+            return Err(Error::new("Write buffer budget must be positive"));
+        }
+
+        unsafe {
+            ffi::rocksdb_write_buffer_manager_set_buffer_size(self.inner.as_ptr(), buffer_size);
+        }
+        Ok(())
+    }
+
+    /// Change whether writes stall while the budget is exceeded
+    pub fn set_allow_stall(&self, allow_stall: bool) {
+        unsafe {
+            ffi::rocksdb_write_buffer_manager_set_allow_stall(self.inner.as_ptr(), allow_stall);
+        }
+    }
+
+    /// Get the raw pointer for FFI calls
+    pub(crate) fn as_ptr(&self) -> *mut ffi::rocksdb_write_buffer_manager_t {
+        self.inner.as_ptr()
+    }
+}
+
+impl Drop for WriteBufferManager {
+    fn drop(&mut self) {
+        // Catch panics to prevent double-panic during unwinding
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            ffi::rocksdb_write_buffer_manager_destroy(self.inner.as_ptr());
+        }));
+    }
+}
+
+// The RocksDB write buffer manager is internally synchronized
+unsafe impl Send for WriteBufferManager {}
+unsafe impl Sync for WriteBufferManager {}
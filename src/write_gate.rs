@@ -0,0 +1,188 @@
+//! Cooperative backpressure based on compaction debt
+//!
+//! When compactions fall behind, RocksDB first slows every write down and
+//! then stops writes altogether until it catches up. Those stalls hit all
+//! writers at once, latency-sensitive ones included. A [`WriteGate`] watches
+//! the same signals against lower, caller-chosen thresholds, so bulk
+//! producers can back off before RocksDB has to stall anyone.
+
+use crate::db::{ColumnFamilyHandle, DB};
+use crate::error::Result;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How far compactions are behind, as read by [`WriteGate::debt`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactionDebt {
+    /// Bytes compactions must rewrite to bring every level under its target
+    /// size (`rocksdb.estimate-pending-compaction-bytes`)
+    pub pending_compaction_bytes: u64,
+    /// Files in level 0 (`rocksdb.num-files-at-level0`)
+    pub l0_files: u64,
+}
+
+/// Why a [`WriteGate`] asks writers to back off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BackOff {
+    /// Pending compaction bytes reached the limit
+    PendingCompactionBytes {
+        /// The current estimate
+        pending: u64,
+        /// The limit set with [`WriteGate::set_max_pending_compaction_bytes`]
+        limit: u64,
+    },
+    /// The number of level 0 files reached the limit
+    Level0Files {
+        /// The current number of files
+        files: u64,
+        /// The limit set with [`WriteGate::set_max_l0_files`]
+        limit: u64,
+    },
+}
+
+/// A check of compaction debt against thresholds, for producers to consult
+/// before writing
+///
+/// Created with [`DB::write_gate`] or [`DB::write_gate_cf`]. The gate never
+/// blocks writes itself: producers call [`WriteGate::check`] or
+/// [`WriteGate::wait`] and decide what to do. A gate with no thresholds set
+/// is always open. Set the thresholds below RocksDB's own
+/// (`level0_slowdown_writes_trigger` and
+/// `soft_pending_compaction_bytes_limit`) for the gate to act first.
+///
+/// # Example
+///
+/// ```no_run
+/// use rust_small_rocksdb::{DB, Options};
+/// use std::time::Duration;
+///
+/// let mut opts = Options::default();
+/// opts.create_if_missing(true);
+/// let db = DB::open(&opts, "/tmp/my_db").unwrap();
+///
+/// let mut gate = db.write_gate();
+/// gate.set_max_l0_files(12)
+///     .set_max_pending_compaction_bytes(32 * 1024 * 1024 * 1024);
+///
+/// for chunk in 0..1000u32 {
+///     if let Some(reason) = gate.wait(Duration::from_secs(30)).unwrap() {
+///         println!("Compactions still behind ({:?}), writing anyway", reason);
+///     }
+///     db.put(&chunk.to_be_bytes(), b"value").unwrap();
+/// }
+/// ```
+pub struct WriteGate<'a> {
+    db: &'a DB,
+    cf_handle: Option<&'a ColumnFamilyHandle>,
+    max_pending_compaction_bytes: Option<u64>,
+    max_l0_files: Option<u64>,
+    poll_interval: Duration,
+}
+
+impl<'a> WriteGate<'a> {
+    /// Default time between checks in [`WriteGate::wait`]
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Create a gate with no thresholds (internal use only)
+    pub(crate) fn new(db: &'a DB, cf_handle: Option<&'a ColumnFamilyHandle>) -> Self {
+        WriteGate {
+            db,
+            cf_handle,
+            max_pending_compaction_bytes: None,
+            max_l0_files: None,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Back off once pending compaction bytes reach `value`
+    pub fn set_max_pending_compaction_bytes(&mut self, value: u64) -> &mut Self {
+        self.max_pending_compaction_bytes = Some(value);
+        self
+    }
+
+    /// Back off once level 0 holds `value` files
+    pub fn set_max_l0_files(&mut self, value: u64) -> &mut Self {
+        self.max_l0_files = Some(value);
+        self
+    }
+
+    /// Set how often [`WriteGate::wait`] rechecks the debt (default: 100 ms)
+    pub fn set_poll_interval(&mut self, value: Duration) -> &mut Self {
+        self.poll_interval = value;
+        self
+    }
+
+    /// Read the current compaction debt
+    pub fn debt(&self) -> Result<CompactionDebt> {
+        Ok(CompactionDebt {
+            pending_compaction_bytes: self.property("rocksdb.estimate-pending-compaction-bytes")?,
+            l0_files: self.string_property("rocksdb.num-files-at-level0")?,
+        })
+    }
+
+    /// Check whether writers should back off now
+    ///
+    /// Returns `None` when every threshold is respected.
+    pub fn check(&self) -> Result<Option<BackOff>> {
+        if self.max_pending_compaction_bytes.is_none() && self.max_l0_files.is_none() {
+            return Ok(None);
+        }
+
+        let debt = self.debt()?;
+        if let Some(limit) = self.max_l0_files
+            && debt.l0_files >= limit
+        {
+            return Ok(Some(BackOff::Level0Files {
+                files: debt.l0_files,
+                limit,
+            }));
+        }
+        if let Some(limit) = self.max_pending_compaction_bytes
+            && debt.pending_compaction_bytes >= limit
+        {
+            return Ok(Some(BackOff::PendingCompactionBytes {
+                pending: debt.pending_compaction_bytes,
+                limit,
+            }));
+        }
+        Ok(None)
+    }
+
+    /// Block until writers no longer need to back off, or until `timeout`
+    ///
+    /// Returns `None` once the gate is open, or the reason it is still
+    /// closed when the timeout runs out.
+    pub fn wait(&self, timeout: Duration) -> Result<Option<BackOff>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let back_off = self.check()?;
+            let now = Instant::now();
+            if back_off.is_none() || now >= deadline {
+                return Ok(back_off);
+            }
+            thread::sleep(self.poll_interval.min(deadline - now));
+        }
+    }
+
+    /// Read a numeric property of the watched column family (0 if unavailable)
+    fn property(&self, name: &str) -> Result<u64> {
+        let value = match self.cf_handle {
+            Some(cf_handle) => self.db.property_int_value_cf(cf_handle, name)?,
+            None => self.db.property_int_value(name)?,
+        };
+        Ok(value.unwrap_or(0))
+    }
+
+    /// Read a property RocksDB only reports as a string, parsed as a number
+    /// (0 if unavailable)
+    fn string_property(&self, name: &str) -> Result<u64> {
+        let value = match self.cf_handle {
+            Some(cf_handle) => self.db.property_value_cf(cf_handle, name)?,
+            None => self.db.property_value(name)?,
+        };
+        Ok(value
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(0))
+    }
+}
@@ -1,5 +1,11 @@
-use rust_small_rocksdb::{DB, Options};
+use rust_small_rocksdb::{
+    BlockBasedOptions, BoundColumnFamily, CfPreset, ColumnFamilyDescriptor, CompactionProgress,
+    CompressionType, DB, Env, Error, ErrorKind, Options, TypedCf, WriteBatch, WriteBatchOp,
+};
 use std::fs;
+use std::os::raw::{c_int, c_void};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 
 #[test]
 fn test_create_column_family() {
@@ -54,6 +60,45 @@ fn test_create_multiple_column_families() {
     let _ = fs::remove_dir_all(path);
 }
 
+#[test]
+fn test_create_column_families_in_one_call() {
+    let path = "/tmp/rust_rocksdb_test_create_cfs";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let cf_opts = Options::default();
+    let handles = db
+        .create_column_families(&cf_opts, &["a", "b", "c"])
+        .expect("Failed to create column families");
+    let names: Vec<&str> = handles.iter().map(|handle| handle.name()).collect();
+    assert_eq!(names, vec!["a", "b", "c"]);
+
+    db.put_cf(&handles[1], b"key", b"in b")
+        .expect("Failed to put");
+    let b = db.cf_handle("b").expect("Missing created CF");
+    assert_eq!(
+        db.get_cf(&b, b"key").expect("Failed to get").as_deref(),
+        Some(&b"in b"[..])
+    );
+
+    // An existing name fails, but the column families before it are kept
+    assert!(db.create_column_families(&cf_opts, &["d", "a"]).is_err());
+    assert!(db.cf_handle("d").is_some());
+
+    drop(b);
+    drop(handles);
+    drop(db);
+
+    let mut listed = DB::list_column_families(&opts, path).expect("Failed to list CFs");
+    listed.sort();
+    assert_eq!(listed, vec!["a", "b", "c", "d", "default"]);
+
+    let _ = fs::remove_dir_all(path);
+}
+
 #[test]
 fn test_column_family_invalid_name() {
     let path = "/tmp/rust_rocksdb_test_cf_invalid";
@@ -142,6 +187,40 @@ fn test_delete_cf() {
     let _ = fs::remove_dir_all(path);
 }
 
+#[test]
+fn test_contains_key_cf() {
+    let path = "/tmp/rust_rocksdb_test_contains_key_cf";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let cf_opts = Options::default();
+    let cf_handle = db
+        .create_column_family(&cf_opts, "users")
+        .expect("Failed to create column family");
+
+    db.put_cf(&cf_handle, b"user:1", b"Alice")
+        .expect("Failed to put");
+
+    assert!(
+        db.contains_key_cf(&cf_handle, b"user:1")
+            .expect("Failed to check key")
+    );
+    assert!(
+        !db.contains_key_cf(&cf_handle, b"user:2")
+            .expect("Failed to check key")
+    );
+    // The key only exists in its own column family
+    assert!(!db.contains_key(b"user:1").expect("Failed to check key"));
+
+    drop(cf_handle);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
 #[test]
 fn test_cf_isolation() {
     let path = "/tmp/rust_rocksdb_test_cf_isolation";
@@ -207,6 +286,151 @@ fn test_drop_column_family() {
     let _ = fs::remove_dir_all(path);
 }
 
+#[test]
+fn test_drop_column_family_by_name() {
+    let path = "/tmp/rust_rocksdb_test_drop_cf_by_name";
+    let _ = fs::remove_dir_all(path);
+
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).expect("Failed to open database");
+        let cf_opts = Options::default();
+        let handles = db
+            .create_column_families(&cf_opts, &["keep", "temp"])
+            .expect("Failed to create column families");
+        drop(handles);
+        drop(db);
+    }
+
+    // After a reopen only the names are known
+    let opts = Options::default();
+    let cf_names = vec!["default", "keep", "temp"];
+    let cf_opts = vec![Options::default(), Options::default(), Options::default()];
+    let (db, cf_handles) = DB::open_with_column_families(&opts, path, &cf_names, &cf_opts)
+        .expect("Failed to open with CFs");
+    drop(cf_handles);
+
+    db.drop_column_family_by_name("temp")
+        .expect("Failed to drop column family by name");
+    assert!(db.cf_handle("temp").is_none());
+    assert!(db.drop_column_family_by_name("temp").is_err());
+    assert!(db.drop_column_family_by_name("default").is_err());
+    drop(db);
+
+    let mut listed = DB::list_column_families(&opts, path).expect("Failed to list CFs");
+    listed.sort();
+    assert_eq!(listed, vec!["default", "keep"]);
+
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_with_temp_cf() {
+    let path = "/tmp/rust_rocksdb_test_with_temp_cf";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let name = db
+        .with_temp_cf(|scratch| {
+            db.put_cf(scratch, b"key", b"value")?;
+            assert_eq!(db.get_cf(scratch, b"key")?, Some(b"value".to_vec()));
+            assert!(db.get(b"key")?.is_none());
+            Ok(scratch.name().to_string())
+        })
+        .expect("Failed to use temporary column family");
+    assert!(name.starts_with("__temp_cf_"));
+    assert!(db.cf_handle(&name).is_none());
+
+    // The closure's error is returned and the column family still dropped
+    let mut failed_name = String::new();
+    let result: Result<(), _> = db.with_temp_cf(|scratch| {
+        failed_name = scratch.name().to_string();
+        db.put_cf(scratch, b"", b"value")?;
+        Err(Error::new("job failed"))
+    });
+    assert!(result.is_err());
+    assert_ne!(failed_name, name);
+    assert!(db.cf_handle(&failed_name).is_none());
+
+    // So is a column family whose closure panicked
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let _ = db.with_temp_cf(|_| -> Result<(), Error> { panic!("job panicked") });
+    }));
+    assert!(panicked.is_err());
+
+    drop(db);
+    let listed = DB::list_column_families(&opts, path).expect("Failed to list CFs");
+    assert_eq!(listed, vec!["default"]);
+
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_compact_all() {
+    let path = "/tmp/rust_rocksdb_test_compact_all";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open_cf(&opts, path, ["users", "posts"]).expect("Failed to open database");
+    let users = db.cf_handle("users").expect("Failed to get column family");
+    for i in 0..100u32 {
+        let key = format!("key{:03}", i);
+        db.put(key.as_bytes(), b"value")
+            .expect("Failed to put value");
+        db.put_cf(&users, key.as_bytes(), b"value")
+            .expect("Failed to put value in column family");
+        db.delete_cf(&users, key.as_bytes())
+            .expect("Failed to delete value in column family");
+    }
+
+    // Default first, then the others by name
+    let events = Mutex::new(Vec::new());
+    db.compact_all(|progress| events.lock().unwrap().push(progress.clone()))
+        .expect("Failed to compact all column families");
+    let events = events.into_inner().unwrap();
+    assert_eq!(events.len(), 6);
+    let order: Vec<_> = events
+        .iter()
+        .filter_map(|progress| match progress {
+            CompactionProgress::Finished {
+                column_family,
+                completed,
+                total,
+                ..
+            } => Some((column_family.as_str(), *completed, *total)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        order,
+        vec![("default", 1, 3), ("posts", 2, 3), ("users", 3, 3)]
+    );
+
+    let finished = Mutex::new(Vec::new());
+    db.compact_all_parallel(2, |progress| {
+        if let CompactionProgress::Finished { column_family, .. } = progress {
+            finished.lock().unwrap().push(column_family.clone());
+        }
+    })
+    .expect("Failed to compact all column families in parallel");
+    let mut finished = finished.into_inner().unwrap();
+    finished.sort();
+    assert_eq!(finished, vec!["default", "posts", "users"]);
+
+    // Compaction never changes visible data
+    assert_eq!(db.get(b"key042").unwrap(), Some(b"value".to_vec()));
+    assert!(db.get_cf(&users, b"key042").unwrap().is_none());
+
+    drop(users);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
 #[test]
 fn test_open_with_column_families() {
     let path = "/tmp/rust_rocksdb_test_open_with_cf";
@@ -265,6 +489,137 @@ fn test_open_with_column_families() {
     let _ = fs::remove_dir_all(path);
 }
 
+#[test]
+fn test_list_column_families() {
+    let path = "/tmp/rust_rocksdb_test_list_cf";
+    let _ = fs::remove_dir_all(path);
+
+    let opts = Options::default();
+    assert!(DB::list_column_families(&opts, path).is_err());
+
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).expect("Failed to open database");
+
+        let cf_opts = Options::default();
+        let cf = db
+            .create_column_family(&cf_opts, "users")
+            .expect("Failed to create users CF");
+        drop(cf);
+        drop(db);
+    }
+
+    let mut names = DB::list_column_families(&opts, path).expect("Failed to list CFs");
+    names.sort();
+    assert_eq!(names, vec!["default".to_string(), "users".to_string()]);
+
+    // The listed names are enough to reopen the database
+    let cf_names: Vec<&str> = names.iter().map(String::as_str).collect();
+    let cf_opts: Vec<Options> = names.iter().map(|_| Options::default()).collect();
+    let (db, cf_handles) = DB::open_with_column_families(&opts, path, &cf_names, &cf_opts)
+        .expect("Failed to open with listed CFs");
+    assert_eq!(cf_handles.len(), 2);
+
+    drop(cf_handles);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_open_cf_descriptors() {
+    let path = "/tmp/rust_rocksdb_test_open_cf_descriptors";
+    let _ = fs::remove_dir_all(path);
+
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).expect("Failed to open database");
+        let cf_opts = Options::default();
+        let logs = db
+            .create_column_family(&cf_opts, "logs")
+            .expect("Failed to create logs CF");
+        db.put_cf(&logs, b"line:1", b"started")
+            .expect("Failed to put");
+        drop(logs);
+        drop(db);
+    }
+
+    let opts = Options::default();
+
+    // Leaving out an existing column family fails
+    let partial = vec![ColumnFamilyDescriptor::new("default", Options::default())];
+    assert!(DB::open_cf_descriptors(&opts, path, &partial).is_err());
+
+    let mut logs_opts = Options::default();
+    logs_opts.set_compression_type(CompressionType::None);
+    let descriptors = vec![
+        ColumnFamilyDescriptor::new("default", Options::default()),
+        ColumnFamilyDescriptor::new("logs", logs_opts),
+    ];
+    assert_eq!(descriptors[1].name, "logs");
+
+    let db = DB::open_cf_descriptors(&opts, path, &descriptors).expect("Failed to open");
+    let logs = db.cf_handle("logs").expect("Missing logs CF");
+    assert_eq!(
+        db.get_cf(&logs, b"line:1")
+            .expect("Failed to get")
+            .as_deref(),
+        Some(&b"started"[..])
+    );
+
+    drop(logs);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_open_cf_creates_missing() {
+    let path = "/tmp/rust_rocksdb_test_open_cf";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    {
+        let db = DB::open_cf(&opts, path, ["users"]).expect("Failed to open with new CF");
+        let users = db.cf_handle("users").expect("Missing users CF");
+        db.put_cf(&users, b"user:1", b"Alice")
+            .expect("Failed to put");
+        assert!(db.cf_handle("default").is_some());
+        drop(users);
+        drop(db);
+    }
+
+    // Existing column families are opened even when not listed
+    {
+        let db = DB::open_cf(&opts, path, ["posts"]).expect("Failed to reopen");
+        let users = db.cf_handle("users").expect("Unlisted CF was not opened");
+        assert_eq!(
+            db.get_cf(&users, b"user:1")
+                .expect("Failed to get")
+                .as_deref(),
+            Some(&b"Alice"[..])
+        );
+        assert!(db.cf_handle("posts").is_some());
+        drop(users);
+        drop(db);
+    }
+
+    let mut listed = DB::list_column_families(&opts, path).expect("Failed to list CFs");
+    listed.sort();
+    assert_eq!(listed, vec!["default", "posts", "users"]);
+
+    // Failing to list an existing database's column families is an error
+    fs::write(format!("{}/CURRENT", path), "garbage").expect("Failed to write CURRENT");
+    let err = DB::open_cf(&opts, path, ["posts"])
+        .err()
+        .expect("Open should fail");
+    assert_eq!(err.kind(), ErrorKind::Corruption);
+
+    let _ = fs::remove_dir_all(path);
+}
+
 #[test]
 fn test_open_with_column_families_errors() {
     let path = "/tmp/rust_rocksdb_test_open_with_cf_errors";
@@ -288,3 +643,780 @@ fn test_open_with_column_families_errors() {
 
     let _ = fs::remove_dir_all(path);
 }
+
+#[test]
+fn test_range_cf() {
+    let path = "/tmp/rust_rocksdb_test_range_cf";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let cf_opts = Options::default();
+    let cf = db
+        .create_column_family(&cf_opts, "ranged")
+        .expect("Failed to create column family");
+
+    db.put(b"b", b"default").expect("Failed to put");
+    db.put_cf(&cf, b"a", b"1").expect("Failed to put");
+    db.put_cf(&cf, b"b", b"2").expect("Failed to put");
+    db.put_cf(&cf, b"c", b"3").expect("Failed to put");
+
+    let items: Vec<(Vec<u8>, Vec<u8>)> = db
+        .range_cf(&cf, b"b"..)
+        .map(|item| item.map(|(key, value)| (key.into_vec(), value.into_vec())))
+        .collect::<Result<_, _>>()
+        .expect("Failed to iterate");
+    assert_eq!(
+        items,
+        vec![
+            (b"b".to_vec(), b"2".to_vec()),
+            (b"c".to_vec(), b"3".to_vec())
+        ]
+    );
+
+    drop(cf);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_merge_iter_across_column_families() {
+    let path = "/tmp/rust_rocksdb_test_merge_iter";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let cf_opts = Options::default();
+    let jan = db
+        .create_column_family(&cf_opts, "events_01")
+        .expect("Failed to create column family");
+    let feb = db
+        .create_column_family(&cf_opts, "events_02")
+        .expect("Failed to create column family");
+    let empty = db
+        .create_column_family(&cf_opts, "events_03")
+        .expect("Failed to create column family");
+
+    db.put_cf(&jan, b"a", b"jan-a").expect("Failed to put");
+    db.put_cf(&jan, b"c", b"jan-c").expect("Failed to put");
+    db.put_cf(&feb, b"b", b"feb-b").expect("Failed to put");
+    db.put_cf(&feb, b"c", b"feb-c").expect("Failed to put");
+    db.put_cf(&feb, b"d", b"feb-d").expect("Failed to put");
+
+    let items: Vec<(u32, Vec<u8>, Vec<u8>)> = db
+        .merge_iter(&[&jan, &feb, &empty])
+        .map(|item| {
+            let (cf_id, key, value) = item.expect("Failed to iterate");
+            (cf_id, key.to_vec(), value.to_vec())
+        })
+        .collect();
+
+    assert_ne!(jan.id(), feb.id());
+    assert_eq!(
+        items,
+        vec![
+            (jan.id(), b"a".to_vec(), b"jan-a".to_vec()),
+            (feb.id(), b"b".to_vec(), b"feb-b".to_vec()),
+            // Duplicate keys come out in handle order
+            (jan.id(), b"c".to_vec(), b"jan-c".to_vec()),
+            (feb.id(), b"c".to_vec(), b"feb-c".to_vec()),
+            (feb.id(), b"d".to_vec(), b"feb-d".to_vec()),
+        ]
+    );
+
+    assert_eq!(db.merge_iter(&[]).count(), 0);
+
+    drop(jan);
+    drop(feb);
+    drop(empty);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_column_family_presets() {
+    let path = "/tmp/rust_rocksdb_test_cf_presets";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let presets = [
+        ("point_lookup", CfPreset::PointLookup),
+        ("write_heavy", CfPreset::WriteHeavy),
+        ("blob", CfPreset::Blob),
+        ("time_series", CfPreset::TimeSeries),
+    ];
+    let large_value = vec![b'x'; 8 * 1024];
+
+    for (name, preset) in presets {
+        let mut cf_opts = Options::default();
+        cf_opts.apply_preset(preset);
+        assert_eq!(cf_opts.compression_type(), Some(CompressionType::Lz4));
+        let bottommost = match preset {
+            CfPreset::TimeSeries => Some(CompressionType::Zstd),
+            _ => None,
+        };
+        assert_eq!(cf_opts.bottommost_compression_type(), bottommost);
+        if preset == CfPreset::Blob {
+            assert_eq!(cf_opts.blob_compression_type(), Some(CompressionType::Lz4));
+        }
+
+        // The bundled librocksdb.a is built without compression libraries
+        cf_opts
+            .set_compression_type(CompressionType::None)
+            .set_bottommost_compression_type(CompressionType::None);
+        if preset == CfPreset::Blob {
+            cf_opts.set_blob_compression_type(CompressionType::None);
+        }
+
+        let cf = db
+            .create_column_family(&cf_opts, name)
+            .expect("Failed to create column family");
+        db.put_cf(&cf, b"small", b"value").expect("Failed to put");
+        db.put_cf(&cf, b"large", &large_value)
+            .expect("Failed to put");
+        db.flush_cf(&cf).expect("Failed to flush");
+
+        assert_eq!(
+            db.get_cf(&cf, b"small").unwrap().as_deref(),
+            Some(&b"value"[..])
+        );
+        assert_eq!(db.get_cf(&cf, b"large").unwrap(), Some(large_value.clone()));
+    }
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_block_based_table_factory() {
+    let path = "/tmp/rust_rocksdb_test_block_based_table";
+    let _ = fs::remove_dir_all(path);
+
+    let mut table_opts = BlockBasedOptions::default();
+    table_opts
+        .set_block_size(16 * 1024)
+        .set_bloom_filter(10.0)
+        .set_whole_key_filtering(true)
+        .set_cache_index_and_filter_blocks(true)
+        .set_data_block_hash_index(true);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true)
+        .set_block_based_table_factory(&table_opts);
+    // The table options were copied into the factory
+    drop(table_opts);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    db.put(b"key", b"value").expect("Failed to put");
+    db.flush().expect("Failed to flush");
+    assert_eq!(db.get(b"key").unwrap().as_deref(), Some(&b"value"[..]));
+    assert_eq!(db.get(b"missing").unwrap(), None);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+unsafe extern "C" {
+    fn rocksdb_options_set_max_open_files(opt: *mut c_void, value: c_int);
+    fn rocksdb_options_get_max_open_files(opt: *mut c_void) -> c_int;
+    fn rocksdb_column_family_handle_get_id(handle: *mut c_void) -> u32;
+    fn rocksdb_get_latest_sequence_number(db: *mut c_void) -> u64;
+    fn rocksdb_create_mem_env() -> *mut c_void;
+}
+
+#[test]
+fn test_raw_escape_hatches() {
+    let path = "/tmp/rust_rocksdb_test_raw_escape_hatches";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    unsafe {
+        rocksdb_options_set_max_open_files(opts.as_raw(), 64);
+        assert_eq!(rocksdb_options_get_max_open_files(opts.as_raw()), 64);
+    }
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    let cf = db
+        .create_column_family(&Options::default(), "raw")
+        .expect("Failed to create column family");
+    db.put_cf(&cf, b"key", b"value").expect("Failed to put");
+
+    unsafe {
+        assert_eq!(rocksdb_column_family_handle_get_id(cf.as_raw()), cf.id());
+        assert_eq!(
+            rocksdb_get_latest_sequence_number(db.as_raw()),
+            db.latest_sequence_number()
+        );
+    }
+
+    drop(cf);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_env_from_raw() {
+    let path = "/tmp/rust_rocksdb_test_env_from_raw";
+    let _ = fs::remove_dir_all(path);
+
+    // An environment built outside the crate, here one keeping files in memory
+    let env = unsafe { Env::from_raw(rocksdb_create_mem_env()) };
+    let mut opts = Options::default();
+    opts.create_if_missing(true).set_env(&env);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    db.put(b"key", b"value").expect("Failed to put");
+    db.flush().expect("Failed to flush");
+    assert_eq!(db.get(b"key").unwrap().as_deref(), Some(&b"value"[..]));
+    assert!(!Path::new(path).exists());
+
+    drop(db);
+    drop(opts);
+    drop(env);
+}
+
+#[test]
+fn test_open_column_families_with_ttl() {
+    let path = "/tmp/rust_rocksdb_test_cf_ttl";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    {
+        let db = DB::open_with_ttl(&opts, path, 0).expect("Failed to open database");
+        let cf_handle = db
+            .create_column_family(&Options::default(), "cache")
+            .expect("Failed to create column family");
+        drop(cf_handle);
+    }
+
+    let cf_names = ["default", "cache"];
+    let cf_opts = [Options::default(), Options::default()];
+    assert!(DB::open_with_column_families_and_ttl(&opts, path, &cf_names, &cf_opts, &[0]).is_err());
+
+    let (db, cf_handles) =
+        DB::open_with_column_families_and_ttl(&opts, path, &cf_names, &cf_opts, &[0, 1])
+            .expect("Failed to open database");
+    db.put(b"kept", b"value").expect("Failed to put value");
+    db.put_cf(&cf_handles[1], b"cached", b"value")
+        .expect("Failed to put value");
+
+    std::thread::sleep(std::time::Duration::from_millis(2100));
+    db.compact_range(None, None);
+    db.compact_range_cf(&cf_handles[1], None, None);
+
+    // Only the column family with a TTL expires entries
+    assert_eq!(db.get(b"kept").unwrap(), Some(b"value".to_vec()));
+    assert_eq!(db.get_cf(&cf_handles[1], b"cached").unwrap(), None);
+
+    drop(cf_handles);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_disable_wal_cf() {
+    let path = "/tmp/rust_rocksdb_test_disable_wal_cf";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let cf_opts = Options::default();
+    let cache_cf = db
+        .create_column_family(&cf_opts, "cache")
+        .expect("Failed to create cache CF");
+    let users_cf = db
+        .create_column_family(&cf_opts, "users")
+        .expect("Failed to create users CF");
+
+    assert!(!db.is_wal_disabled_cf(&cache_cf));
+    db.set_disable_wal_cf(&cache_cf, true);
+    assert!(db.is_wal_disabled_cf(&cache_cf));
+    assert!(!db.is_wal_disabled_cf(&users_cf));
+
+    db.put_cf(&cache_cf, b"page:1", b"cached")
+        .expect("Failed to put to cache CF");
+    db.put_cf(&users_cf, b"user:1", b"Alice")
+        .expect("Failed to put to users CF");
+
+    let mut cache_only = WriteBatch::new();
+    cache_only.put_cf(&cache_cf, b"page:2", b"cached");
+    db.write(&cache_only).expect("Failed to write cache batch");
+
+    // A batch touching a durable column family keeps the WAL
+    let mut mixed = WriteBatch::new();
+    mixed.put_cf(&cache_cf, b"page:3", b"cached");
+    mixed.put_cf(&users_cf, b"user:2", b"Bob");
+    db.write(&mixed).expect("Failed to write mixed batch");
+
+    // So does one whose durable write is a range deletion
+    let mut ranged = WriteBatch::new();
+    ranged.delete_range_cf(&users_cf, b"user:0", b"user:1");
+    ranged.put_cf(&cache_cf, b"page:4", b"cached");
+    db.write(&ranged).expect("Failed to write ranged batch");
+
+    // Skipped writes leave gaps in the WAL, so it can't be tailed
+    assert_eq!(
+        db.get_updates_since(0)
+            .err()
+            .expect("WAL read should fail")
+            .kind(),
+        ErrorKind::NotSupported
+    );
+
+    // Skipping the WAL does not affect reads
+    for key in [&b"page:1"[..], b"page:2", b"page:3", b"page:4"] {
+        assert_eq!(
+            db.get_cf(&cache_cf, key).expect("Failed to get").as_deref(),
+            Some(&b"cached"[..])
+        );
+    }
+
+    db.set_disable_wal_cf(&cache_cf, false);
+    assert!(!db.is_wal_disabled_cf(&cache_cf));
+
+    // The mixed batches were logged in full, so the WAL reads back from there
+    let mixed_seq = db.latest_sequence_number() - 3;
+    let logged: Vec<Vec<WriteBatchOp>> = db
+        .get_updates_since(mixed_seq)
+        .expect("Failed to read WAL")
+        .map(|update| {
+            update
+                .expect("Failed to read WAL batch")
+                .1
+                .operations()
+                .expect("Failed to decode batch")
+        })
+        .collect();
+    assert_eq!(logged.len(), 2);
+    assert_eq!(logged[0].len(), 2);
+    assert_eq!(
+        logged[1],
+        vec![
+            WriteBatchOp::DeleteRange {
+                cf_id: users_cf.id(),
+                start: b"user:0".to_vec(),
+                end: b"user:1".to_vec(),
+            },
+            WriteBatchOp::Put {
+                cf_id: cache_cf.id(),
+                key: b"page:4".to_vec(),
+                value: b"cached".to_vec(),
+            },
+        ]
+    );
+
+    drop(cache_cf);
+    drop(users_cf);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_cf_handle_by_name() {
+    let path = "/tmp/rust_rocksdb_test_cf_handle_by_name";
+    let _ = fs::remove_dir_all(path);
+
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).expect("Failed to open database");
+        assert_eq!(db.cf_handle("default").expect("Missing default CF").id(), 0);
+        assert!(db.cf_handle("users").is_none());
+
+        let cf_opts = Options::default();
+        let users = db
+            .create_column_family(&cf_opts, "users")
+            .expect("Failed to create users CF");
+        assert_eq!(users.name(), "users");
+
+        let found = db.cf_handle("users").expect("Missing created CF");
+        assert_eq!(found.id(), users.id());
+        db.put_cf(&found, b"user:1", b"Alice")
+            .expect("Failed to put");
+
+        let temp = db
+            .create_column_family(&cf_opts, "temp")
+            .expect("Failed to create temp CF");
+        db.drop_column_family(temp).expect("Failed to drop temp CF");
+        assert!(db.cf_handle("temp").is_none());
+
+        drop(found);
+        drop(users);
+        drop(db);
+    }
+
+    let opts = Options::default();
+    let cf_names = vec!["default", "users"];
+    let cf_opts = vec![Options::default(), Options::default()];
+    let (db, cf_handles) = DB::open_with_column_families(&opts, path, &cf_names, &cf_opts)
+        .expect("Failed to open with CFs");
+    drop(cf_handles);
+
+    // The database keeps its own handles, so the returned ones can go
+    let users = db.cf_handle("users").expect("Missing opened CF");
+    assert_eq!(
+        db.get_cf(&users, b"user:1")
+            .expect("Failed to get")
+            .as_deref(),
+        Some(&b"Alice"[..])
+    );
+
+    // Handles can be shared with other threads
+    let handle = db.cf_handle("users").expect("Missing opened CF");
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            db.put_cf(&handle, b"user:2", b"Bob")
+                .expect("Failed to put from thread");
+        });
+    });
+    assert!(
+        db.contains_key_cf(&users, b"user:2")
+            .expect("Failed to check")
+    );
+
+    drop(handle);
+    drop(users);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_typed_cf() {
+    let path = "/tmp/rust_rocksdb_test_typed_cf";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open_cf(&opts, path, ["counts"]).expect("Failed to open database");
+    let handle = db
+        .cf_handle("counts")
+        .expect("Failed to find column family");
+
+    let counts = TypedCf::<String, u64>::new(&db, &handle);
+    counts
+        .put(&"b".to_string(), &2)
+        .expect("Failed to put typed value");
+    counts
+        .put(&"a".to_string(), &1)
+        .expect("Failed to put typed value");
+    assert_eq!(
+        counts
+            .get(&"a".to_string())
+            .expect("Failed to get typed value"),
+        Some(1)
+    );
+    assert!(db.get(b"a").expect("Failed to get").is_none());
+
+    let entries: Vec<(String, u64)> = counts
+        .iter()
+        .collect::<Result<_, _>>()
+        .expect("Failed to decode entries");
+    assert_eq!(entries, vec![("a".to_string(), 1), ("b".to_string(), 2)]);
+
+    drop(handle);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_bound_column_family() {
+    let path = "/tmp/rust_rocksdb_test_bound_cf";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = Arc::new(DB::open_cf(&opts, path, ["users"]).expect("Failed to open database"));
+
+    assert!(BoundColumnFamily::new(&db, "missing").is_none());
+    let users = BoundColumnFamily::new(&db, "users").expect("Missing users CF");
+    assert_eq!(users.name(), "users");
+    drop(db);
+
+    // The bound handle keeps the database open on another thread
+    let worker = {
+        let users = users.clone();
+        std::thread::spawn(move || {
+            users.put(b"user:1", b"Alice").expect("Failed to put");
+        })
+    };
+    worker.join().expect("Worker panicked");
+
+    assert_eq!(
+        users.get(b"user:1").expect("Failed to get").as_deref(),
+        Some(&b"Alice"[..])
+    );
+    assert!(users.contains_key(b"user:1").expect("Failed to check"));
+
+    {
+        let mut iter = users.raw_iterator();
+        iter.seek_to_first();
+        assert_eq!(iter.key(), Some(&b"user:1"[..]));
+    }
+    let scan = users.range(..);
+    let scanned = std::thread::spawn(move || scan.count())
+        .join()
+        .expect("Scan panicked");
+    assert_eq!(scanned, 1);
+
+    users.delete(b"user:1").expect("Failed to delete");
+    assert!(
+        users
+            .db()
+            .get_cf(users.handle(), b"user:1")
+            .expect("Failed to get")
+            .is_none()
+    );
+
+    // Dropping the last bound handle closes the database
+    drop(users);
+    let reopened = DB::open_cf(&opts, path, ["users"]).expect("Database was not closed");
+    drop(reopened);
+
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_targeted_compaction() {
+    let path = "/tmp/rust_rocksdb_test_targeted_compaction";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let cf_opts = Options::default();
+    let cf = db
+        .create_column_family(&cf_opts, "hot")
+        .expect("Failed to create column family");
+
+    // Three overlapping level 0 files: a..c, b..d and x..z
+    for keys in [[b"a", b"c"], [b"b", b"d"], [b"x", b"z"]] {
+        for key in keys {
+            db.put_cf(&cf, key, b"value").expect("Failed to put");
+        }
+        db.flush_cf(&cf).expect("Failed to flush");
+    }
+
+    let metadata = db.column_family_metadata_cf(&cf);
+    assert_eq!(metadata.name, "hot");
+    assert_eq!(metadata.file_count, 3);
+    assert_eq!(metadata.level0_files().len(), 3);
+    assert!(metadata.size > 0);
+    assert_eq!(metadata.files_containing(b"c").count(), 2);
+
+    let levels = db.level_info_cf(&cf);
+    assert_eq!(levels.len(), metadata.levels.len());
+    assert_eq!(levels[0].level, 0);
+    assert_eq!(levels[0].files, 3);
+    assert_eq!(levels[0].bytes, metadata.size);
+    assert!(
+        levels[1..]
+            .iter()
+            .all(|level| level.files == 0 && level.bytes == 0)
+    );
+    assert_eq!(
+        db.level_info()
+            .iter()
+            .map(|level| level.files)
+            .sum::<usize>(),
+        0
+    );
+
+    // Only the two files around "c" are compacted
+    assert_eq!(db.compact_file_overlaps(&cf, b"c"), 2);
+    let metadata = db.column_family_metadata_cf(&cf);
+    assert_eq!(metadata.file_count, 2);
+    assert_eq!(metadata.level0_files().len(), 1);
+    assert_eq!(metadata.level0_files()[0].smallest_key, b"x".to_vec());
+
+    assert_eq!(db.compact_file_overlaps(&cf, b"m"), 0);
+
+    assert_eq!(db.compact_level0(&cf), 1);
+    let metadata = db.column_family_metadata_cf(&cf);
+    assert!(metadata.level0_files().is_empty());
+    assert_eq!(db.compact_level0(&cf), 0);
+
+    assert_eq!(
+        db.get_cf(&cf, b"d").expect("Failed to get"),
+        Some(b"value".to_vec())
+    );
+
+    drop(cf);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_save_and_load_options() {
+    let path = "/tmp/rust_rocksdb_test_save_load_options";
+    let saved_dir = "/tmp/rust_rocksdb_test_save_load_options_saved";
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_dir_all(saved_dir);
+
+    // Nothing to load before the database exists
+    let err = Options::load_latest(path, None, None)
+        .err()
+        .expect("Load should fail");
+    assert_ne!(err.kind(), ErrorKind::Other);
+
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true).set_max_open_files(512);
+        let db = DB::open(&opts, path).expect("Failed to open database");
+        let mut cf_opts = Options::default();
+        cf_opts.set_compression_type(CompressionType::None);
+        let events = db
+            .create_column_family(&cf_opts, "events")
+            .expect("Failed to create events CF");
+        db.put_cf(&events, b"event:1", b"login")
+            .expect("Failed to put");
+
+        let saved = db.save_options(saved_dir).expect("Failed to save options");
+        assert!(saved.starts_with(saved_dir));
+        assert!(saved.exists());
+    }
+
+    // The saved copy describes the same column families as the database
+    for dir in [path, saved_dir] {
+        let (_, descriptors) = Options::load_latest(dir, None, None).expect("Failed to load");
+        let names: Vec<&str> = descriptors.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["default", "events"]);
+    }
+
+    let (opts, descriptors) =
+        Options::load_latest(saved_dir, None, None).expect("Failed to load options");
+    let db = DB::open_cf_descriptors(&opts, path, &descriptors).expect("Failed to reopen");
+    let events = db.cf_handle("events").expect("Missing events CF");
+    assert_eq!(
+        db.get_cf(&events, b"event:1").expect("Failed to get"),
+        Some(b"login".to_vec())
+    );
+
+    drop(events);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_dir_all(saved_dir);
+}
+
+#[test]
+fn test_load_latest_restores_prefix_settings() {
+    let path = "/tmp/rust_rocksdb_test_load_prefix_settings";
+    let _ = fs::remove_dir_all(path);
+
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let db = DB::open(&opts, path).expect("Failed to open database");
+        let mut table_opts = BlockBasedOptions::default();
+        table_opts
+            .set_bloom_filter(10.0)
+            .set_whole_key_filtering(false);
+        let mut cf_opts = Options::default();
+        cf_opts
+            .set_fixed_prefix_extractor(5)
+            .set_block_based_table_factory(&table_opts);
+        let _users = db
+            .create_column_family(&cf_opts, "users")
+            .expect("Failed to create users CF");
+    }
+
+    let (opts, descriptors) = Options::load_latest(path, None, None).expect("Failed to load");
+    assert_eq!(opts.prefix_len(), None);
+    let users = descriptors
+        .iter()
+        .find(|d| d.name == "users")
+        .expect("Missing users CF");
+    assert_eq!(users.options.prefix_len(), Some(5));
+    let default = descriptors
+        .iter()
+        .find(|d| d.name == "default")
+        .expect("Missing default CF");
+    assert_eq!(default.options.prefix_len(), None);
+
+    let db = DB::open_cf_descriptors(&opts, path, &descriptors).expect("Failed to reopen");
+    let users = db.cf_handle("users").expect("Missing users CF");
+    db.put_cf(&users, b"user:1", b"alice")
+        .expect("Failed to put");
+    assert_eq!(
+        db.get_cf(&users, b"user:1").expect("Failed to get"),
+        Some(b"alice".to_vec())
+    );
+
+    drop(users);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_clone_options_per_column_family() {
+    let path = "/tmp/rust_rocksdb_test_clone_options";
+    let _ = fs::remove_dir_all(path);
+
+    let mut base = Options::default();
+    base.set_max_key_size(8);
+    let mut logs_opts = base.clone();
+    logs_opts.set_compression_type(CompressionType::None);
+
+    let mut opts = base.clone();
+    opts.create_if_missing(true)
+        .create_missing_column_families(true);
+    let descriptors = vec![
+        ColumnFamilyDescriptor::new("default", base.clone()),
+        ColumnFamilyDescriptor::new("logs", logs_opts),
+    ];
+    let db = DB::open_cf_descriptors(&opts, path, &descriptors).expect("Failed to open");
+
+    // Settings made before cloning carry over to the copies
+    assert!(db.put(b"a key too long", b"value").is_err());
+    db.put(b"short", b"value").expect("Failed to put");
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_optimize_presets() {
+    let path = "/tmp/rust_rocksdb_test_optimize_presets";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true)
+        .optimize_level_style_compaction(64 * 1024 * 1024);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let mut lookup_opts = Options::default();
+    lookup_opts.optimize_for_point_lookup(8);
+    let mut universal_opts = Options::default();
+    universal_opts.optimize_universal_style_compaction(64 * 1024 * 1024);
+
+    for (name, cf_opts) in [("lookup", lookup_opts), ("universal", universal_opts)] {
+        let cf = db
+            .create_column_family(&cf_opts, name)
+            .expect("Failed to create column family");
+        db.put_cf(&cf, b"key", b"value").expect("Failed to put");
+        db.flush_cf(&cf).expect("Failed to flush");
+        assert_eq!(
+            db.get_cf(&cf, b"key").expect("Failed to get"),
+            Some(b"value".to_vec())
+        );
+    }
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
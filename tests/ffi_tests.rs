@@ -0,0 +1,23 @@
+#![cfg(feature = "unsafe-ffi")]
+
+use rust_small_rocksdb::{DB, Options, ffi};
+use std::fs;
+
+#[test]
+fn test_raw_ffi_on_wrapped_handles() {
+    let path = "/tmp/rust_rocksdb_test_ffi_module";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    db.put(b"key", b"value").expect("Failed to put");
+
+    let seq =
+        unsafe { ffi::rocksdb_get_latest_sequence_number(db.as_raw() as *mut ffi::rocksdb_t) };
+    assert_eq!(seq, db.latest_sequence_number());
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
@@ -1,5 +1,20 @@
-use rust_small_rocksdb::{DB, Options};
+use rust_small_rocksdb::{
+    BackOff, BackupRestore, BlockBasedOptions, BottommostLevelCompaction, Cache, CancellationToken,
+    Checkpoint, ChecksummedDB, ChunkedWriter, CompactRangeOptions, CompactionAction,
+    CompactionScheduler, CompactionStyle, CompressionType, DB, DBIterator, DBIteratorAdapter,
+    DbManager, Direction, DirectorySink, Env, Error, ErrorKind, ErrorSubcode,
+    FifoCompactionOptions, FlushOptions, GetResult, HotBackup, IteratorPool, KvRead, KvWrite,
+    MemKv, Migrator, OpenProgress, Options, OwnedIterator, PerfContext, PerfLevel, PerfMetric,
+    ProbeAction, ProbeStatus, QuotaWriter, RaftApplier, RateLimiter, RateLimiterMode, ReadMode,
+    ReadOptions, RenameToken, Session, SharedResources, SstFileManager, ThrottledWriter, Ticker,
+    TypedDb, UniversalCompactionOptions, WriteBatch, WriteBatchWithIndex, WriteBufferManager,
+    WriteMode,
+};
 use std::fs;
+use std::ops::Bound;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 #[test]
 fn test_open_and_close() {
@@ -42,6 +57,84 @@ fn test_put_and_get() {
     let _ = fs::remove_dir_all(path);
 }
 
+#[test]
+fn test_empty_value_vs_missing_key() {
+    let path = "/tmp/rust_rocksdb_test_empty_value_vs_missing_key";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    db.put(b"empty", b"").expect("Failed to put value");
+    db.put(b"full", b"value").expect("Failed to put value");
+
+    // get keeps the distinction as Some(vec![]) vs None
+    assert_eq!(db.get(b"empty").unwrap(), Some(Vec::new()));
+    assert_eq!(db.get(b"missing").unwrap(), None);
+    assert!(db.contains_key(b"empty").unwrap());
+
+    assert_eq!(db.get_result(b"empty").unwrap(), GetResult::FoundEmpty);
+    assert_eq!(
+        db.get_result(b"full").unwrap(),
+        GetResult::Found(b"value".to_vec())
+    );
+    assert_eq!(db.get_result(b"missing").unwrap(), GetResult::NotFound);
+    assert!(db.get_result(b"empty").unwrap().exists());
+    assert_eq!(
+        db.get_result(b"empty").unwrap().into_option(),
+        Some(Vec::new())
+    );
+
+    assert_eq!(db.get_non_empty(b"empty").unwrap(), None);
+    assert_eq!(db.get_non_empty(b"full").unwrap(), Some(b"value".to_vec()));
+    assert_eq!(db.get_non_empty(b"missing").unwrap(), None);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_write_and_read_modes() {
+    let path = "/tmp/rust_rocksdb_test_write_and_read_modes";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    db.put_opt(b"synced", b"1", WriteMode::Sync)
+        .expect("Failed to put value");
+    db.put_opt(b"buffered", b"2", WriteMode::Buffered)
+        .expect("Failed to put value");
+    let mut batch = WriteBatch::new();
+    batch.put(b"batched", b"3");
+    db.write_opt(&batch, WriteMode::Sync)
+        .expect("Failed to write batch");
+
+    for (key, value) in [
+        (&b"synced"[..], &b"1"[..]),
+        (b"buffered", b"2"),
+        (b"batched", b"3"),
+    ] {
+        assert_eq!(
+            db.get_opt(key, ReadMode::Cached).unwrap().as_deref(),
+            Some(value)
+        );
+        assert_eq!(
+            db.get_opt(key, ReadMode::NoFillCache).unwrap().as_deref(),
+            Some(value)
+        );
+    }
+
+    db.delete_opt(b"synced", WriteMode::Sync)
+        .expect("Failed to delete key");
+    assert_eq!(db.get(b"synced").unwrap(), None);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
 #[test]
 fn test_delete() {
     let path = "/tmp/rust_rocksdb_test_delete";
@@ -71,6 +164,195 @@ fn test_delete() {
     let _ = fs::remove_dir_all(path);
 }
 
+#[test]
+fn test_get_pinned() {
+    let path = "/tmp/rust_rocksdb_test_get_pinned";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let value = vec![7u8; 16 * 1024];
+    db.put(b"memtable", &value).expect("Failed to put value");
+    db.put(b"empty", b"").expect("Failed to put value");
+
+    let pinned = db
+        .get_pinned(b"memtable")
+        .expect("Failed to get value")
+        .expect("Expected a value");
+    assert_eq!(&*pinned, value.as_slice());
+    // The pin keeps the value readable after it is overwritten and flushed
+    db.put(b"memtable", b"new").expect("Failed to put value");
+    db.flush().expect("Failed to flush");
+    assert_eq!(pinned.len(), value.len());
+    drop(pinned);
+
+    let pinned = db
+        .get_pinned(b"memtable")
+        .expect("Failed to get value")
+        .expect("Expected a value");
+    assert_eq!(pinned.as_ref(), b"new");
+    drop(pinned);
+
+    let empty = db
+        .get_pinned(b"empty")
+        .expect("Failed to get value")
+        .expect("Expected an empty value");
+    assert!(empty.is_empty());
+    drop(empty);
+    assert!(
+        db.get_pinned(b"missing")
+            .expect("Failed to get value")
+            .is_none()
+    );
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_get_into() {
+    let path = "/tmp/rust_rocksdb_test_get_into";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    db.put(b"long", &[1u8; 4096]).expect("Failed to put value");
+    db.put(b"short", b"abc").expect("Failed to put value");
+
+    let mut buf = Vec::new();
+    assert!(db.get_into(b"long", &mut buf).expect("Failed to get value"));
+    assert_eq!(buf, vec![1u8; 4096]);
+    let capacity = buf.capacity();
+
+    // A shorter value replaces the contents without reallocating
+    assert!(
+        db.get_into(b"short", &mut buf)
+            .expect("Failed to get value")
+    );
+    assert_eq!(buf, b"abc");
+    assert_eq!(buf.capacity(), capacity);
+
+    // A miss leaves the buffer empty
+    assert!(
+        !db.get_into(b"missing", &mut buf)
+            .expect("Failed to get value")
+    );
+    assert!(buf.is_empty());
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_typed_db() {
+    let path = "/tmp/rust_rocksdb_test_typed_db";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let typed = TypedDb::<i64, String>::new(&db);
+    for id in [3, -1, 0, -100, 42] {
+        typed
+            .put(&id, &format!("id {}", id))
+            .expect("Failed to put typed value");
+    }
+    assert_eq!(
+        typed.get(&-1).expect("Failed to get typed value"),
+        Some("id -1".to_string())
+    );
+    assert_eq!(typed.get(&7).expect("Failed to get typed value"), None);
+    assert!(typed.contains_key(&42).expect("Failed to check key"));
+
+    // Negative keys sort before positive ones
+    let ids: Vec<i64> = typed
+        .iter()
+        .map(|entry| entry.expect("Failed to decode entry").0)
+        .collect();
+    assert_eq!(ids, vec![-100, -1, 0, 3, 42]);
+    let ids: Vec<i64> = typed
+        .range(-1..=3)
+        .map(|entry| entry.expect("Failed to decode entry").0)
+        .collect();
+    assert_eq!(ids, vec![-1, 0, 3]);
+
+    typed.delete(&0).expect("Failed to delete typed key");
+    assert!(!typed.contains_key(&0).expect("Failed to check key"));
+
+    // Bytes that don't match the key type surface as errors
+    db.put(b"short", b"value").expect("Failed to put");
+    let decoded: Vec<_> = typed.iter().collect();
+    assert_eq!(decoded.len(), 5);
+    assert!(decoded.iter().any(|entry| entry.is_err()));
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_typed_db_json() {
+    use rust_small_rocksdb::{JsonCodec, OrderedCodec};
+    use std::collections::BTreeMap;
+
+    let path = "/tmp/rust_rocksdb_test_typed_db_json";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let typed = TypedDb::<u32, (String, Vec<i64>), OrderedCodec, JsonCodec>::new(&db);
+    let value = ("scores".to_string(), vec![3, -1, 42]);
+    typed.put(&1, &value).expect("Failed to put typed value");
+    assert_eq!(
+        typed.get(&1).expect("Failed to get typed value"),
+        Some(value)
+    );
+    assert_eq!(
+        db.get(&1u32.to_be_bytes()).unwrap().as_deref(),
+        Some(&br#"["scores",[3,-1,42]]"#[..])
+    );
+
+    // Stored JSON of another shape fails to decode
+    let maps = TypedDb::<u32, BTreeMap<String, u64>, OrderedCodec, JsonCodec>::new(&db);
+    assert!(maps.get(&1).is_err());
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_contains_key() {
+    let path = "/tmp/rust_rocksdb_test_contains_key";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let large_value = vec![0xABu8; 1024 * 1024];
+    db.put(b"big", &large_value).expect("Failed to put value");
+    db.put(b"empty", b"").expect("Failed to put empty value");
+
+    assert!(db.contains_key(b"big").expect("Failed to check key"));
+    // An empty value still means the key exists
+    assert!(db.contains_key(b"empty").expect("Failed to check key"));
+    assert!(!db.contains_key(b"missing").expect("Failed to check key"));
+
+    db.delete(b"big").expect("Failed to delete");
+    assert!(!db.contains_key(b"big").expect("Failed to check key"));
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
 #[test]
 fn test_multiple_operations() {
     let path = "/tmp/rust_rocksdb_test_multiple";
@@ -212,50 +494,3463 @@ fn test_iterator_reverse() {
 }
 
 #[test]
-fn test_raw_iterator() {
-    let path = "/tmp/rust_rocksdb_test_raw_iterator";
+fn test_iterator_prefetch() {
+    let path = "/tmp/rust_rocksdb_test_iterator_prefetch";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    for i in 0..100u32 {
+        db.put(&i.to_be_bytes(), &i.to_le_bytes())
+            .expect("Failed to put value");
+    }
+
+    let collect = |iter: DBIteratorAdapter<'_>| -> Vec<_> {
+        iter.map(|item| item.expect("Failed to iterate")).collect()
+    };
+    let expected = collect(db.iter(Direction::Forward));
+    assert_eq!(expected.len(), 100);
+    // Batch sizes that do and don't divide the entry count
+    for entries in [0, 1, 7, 100, 1000] {
+        assert_eq!(
+            collect(db.iter(Direction::Forward).with_prefetch(entries)),
+            expected
+        );
+    }
+    let reversed = collect(db.iter(Direction::Reverse).with_prefetch(16));
+    assert!(reversed.iter().rev().eq(expected.iter()));
+    assert_eq!(
+        db.range(..&10u32.to_be_bytes()[..])
+            .with_prefetch(4)
+            .count(),
+        10
+    );
+
+    // Cancellation takes effect once the buffered entries are used up
+    let token = CancellationToken::new();
+    let mut iter = db
+        .iter(Direction::Forward)
+        .with_cancellation(&token)
+        .with_prefetch(8);
+    iter.next().unwrap().expect("Failed to iterate");
+    token.clone().cancel();
+    for _ in 1..8 {
+        iter.next().unwrap().expect("Failed to iterate");
+    }
+    let err = iter.next().unwrap().expect_err("Scan should be cancelled");
+    assert_eq!(err.kind(), ErrorKind::Cancelled);
+    assert_eq!(err.last_valid_key(), Some(&7u32.to_be_bytes()[..]));
+    assert!(iter.next().is_none());
+    drop(iter);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_owned_iterator() {
+    let path = "/tmp/rust_rocksdb_test_owned_iterator";
     let _ = fs::remove_dir_all(path);
 
     let mut opts = Options::default();
     opts.create_if_missing(true);
+    let db = Arc::new(DB::open(&opts, path).expect("Failed to open database"));
+    for i in 0..20u32 {
+        let key = format!("key{:02}", i);
+        db.put(key.as_bytes(), b"value")
+            .expect("Failed to put value");
+    }
+
+    // The scans move to other threads and keep the database open there
+    let forward = OwnedIterator::new(&db, Direction::Forward).with_prefetch(8);
+    let reverse = OwnedIterator::new(&db, Direction::Reverse);
+    let range = OwnedIterator::range(&db, &b"key05"[..]..&b"key10"[..]);
+    let prefix = OwnedIterator::prefix(&db, b"key1");
+    drop(db);
+
+    let keys = |iter: OwnedIterator| {
+        std::thread::spawn(move || -> Vec<Vec<u8>> {
+            iter.map(|item| item.expect("Failed to iterate").0.to_vec())
+                .collect()
+        })
+        .join()
+        .expect("Scan panicked")
+    };
+    let forward = keys(forward);
+    assert_eq!(forward.len(), 20);
+    assert!(keys(reverse).iter().rev().eq(forward.iter()));
+    assert_eq!(keys(range), forward[5..10]);
+    assert_eq!(keys(prefix), forward[10..20]);
+
+    // Dropping the last iterator closed the database
+    let reopened = DB::open(&opts, path).expect("Database was not closed");
+    drop(reopened);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_stream() {
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::task::{Context, Poll, Wake, Waker};
+
+    // Counts the times the stream yields to the executor
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let path = "/tmp/rust_rocksdb_test_stream";
+    let _ = fs::remove_dir_all(path);
 
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
     let db = DB::open(&opts, path).expect("Failed to open database");
+    for i in 0..100u32 {
+        db.put(&i.to_be_bytes(), b"value")
+            .expect("Failed to put value");
+    }
 
-    // Insert test data
-    db.put(b"aaa", b"value1").unwrap();
-    db.put(b"bbb", b"value2").unwrap();
-    db.put(b"ccc", b"value3").unwrap();
+    let counter = Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
 
-    // Test seek
-    let mut iter = db.raw_iterator();
-    iter.seek(b"bbb");
+    let keys = {
+        let mut stream = db
+            .stream(&10u32.to_be_bytes()[..]..&60u32.to_be_bytes()[..])
+            .with_chunk_size(20);
+        let mut future = pin!(async {
+            let mut keys = Vec::new();
+            while let Some(item) = stream.next().await {
+                keys.push(item.expect("Failed to stream").0);
+            }
+            keys
+        });
+        // The stream never waits on anything, so polling in a loop completes it
+        loop {
+            if let Poll::Ready(keys) = future.as_mut().poll(&mut cx) {
+                break keys;
+            }
+        }
+    };
 
-    assert!(iter.valid());
-    assert_eq!(iter.key(), Some(&b"bbb"[..]));
-    assert_eq!(iter.value(), Some(&b"value2"[..]));
+    assert_eq!(keys.len(), 50);
+    assert_eq!(&*keys[0], &10u32.to_be_bytes()[..]);
+    assert_eq!(&*keys[49], &59u32.to_be_bytes()[..]);
+    // One yield after each full chunk
+    assert_eq!(counter.0.load(Ordering::SeqCst), 2);
 
-    // Test next
-    iter.next();
-    assert!(iter.valid());
-    assert_eq!(iter.key(), Some(&b"ccc"[..]));
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
 
-    // Test prev
-    iter.prev();
-    assert!(iter.valid());
-    assert_eq!(iter.key(), Some(&b"bbb"[..]));
+#[cfg(feature = "async")]
+#[test]
+fn test_owned_stream() {
+    use futures_core::Stream;
+    use std::pin::Pin;
+    use std::task::{Context, Poll, Waker};
 
-    // Test seek_to_first
-    iter.seek_to_first();
-    assert!(iter.valid());
-    assert_eq!(iter.key(), Some(&b"aaa"[..]));
+    let path = "/tmp/rust_rocksdb_test_owned_stream";
+    let _ = fs::remove_dir_all(path);
 
-    // Test seek_to_last
-    iter.seek_to_last();
-    assert!(iter.valid());
-    assert_eq!(iter.key(), Some(&b"ccc"[..]));
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = Arc::new(DB::open(&opts, path).expect("Failed to open database"));
+    for i in 0..100u32 {
+        db.put(&i.to_be_bytes(), b"value")
+            .expect("Failed to put value");
+    }
 
-    // Drop iterator before DB
-    drop(iter);
+    let mut stream = OwnedIterator::range(&db, &10u32.to_be_bytes()[..]..&60u32.to_be_bytes()[..])
+        .into_stream()
+        .with_chunk_size(20);
+    drop(db);
+
+    // The stream owns the database, so it can move to another thread
+    let keys = std::thread::spawn(move || {
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut keys = Vec::new();
+        loop {
+            match Stream::poll_next(Pin::new(&mut stream), &mut cx) {
+                Poll::Ready(Some(item)) => keys.push(item.expect("Failed to stream").0),
+                Poll::Ready(None) => break keys,
+                Poll::Pending => {}
+            }
+        }
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(keys.len(), 50);
+    assert_eq!(&*keys[0], &10u32.to_be_bytes()[..]);
+    assert_eq!(&*keys[49], &59u32.to_be_bytes()[..]);
+
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_range() {
+    let path = "/tmp/rust_rocksdb_test_range";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    for key in [&b"a"[..], b"b", b"c", b"m", b"m\0", b"n"] {
+        db.put(key, b"v").expect("Failed to put value");
+    }
+
+    let keys = |iter: DBIteratorAdapter| -> Vec<Vec<u8>> {
+        iter.map(|item| item.expect("Failed to iterate").0.into_vec())
+            .collect()
+    };
+
+    assert_eq!(
+        keys(db.range(b"b"..b"m")),
+        vec![b"b".to_vec(), b"c".to_vec()]
+    );
+    // An inclusive end stops right after the key, before its extensions
+    assert_eq!(
+        keys(db.range(b"b"..=b"m")),
+        vec![b"b".to_vec(), b"c".to_vec(), b"m".to_vec()]
+    );
+    assert_eq!(
+        keys(db.range((Bound::Excluded(&b"m"[..]), Bound::Unbounded))),
+        vec![b"m\0".to_vec(), b"n".to_vec()]
+    );
+    assert_eq!(keys(db.range(..b"b")), vec![b"a".to_vec()]);
+    assert_eq!(keys(db.range(..)).len(), 6);
+    assert!(keys(db.range(b"x"..b"z")).is_empty());
+    assert!(keys(db.range(b"m"..b"c")).is_empty());
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_prefix_iter() {
+    let path = "/tmp/rust_rocksdb_test_prefix_iter";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    for key in [
+        &b"user:1:email"[..],
+        b"user:1:name",
+        b"user:10:name",
+        b"user:2:name",
+    ] {
+        db.put(key, b"v").expect("Failed to put value");
+    }
+    db.put(b"\x01\xff", b"v").expect("Failed to put value");
+    db.put(b"\x02", b"v").expect("Failed to put value");
+
+    let keys = |prefix: &[u8]| -> Vec<Vec<u8>> {
+        db.prefix_iter(prefix)
+            .map(|item| item.expect("Failed to iterate").0.into_vec())
+            .collect()
+    };
+
+    assert_eq!(
+        keys(b"user:1:"),
+        vec![b"user:1:email".to_vec(), b"user:1:name".to_vec()]
+    );
+    assert_eq!(keys(b"user:1").len(), 3);
+    assert!(keys(b"user:3").is_empty());
+    // A prefix ending in 0xff has to carry into the previous byte
+    assert_eq!(keys(b"\x01\xff"), vec![b"\x01\xff".to_vec()]);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+
+    // With a matching prefix extractor RocksDB checks the prefix itself
+    let mut opts = Options::default();
+    opts.create_if_missing(true).set_fixed_prefix_extractor(5);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    for key in [&b"aaaaa1"[..], b"aaaaa2", b"aaaab1"] {
+        db.put(key, b"v").expect("Failed to put value");
+    }
+    assert_eq!(db.prefix_iter(b"aaaaa").count(), 2);
+    assert_eq!(db.prefix_iter(b"aaaa").count(), 3);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_rename_prefix() {
+    let path = "/tmp/rust_rocksdb_test_rename_prefix";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    for i in 0..25u32 {
+        db.put(format!("tenant:17/{:02}", i).as_bytes(), &i.to_be_bytes())
+            .expect("Failed to put value");
+    }
+    db.put(b"tenant:170/0", b"other")
+        .expect("Failed to put value");
+    db.put(b"tenant:18/0", b"other")
+        .expect("Failed to put value");
+
+    let err = db
+        .rename_prefix(b"tenant:1", b"tenant:17/")
+        .err()
+        .expect("Overlapping prefixes should be rejected");
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+
+    let mut rename = db
+        .rename_prefix(b"tenant:17/", b"tenant:42/")
+        .expect("Failed to start rename");
+    rename.set_batch_size(10);
+    assert!(rename.step().expect("Failed to rename"));
+    assert_eq!(rename.renamed(), 10);
+    // Each step moves its entries atomically
+    assert!(db.get(b"tenant:17/09").expect("Failed to get").is_none());
+    assert_eq!(
+        db.get(b"tenant:42/09").expect("Failed to get"),
+        Some(9u32.to_be_bytes().to_vec())
+    );
+    assert!(db.get(b"tenant:17/10").expect("Failed to get").is_some());
+
+    // Resume in a new rename from the stored token
+    let token =
+        RenameToken::from_bytes(&rename.token().to_bytes()).expect("Failed to decode token");
+    assert_eq!(token, rename.token());
+    let mut resumed = db
+        .rename_prefix(b"tenant:17/", b"tenant:42/")
+        .expect("Failed to start rename");
+    resumed
+        .set_batch_size(10)
+        .resume_from(&token)
+        .expect("Failed to resume rename");
+    assert_eq!(resumed.run().expect("Failed to rename"), 25);
+    assert!(resumed.is_done());
+
+    assert_eq!(db.prefix_iter(b"tenant:17/").count(), 0);
+    assert_eq!(db.prefix_iter(b"tenant:42/").count(), 25);
+    assert_eq!(
+        db.get(b"tenant:170/0").expect("Failed to get"),
+        Some(b"other".to_vec())
+    );
+    assert_eq!(
+        db.get(b"tenant:18/0").expect("Failed to get"),
+        Some(b"other".to_vec())
+    );
+
+    let mut other = db
+        .rename_prefix(b"tenant:18/", b"tenant:19/")
+        .expect("Failed to start rename");
+    let err = other
+        .resume_from(&token)
+        .err()
+        .expect("Token for other prefixes should be rejected");
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+
+    // Each rename holds a snapshot, released before the database closes
+    drop(rename);
+    drop(resumed);
+    drop(other);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_iterate_bounds() {
+    let path = "/tmp/rust_rocksdb_test_iterate_bounds";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    for key in [&b"a"[..], b"b", b"c", b"d", b"e"] {
+        db.put(key, b"v").expect("Failed to put value");
+    }
+
+    let mut read_opts = ReadOptions::default();
+    read_opts
+        .set_iterate_lower_bound(b"b")
+        .set_iterate_upper_bound(b"d")
+        .set_fill_cache(false);
+    assert_eq!(read_opts.iterate_lower_bound(), Some(&b"b"[..]));
+    assert_eq!(read_opts.iterate_upper_bound(), Some(&b"d"[..]));
+
+    let mut iter = db.raw_iterator_opt(read_opts);
+    iter.seek_to_first();
+    assert_eq!(iter.key(), Some(&b"b"[..]));
+    iter.next();
+    assert_eq!(iter.key(), Some(&b"c"[..]));
+    iter.next();
+    assert!(!iter.valid());
+    // Seeking outside the bounds stays within them
+    iter.seek(b"a");
+    assert_eq!(iter.key(), Some(&b"b"[..]));
+    iter.seek(b"e");
+    assert!(!iter.valid());
+    drop(iter);
+
+    let mut read_opts = ReadOptions::default();
+    read_opts.set_iterate_upper_bound(b"c");
+    let keys: Vec<Box<[u8]>> = db
+        .iter_opt(Direction::Reverse, read_opts)
+        .map(|item| item.expect("Failed to iterate").0)
+        .collect();
+    assert_eq!(keys, vec![b"b".to_vec().into(), b"a".to_vec().into()]);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+/// Create an iterator whose bounds came from buffers that are gone by the time it is used
+fn iterator_with_dropped_bounds(db: &DB) -> DBIterator<'_> {
+    let lower = String::from("b");
+    let upper = vec![b'd'];
+    let mut read_opts = ReadOptions::default();
+    read_opts
+        .set_iterate_lower_bound(lower.as_bytes())
+        .set_iterate_upper_bound(upper.clone());
+    drop(lower);
+    drop(upper);
+    db.raw_iterator_opt(read_opts)
+}
+
+#[test]
+fn test_iterate_bounds_outlive_caller_buffers() {
+    let path = "/tmp/rust_rocksdb_test_iterate_bounds_outlive_caller_buffers";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    for key in [&b"a"[..], b"b", b"c", b"d", b"e"] {
+        db.put(key, b"v").expect("Failed to put value");
+    }
+
+    let mut iter = iterator_with_dropped_bounds(&db);
+    // Fill freed memory with 0xff, which would lift a dangling upper bound
+    // past every key and a dangling lower bound above them
+    let noise: Vec<Vec<u8>> = (0..1000).map(|_| vec![0xff; 1]).collect();
+
+    let mut keys = Vec::new();
+    iter.seek_to_first();
+    while let Some(key) = iter.key() {
+        keys.push(key.to_vec());
+        iter.next();
+    }
+    assert_eq!(keys, vec![b"b".to_vec(), b"c".to_vec()]);
+    drop(noise);
+    drop(iter);
+
+    // Replacing a bound frees the old buffer; only the latest one applies
+    let mut read_opts = ReadOptions::default();
+    read_opts
+        .set_iterate_upper_bound(b"b")
+        .set_iterate_upper_bound(b"e");
+    let count = db.iter_opt(Direction::Forward, read_opts).count();
+    assert_eq!(count, 4);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_iterator_refresh() {
+    let path = "/tmp/rust_rocksdb_test_iterator_refresh";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    db.put(b"a", b"1").expect("Failed to put value");
+    let mut read_opts = ReadOptions::default();
+    read_opts.set_iterate_upper_bound(b"m");
+    let mut iter = db.raw_iterator_opt(read_opts);
+
+    db.put(b"b", b"2").expect("Failed to put value");
+    db.put(b"x", b"3").expect("Failed to put value");
+    db.delete(b"a").expect("Failed to delete key");
+
+    // Still the view from before the writes
+    iter.seek_to_first();
+    assert_eq!(iter.key(), Some(&b"a"[..]));
+    iter.next();
+    assert!(!iter.valid());
+
+    iter.refresh().expect("Failed to refresh iterator");
+    iter.seek_to_first();
+    assert_eq!(iter.item(), Some((&b"b"[..], &b"2"[..])));
+    // The upper bound survives the refresh
+    iter.next();
+    assert!(!iter.valid());
+
+    drop(iter);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_tailing_iterator() {
+    let path = "/tmp/rust_rocksdb_test_tailing_iterator";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    db.put(b"job:1", b"a").expect("Failed to put value");
+    let mut iter = db.tailing_iterator();
+    iter.seek_to_first();
+    assert_eq!(iter.key(), Some(&b"job:1"[..]));
+    iter.next();
+    assert!(!iter.valid());
+
+    // Written after the iterator ran off the end
+    db.put(b"job:2", b"b").expect("Failed to put value");
+    db.put(b"job:3", b"c").expect("Failed to put value");
+    iter.seek(b"job:1");
+    iter.next();
+    assert_eq!(iter.item(), Some((&b"job:2"[..], &b"b"[..])));
+    iter.next();
+    assert_eq!(iter.key(), Some(&b"job:3"[..]));
+    iter.next();
+    assert!(!iter.valid());
+    iter.status().expect("Failed to iterate");
+
+    drop(iter);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_raw_iterator() {
+    let path = "/tmp/rust_rocksdb_test_raw_iterator";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    // Insert test data
+    db.put(b"aaa", b"value1").unwrap();
+    db.put(b"bbb", b"value2").unwrap();
+    db.put(b"ccc", b"value3").unwrap();
+
+    // Test seek
+    let mut iter = db.raw_iterator();
+    iter.seek(b"bbb");
+
+    assert!(iter.valid());
+    assert_eq!(iter.key(), Some(&b"bbb"[..]));
+    assert_eq!(iter.value(), Some(&b"value2"[..]));
+
+    // Test next
+    iter.next();
+    assert!(iter.valid());
+    assert_eq!(iter.key(), Some(&b"ccc"[..]));
+
+    // Test prev
+    iter.prev();
+    assert!(iter.valid());
+    assert_eq!(iter.key(), Some(&b"bbb"[..]));
+
+    // Test seek_to_first
+    iter.seek_to_first();
+    assert!(iter.valid());
+    assert_eq!(iter.key(), Some(&b"aaa"[..]));
+
+    // Test seek_to_last
+    iter.seek_to_last();
+    assert!(iter.valid());
+    assert_eq!(iter.key(), Some(&b"ccc"[..]));
+
+    // Drop iterator before DB
+    drop(iter);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_checkpoint() {
+    let path = "/tmp/rust_rocksdb_test_checkpoint";
+    let checkpoint_path = "/tmp/rust_rocksdb_test_checkpoint_copy";
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_dir_all(checkpoint_path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    db.put(b"key", b"value").expect("Failed to put value");
+
+    let checkpoint = Checkpoint::new(&db).expect("Failed to create checkpoint object");
+    checkpoint
+        .create_checkpoint(checkpoint_path)
+        .expect("Failed to create checkpoint");
+    drop(checkpoint);
+    drop(db);
+
+    let copy = DB::open(&Options::default(), checkpoint_path).expect("Failed to open checkpoint");
+    assert_eq!(copy.get(b"key").unwrap().as_deref(), Some(&b"value"[..]));
+
+    drop(copy);
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_dir_all(checkpoint_path);
+}
+
+#[test]
+fn test_clone_to_is_independent() {
+    let path = "/tmp/rust_rocksdb_test_clone_to";
+    let clone_path = "/tmp/rust_rocksdb_test_clone_to_copy";
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_dir_all(clone_path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    db.put(b"shared", b"original").expect("Failed to put value");
+    let mut users_opts = Options::default();
+    users_opts.set_compaction_style(CompactionStyle::Universal);
+    let users = db
+        .create_column_family(&users_opts, "users")
+        .expect("Failed to create column family");
+    db.put_cf(&users, b"user:1", b"Alice")
+        .expect("Failed to put value");
+
+    let copy = db.clone_to(clone_path).expect("Failed to clone database");
+    assert_eq!(copy.path(), clone_path);
+    assert_eq!(
+        copy.get(b"shared").unwrap().as_deref(),
+        Some(&b"original"[..])
+    );
+
+    // Column families come along
+    let copy_users = copy.cf_handle("users").expect("Missing column family");
+    assert_eq!(
+        copy.get_cf(&copy_users, b"user:1").unwrap(),
+        Some(b"Alice".to_vec())
+    );
+    drop(copy_users);
+
+    // Each column family keeps its own options
+    let options_file = fs::read_dir(clone_path)
+        .expect("Failed to list copy")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("OPTIONS-")
+        })
+        .max()
+        .expect("Missing OPTIONS file");
+    let persisted = fs::read_to_string(options_file).expect("Failed to read OPTIONS file");
+    let cf_section = |name: &str| {
+        let start = persisted
+            .find(&format!("[CFOptions \"{}\"]", name))
+            .expect("Missing column family section");
+        let section = &persisted[start + 1..];
+        section[..section.find("\n[").unwrap_or(section.len())].to_string()
+    };
+    assert!(cf_section("users").contains("compaction_style=kCompactionStyleUniversal"));
+    assert!(cf_section("default").contains("compaction_style=kCompactionStyleLevel"));
+
+    // Writes after the clone do not cross over
+    db.put(b"only_original", b"1").expect("Failed to put value");
+    copy.put(b"only_copy", b"2").expect("Failed to put value");
+    assert_eq!(copy.get(b"only_original").unwrap(), None);
+    assert_eq!(db.get(b"only_copy").unwrap(), None);
+
+    drop(copy);
+    drop(users);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_dir_all(clone_path);
+}
+
+#[test]
+fn test_compression_options() {
+    let path = "/tmp/rust_rocksdb_test_compression_options";
+    let _ = fs::remove_dir_all(path);
+
+    // The bundled library links no compression codecs, so the dictionary
+    // parameters are exercised with CompressionType::None
+    let mut opts = Options::default();
+    opts.create_if_missing(true)
+        .set_compression_type(CompressionType::None)
+        .set_bottommost_compression_type(CompressionType::None)
+        .set_compression_options(-14, 3, 0, 16 * 1024)
+        .set_zstd_max_train_bytes(100 * 16 * 1024)
+        .set_use_zstd_dict_trainer(true)
+        .set_max_dict_buffer_bytes(0)
+        .set_bottommost_compression_options(-14, 19, 0, 16 * 1024);
+
+    // Training a dictionary needs zstd
+    let err = DB::open(&opts, path).err().expect("Open should fail");
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+
+    // Raw samples are used as the dictionary without training
+    opts.set_zstd_max_train_bytes(0)
+        .set_bottommost_zstd_max_train_bytes(0);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    db.put(b"doc", br#"{"id":1,"name":"Alice"}"#)
+        .expect("Failed to put value");
+    assert!(db.get(b"doc").unwrap().is_some());
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_env_priorities_and_subcompactions() {
+    let path = "/tmp/rust_rocksdb_test_env_priorities";
+    let _ = fs::remove_dir_all(path);
+
+    let env = Env::new();
+    env.lower_thread_pool_cpu_priority();
+    env.lower_thread_pool_io_priority();
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true)
+        .set_env(&env)
+        .set_max_subcompactions(4);
+
+    // The options keep their own reference to the environment
+    drop(env);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    drop(opts);
+
+    db.put(b"key", b"value").expect("Failed to put value");
+    assert_eq!(db.get(b"key").unwrap().as_deref(), Some(&b"value"[..]));
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_compact_range() {
+    let path = "/tmp/rust_rocksdb_test_compact_range";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let cf_opts = Options::default();
+    let cf = db
+        .create_column_family(&cf_opts, "compacted")
+        .expect("Failed to create column family");
+
+    for i in 0..100u32 {
+        let key = format!("key{:03}", i);
+        db.put(key.as_bytes(), b"value")
+            .expect("Failed to put value");
+        db.put_cf(&cf, key.as_bytes(), b"value")
+            .expect("Failed to put value in column family");
+    }
+    db.delete(b"key050").expect("Failed to delete key");
+
+    db.compact_range(Some(&b"key000"[..]), Some(&b"key049"[..]));
+    db.compact_range(None, None);
+
+    let mut compact_opts = CompactRangeOptions::default();
+    compact_opts
+        .set_exclusive_manual_compaction(true)
+        .set_bottommost_level_compaction(BottommostLevelCompaction::Force)
+        .set_change_level(true)
+        .set_target_level(-1);
+    db.compact_range_opt(None, None, &compact_opts);
+    db.compact_range_cf(&cf, None, Some(&b"key010"[..]));
+    db.compact_range_cf_opt(&cf, None, None, &compact_opts);
+
+    // Compaction never changes visible data
+    assert_eq!(db.get(b"key000").unwrap().as_deref(), Some(&b"value"[..]));
+    assert_eq!(db.get(b"key050").unwrap(), None);
+    assert_eq!(
+        db.get_cf(&cf, b"key099").unwrap().as_deref(),
+        Some(&b"value"[..])
+    );
+
+    drop(cf);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_flush() {
+    let path = "/tmp/rust_rocksdb_test_flush";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    {
+        let db = DB::open(&opts, path).expect("Failed to open database");
+        let cf_opts = Options::default();
+        let cf = db
+            .create_column_family(&cf_opts, "flushed")
+            .expect("Failed to create column family");
+
+        db.put(b"key", b"value").expect("Failed to put value");
+        db.put_cf(&cf, b"cf_key", b"cf_value")
+            .expect("Failed to put value in column family");
+
+        db.flush().expect("Failed to flush default column family");
+        db.flush_cf(&cf).expect("Failed to flush column family");
+
+        let mut flush_opts = FlushOptions::default();
+        flush_opts.set_wait(false);
+        db.flush_opt(&flush_opts)
+            .expect("Failed to start background flush");
+        db.flush_cf_opt(&cf, &flush_opts)
+            .expect("Failed to start background flush of column family");
+    }
+
+    // Flushed data survives reopening
+    let cf_names = vec!["default", "flushed"];
+    let cf_opts = vec![Options::default(), Options::default()];
+    let (db, handles) = DB::open_with_column_families(&opts, path, &cf_names, &cf_opts)
+        .expect("Failed to reopen database");
+    assert_eq!(db.get(b"key").unwrap().as_deref(), Some(&b"value"[..]));
+    assert_eq!(
+        db.get_cf(&handles[1], b"cf_key").unwrap().as_deref(),
+        Some(&b"cf_value"[..])
+    );
+
+    drop(handles);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_manual_wal_flush() {
+    let path = "/tmp/rust_rocksdb_test_manual_wal_flush";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true).set_manual_wal_flush(true);
+
+    {
+        let db = DB::open(&opts, path).expect("Failed to open database");
+        for i in 0..10u32 {
+            db.put(&i.to_be_bytes(), b"value")
+                .expect("Failed to put value");
+        }
+        db.flush_wal(false).expect("Failed to flush WAL");
+
+        db.put(b"synced", b"value").expect("Failed to put value");
+        db.sync_wal().expect("Failed to sync WAL");
+    }
+
+    // Flushed WAL entries are replayed on reopen
+    let db = DB::open(&opts, path).expect("Failed to reopen database");
+    assert!(db.get(&9u32.to_be_bytes()).unwrap().is_some());
+    assert_eq!(db.get(b"synced").unwrap().as_deref(), Some(&b"value"[..]));
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_throttled_writer() {
+    let path = "/tmp/rust_rocksdb_test_throttled_writer";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    // The bucket starts with one second of burst, so the extra writes wait
+    let mut writer = ThrottledWriter::new(&db);
+    writer.set_ops_per_sec(20).set_bytes_per_sec(1024 * 1024);
+
+    let start = Instant::now();
+    for i in 0..25u32 {
+        writer
+            .put(&i.to_be_bytes(), b"value")
+            .expect("Failed to put value");
+    }
+    assert!(start.elapsed() >= Duration::from_millis(200));
+
+    let mut batch = WriteBatch::new();
+    batch.put(b"batched", b"value");
+    writer.write(&batch).expect("Failed to write batch");
+    writer
+        .delete(&0u32.to_be_bytes())
+        .expect("Failed to delete");
+
+    assert_eq!(
+        db.get(&24u32.to_be_bytes()).unwrap().as_deref(),
+        Some(&b"value"[..])
+    );
+    assert_eq!(db.get(b"batched").unwrap().as_deref(), Some(&b"value"[..]));
+    assert_eq!(db.get(&0u32.to_be_bytes()).unwrap(), None);
+
+    // Without limits writes are not delayed
+    let unlimited = ThrottledWriter::new(&db);
+    let start = Instant::now();
+    for i in 0..100u32 {
+        unlimited
+            .put(&i.to_be_bytes(), b"value")
+            .expect("Failed to put value");
+    }
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_chunked_writer() {
+    let path = "/tmp/rust_rocksdb_test_chunked_writer";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let mut writer = ChunkedWriter::new(&db);
+    writer.set_max_ops(10).set_write_mode(WriteMode::Sync);
+    for i in 0..25u32 {
+        writer
+            .put(&i.to_be_bytes(), b"value")
+            .expect("Failed to put value");
+    }
+    assert_eq!(writer.batches_written(), 2);
+    assert_eq!(writer.ops_written(), 20);
+    assert_eq!(writer.pending_ops(), 5);
+    assert_eq!(db.get(&24u32.to_be_bytes()).unwrap(), None);
+    writer
+        .delete(&0u32.to_be_bytes())
+        .expect("Failed to delete key");
+    assert_eq!(writer.finish().expect("Failed to finish"), 3);
+    assert_eq!(
+        db.get(&24u32.to_be_bytes()).unwrap(),
+        Some(b"value".to_vec())
+    );
+    assert_eq!(db.get(&0u32.to_be_bytes()).unwrap(), None);
+
+    // A byte limit splits before the batch would outgrow it
+    let mut writer = ChunkedWriter::new(&db);
+    writer.set_max_bytes(1000);
+    let value = vec![7u8; 300];
+    for i in 0..7u32 {
+        writer
+            .put(&i.to_be_bytes(), &value)
+            .expect("Failed to put value");
+    }
+    assert_eq!(writer.batches_written(), 2);
+    assert_eq!(writer.pending_ops(), 1);
+    assert_eq!(writer.finish().expect("Failed to finish"), 3);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_property_value() {
+    let path = "/tmp/rust_rocksdb_test_property_value";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let cf_opts = Options::default();
+    let cf = db
+        .create_column_family(&cf_opts, "metrics")
+        .expect("Failed to create column family");
+
+    db.put(b"key", b"value").expect("Failed to put value");
+    db.flush().expect("Failed to flush");
+
+    let stats = db
+        .property_value("rocksdb.stats")
+        .expect("Failed to read property");
+    assert!(stats.is_some_and(|stats| !stats.is_empty()));
+    assert!(db.property_value("rocksdb.levelstats").unwrap().is_some());
+
+    let cf_stats = db
+        .property_value_cf(&cf, "rocksdb.sstables")
+        .expect("Failed to read column family property");
+    assert!(cf_stats.is_some());
+
+    // Unknown properties are reported as missing, not as errors
+    assert_eq!(db.property_value("rocksdb.no-such-property").unwrap(), None);
+    assert!(db.property_value("bad\0name").is_err());
+
+    drop(cf);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_iterator_reports_corruption() {
+    let path = "/tmp/rust_rocksdb_test_iterator_corruption";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true)
+        .set_compression_type(CompressionType::None);
+
+    {
+        let db = DB::open(&opts, path).expect("Failed to open database");
+        for i in 0..2000u32 {
+            db.put(&i.to_be_bytes(), &[i as u8; 1024])
+                .expect("Failed to put value");
+        }
+        db.flush().expect("Failed to flush");
+    }
+
+    // Damage a data block in the middle of the table file
+    let sst = fs::read_dir(path)
+        .expect("Failed to read database directory")
+        .map(|entry| entry.unwrap().path())
+        .find(|p| p.extension().is_some_and(|ext| ext == "sst"))
+        .expect("No SST file written");
+    let mut data = fs::read(&sst).expect("Failed to read SST file");
+    let middle = data.len() / 2;
+    for byte in &mut data[middle..middle + 64] {
+        *byte ^= 0xFF;
+    }
+    fs::write(&sst, data).expect("Failed to write SST file");
+
+    let db = DB::open(&opts, path).expect("Failed to reopen database");
+    let mut read = 0;
+    let mut failure = None;
+    for item in db.iter(Direction::Forward) {
+        match item {
+            Ok(_) => read += 1,
+            Err(e) => {
+                failure = Some(e);
+                break;
+            }
+        }
+    }
+
+    let err = failure.expect("Iteration should fail on the damaged block");
+    assert_eq!(err.kind(), ErrorKind::Corruption);
+    assert!(read > 0);
+    let last_valid_key = err.last_valid_key().expect("Missing last valid key");
+    assert_eq!(last_valid_key, &(read - 1u32).to_be_bytes()[..]);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_error_kind_for_invalid_argument() {
+    let path = "/tmp/rust_rocksdb_test_error_kind_missing_db";
+    let _ = fs::remove_dir_all(path);
+
+    // Opening a missing database without create_if_missing is an invalid argument
+    let opts = Options::default();
+    let err = DB::open(&opts, path).err().expect("Open should fail");
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert!(err.last_valid_key().is_none());
+
+    // The raw status stays available alongside the kind
+    assert_eq!(err.code(), Some(4));
+    assert_eq!(err.subcode(), None);
+    assert!(err.message().starts_with("Invalid argument: "));
+    assert_eq!(err.to_string(), format!("RocksDB error: {}", err.message()));
+
+    // Errors raised by the crate itself keep their own kind
+    let err = Error::new("custom");
+    assert_eq!(err.kind(), ErrorKind::Other);
+    assert_eq!(err.code(), None);
+    assert_eq!(err.message(), "custom");
+
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_property_int_value() {
+    let path = "/tmp/rust_rocksdb_test_property_int_value";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let cf_opts = Options::default();
+    let cf = db
+        .create_column_family(&cf_opts, "metrics")
+        .expect("Failed to create column family");
+
+    for i in 0..100u32 {
+        db.put(&i.to_be_bytes(), b"value")
+            .expect("Failed to put value");
+    }
+    db.put_cf(&cf, b"key", b"value")
+        .expect("Failed to put value");
+
+    let keys = db
+        .property_int_value("rocksdb.estimate-num-keys")
+        .expect("Failed to read property")
+        .expect("Missing property");
+    assert!(keys > 0);
+
+    let mem = db
+        .property_int_value("rocksdb.cur-size-all-mem-tables")
+        .expect("Failed to read property");
+    assert!(mem.is_some_and(|mem| mem > 0));
+    assert!(
+        db.property_int_value("rocksdb.num-running-compactions")
+            .unwrap()
+            .is_some()
+    );
+
+    let cf_keys = db
+        .property_int_value_cf(&cf, "rocksdb.estimate-num-keys")
+        .expect("Failed to read column family property");
+    assert_eq!(cf_keys, Some(1));
+
+    // String-only and unknown properties have no integer value
+    assert_eq!(db.property_int_value("rocksdb.stats").unwrap(), None);
+    assert_eq!(
+        db.property_int_value("rocksdb.no-such-property").unwrap(),
+        None
+    );
+
+    drop(cf);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_multi_get_and_get_many() {
+    let path = "/tmp/rust_rocksdb_test_multi_get";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let cf_opts = Options::default();
+    let cf = db
+        .create_column_family(&cf_opts, "users")
+        .expect("Failed to create column family");
+
+    db.put(b"key1", b"value1").expect("Failed to put value");
+    db.put(b"key3", b"value3").expect("Failed to put value");
+    db.put_cf(&cf, b"user:1", b"Alice")
+        .expect("Failed to put value in column family");
+
+    let values: Vec<Option<Vec<u8>>> = db
+        .multi_get([&b"key1"[..], b"key2", b"key3"])
+        .into_iter()
+        .map(|value| value.expect("Failed to get value"))
+        .collect();
+    assert_eq!(
+        values,
+        vec![Some(b"value1".to_vec()), None, Some(b"value3".to_vec())]
+    );
+
+    let found = db
+        .get_many(vec![b"key1".to_vec(), b"key2".to_vec(), b"key3".to_vec()])
+        .expect("Failed to get values");
+    assert_eq!(found.len(), 2);
+    assert_eq!(
+        found.get(&b"key1"[..]).map(Vec::as_slice),
+        Some(&b"value1"[..])
+    );
+    assert!(!found.contains_key(&b"key2"[..]));
+
+    let cf_values = db.multi_get_cf(&cf, [b"user:1", b"user:2"]);
+    assert_eq!(cf_values.len(), 2);
+    assert_eq!(
+        cf_values[0].as_ref().unwrap().as_deref(),
+        Some(&b"Alice"[..])
+    );
+    let cf_found = db
+        .get_many_cf(&cf, [b"user:1", b"user:2"])
+        .expect("Failed to get values from column family");
+    assert_eq!(cf_found.len(), 1);
+
+    assert!(db.multi_get(Vec::<Vec<u8>>::new()).is_empty());
+
+    drop(cf);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_approximate_sizes() {
+    let path = "/tmp/rust_rocksdb_test_approximate_sizes";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true)
+        .set_compression_type(CompressionType::None);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let cf_opts = Options::default();
+    let cf = db
+        .create_column_family(&cf_opts, "sharded")
+        .expect("Failed to create column family");
+
+    for i in 0..1000u32 {
+        let key = format!("a{:04}", i);
+        db.put(key.as_bytes(), &[0u8; 1024])
+            .expect("Failed to put value");
+        db.put_cf(&cf, key.as_bytes(), &[0u8; 1024])
+            .expect("Failed to put value in column family");
+    }
+    db.flush().expect("Failed to flush");
+
+    let sizes = db
+        .approximate_sizes(&[(&b"a"[..], &b"b"[..]), (&b"x"[..], &b"y"[..])])
+        .expect("Failed to approximate sizes");
+    assert_eq!(sizes.len(), 2);
+    assert!(sizes[0] > 0);
+    assert_eq!(sizes[1], 0);
+
+    // The column family has not been flushed, so only memtables count
+    let ranges = [(&b"a"[..], &b"b"[..])];
+    let in_memtables = db
+        .approximate_sizes_cf(&cf, &ranges, true, false)
+        .expect("Failed to approximate sizes");
+    assert!(in_memtables[0] > 0);
+    let in_files = db
+        .approximate_sizes_cf(&cf, &ranges, false, true)
+        .expect("Failed to approximate sizes");
+    assert_eq!(in_files[0], 0);
+
+    assert!(db.approximate_sizes_cf(&cf, &ranges, false, false).is_err());
+
+    drop(cf);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_scan_cancellation() {
+    let path = "/tmp/rust_rocksdb_test_scan_cancellation";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let cf_opts = Options::default();
+    let cf = db
+        .create_column_family(&cf_opts, "events")
+        .expect("Failed to create column family");
+
+    for i in 0..100u32 {
+        db.put(&i.to_be_bytes(), b"value")
+            .expect("Failed to put value");
+        db.put_cf(&cf, &i.to_be_bytes(), b"value")
+            .expect("Failed to put value in column family");
+    }
+
+    let token = CancellationToken::new();
+    let mut iter = db.iter(Direction::Forward).with_cancellation(&token);
+    for _ in 0..10 {
+        iter.next().unwrap().expect("Failed to iterate");
+    }
+
+    token.clone().cancel();
+    assert!(token.is_cancelled());
+
+    let err = iter.next().unwrap().expect_err("Scan should be cancelled");
+    assert_eq!(err.kind(), ErrorKind::Cancelled);
+    assert_eq!(err.last_valid_key(), Some(&9u32.to_be_bytes()[..]));
+    assert!(iter.next().is_none());
+    drop(iter);
+
+    // Merged scans honour the same token
+    let merged = db.merge_iter(&[&cf]).with_cancellation(&token);
+    let items: Vec<_> = merged.collect();
+    assert_eq!(items.len(), 1);
+    assert!(items[0].is_err());
+
+    // An uncancelled token does not affect the scan
+    let fresh = CancellationToken::new();
+    assert_eq!(
+        db.iter(Direction::Forward)
+            .with_cancellation(&fresh)
+            .count(),
+        100
+    );
+
+    drop(cf);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_approximate_memtable_size() {
+    let path = "/tmp/rust_rocksdb_test_approximate_memtable_size";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let cf_opts = Options::default();
+    let cf = db
+        .create_column_family(&cf_opts, "logs")
+        .expect("Failed to create column family");
+
+    for i in 0..1000u32 {
+        let key = format!("log:{:04}", i);
+        db.put(key.as_bytes(), &[0u8; 512])
+            .expect("Failed to put value");
+        db.put_cf(&cf, key.as_bytes(), &[0u8; 512])
+            .expect("Failed to put value in column family");
+    }
+
+    let pending = db
+        .approximate_memtable_size(b"log:", b"log;")
+        .expect("Failed to approximate memtable size");
+    assert!(pending > 0);
+    assert_eq!(db.approximate_memtable_size(b"x", b"y").unwrap(), 0);
+
+    // Flushing moves the data out of the memtables
+    db.flush().expect("Failed to flush");
+    assert_eq!(db.approximate_memtable_size(b"log:", b"log;").unwrap(), 0);
+
+    let cf_pending = db
+        .approximate_memtable_size_cf(&cf, b"log:", b"log;")
+        .expect("Failed to approximate memtable size");
+    assert!(cf_pending > 0);
+
+    drop(cf);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_statistics_snapshot_delta() {
+    let path = "/tmp/rust_rocksdb_test_statistics";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true).enable_statistics();
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    // Activity before the measured phase
+    for i in 0..50u32 {
+        db.put(&i.to_be_bytes(), b"value").expect("Failed to put");
+    }
+
+    let before = opts.statistics_snapshot();
+    assert!(before.get(Ticker::NumberKeysWritten) >= 50);
+
+    for i in 0..10u32 {
+        db.put(&i.to_be_bytes(), b"value").expect("Failed to put");
+        db.get(&i.to_be_bytes()).expect("Failed to get");
+    }
+
+    let delta = opts.statistics_snapshot().delta(&before);
+    assert_eq!(delta.get(Ticker::NumberKeysWritten), 10);
+    assert_eq!(delta.get(Ticker::NumberKeysRead), 10);
+    assert_eq!(delta.get(Ticker::MemtableHit), 10);
+    assert_eq!(
+        opts.ticker_count(Ticker::NumberKeysWritten),
+        before.get(Ticker::NumberKeysWritten) + 10
+    );
+
+    let dump = opts
+        .statistics_string()
+        .expect("Statistics should be enabled");
+    assert!(dump.contains("rocksdb.number.keys.written"));
+
+    // Without statistics there is nothing to read
+    assert_eq!(Options::default().statistics_string(), None);
+    assert_eq!(
+        Options::default().ticker_count(Ticker::NumberKeysWritten),
+        0
+    );
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_perf_context() {
+    let path = "/tmp/rust_rocksdb_test_perf_context";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    for i in 0..100u32 {
+        db.put(&i.to_be_bytes(), b"value").expect("Failed to put");
+    }
+    db.flush().expect("Failed to flush");
+
+    PerfContext::set_level(PerfLevel::EnableCount);
+    let mut perf = match PerfContext::new() {
+        Ok(perf) => perf,
+        Err(e) => {
+            // The bundled librocksdb.a is built with NPERF_CONTEXT
+            assert_eq!(e.kind(), ErrorKind::NotSupported);
+            PerfContext::set_level(PerfLevel::Disable);
+            drop(db);
+            let _ = fs::remove_dir_all(path);
+            return;
+        }
+    };
+
+    perf.reset();
+    db.get(&7u32.to_be_bytes()).expect("Failed to get");
+    assert!(perf.metric(PerfMetric::GetReadBytes) > 0);
+    assert!(perf.metric(PerfMetric::UserKeyComparisonCount) > 0);
+    assert!(perf.report(true).contains("user_key_comparison_count"));
+
+    // Reset clears the counters
+    perf.reset();
+    assert_eq!(perf.metric(PerfMetric::GetReadBytes), 0);
+
+    // Nothing is collected once disabled
+    PerfContext::set_level(PerfLevel::Disable);
+    db.get(&7u32.to_be_bytes()).expect("Failed to get");
+    assert_eq!(perf.metric(PerfMetric::GetReadBytes), 0);
+
+    drop(perf);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_read_amp_report() {
+    let path = "/tmp/rust_rocksdb_test_read_amp_report";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true).enable_statistics();
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    for i in 0..100u32 {
+        db.put(&i.to_be_bytes(), &[0u8; 100])
+            .expect("Failed to put");
+    }
+    db.flush().expect("Failed to flush");
+
+    let before = db.read_amp_report();
+
+    for i in 0..10u32 {
+        db.get(&i.to_be_bytes()).expect("Failed to get");
+    }
+    let report = db.read_amp_report().delta(&before);
+
+    assert_eq!(report.db_bytes_returned, 1000);
+    // The first lookup reads the data block from the file, later ones hit the cache
+    assert!(report.db_block_cache_misses > 0);
+    assert!(report.db_block_cache_hits > 0);
+    assert!(report.db_block_cache_bytes_read > 0);
+    // No table option enabled read amplification tracking
+    assert_eq!(report.block_read_amplification(), None);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_read_amp_bytes_per_bit() {
+    let path = "/tmp/rust_rocksdb_test_read_amp_bytes_per_bit";
+    let _ = fs::remove_dir_all(path);
+
+    let mut table_opts = BlockBasedOptions::default();
+    table_opts
+        .set_block_size(16 * 1024)
+        .set_read_amp_bytes_per_bit(32);
+    let mut opts = Options::default();
+    opts.create_if_missing(true)
+        .enable_statistics()
+        .set_block_based_table_factory(&table_opts);
+    opts.validate()
+        .expect("RocksDB should accept the table options");
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    for i in 0..1000u32 {
+        db.put(&i.to_be_bytes(), &[0u8; 100])
+            .expect("Failed to put");
+    }
+    db.flush().expect("Failed to flush");
+
+    for i in (0..1000u32).step_by(100) {
+        db.get(&i.to_be_bytes()).expect("Failed to get");
+    }
+    let report = db.read_amp_report();
+
+    // Point lookups use a small part of each 16 KiB block they load
+    assert!(report.db_block_bytes_read > report.db_useful_block_bytes);
+    assert!(
+        report
+            .block_read_amplification()
+            .expect("Expected read amplification tracking")
+            > 1.0
+    );
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_bloom_filter_report() {
+    let path = "/tmp/rust_rocksdb_test_bloom_filter_report";
+    let _ = fs::remove_dir_all(path);
+
+    let mut table_opts = BlockBasedOptions::default();
+    table_opts.set_bloom_filter(10.0);
+    let mut opts = Options::default();
+    opts.create_if_missing(true)
+        .enable_statistics()
+        .set_block_based_table_factory(&table_opts);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    for i in 0..1000u32 {
+        db.put(&i.to_be_bytes(), b"value").expect("Failed to put");
+    }
+    db.flush().expect("Failed to flush");
+
+    let before = db.bloom_filter_report();
+    for i in 0..100u32 {
+        db.get(&i.to_be_bytes()).expect("Failed to get");
+    }
+    for i in 10_000..11_000u32 {
+        db.get(&i.to_be_bytes()).expect("Failed to get");
+    }
+    let report = db.bloom_filter_report().delta(&before);
+
+    // Every present key passes the filter, most missing keys are ruled out
+    assert!(report.full_true_positive >= 100);
+    assert!(report.useful > 900);
+    assert_eq!(report.checked(), report.useful + report.full_positive);
+    assert!(report.useful_ratio().expect("Expected filter checks") > 0.8);
+    assert!(
+        report
+            .false_positive_rate()
+            .expect("Expected checks for missing keys")
+            < 0.1
+    );
+    assert_eq!(report.prefix_useful_ratio(), None);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_rate_limiter() {
+    let path = "/tmp/rust_rocksdb_test_rate_limiter";
+    let _ = fs::remove_dir_all(path);
+
+    assert!(RateLimiter::new(0).is_err());
+    assert!(RateLimiter::with_mode(1024, 0, 10, RateLimiterMode::AllIo, false).is_err());
+
+    let limiter =
+        RateLimiter::new_auto_tuned(64 * 1024 * 1024).expect("Failed to create rate limiter");
+    let mut opts = Options::default();
+    opts.create_if_missing(true).set_ratelimiter(&limiter);
+    // The options share ownership of the limiter
+    drop(limiter);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    for i in 0..1000u32 {
+        db.put(&i.to_be_bytes(), &[0u8; 256])
+            .expect("Failed to put");
+    }
+    db.flush().expect("Failed to flush");
+    db.compact_range(None, None);
+    assert_eq!(db.get(&5u32.to_be_bytes()).unwrap(), Some(vec![0u8; 256]));
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_shared_write_buffer_manager() {
+    let path_a = "/tmp/rust_rocksdb_test_wbm_a";
+    let path_b = "/tmp/rust_rocksdb_test_wbm_b";
+    let _ = fs::remove_dir_all(path_a);
+    let _ = fs::remove_dir_all(path_b);
+
+    assert!(WriteBufferManager::new(0, false).is_err());
+
+    let wbm = WriteBufferManager::new(64 * 1024 * 1024, false)
+        .expect("Failed to create write buffer manager");
+    assert_eq!(wbm.buffer_size(), 64 * 1024 * 1024);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true).set_write_buffer_manager(&wbm);
+
+    let db_a = DB::open(&opts, path_a).expect("Failed to open database");
+    let db_b = DB::open(&opts, path_b).expect("Failed to open database");
+    let baseline = wbm.memory_usage();
+
+    for i in 0..1000u32 {
+        db_a.put(&i.to_be_bytes(), &[1u8; 512])
+            .expect("Failed to put");
+    }
+    let after_a = wbm.memory_usage();
+    assert!(after_a > baseline);
+
+    // Writes to the second database count against the same budget
+    for i in 0..1000u32 {
+        db_b.put(&i.to_be_bytes(), &[2u8; 512])
+            .expect("Failed to put");
+    }
+    assert!(wbm.memory_usage() > after_a);
+    assert!(wbm.mutable_memtable_memory_usage() > 0);
+
+    wbm.set_buffer_size(128 * 1024 * 1024)
+        .expect("Failed to resize budget");
+    assert_eq!(wbm.buffer_size(), 128 * 1024 * 1024);
+    wbm.set_allow_stall(true);
+
+    drop(db_a);
+    drop(db_b);
+    let _ = fs::remove_dir_all(path_a);
+    let _ = fs::remove_dir_all(path_b);
+}
+
+#[test]
+fn test_key_and_value_size_limits() {
+    let path = "/tmp/rust_rocksdb_test_size_limits";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true)
+        .set_max_key_size(8)
+        .set_max_value_size(16);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    let cf = db
+        .create_column_family(&Options::default(), "limited")
+        .expect("Failed to create column family");
+
+    db.put(b"key", b"small value").expect("Failed to put");
+
+    let err = db.put(b"much too long key", b"v").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert!(
+        err.to_string()
+            .contains("Key size 17 exceeds the limit of 8 bytes")
+    );
+
+    let err = db.put_cf(&cf, b"key", &[0u8; 17]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert!(
+        err.to_string()
+            .contains("Value size 17 exceeds the limit of 16 bytes")
+    );
+
+    // A batch with one oversized entry is rejected as a whole
+    let mut batch = WriteBatch::new();
+    batch.put(b"ok", b"value");
+    batch.put(b"ok2", &[0u8; 32]);
+    let err = db.write(&batch).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert_eq!(db.get(b"ok").unwrap(), None);
+
+    // Entries after a range deletion are checked too
+    let mut batch = WriteBatch::new();
+    batch.delete_range_cf(&cf, b"a", b"b");
+    batch.put_cf(&cf, b"ok", &[0u8; 32]);
+    let err = db.write(&batch).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert_eq!(db.get_cf(&cf, b"ok").unwrap(), None);
+
+    // A batch whose entries can't all be checked is rejected
+    let mut data = vec![0u8; 8];
+    data.extend(1u32.to_le_bytes());
+    data.extend([0x7, 1, b'k']);
+    let batch = WriteBatch::from_data(&data).expect("Failed to rebuild batch");
+    let err = db.write(&batch).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotSupported);
+
+    drop(cf);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_sst_file_manager() {
+    let path = "/tmp/rust_rocksdb_test_sst_file_manager";
+    let _ = fs::remove_dir_all(path);
+
+    let sfm = SstFileManager::new().expect("Failed to create SST file manager");
+    sfm.set_delete_rate_bytes_per_second(1024 * 1024);
+    assert_eq!(sfm.delete_rate_bytes_per_second(), 1024 * 1024);
+    sfm.set_max_trash_db_ratio(0.5);
+    assert_eq!(sfm.max_trash_db_ratio(), 0.5);
+    assert!(!sfm.is_max_allowed_space_reached());
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true).set_sst_file_manager(&sfm);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    for i in 0..1000u32 {
+        db.put(&i.to_be_bytes(), &[0u8; 256])
+            .expect("Failed to put");
+    }
+    db.flush().expect("Failed to flush");
+    assert!(sfm.total_size() > 0);
+
+    // Once the limit is below the tracked size, the space counts as used up
+    sfm.set_max_allowed_space_usage(1);
+    assert!(sfm.is_max_allowed_space_reached());
+    sfm.set_max_allowed_space_usage(0);
+    assert!(!sfm.is_max_allowed_space_reached());
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_space_limit_error_subcode() {
+    let path = "/tmp/rust_rocksdb_test_space_limit_subcode";
+    let _ = fs::remove_dir_all(path);
+
+    let sfm = SstFileManager::new().expect("Failed to create SST file manager");
+    sfm.set_max_allowed_space_usage(1024);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true).set_sst_file_manager(&sfm);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    for i in 0..1000u32 {
+        db.put(&i.to_be_bytes(), &[0u8; 256])
+            .expect("Failed to put");
+    }
+    // The flushed file takes the database past its space limit
+    let err = db.flush().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::IOError);
+    assert_eq!(err.subcode(), Some(ErrorSubcode::SpaceLimit));
+    assert_eq!(ErrorSubcode::SpaceLimit.as_raw(), 8);
+    assert_eq!(ErrorSubcode::NoSpace.as_raw(), 4);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_migrator() {
+    let path = "/tmp/rust_rocksdb_test_migrator";
+    let _ = fs::remove_dir_all(path);
+
+    // v1 values are raw, v2 values start with a version byte
+    let migrator = Migrator::new("test-v1-to-v2", |value| {
+        if value.first() == Some(&2) {
+            return None;
+        }
+        let mut v2 = vec![2];
+        v2.extend_from_slice(value);
+        Some(v2)
+    })
+    .expect("Failed to create migrator");
+    assert!(Migrator::new("bad\0name", |_| None).is_err());
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true).set_migrator(&migrator);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    for i in 0..100u32 {
+        db.put(&i.to_be_bytes(), b"v1").expect("Failed to put");
+    }
+    db.flush().expect("Failed to flush");
+
+    // Not yet compacted: stored as v1, upgraded on read
+    assert_eq!(db.get(&1u32.to_be_bytes()).unwrap(), Some(b"v1".to_vec()));
+    assert_eq!(
+        migrator.get(&db, &1u32.to_be_bytes()).unwrap(),
+        Some(b"\x02v1".to_vec())
+    );
+
+    let mut compact_opts = CompactRangeOptions::default();
+    compact_opts.set_bottommost_level_compaction(BottommostLevelCompaction::Force);
+    db.compact_range_opt(None, None, &compact_opts);
+
+    for i in 0..100u32 {
+        assert_eq!(db.get(&i.to_be_bytes()).unwrap(), Some(b"\x02v1".to_vec()));
+    }
+    // Already-migrated values are left alone, and reading them isn't
+    // counted as part of the migration
+    let unchanged = migrator.progress(&db).unwrap().unchanged;
+    assert_eq!(
+        migrator.get(&db, &1u32.to_be_bytes()).unwrap(),
+        Some(b"\x02v1".to_vec())
+    );
+
+    let progress = migrator.progress(&db).expect("Failed to get progress");
+    assert_eq!(progress.migrated, 100);
+    assert_eq!(progress.upgraded_on_read, 1);
+    assert_eq!(progress.unchanged, unchanged);
+    assert_eq!(progress.failed, 0);
+    assert!(progress.estimated_keys > 0);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_env_thread_pools() {
+    let path = "/tmp/rust_rocksdb_test_env_thread_pools";
+    let _ = fs::remove_dir_all(path);
+
+    let env = Env::new();
+    env.set_background_threads(4);
+    env.set_high_priority_background_threads(2);
+    env.set_bottom_priority_background_threads(1);
+    assert!(env.background_threads() >= 4);
+    assert!(env.high_priority_background_threads() >= 2);
+    assert!(env.bottom_priority_background_threads() >= 1);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true)
+        .set_env(&env)
+        .increase_parallelism(6)
+        .set_max_background_jobs(6);
+    assert!(env.background_threads() >= 6);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    db.put(b"key", b"value").expect("Failed to put value");
+    db.flush().expect("Failed to flush");
+    assert_eq!(db.get(b"key").unwrap().as_deref(), Some(&b"value"[..]));
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_sync() {
+    let path = "/tmp/rust_rocksdb_test_sync";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true).set_manual_wal_flush(true);
+
+    {
+        let db = DB::open(&opts, path).expect("Failed to open database");
+        db.put(b"applied_index", &42u64.to_be_bytes())
+            .expect("Failed to put value");
+        db.sync().expect("Failed to sync");
+
+        // The memtable was flushed to an SST file
+        assert_eq!(
+            db.property_int_value("rocksdb.num-entries-active-mem-table")
+                .unwrap(),
+            Some(0)
+        );
+    }
+
+    let db = DB::open(&opts, path).expect("Failed to reopen database");
+    assert_eq!(
+        db.get(b"applied_index").unwrap(),
+        Some(42u64.to_be_bytes().to_vec())
+    );
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_raft_applier() {
+    let path = "/tmp/rust_rocksdb_test_raft_applier";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    let mut entry = WriteBatch::new();
+    entry.put(b"user:1", b"Alice");
+    let payload = entry.data().to_vec();
+
+    {
+        let db = DB::open(&opts, path).expect("Failed to open database");
+        let applier = RaftApplier::new(&db);
+        assert_eq!(applier.last_applied_index().unwrap(), 0);
+
+        assert!(applier.apply(1, &payload).expect("Failed to apply"));
+        let mut second = WriteBatch::new();
+        second.put(b"user:2", b"Bob");
+        assert!(applier.apply_batch(2, second).expect("Failed to apply"));
+
+        // Replayed and stale entries are skipped
+        let mut stale = WriteBatch::new();
+        stale.put(b"user:1", b"Mallory");
+        assert!(!applier.apply_batch(1, stale).expect("Failed to apply"));
+        assert_eq!(db.get(b"user:1").unwrap(), Some(b"Alice".to_vec()));
+    }
+
+    // The index survives a restart
+    let db = DB::open(&opts, path).expect("Failed to reopen database");
+    let applier = RaftApplier::new(&db);
+    assert_eq!(applier.last_applied_index().unwrap(), 2);
+    assert_eq!(db.get(b"user:2").unwrap(), Some(b"Bob".to_vec()));
+
+    // A corrupt entry is rejected without advancing the index
+    let err = applier.apply(3, &payload[..payload.len() - 1]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Corruption);
+    assert_eq!(applier.last_applied_index().unwrap(), 2);
+
+    // A malformed index is reported as corruption
+    db.put(RaftApplier::DEFAULT_INDEX_KEY, b"bad")
+        .expect("Failed to put");
+    let err = applier.last_applied_index().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Corruption);
+
+    drop(applier);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_raft_applier_cf() {
+    let path = "/tmp/rust_rocksdb_test_raft_applier_cf";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    let meta = db
+        .create_column_family(&Options::default(), "meta")
+        .expect("Failed to create column family");
+
+    let mut applier = RaftApplier::with_cf(&db, &meta);
+    applier.set_index_key(b"applied");
+
+    let mut entry = WriteBatch::new();
+    entry.put(b"key", b"value");
+    assert!(applier.apply_batch(7, entry).expect("Failed to apply"));
+
+    assert_eq!(applier.last_applied_index().unwrap(), 7);
+    assert_eq!(
+        db.get_cf(&meta, b"applied").unwrap(),
+        Some(7u64.to_be_bytes().to_vec())
+    );
+    assert_eq!(db.get(RaftApplier::DEFAULT_INDEX_KEY).unwrap(), None);
+    assert_eq!(db.get(b"key").unwrap(), Some(b"value".to_vec()));
+
+    drop(applier);
+    drop(meta);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_open_with_ttl() {
+    let path = "/tmp/rust_rocksdb_test_open_with_ttl";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open_with_ttl(&opts, path, 1).expect("Failed to open database");
+
+    db.put(b"session", b"user:1").expect("Failed to put value");
+    // The timestamp suffix is hidden from reads
+    assert_eq!(db.get(b"session").unwrap(), Some(b"user:1".to_vec()));
+
+    // Expired entries are dropped by compaction
+    std::thread::sleep(Duration::from_millis(2100));
+    db.compact_range(None, None);
+    assert_eq!(db.get(b"session").unwrap(), None);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_secondary_instance() {
+    let path = "/tmp/rust_rocksdb_test_secondary_primary";
+    let secondary_path = "/tmp/rust_rocksdb_test_secondary";
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_dir_all(secondary_path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let primary = DB::open(&opts, path).expect("Failed to open database");
+    primary.put(b"before", b"1").expect("Failed to put value");
+    primary.flush().expect("Failed to flush");
+
+    let mut secondary_opts = Options::default();
+    secondary_opts.set_max_open_files(-1);
+    let secondary = DB::open_as_secondary(&secondary_opts, path, secondary_path)
+        .expect("Failed to open secondary");
+    assert_eq!(secondary.get(b"before").unwrap(), Some(b"1".to_vec()));
+
+    // New writes only become visible after catching up
+    primary.put(b"after", b"2").expect("Failed to put value");
+    assert_eq!(secondary.get(b"after").unwrap(), None);
+    secondary
+        .try_catch_up_with_primary()
+        .expect("Failed to catch up");
+    assert_eq!(secondary.get(b"after").unwrap(), Some(b"2".to_vec()));
+
+    assert!(secondary.put(b"key", b"value").is_err());
+    assert!(primary.try_catch_up_with_primary().is_err());
+
+    drop(secondary);
+    drop(primary);
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_dir_all(secondary_path);
+}
+
+#[test]
+fn test_read_your_writes_session() {
+    let path = "/tmp/rust_rocksdb_test_session_primary";
+    let secondary_path = "/tmp/rust_rocksdb_test_session_secondary";
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_dir_all(secondary_path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let primary = DB::open(&opts, path).expect("Failed to open database");
+    let mut secondary_opts = Options::default();
+    secondary_opts.set_max_open_files(-1);
+    let replica = DB::open_as_secondary(&secondary_opts, path, secondary_path)
+        .expect("Failed to open secondary");
+
+    let mut session = Session::new(&primary, &replica);
+    session.set_max_wait(Duration::from_millis(100));
+
+    session.put(b"user:1", b"Alice").expect("Failed to put");
+    assert_eq!(
+        session.last_write_sequence(),
+        primary.latest_sequence_number()
+    );
+    assert!(!session.replica_is_current());
+
+    // The read catches the replica up rather than missing the write
+    assert_eq!(session.get(b"user:1").unwrap(), Some(b"Alice".to_vec()));
+    assert!(session.replica_is_current());
+
+    session.delete(b"user:1").expect("Failed to delete");
+    assert_eq!(session.get(b"user:1").unwrap(), None);
+
+    // A sequence the replica can never reach falls back to the primary
+    let mut batch = WriteBatch::new();
+    batch.put(b"user:2", b"Bob");
+    session.write(&batch).expect("Failed to write");
+    session.set_max_wait(Duration::ZERO);
+    session.observe(u64::MAX);
+    assert_eq!(session.get(b"user:2").unwrap(), Some(b"Bob".to_vec()));
+
+    drop(replica);
+    drop(primary);
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_dir_all(secondary_path);
+}
+
+#[test]
+fn test_hot_backup_to_directory_sink() {
+    let path = "/tmp/rust_rocksdb_test_hot_backup";
+    let sink_path = "/tmp/rust_rocksdb_test_hot_backup_sink";
+    let restore_path = "/tmp/rust_rocksdb_test_hot_backup_restore";
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_dir_all(sink_path);
+    let _ = fs::remove_dir_all(restore_path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    for i in 0..100u32 {
+        db.put(&i.to_be_bytes(), b"flushed").expect("Failed to put");
+    }
+    db.flush().expect("Failed to flush");
+
+    let mut sink = DirectorySink::new(sink_path).expect("Failed to create sink");
+    let backup = HotBackup::new(&db);
+    let first = backup.run(&mut sink).expect("Failed to back up");
+    assert!(first.uploaded_files > 0);
+    assert_eq!(first.uploaded_files, first.files);
+
+    // Unflushed writes travel in the WAL; the flushed SST is not uploaded again
+    db.put(b"wal_only", b"value").expect("Failed to put");
+    let second = backup.run(&mut sink).expect("Failed to back up");
+    assert_ne!(second.id, first.id);
+    assert!(second.uploaded_files < second.files);
+    assert!(!Path::new(&format!("{}.backup-staging", path)).exists());
+
+    let latest = fs::read_to_string(format!("{}/LATEST", sink_path)).unwrap();
+    assert_eq!(latest, second.id);
+
+    BackupRestore::new(&mut sink)
+        .restore(&second.id, restore_path)
+        .expect("Failed to restore");
+    let restored = DB::open(&Options::default(), restore_path).expect("Failed to open backup");
+    assert_eq!(
+        restored.get(&5u32.to_be_bytes()).unwrap(),
+        Some(b"flushed".to_vec())
+    );
+    assert_eq!(restored.get(b"wal_only").unwrap(), Some(b"value".to_vec()));
+    drop(restored);
+    let _ = fs::remove_dir_all(restore_path);
+
+    // A new database reuses the file numbers; its SST must not be mistaken
+    // for the one already in the sink
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    for i in 0..100u32 {
+        db.put(&i.to_be_bytes(), b"rewritten")
+            .expect("Failed to put");
+    }
+    db.flush().expect("Failed to flush");
+    HotBackup::new(&db)
+        .run(&mut sink)
+        .expect("Failed to back up");
+    BackupRestore::new(&mut sink)
+        .restore_latest(restore_path)
+        .expect("Failed to restore");
+    let restored = DB::open(&Options::default(), restore_path).expect("Failed to open backup");
+    assert_eq!(
+        restored.get(&5u32.to_be_bytes()).unwrap(),
+        Some(b"rewritten".to_vec())
+    );
+
+    drop(restored);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_dir_all(sink_path);
+    let _ = fs::remove_dir_all(restore_path);
+}
+
+#[test]
+fn test_destroy() {
+    let path = "/tmp/rust_rocksdb_test_destroy";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    db.put(b"key", b"value").expect("Failed to put value");
+    db.flush().expect("Failed to flush");
+
+    // An open database is locked
+    assert!(DB::destroy(&opts, path).is_err());
+    drop(db);
+
+    DB::destroy(&opts, path).expect("Failed to destroy database");
+    let mut reopen_opts = Options::default();
+    reopen_opts.create_if_missing(false);
+    assert!(DB::open(&reopen_opts, path).is_err());
+
+    // Destroying a missing database is not an error
+    DB::destroy(&opts, path).expect("Failed to destroy missing database");
+
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_restore_from_backup_source() {
+    let path = "/tmp/rust_rocksdb_test_restore_source";
+    let sink_path = "/tmp/rust_rocksdb_test_restore_source_sink";
+    let latest_path = "/tmp/rust_rocksdb_test_restore_latest";
+    let pitr_path = "/tmp/rust_rocksdb_test_restore_pitr";
+    for p in [path, sink_path, latest_path, pitr_path] {
+        let _ = fs::remove_dir_all(p);
+    }
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true).set_wal_ttl_seconds(3600);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    db.put(b"base", b"1").expect("Failed to put");
+
+    let mut sink = DirectorySink::new(sink_path).expect("Failed to create sink");
+    let backup = HotBackup::new(&db);
+    let info = backup.run(&mut sink).expect("Failed to back up");
+
+    // Archive the WAL in two segments, remembering a point between them
+    let mut next_seq = info.id.parse::<u64>().unwrap() + 1;
+    db.put(b"first", b"2").expect("Failed to put");
+    next_seq = backup
+        .archive_wal(&mut sink, next_seq)
+        .expect("Failed to archive");
+    let target = db.latest_sequence_number();
+    db.put(b"second", b"3").expect("Failed to put");
+    next_seq = backup
+        .archive_wal(&mut sink, next_seq)
+        .expect("Failed to archive");
+    assert_eq!(next_seq, db.latest_sequence_number() + 1);
+    // Nothing new to archive
+    assert_eq!(backup.archive_wal(&mut sink, next_seq).unwrap(), next_seq);
+
+    let mut restore = BackupRestore::new(&mut sink);
+    assert_eq!(restore.list_backups().unwrap(), vec![info.id.clone()]);
+    assert_eq!(restore.restore_latest(latest_path).unwrap(), info.id);
+    assert!(restore.restore(&info.id, latest_path).is_err());
+
+    let reached = restore
+        .restore_to_sequence(pitr_path, &Options::default(), target)
+        .expect("Failed to restore to sequence");
+    assert_eq!(reached, target);
+    assert!(
+        restore
+            .restore_to_sequence("/tmp/unused", &opts, 0)
+            .is_err()
+    );
+
+    let latest = DB::open(&Options::default(), latest_path).expect("Failed to open");
+    assert_eq!(latest.get(b"base").unwrap(), Some(b"1".to_vec()));
+    assert_eq!(latest.get(b"first").unwrap(), None);
+    drop(latest);
+
+    let pitr = DB::open(&Options::default(), pitr_path).expect("Failed to open");
+    assert_eq!(pitr.get(b"base").unwrap(), Some(b"1".to_vec()));
+    assert_eq!(pitr.get(b"first").unwrap(), Some(b"2".to_vec()));
+    assert_eq!(pitr.get(b"second").unwrap(), None);
+    drop(pitr);
+
+    // A damaged segment is reported as corruption
+    let segment = format!("{}/{}/{:020}", sink_path, HotBackup::WAL_PREFIX, next_seq);
+    let mut short_body = next_seq.to_be_bytes().to_vec();
+    short_body.extend(4u64.to_be_bytes());
+    short_body.extend([0u8; 4]);
+    let mut huge_len = next_seq.to_be_bytes().to_vec();
+    huge_len.extend(u64::MAX.to_be_bytes());
+    for data in [short_body, huge_len] {
+        fs::write(&segment, data).expect("Failed to write segment");
+        let _ = fs::remove_dir_all(pitr_path);
+        let err = BackupRestore::new(&mut sink)
+            .restore_to_sequence(pitr_path, &Options::default(), u64::MAX)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Corruption);
+    }
+
+    drop(db);
+    for p in [path, sink_path, latest_path, pitr_path] {
+        let _ = fs::remove_dir_all(p);
+    }
+}
+
+#[test]
+fn test_repair() {
+    let path = "/tmp/rust_rocksdb_test_repair";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    {
+        let db = DB::open(&opts, path).expect("Failed to open database");
+        db.put(b"flushed", b"1").expect("Failed to put value");
+        db.flush().expect("Failed to flush");
+        db.put(b"in_wal", b"2").expect("Failed to put value");
+    }
+
+    // Losing CURRENT makes the database unopenable
+    fs::remove_file(format!("{}/CURRENT", path)).unwrap();
+    let mut no_create = Options::default();
+    no_create.create_if_missing(false);
+    assert!(DB::open(&no_create, path).is_err());
+
+    DB::repair(&opts, path).expect("Failed to repair database");
+    let db = DB::open(&no_create, path).expect("Failed to open repaired database");
+    assert_eq!(db.get(b"flushed").unwrap(), Some(b"1".to_vec()));
+    assert_eq!(db.get(b"in_wal").unwrap(), Some(b"2".to_vec()));
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_snapshot() {
+    let path = "/tmp/rust_rocksdb_test_snapshot";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    db.put(b"balance", b"100").expect("Failed to put value");
+    let snapshot = db.snapshot().expect("Failed to take snapshot");
+    db.put(b"balance", b"0").expect("Failed to put value");
+    db.put(b"new", b"1").expect("Failed to put value");
+
+    assert_eq!(snapshot.get(b"balance").unwrap(), Some(b"100".to_vec()));
+    assert_eq!(snapshot.get(b"new").unwrap(), None);
+    assert_eq!(db.get(b"balance").unwrap(), Some(b"0".to_vec()));
+
+    drop(snapshot);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_snapshot_iteration() {
+    let path = "/tmp/rust_rocksdb_test_snapshot_iteration";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    db.put(b"a", b"1").expect("Failed to put value");
+    db.put(b"b", b"2").expect("Failed to put value");
+    let snapshot = db.snapshot().expect("Failed to take snapshot");
+
+    db.put(b"c", b"3").expect("Failed to put value");
+    db.delete(b"a").expect("Failed to delete key");
+    db.put(b"b", b"changed").expect("Failed to put value");
+
+    let expected = vec![
+        (b"a".to_vec(), b"1".to_vec()),
+        (b"b".to_vec(), b"2".to_vec()),
+    ];
+    let first_pass: Vec<(Vec<u8>, Vec<u8>)> = snapshot
+        .iter(Direction::Forward)
+        .map(|item| item.map(|(key, value)| (key.into_vec(), value.into_vec())))
+        .collect::<Result<_, _>>()
+        .expect("Failed to iterate");
+    assert_eq!(first_pass, expected);
+
+    // The iterator keeps the snapshot alive after the handle is dropped
+    let mut read_opts = ReadOptions::default();
+    read_opts.set_iterate_lower_bound(b"b");
+    let mut iter = snapshot.raw_iterator_opt(read_opts);
+    drop(snapshot);
+    db.put(b"d", b"4").expect("Failed to put value");
+
+    iter.seek_to_first();
+    assert_eq!(iter.item(), Some((&b"b"[..], &b"2"[..])));
+    iter.next();
+    assert!(!iter.valid());
+    drop(iter);
+
+    // Writes are visible to a plain iterator
+    assert_eq!(db.iter(Direction::Forward).count(), 3);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_named_snapshots() {
+    let path = "/tmp/rust_rocksdb_test_named_snapshots";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    db.put(b"key", b"old").expect("Failed to put value");
+    let sequence = db
+        .register_snapshot("export", None)
+        .expect("Failed to register snapshot");
+    db.register_snapshot("short", Some(Duration::from_millis(50)))
+        .expect("Failed to register snapshot");
+    db.put(b"key", b"new").expect("Failed to put value");
+
+    let err = db.register_snapshot("export", None).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+
+    let names: Vec<String> = db.snapshots().into_iter().map(|info| info.name).collect();
+    assert_eq!(names, vec!["export".to_string(), "short".to_string()]);
+    let export = &db.snapshots()[0];
+    assert_eq!(export.sequence_number, sequence);
+    assert_eq!(export.expires_in, None);
+
+    let snapshot = db
+        .named_snapshot("export")
+        .expect("Failed to find snapshot");
+    assert_eq!(snapshot.get(b"key").unwrap(), Some(b"old".to_vec()));
+
+    // A released snapshot stays readable through handles already taken
+    assert!(db.release_snapshot("export"));
+    assert!(!db.release_snapshot("export"));
+    assert!(db.named_snapshot("export").is_none());
+    assert_eq!(snapshot.get(b"key").unwrap(), Some(b"old".to_vec()));
+    drop(snapshot);
+
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(db.named_snapshot("short").is_none());
+    assert!(db.snapshots().is_empty());
+    assert_eq!(db.release_expired_snapshots(), 0);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_open_progress() {
+    let path = "/tmp/rust_rocksdb_test_open_progress";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    {
+        let db = DB::open(&opts, path).expect("Failed to open database");
+        // Left in the WAL, so the next open has to replay it
+        db.put(b"key", b"value").expect("Failed to put value");
+    }
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    opts.set_open_progress(move |progress| {
+        recorded
+            .lock()
+            .expect("Failed to lock events")
+            .push(progress.clone());
+    });
+    let db = DB::open(&opts, path).expect("Failed to reopen database");
+    assert_eq!(
+        db.get(b"key").expect("Failed to get value"),
+        Some(b"value".to_vec())
+    );
+
+    let events = events.lock().expect("Failed to lock events").clone();
+    match events.first() {
+        Some(OpenProgress::Started {
+            wal_files,
+            wal_bytes,
+            ..
+        }) => {
+            assert!(*wal_files >= 1);
+            assert!(*wal_bytes > 0);
+        }
+        other => panic!("Expected Started first, got {:?}", other),
+    }
+    assert!(events.contains(&OpenProgress::ManifestRecovered));
+    assert!(
+        events
+            .iter()
+            .any(|event| matches!(event, OpenProgress::ReplayingWal { .. }))
+    );
+    assert!(matches!(events.last(), Some(OpenProgress::Finished { .. })));
+
+    // The log messages still reach the LOG file
+    drop(db);
+    let log = fs::read_to_string(Path::new(path).join("LOG")).expect("Failed to read LOG");
+    assert!(log.contains("Recovering log #"));
+
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_open_progress_log_options() {
+    let path = "/tmp/rust_rocksdb_test_open_progress_dirs";
+    let wal_path = "/tmp/rust_rocksdb_test_open_progress_dirs_wal";
+    let log_path = "/tmp/rust_rocksdb_test_open_progress_dirs_log";
+    for p in [path, wal_path, log_path] {
+        let _ = fs::remove_dir_all(p);
+    }
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true)
+        .set_wal_dir(wal_path)
+        .set_db_log_dir(log_path)
+        .set_max_log_file_size(1024)
+        .set_keep_log_file_num(2);
+    {
+        let db = DB::open(&opts, path).expect("Failed to open database");
+        db.put(b"key", b"value").expect("Failed to put value");
+    }
+    // RocksDB names the log in a shared directory after the database path
+    let log_file = Path::new(log_path).join("tmp_rust_rocksdb_test_open_progress_dirs_LOG");
+    assert!(log_file.exists());
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    opts.set_open_progress(move |progress| {
+        recorded
+            .lock()
+            .expect("Failed to lock events")
+            .push(progress.clone());
+    });
+    let db = DB::open(&opts, path).expect("Failed to reopen database");
+
+    // The WALs are counted in the WAL directory
+    match events.lock().expect("Failed to lock events").first() {
+        Some(OpenProgress::Started {
+            wal_files,
+            wal_bytes,
+            ..
+        }) => {
+            assert!(*wal_files >= 1);
+            assert!(*wal_bytes > 0);
+        }
+        other => panic!("Expected Started first, got {:?}", other),
+    }
+
+    // The log is written where RocksDB would, rolled over at the size limit
+    drop(db);
+    let logs: Vec<String> = fs::read_dir(log_path)
+        .expect("Failed to list log directory")
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert!(logs.len() <= 2, "Kept too many logs: {:?}", logs);
+    let log = fs::read_to_string(&log_file).expect("Failed to read LOG");
+    assert!(log.len() < 2048);
+    assert!(!Path::new(path).join("LOG").exists());
+
+    for p in [path, wal_path, log_path] {
+        let _ = fs::remove_dir_all(p);
+    }
+}
+
+#[test]
+fn test_write_gate() {
+    let path = "/tmp/rust_rocksdb_test_write_gate";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    // No thresholds: always open
+    let mut gate = db.write_gate();
+    assert_eq!(gate.check().expect("Failed to check gate"), None);
+
+    for i in 0..3u32 {
+        db.put(&i.to_be_bytes(), b"value")
+            .expect("Failed to put value");
+        db.flush().expect("Failed to flush");
+    }
+    let debt = gate.debt().expect("Failed to read compaction debt");
+    assert_eq!(debt.l0_files, 3);
+
+    gate.set_max_l0_files(4);
+    assert_eq!(gate.check().expect("Failed to check gate"), None);
+
+    gate.set_max_l0_files(3)
+        .set_poll_interval(Duration::from_millis(10));
+    assert_eq!(
+        gate.check().expect("Failed to check gate"),
+        Some(BackOff::Level0Files { files: 3, limit: 3 })
+    );
+    // Three files are below the compaction trigger, so nothing drains level 0
+    let started = Instant::now();
+    assert!(
+        gate.wait(Duration::from_millis(50))
+            .expect("Failed to wait on gate")
+            .is_some()
+    );
+    assert!(started.elapsed() >= Duration::from_millis(50));
+
+    db.compact_range(None, None);
+    assert_eq!(
+        gate.wait(Duration::from_secs(5))
+            .expect("Failed to wait on gate"),
+        None
+    );
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_probe() {
+    let path = "/tmp/rust_rocksdb_test_probe";
+    let _ = fs::remove_dir_all(path);
+
+    let report = DB::probe(path);
+    assert_eq!(report.status, ProbeStatus::NotFound);
+    assert_eq!(report.action, ProbeAction::Create);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    {
+        let db = DB::open(&opts, path).expect("Failed to open database");
+        db.put(b"flushed", b"1").expect("Failed to put value");
+        db.flush().expect("Failed to flush");
+        assert_eq!(DB::probe(path).status, ProbeStatus::Locked);
+        db.put(b"in_wal", b"2").expect("Failed to put value");
+    }
+    assert!(DB::probe(path).is_healthy());
+
+    // Cutting the last record short looks like a crash mid-write
+    let newest_wal = fs::read_dir(path)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|file| file.extension().is_some_and(|ext| ext == "log"))
+        .max()
+        .expect("Failed to find WAL");
+    let len = fs::metadata(&newest_wal).unwrap().len();
+    fs::OpenOptions::new()
+        .write(true)
+        .open(&newest_wal)
+        .unwrap()
+        .set_len(len - 3)
+        .unwrap();
+    let report = DB::probe(path);
+    assert_eq!(report.status, ProbeStatus::TornWal);
+    assert_eq!(report.action, ProbeAction::Open);
+
+    let current = fs::read_to_string(format!("{}/CURRENT", path)).unwrap();
+    fs::remove_file(format!("{}/{}", path, current.trim_end())).unwrap();
+    let report = DB::probe(path);
+    assert_eq!(report.status, ProbeStatus::MissingManifest);
+    assert_eq!(report.action, ProbeAction::Repair);
+
+    fs::remove_file(format!("{}/CURRENT", path)).unwrap();
+    assert_eq!(DB::probe(path).status, ProbeStatus::MissingCurrent);
+
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_cache_stats() {
+    let path = "/tmp/rust_rocksdb_test_cache_stats";
+    let _ = fs::remove_dir_all(path);
+
+    let block_cache = Cache::new_lru(8 * 1024 * 1024);
+    let row_cache = Cache::new_lru(1024 * 1024);
+    let mut table_opts = BlockBasedOptions::default();
+    table_opts.set_block_cache(&block_cache);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true)
+        .enable_statistics()
+        .set_block_based_table_factory(&table_opts)
+        .set_row_cache(&row_cache);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    for i in 0..100u32 {
+        db.put(&i.to_be_bytes(), b"value").expect("Failed to put");
+    }
+    db.flush().expect("Failed to flush");
+    for _ in 0..3 {
+        for i in 0..100u32 {
+            db.get(&i.to_be_bytes()).expect("Failed to get");
+        }
+    }
+
+    let stats = db.cache_stats().expect("Failed to get cache stats");
+    assert_eq!(stats.block_cache.capacity, 8 * 1024 * 1024);
+    assert!(stats.block_cache.usage > 0);
+    assert!(stats.block_cache.misses > 0);
+
+    let row = stats.row_cache.expect("Row cache should be reported");
+    assert_eq!(row.capacity, 1024 * 1024);
+    assert!(row.usage > 0);
+    // The first pass misses, the later ones hit
+    assert_eq!(row.misses, 100);
+    assert_eq!(row.hits, 200);
+    assert!(row.hit_ratio().unwrap() > 0.6);
+    assert_eq!(row_cache.usage(), row.usage);
+
+    block_cache.set_capacity(16 * 1024 * 1024);
+    assert_eq!(
+        db.cache_stats().unwrap().block_cache.capacity,
+        16 * 1024 * 1024
+    );
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_sample_keys() {
+    let path = "/tmp/rust_rocksdb_test_sample_keys";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    assert!(
+        db.sample_keys(10, None, None)
+            .expect("Failed to sample keys")
+            .is_empty()
+    );
+
+    for i in 0..50_000u32 {
+        db.put(format!("k{:06}", i).as_bytes(), b"value")
+            .expect("Failed to put value");
+    }
+    db.put(b"z", b"outside").expect("Failed to put value");
+    db.flush().expect("Failed to flush");
+
+    let sample = db
+        .sample_keys(200, Some(&b"k"[..]), Some(&b"l"[..]))
+        .expect("Failed to sample keys");
+    assert_eq!(sample.len(), 200);
+    assert!(sample.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(sample.iter().all(|key| key.starts_with(b"k")));
+
+    // Both halves of the range should be represented
+    let low = sample
+        .iter()
+        .filter(|key| key.as_slice() < &b"k025000"[..])
+        .count();
+    assert!(low > 20 && low < 180, "skewed sample: {} of 200 low", low);
+
+    // Small ranges are sampled exactly, without duplicates
+    let small = db
+        .sample_keys(100, Some(&b"k000000"[..]), Some(&b"k000010"[..]))
+        .expect("Failed to sample keys");
+    assert_eq!(small.len(), 10);
+    assert_eq!(small[0], b"k000000");
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_iterator_pool() {
+    let path = "/tmp/rust_rocksdb_test_iterator_pool";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    db.put(b"a:1", b"one").expect("Failed to put value");
+    db.put(b"b:1", b"two").expect("Failed to put value");
+
+    let pool = IteratorPool::new(&db, 2);
+    assert_eq!(pool.idle_count(), 0);
+
+    {
+        let mut iter = pool.get().expect("Failed to get iterator");
+        iter.seek(b"a:");
+        assert_eq!(iter.key(), Some(&b"a:1"[..]));
+    }
+    assert_eq!(pool.idle_count(), 1);
+
+    // A reused iterator sees writes made since it was returned
+    db.put(b"a:2", b"three").expect("Failed to put value");
+    {
+        let mut iter = pool.get().expect("Failed to get iterator");
+        assert_eq!(pool.idle_count(), 0);
+        iter.seek(b"a:2");
+        assert_eq!(iter.item(), Some((&b"a:2"[..], &b"three"[..])));
+    }
+
+    // Only max_per_thread iterators are kept
+    {
+        let _first = pool.get().expect("Failed to get iterator");
+        let _second = pool.get().expect("Failed to get iterator");
+        let _third = pool.get().expect("Failed to get iterator");
+    }
+    assert_eq!(pool.idle_count(), 2);
+
+    // Other threads do not take this thread's iterators
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            assert_eq!(pool.idle_count(), 0);
+            let mut iter = pool.get().expect("Failed to get iterator");
+            iter.seek_to_last();
+            assert_eq!(iter.key(), Some(&b"b:1"[..]));
+        });
+    });
+    assert_eq!(pool.idle_count(), 2);
+
+    pool.clear();
+    assert_eq!(pool.idle_count(), 0);
+
+    drop(pool);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_checksummed_values() {
+    let path = "/tmp/rust_rocksdb_test_checksummed_values";
+    let _ = fs::remove_dir_all(path);
+
+    // CRC32C check value from the specification
+    let encoded = ChecksummedDB::encode_value(b"123456789");
+    assert_eq!(&encoded[..9], b"123456789");
+    assert_eq!(&encoded[9..], &0xE306_9283u32.to_le_bytes());
+    assert_eq!(
+        ChecksummedDB::decode_value(&encoded).expect("Failed to decode"),
+        b"123456789"
+    );
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    let store = ChecksummedDB::new(&db);
+
+    store.put(b"key", b"value").expect("Failed to put value");
+    assert_eq!(
+        store.get(b"key").expect("Failed to get value").as_deref(),
+        Some(&b"value"[..])
+    );
+    assert_eq!(
+        db.get(b"key")
+            .expect("Failed to get value")
+            .map(|v| v.len()),
+        Some(9)
+    );
+    assert_eq!(store.get(b"missing").expect("Failed to get value"), None);
+
+    let mut batch = WriteBatch::new();
+    ChecksummedDB::batch_put(&mut batch, b"batched", b"value");
+    db.write(&batch).expect("Failed to write batch");
+    assert_eq!(
+        store
+            .get(b"batched")
+            .expect("Failed to get value")
+            .as_deref(),
+        Some(&b"value"[..])
+    );
+
+    // A flipped byte and a value written without a checksum are both caught
+    let mut damaged = db.get(b"key").expect("Failed to get value").unwrap();
+    damaged[0] ^= 0x01;
+    db.put(b"key", &damaged).expect("Failed to put value");
+    let err = store.get(b"key").expect_err("Damaged value was accepted");
+    assert_eq!(err.kind(), ErrorKind::Corruption);
+
+    db.put(b"raw", b"ab").expect("Failed to put value");
+    let err = store
+        .get(b"raw")
+        .expect_err("Unchecksummed value was accepted");
+    assert_eq!(err.kind(), ErrorKind::Corruption);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_db_manager() {
+    let root = "/tmp/rust_rocksdb_test_db_manager";
+    let _ = fs::remove_dir_all(root);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    assert!(DbManager::new(root, &opts, 0).is_err());
+    let manager = DbManager::new(root, &opts, 2).expect("Failed to create manager");
+
+    assert!(manager.get("").is_err());
+    assert!(manager.get("..").is_err());
+    assert_eq!(
+        manager.get("a/b").err().expect("Expected an error").kind(),
+        ErrorKind::InvalidArgument
+    );
+
+    // Threads asking for the same database at once share one instance
+    let dbs: Vec<Arc<DB>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..4)
+            .map(|_| scope.spawn(|| manager.get("shared").expect("Failed to open shared")))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert!(dbs.iter().all(|db| Arc::ptr_eq(db, &dbs[0])));
+    drop(dbs);
+    assert!(manager.close("shared"));
+
+    let a = manager.get("a").expect("Failed to open a");
+    a.put(b"key", b"in a").expect("Failed to put value");
+    let again = manager.get("a").expect("Failed to get a");
+    assert!(Arc::ptr_eq(&a, &again));
+    drop(again);
+
+    let b = manager.get("b").expect("Failed to open b");
+    drop(b);
+    assert_eq!(manager.open_count(), 2);
+
+    // "a" is still held, so the idle "b" is closed to make room
+    let c = manager.get("c").expect("Failed to open c");
+    assert_eq!(manager.open_count(), 2);
+    assert!(manager.is_open("a"));
+    assert!(!manager.is_open("b"));
+    drop(c);
+
+    // Every database is held: the bound is exceeded rather than closing one in use
+    let held: Vec<Arc<DB>> = ["b", "c", "d"]
+        .iter()
+        .map(|name| manager.get(name).expect("Failed to open database"))
+        .collect();
+    assert_eq!(manager.open_count(), 4);
+    drop(held);
+
+    drop(a);
+    assert!(manager.close("a"));
+    assert!(!manager.close("a"));
+    let a = manager.get("a").expect("Failed to reopen a");
+    assert_eq!(
+        a.get(b"key").expect("Failed to get value").as_deref(),
+        Some(&b"in a"[..])
+    );
+    drop(a);
+
+    assert_eq!(
+        manager.list().expect("Failed to list databases"),
+        vec!["a", "b", "c", "d", "shared"]
+    );
+    manager.close_all();
+    assert_eq!(manager.open_count(), 0);
+
+    drop(manager);
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_shared_resources() {
+    let root = "/tmp/rust_rocksdb_test_shared_resources";
+    let _ = fs::remove_dir_all(root);
+
+    let mut resources = SharedResources::new();
+    assert!(resources.block_cache().is_none());
+    resources
+        .set_env(Env::new())
+        .set_block_cache(Cache::new_lru(8 * 1024 * 1024))
+        .set_write_buffer_manager(
+            WriteBufferManager::new(64 * 1024 * 1024, false)
+                .expect("Failed to create write buffer manager"),
+        )
+        .set_rate_limiter(RateLimiter::new(64 * 1024 * 1024).expect("Failed to create limiter"));
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let manager =
+        DbManager::with_resources(root, &opts, 4, resources).expect("Failed to create manager");
+    let shared = manager.resources().expect("Missing shared resources");
+
+    for name in ["a", "b"] {
+        let db = manager.get(name).expect("Failed to open database");
+        for i in 0..100u32 {
+            db.put(&i.to_be_bytes(), &[0u8; 1024])
+                .expect("Failed to put value");
+        }
+    }
+
+    // Both databases count against the same memtable budget
+    let wbm = shared
+        .write_buffer_manager()
+        .expect("Missing write buffer manager");
+    assert!(wbm.memory_usage() > 0);
+
+    // Reads from both databases go through the same block cache
+    for name in ["a", "b"] {
+        let db = manager.get(name).expect("Failed to get database");
+        db.flush().expect("Failed to flush");
+        db.get(&0u32.to_be_bytes()).expect("Failed to get value");
+    }
+    let cache = shared.block_cache().expect("Missing block cache");
+    assert!(cache.usage() > 0);
+
+    let stats = manager
+        .get("a")
+        .expect("Failed to get database")
+        .cache_stats()
+        .expect("Failed to get cache stats");
+    assert_eq!(stats.block_cache.capacity, 8 * 1024 * 1024);
+
+    drop(manager);
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn test_options_validate() {
+    let path = "/tmp/rust_rocksdb_test_options_validate";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.validate().expect("Default options should be valid");
+
+    // A prefix-only bloom filter needs a prefix extractor
+    let mut table_opts = BlockBasedOptions::default();
+    table_opts.set_bloom_filter(10.0);
+    table_opts.set_whole_key_filtering(false);
+    opts.set_block_based_table_factory(&table_opts);
+    let err = DB::open(&opts, path).err().expect("Open should fail");
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert!(!Path::new(path).exists());
+
+    opts.set_fixed_prefix_extractor(4);
+    opts.validate().expect("Options should be valid");
+
+    // error_if_exists without create_if_missing can never open
+    opts.create_if_missing(false);
+    opts.error_if_exists(true);
+    assert!(opts.validate().is_err());
+
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_mem_kv() {
+    let kv = MemKv::new();
+    assert!(kv.is_empty());
+    kv.put(b"b", b"2").expect("Failed to put");
+    kv.put(b"a", b"1").expect("Failed to put");
+
+    let snapshot = kv.snapshot();
+    kv.put(b"a", b"10").expect("Failed to put");
+    kv.delete(b"b").expect("Failed to delete");
+    kv.delete(b"missing").expect("Failed to delete");
+
+    assert_eq!(kv.entries(), vec![(b"a".to_vec(), b"10".to_vec())]);
+    assert!(!kv.contains_key(b"b").expect("Failed to check key"));
+
+    // The snapshot keeps the contents from before the writes
+    assert_eq!(
+        snapshot.entries(),
+        vec![
+            (b"a".to_vec(), b"1".to_vec()),
+            (b"b".to_vec(), b"2".to_vec())
+        ]
+    );
+    assert_eq!(
+        snapshot.get(b"b").expect("Failed to get"),
+        Some(b"2".to_vec())
+    );
+}
+
+#[test]
+fn test_write_batch_with_index() {
+    let path = "/tmp/rust_rocksdb_test_write_batch_with_index";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true).set_max_value_size(16);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    db.put(b"a", b"1").expect("Failed to put");
+    db.put(b"b", b"2").expect("Failed to put");
+
+    let batch = WriteBatchWithIndex::new();
+    assert!(batch.is_empty());
+    batch.put(b"c", b"3");
+    batch.put(b"c", b"30");
+    batch.delete(b"b");
+
+    // The batch sees only its own writes; the database view falls back to stored keys
+    assert_eq!(
+        batch.get_from_batch(b"c").expect("Failed to get"),
+        Some(b"30".to_vec())
+    );
+    assert_eq!(batch.get_from_batch(b"a").expect("Failed to get"), None);
+    assert_eq!(
+        db.get_with_batch(&batch, b"a").expect("Failed to get"),
+        Some(b"1".to_vec())
+    );
+    assert_eq!(
+        db.get_with_batch(&batch, b"b").expect("Failed to get"),
+        None
+    );
+    assert_eq!(db.get(b"c").expect("Failed to get"), None);
+
+    // Generic code can stage writes in the batch
+    KvWrite::put(&batch, b"d", b"4").expect("Failed to put");
+    assert!(KvRead::contains_key(&batch, b"d").expect("Failed to check key"));
+
+    db.write_indexed(&batch).expect("Failed to write batch");
+    assert_eq!(db.get(b"b").expect("Failed to get"), None);
+    assert_eq!(db.get(b"c").expect("Failed to get"), Some(b"30".to_vec()));
+    assert_eq!(db.get(b"d").expect("Failed to get"), Some(b"4".to_vec()));
+
+    // Indexed batches go through the same size limits as plain ones
+    batch.clear();
+    batch.put(b"e", &[0u8; 17]);
+    let err = db
+        .write_indexed(&batch)
+        .expect_err("Oversized value should be rejected");
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert_eq!(db.get(b"e").expect("Failed to get"), None);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_write_batch_check_ranges() {
+    let path = "/tmp/rust_rocksdb_test_batch_check_ranges";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let mut batch = WriteBatch::new();
+    batch.set_check_ranges(true);
+    batch.put(b"a", b"1");
+    batch.delete_range(b"z", b"a");
+
+    // The batch is rejected as a whole
+    let err = db.write(&batch).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert_eq!(db.get(b"a").expect("Failed to get"), None);
+
+    // The range is kept in the batch rather than dropped
+    assert_eq!(batch.len(), 2);
+
+    batch.clear();
+    batch.put(b"a", b"1");
+    db.write(&batch).expect("Failed to write batch");
+    assert_eq!(db.get(b"a").expect("Failed to get"), Some(b"1".to_vec()));
+
+    // The checked variant reports the range right away, whatever the setting
+    let mut batch = WriteBatch::new();
+    batch.set_check_ranges(false);
+    let err = batch.try_delete_range(b"z", b"a").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert!(batch.is_empty());
+    batch
+        .try_delete_range(b"a", b"b")
+        .expect("Failed to add range deletion");
+    db.write(&batch).expect("Failed to write batch");
+    assert_eq!(db.get(b"a").expect("Failed to get"), None);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_compaction_scheduler_tombstones() {
+    let path = "/tmp/rust_rocksdb_test_compaction_scheduler";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = Arc::new(DB::open(&opts, path).expect("Failed to open database"));
+    for i in 0..100u32 {
+        db.put(&i.to_be_bytes(), b"value").expect("Failed to put");
+    }
+    db.flush().expect("Failed to flush");
+    for i in 0..100u32 {
+        db.delete(&i.to_be_bytes()).expect("Failed to delete");
+    }
+    db.flush().expect("Failed to flush");
+
+    // A window starting an hour from now excludes the present
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("Clock before epoch");
+    let mut idle = CompactionScheduler::new(&db);
+    idle.set_max_tombstone_ratio(0.5).add_off_peak_window(
+        now + Duration::from_secs(3600),
+        now + Duration::from_secs(7200),
+    );
+    assert!(!idle.in_off_peak_window());
+    assert!(idle.run_once().expect("Failed to run").is_empty());
+
+    let mut scheduler = CompactionScheduler::new(&db);
+    scheduler.set_max_tombstone_ratio(0.5);
+    let actions = scheduler.run_once().expect("Failed to run");
+    assert_eq!(actions.len(), 1);
+    assert!(matches!(
+        &actions[0],
+        CompactionAction::Tombstones {
+            deletions: 100,
+            entries: 100,
+            ..
+        }
+    ));
+
+    // Files are only suggested once
+    assert!(scheduler.run_once().expect("Failed to run").is_empty());
+
+    drop(scheduler);
+    drop(idle);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_set_options() {
+    let path = "/tmp/rust_rocksdb_test_set_options";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    db.set_options(&[
+        ("write_buffer_size", "134217728"),
+        ("disable_auto_compactions", "true"),
+    ])
+    .expect("Failed to set column family options");
+
+    // Unknown options and database options on a column family are rejected
+    let err = db.set_options(&[("no_such_option", "1")]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert!(db.set_options(&[("max_background_jobs", "4")]).is_err());
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_table_properties() {
+    let path = "/tmp/rust_rocksdb_test_table_properties";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    assert!(db.table_properties().expect("Failed to list").is_empty());
+
+    for i in 0..10u32 {
+        db.put(&i.to_be_bytes(), b"value").expect("Failed to put");
+    }
+    db.flush().expect("Failed to flush");
+    for i in 0..5u32 {
+        db.delete(&i.to_be_bytes()).expect("Failed to delete");
+    }
+    db.flush().expect("Failed to flush");
+
+    let mut files = db.table_properties().expect("Failed to list");
+    files.sort_by_key(|file| file.largest_seqno);
+    assert_eq!(files.len(), 2);
+    for file in &files {
+        assert_eq!(file.column_family, "default");
+        assert!(file.name.ends_with(".sst"));
+        assert!(Path::new(&file.directory).join(&file.name).exists());
+        assert!(file.size > 0);
+        assert!(file.created.is_some());
+    }
+    assert_eq!((files[0].num_entries, files[0].num_deletions), (10, 0));
+    assert_eq!((files[1].num_entries, files[1].num_deletions), (5, 5));
+    assert_eq!(files[1].tombstone_ratio(), 1.0);
+    assert_eq!(files[1].smallest_key, 0u32.to_be_bytes());
+    assert_eq!(files[1].largest_key, 4u32.to_be_bytes());
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_compact_on_deletion_collector() {
+    let path = "/tmp/rust_rocksdb_test_compact_on_deletion";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true)
+        .add_compact_on_deletion_collector_factory(100, 50, 0.5);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    for i in 0..1000u32 {
+        db.put(&i.to_be_bytes(), b"value").expect("Failed to put");
+    }
+    db.flush().expect("Failed to flush");
+    for i in 0..1000u32 {
+        db.delete(&i.to_be_bytes()).expect("Failed to delete");
+    }
+    db.flush().expect("Failed to flush");
+
+    assert_eq!(db.iter(Direction::Forward).count(), 0);
+
+    // Two L0 files are below the compaction trigger, so only the collector
+    // marking the tombstone file gets it compacted, which drops every key
+    let sst_size = || {
+        db.property_int_value("rocksdb.live-sst-files-size")
+            .expect("Failed to read property")
+            .expect("Missing property")
+    };
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while sst_size() > 0 && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    assert_eq!(sst_size(), 0);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_universal_compaction() {
+    let path = "/tmp/rust_rocksdb_test_universal_compaction";
+    let _ = fs::remove_dir_all(path);
+
+    let mut universal = UniversalCompactionOptions::default();
+    universal
+        .set_size_ratio(10)
+        .set_min_merge_width(2)
+        .set_max_merge_width(u32::MAX)
+        .set_max_size_amplification_percent(150);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true)
+        .set_compaction_style(CompactionStyle::Universal)
+        .set_universal_compaction_options(&universal);
+    drop(universal);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    // Overwrite the same keys across several sorted runs
+    for round in 0..5u32 {
+        for i in 0..100u32 {
+            db.put(&i.to_be_bytes(), &round.to_be_bytes())
+                .expect("Failed to put");
+        }
+        db.flush().expect("Failed to flush");
+    }
+    db.compact_range(None, None);
+
+    assert_eq!(db.iter(Direction::Forward).count(), 100);
+    assert_eq!(
+        db.get(&7u32.to_be_bytes()).expect("Failed to get"),
+        Some(4u32.to_be_bytes().to_vec())
+    );
+
+    // The options RocksDB persisted show what it actually applied
+    let options_file = fs::read_dir(path)
+        .expect("Failed to list database")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with("OPTIONS-")
+        })
+        .max()
+        .expect("Missing OPTIONS file");
+    let persisted = fs::read_to_string(options_file).expect("Failed to read OPTIONS file");
+    for setting in [
+        "compaction_style=kCompactionStyleUniversal",
+        "size_ratio=10;",
+        "min_merge_width=2;",
+        // Capped to a C int rather than wrapping to -1
+        "max_merge_width=2147483647;",
+        "max_size_amplification_percent=150;",
+    ] {
+        assert!(persisted.contains(setting), "OPTIONS lacks {}", setting);
+    }
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_fifo_compaction() {
+    let path = "/tmp/rust_rocksdb_test_fifo_compaction";
+    let _ = fs::remove_dir_all(path);
+
+    let mut fifo = FifoCompactionOptions::default();
+    fifo.set_max_table_files_size(256 * 1024);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true)
+        .set_compaction_style(CompactionStyle::Fifo)
+        .set_fifo_compaction_options(&fifo)
+        .set_ttl(0);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    // Each flush writes a file of about 100 KiB, so only the newest few fit
+    let value = vec![0u8; 1024];
+    for round in 0..10u32 {
+        for i in 0..100u32 {
+            db.put(&(round * 100 + i).to_be_bytes(), &value)
+                .expect("Failed to put");
+        }
+        db.flush().expect("Failed to flush");
+    }
+    db.compact_range(None, None);
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while db
+        .get(&0u32.to_be_bytes())
+        .expect("Failed to get")
+        .is_some()
+    {
+        assert!(Instant::now() < deadline, "Oldest file was never dropped");
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    assert!(
+        db.get(&999u32.to_be_bytes())
+            .expect("Failed to get")
+            .is_some()
+    );
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_quota_writer() {
+    let path = "/tmp/rust_rocksdb_test_quota_writer";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true).set_max_key_size(64);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    let throttled = db
+        .create_column_family(&Options::default(), "throttled")
+        .expect("Failed to create column family");
+    let capped = db
+        .create_column_family(&Options::default(), "capped")
+        .expect("Failed to create column family");
+
+    let mut writer = QuotaWriter::new(&db);
+    writer
+        .set_bytes_per_sec(&throttled, 100)
+        .set_max_size(&capped, 1024 * 1024);
+
+    // A write that fails isn't charged, leaving room for the next one
+    let err = writer.put_cf(&throttled, &[b'k'; 70], b"").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert_eq!(writer.bytes_written(&throttled), 0);
+
+    // The bucket holds one second's worth, so the second write doesn't fit
+    writer
+        .put_cf(&throttled, b"k1", &[0u8; 58])
+        .expect("Failed to put");
+    let err = writer.put_cf(&throttled, b"k2", &[0u8; 58]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::QuotaExceeded);
+    assert!(err.to_string().contains("throttled"));
+    assert_eq!(db.get_cf(&throttled, b"k2").unwrap(), None);
+    assert_eq!(writer.bytes_written(&throttled), 60);
+
+    // A refused batch writes nothing, not even to unlimited column families
+    let mut batch = WriteBatch::new();
+    batch.put(b"default_key", b"value");
+    batch.put_cf(&throttled, b"k3", &[0u8; 58]);
+    assert_eq!(
+        writer.write(&batch).unwrap_err().kind(),
+        ErrorKind::QuotaExceeded
+    );
+    assert_eq!(db.get(b"default_key").unwrap(), None);
+
+    // Writes after a range deletion are charged too
+    let mut batch = WriteBatch::new();
+    batch.delete_range_cf(&throttled, b"a", b"b");
+    batch.put_cf(&throttled, b"k3", &[0u8; 58]);
+    assert_eq!(
+        writer.write(&batch).unwrap_err().kind(),
+        ErrorKind::QuotaExceeded
+    );
+    assert_eq!(db.get_cf(&throttled, b"k3").unwrap(), None);
+
+    // Column families without quotas are written freely
+    for i in 0..100u32 {
+        writer
+            .put(&i.to_be_bytes(), &[0u8; 1024])
+            .expect("Failed to put");
+    }
+    assert_eq!(
+        writer.bytes_written(&db.cf_handle("default").unwrap()),
+        102_800
+    );
+
+    writer
+        .put_cf(&capped, b"big1", &vec![0u8; 512 * 1024])
+        .expect("Failed to put");
+    assert_eq!(
+        writer
+            .put_cf(&capped, b"big2", &vec![0u8; 600 * 1024])
+            .unwrap_err()
+            .kind(),
+        ErrorKind::QuotaExceeded
+    );
+    writer
+        .delete_cf(&capped, b"big1")
+        .expect("Deletes of a few bytes still fit");
+
+    drop(writer);
+    drop(throttled);
+    drop(capped);
     drop(db);
     let _ = fs::remove_dir_all(path);
 }
@@ -0,0 +1,134 @@
+use rust_small_rocksdb::{ErrorKind, Options, PartitionPeriod, Partitioner};
+use std::fs;
+
+const HOUR: u64 = 60 * 60;
+// 2024-01-01T00:00:00Z
+const BASE: u64 = 1_704_067_200;
+
+fn open(path: &str, retention: usize) -> Partitioner {
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    Partitioner::open(opts, path, "events_", PartitionPeriod::Hourly, retention)
+        .expect("Failed to open partitioned database")
+}
+
+#[test]
+fn test_partitioner_routes_writes_by_timestamp() {
+    let path = "/tmp/rust_rocksdb_test_partitioner_routes";
+    let _ = fs::remove_dir_all(path);
+
+    let events = open(path, 24);
+    events
+        .put(BASE + 10, b"a", b"first")
+        .expect("Failed to put");
+    // Partitions can be created from other threads
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            events
+                .put(BASE + HOUR + 5, b"a", b"second")
+                .expect("Failed to put");
+        });
+    });
+
+    assert_eq!(events.partitions(), vec![BASE, BASE + HOUR]);
+    assert_eq!(events.partition_name(BASE + 10), format!("events_{}", BASE));
+
+    // Each hour has its own copy of the key
+    assert_eq!(
+        events.get(BASE + 59, b"a").unwrap().as_deref(),
+        Some(&b"first"[..])
+    );
+    assert_eq!(
+        events.get(BASE + HOUR, b"a").unwrap().as_deref(),
+        Some(&b"second"[..])
+    );
+    assert_eq!(events.get(BASE + 2 * HOUR, b"a").unwrap(), None);
+
+    drop(events);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_partitioner_roll_drops_expired_partitions() {
+    let path = "/tmp/rust_rocksdb_test_partitioner_roll";
+    let _ = fs::remove_dir_all(path);
+
+    let events = open(path, 2);
+    events.put(BASE, b"k", b"0").expect("Failed to put");
+    events.put(BASE + HOUR, b"k", b"1").expect("Failed to put");
+
+    let dropped = events.roll(BASE + 2 * HOUR).expect("Failed to roll");
+    assert_eq!(dropped, vec![format!("events_{}", BASE)]);
+    assert_eq!(events.partitions(), vec![BASE + HOUR, BASE + 2 * HOUR]);
+    assert_eq!(events.get(BASE, b"k").unwrap(), None);
+
+    // Expired windows are not recreated by late writes
+    let err = events
+        .put(BASE, b"k", b"late")
+        .expect_err("Late write should be rejected");
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+
+    drop(events);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_partitioner_roll_keeps_partitions_that_fail_to_drop() {
+    let path = "/tmp/rust_rocksdb_test_partitioner_roll_failure";
+    let _ = fs::remove_dir_all(path);
+
+    let events = open(path, 2);
+    events.put(BASE, b"k", b"0").expect("Failed to put");
+
+    // Dropped behind the partitioner's back, so its own drop fails
+    events
+        .db()
+        .drop_column_family_by_name(&events.partition_name(BASE))
+        .expect("Failed to drop column family");
+    assert!(events.roll(BASE + 2 * HOUR).is_err());
+    assert_eq!(events.partitions(), vec![BASE, BASE + 2 * HOUR]);
+
+    drop(events);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_partitioner_merged_scan_and_reopen() {
+    let path = "/tmp/rust_rocksdb_test_partitioner_scan";
+    let _ = fs::remove_dir_all(path);
+
+    {
+        let events = open(path, 24);
+        events.put(BASE, b"b", b"1").expect("Failed to put");
+        events.put(BASE + HOUR, b"a", b"2").expect("Failed to put");
+        events.put(BASE + HOUR, b"c", b"3").expect("Failed to put");
+    }
+
+    // Partitions are rediscovered on open
+    let events = open(path, 24);
+    assert_eq!(events.partitions(), vec![BASE, BASE + HOUR]);
+
+    let keys: Vec<Vec<u8>> = events.scan(|iter| {
+        iter.map(|item| item.expect("Failed to iterate").1.to_vec())
+            .collect()
+    });
+    assert_eq!(keys, vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]);
+
+    drop(events);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_partitioner_rejects_zero_retention() {
+    let path = "/tmp/rust_rocksdb_test_partitioner_zero_retention";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let err = Partitioner::open(opts, path, "events_", PartitionPeriod::Daily, 0)
+        .err()
+        .expect("Zero retention should be rejected");
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+
+    let _ = fs::remove_dir_all(path);
+}
@@ -0,0 +1,118 @@
+use rust_small_rocksdb::{DB, ErrorKind, Options, SstFileWriter};
+use std::fs;
+
+#[test]
+fn test_sst_file_writer_ingest_default_cf() {
+    let path = "/tmp/rust_rocksdb_test_sst_ingest";
+    let sst_path = "/tmp/rust_rocksdb_test_sst_ingest.sst";
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_file(sst_path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+
+    let mut writer = SstFileWriter::new(&opts).expect("Failed to create SST writer");
+    writer.open(sst_path).expect("Failed to open SST file");
+    writer.put(b"key1", b"value1").expect("Failed to add key1");
+    writer.put(b"key2", b"value2").expect("Failed to add key2");
+    writer.finish().expect("Failed to finish SST file");
+    assert!(writer.file_size() > 0);
+    drop(writer);
+
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    db.ingest_external_file(&[sst_path])
+        .expect("Failed to ingest SST file");
+
+    assert_eq!(db.get(b"key1").unwrap().as_deref(), Some(&b"value1"[..]));
+    assert_eq!(db.get(b"key2").unwrap().as_deref(), Some(&b"value2"[..]));
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_file(sst_path);
+}
+
+#[test]
+fn test_sst_file_writer_ingest_into_column_family() {
+    let path = "/tmp/rust_rocksdb_test_sst_ingest_cf";
+    let sst_path = "/tmp/rust_rocksdb_test_sst_ingest_cf.sst";
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_file(sst_path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    // Write the file with the target column family's options
+    let cf_opts = Options::default();
+    let cf = db
+        .create_column_family(&cf_opts, "bulk")
+        .expect("Failed to create column family");
+
+    let mut writer = SstFileWriter::new(&cf_opts).expect("Failed to create SST writer");
+    writer.open(sst_path).expect("Failed to open SST file");
+    writer.put(b"a", b"1").expect("Failed to add a");
+    writer.put(b"b", b"2").expect("Failed to add b");
+    writer.finish().expect("Failed to finish SST file");
+    drop(writer);
+
+    db.ingest_external_file_cf(&cf, &[sst_path])
+        .expect("Failed to ingest SST file");
+
+    assert_eq!(db.get_cf(&cf, b"a").unwrap().as_deref(), Some(&b"1"[..]));
+    assert_eq!(db.get_cf(&cf, b"b").unwrap().as_deref(), Some(&b"2"[..]));
+    // Data only lands in the target column family
+    assert_eq!(db.get(b"a").unwrap(), None);
+
+    drop(cf);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+    let _ = fs::remove_file(sst_path);
+}
+
+#[test]
+fn test_sst_file_writer_rejects_unsorted_keys() {
+    let sst_path = "/tmp/rust_rocksdb_test_sst_unsorted.sst";
+    let _ = fs::remove_file(sst_path);
+
+    let opts = Options::default();
+    let mut writer = SstFileWriter::new(&opts).expect("Failed to create SST writer");
+    writer.open(sst_path).expect("Failed to open SST file");
+    writer.put(b"b", b"2").expect("Failed to add b");
+
+    // Keys must be strictly increasing
+    assert!(writer.put(b"a", b"1").is_err());
+
+    drop(writer);
+    let _ = fs::remove_file(sst_path);
+}
+
+#[test]
+fn test_sst_file_writer_check_key_order() {
+    let sst_path = "/tmp/rust_rocksdb_test_sst_check_order.sst";
+    let _ = fs::remove_file(sst_path);
+
+    let opts = Options::default();
+    let mut writer = SstFileWriter::new(&opts).expect("Failed to create SST writer");
+    writer.set_check_key_order(true);
+    writer.open(sst_path).expect("Failed to open SST file");
+    writer.put(b"key2", b"2").expect("Failed to add key2");
+
+    // Repeated and smaller keys are rejected before reaching RocksDB
+    for key in [&b"key2"[..], b"key1"] {
+        let err = writer.delete(key).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+        assert!(err.message().contains("key2"));
+    }
+    let err = writer.delete_range(b"z", b"a").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+
+    // Range deletions don't take part in the key order
+    writer
+        .delete_range(b"a", b"b")
+        .expect("Failed to add range deletion");
+    writer.put(b"key3", b"3").expect("Failed to add key3");
+    writer.finish().expect("Failed to finish SST file");
+
+    drop(writer);
+    let _ = fs::remove_file(sst_path);
+}
@@ -0,0 +1,153 @@
+use rust_small_rocksdb::{KvRead, KvWrite, OptimisticTransactionDB, Options, Result};
+use std::fs;
+use std::sync::Arc;
+use std::thread;
+
+fn decode_counter(value: Option<&[u8]>) -> u64 {
+    value
+        .map(|v| u64::from_le_bytes(v.try_into().expect("Invalid counter")))
+        .unwrap_or(0)
+}
+
+#[test]
+fn test_transaction_commit_and_rollback() {
+    let path = "/tmp/rust_rocksdb_test_txn_commit";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = OptimisticTransactionDB::open(&opts, path).expect("Failed to open database");
+
+    let txn = db.transaction().expect("Failed to begin transaction");
+    txn.put(b"a", b"1").expect("Failed to put");
+    // Uncommitted writes are visible inside the transaction only
+    assert_eq!(txn.get(b"a").unwrap().as_deref(), Some(&b"1"[..]));
+    assert_eq!(db.get(b"a").unwrap(), None);
+    txn.commit().expect("Failed to commit");
+    assert_eq!(db.get(b"a").unwrap().as_deref(), Some(&b"1"[..]));
+
+    let txn = db.transaction().expect("Failed to begin transaction");
+    txn.delete(b"a").expect("Failed to delete");
+    txn.rollback().expect("Failed to roll back");
+    assert_eq!(db.get(b"a").unwrap().as_deref(), Some(&b"1"[..]));
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_transaction_write_conflict() {
+    let path = "/tmp/rust_rocksdb_test_txn_conflict";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = OptimisticTransactionDB::open(&opts, path).expect("Failed to open database");
+    db.put(b"key", b"original").expect("Failed to put");
+
+    let txn = db.transaction().expect("Failed to begin transaction");
+    txn.get_for_update(b"key").expect("Failed to read");
+
+    // A concurrent writer changes the tracked key
+    db.put(b"key", b"concurrent").expect("Failed to put");
+
+    txn.put(b"key", b"from_txn").expect("Failed to put");
+    assert!(txn.commit().is_err());
+    assert_eq!(db.get(b"key").unwrap().as_deref(), Some(&b"concurrent"[..]));
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_update_read_modify_write() {
+    let path = "/tmp/rust_rocksdb_test_txn_update";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = OptimisticTransactionDB::open(&opts, path).expect("Failed to open database");
+
+    let new = db
+        .update(b"counter", |old| {
+            Some((decode_counter(old) + 1).to_le_bytes().to_vec())
+        })
+        .expect("Failed to update");
+    assert_eq!(decode_counter(new.as_deref()), 1);
+
+    // Returning None deletes the key
+    let new = db.update(b"counter", |_| None).expect("Failed to update");
+    assert_eq!(new, None);
+    assert_eq!(db.get(b"counter").unwrap(), None);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_update_concurrent_increments() {
+    let path = "/tmp/rust_rocksdb_test_txn_update_concurrent";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = Arc::new(OptimisticTransactionDB::open(&opts, path).expect("Failed to open database"));
+
+    let threads = 4;
+    let increments = 50;
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let db = Arc::clone(&db);
+            thread::spawn(move || {
+                for _ in 0..increments {
+                    db.update(b"counter", |old| {
+                        Some((decode_counter(old) + 1).to_le_bytes().to_vec())
+                    })
+                    .expect("Failed to update");
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().expect("Thread panicked");
+    }
+
+    let value = db.get(b"counter").unwrap();
+    assert_eq!(decode_counter(value.as_deref()), threads * increments);
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+/// Move a value between keys through the generic key-value traits
+fn move_value<S: KvRead + KvWrite>(store: &S, from: &[u8], to: &[u8]) -> Result<()> {
+    if let Some(value) = store.get(from)? {
+        store.put(to, &value)?;
+        store.delete(from)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_kv_traits() {
+    let path = "/tmp/rust_rocksdb_test_txn_kv_traits";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = OptimisticTransactionDB::open(&opts, path).expect("Failed to open database");
+    db.put(b"a", b"1").expect("Failed to put");
+
+    let txn = db.transaction().expect("Failed to begin transaction");
+    move_value(&txn, b"a", b"b").expect("Failed to move in transaction");
+    assert!(txn.contains_key(b"b").expect("Failed to check key"));
+    assert!(db.contains_key(b"a").expect("Failed to check key"));
+    txn.commit().expect("Failed to commit");
+
+    move_value(&db, b"b", b"c").expect("Failed to move");
+    assert_eq!(db.get(b"c").expect("Failed to get"), Some(b"1".to_vec()));
+    assert!(!KvRead::contains_key(&db, b"b").expect("Failed to check key"));
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
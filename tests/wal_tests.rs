@@ -0,0 +1,207 @@
+use rust_small_rocksdb::{DB, ErrorKind, Options, WriteBatch, WriteBatchOp};
+use std::fs;
+
+#[test]
+fn test_write_batch_is_atomic() {
+    let path = "/tmp/rust_rocksdb_test_write_batch";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    db.put(b"stale", b"value").expect("Failed to put value");
+
+    let mut batch = WriteBatch::new();
+    assert!(batch.is_empty());
+    batch.put(b"key1", b"value1");
+    batch.put(b"key2", b"value2");
+    batch.delete(b"stale");
+    assert_eq!(batch.len(), 3);
+
+    db.write(&batch).expect("Failed to write batch");
+    assert_eq!(db.get(b"key1").unwrap().as_deref(), Some(&b"value1"[..]));
+    assert_eq!(db.get(b"key2").unwrap().as_deref(), Some(&b"value2"[..]));
+    assert_eq!(db.get(b"stale").unwrap(), None);
+
+    // Serialized batches round-trip
+    let copy = WriteBatch::from_data(batch.data()).expect("Failed to rebuild batch");
+    assert_eq!(
+        copy.operations().expect("Failed to decode copy"),
+        batch.operations().expect("Failed to decode batch")
+    );
+
+    batch.clear();
+    assert!(batch.is_empty());
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_write_batch_operations_decode_range_deletions() {
+    let path = "/tmp/rust_rocksdb_test_write_batch_operations";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+    let cf = db
+        .create_column_family(&Options::default(), "events")
+        .expect("Failed to create column family");
+
+    // Operations after a range deletion are still decoded
+    let mut batch = WriteBatch::new();
+    batch.delete_range(b"a", b"m");
+    batch.put(b"n", b"1");
+    batch.delete_range_cf(&cf, b"x", b"z");
+    batch.delete_cf(&cf, b"y");
+    assert_eq!(
+        batch.operations().expect("Failed to decode batch"),
+        vec![
+            WriteBatchOp::DeleteRange {
+                cf_id: 0,
+                start: b"a".to_vec(),
+                end: b"m".to_vec(),
+            },
+            WriteBatchOp::Put {
+                cf_id: 0,
+                key: b"n".to_vec(),
+                value: b"1".to_vec(),
+            },
+            WriteBatchOp::DeleteRange {
+                cf_id: cf.id(),
+                start: b"x".to_vec(),
+                end: b"z".to_vec(),
+            },
+            WriteBatchOp::Delete {
+                cf_id: cf.id(),
+                key: b"y".to_vec(),
+            },
+        ]
+    );
+
+    // A single delete has no variant, so nothing is decoded
+    let mut data = vec![0u8; 8];
+    data.extend(1u32.to_le_bytes());
+    data.extend([0x7, 1, b'k']);
+    let err = WriteBatch::from_data(&data)
+        .expect("Failed to rebuild batch")
+        .operations()
+        .expect_err("Single delete should not decode");
+    assert_eq!(err.kind(), ErrorKind::NotSupported);
+
+    // Malformed data is rejected before it reaches RocksDB
+    let mut miscounted = vec![0u8; 8];
+    miscounted.extend(2u32.to_le_bytes());
+    miscounted.extend([0x1, 1, b'k', 1, b'v']);
+    let mut truncated = vec![0u8; 8];
+    truncated.extend(1u32.to_le_bytes());
+    truncated.extend([0x1, 1, b'k', 5, b'v']);
+    for data in [&b""[..], &[0u8; 11], &miscounted, &truncated] {
+        let err = WriteBatch::from_data(data)
+            .err()
+            .expect("Malformed batch should be rejected");
+        assert_eq!(err.kind(), ErrorKind::Corruption);
+    }
+
+    drop(cf);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_get_updates_since_streams_batches() {
+    let path = "/tmp/rust_rocksdb_test_get_updates_since";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true)
+        .set_wal_ttl_seconds(3600)
+        .set_wal_size_limit_mb(64);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let cf_opts = Options::default();
+    let cf = db
+        .create_column_family(&cf_opts, "events")
+        .expect("Failed to create column family");
+
+    db.put(b"a", b"1").expect("Failed to put value");
+    let mut batch = WriteBatch::new();
+    batch.put_cf(&cf, b"b", b"2");
+    batch.delete_cf(&cf, b"c");
+    db.write(&batch).expect("Failed to write batch");
+
+    let mut ops = Vec::new();
+    let mut next_seq = 0;
+    for item in db.get_updates_since(0).expect("Failed to read WAL") {
+        let (seq, batch) = item.expect("Failed to read batch");
+        assert!(seq >= next_seq);
+        next_seq = seq + batch.len() as u64;
+        ops.extend(batch.operations().expect("Failed to decode batch"));
+    }
+
+    assert_eq!(
+        ops,
+        vec![
+            WriteBatchOp::Put {
+                cf_id: 0,
+                key: b"a".to_vec(),
+                value: b"1".to_vec(),
+            },
+            WriteBatchOp::Put {
+                cf_id: cf.id(),
+                key: b"b".to_vec(),
+                value: b"2".to_vec(),
+            },
+            WriteBatchOp::Delete {
+                cf_id: cf.id(),
+                key: b"c".to_vec(),
+            },
+        ]
+    );
+
+    // Resuming from the next sequence number yields only newer batches
+    db.put(b"d", b"4").expect("Failed to put value");
+    let newer: Vec<u64> = db
+        .get_updates_since(next_seq)
+        .expect("Failed to read WAL")
+        .map(|item| item.expect("Failed to read batch").0)
+        .collect();
+    assert_eq!(newer, vec![next_seq]);
+
+    drop(cf);
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}
+
+#[test]
+fn test_latest_sequence_number() {
+    let path = "/tmp/rust_rocksdb_test_latest_sequence_number";
+    let _ = fs::remove_dir_all(path);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true).set_wal_ttl_seconds(3600);
+    let db = DB::open(&opts, path).expect("Failed to open database");
+
+    let start = db.latest_sequence_number();
+    db.put(b"key1", b"value1").expect("Failed to put value");
+    assert_eq!(db.latest_sequence_number(), start + 1);
+
+    // Each operation in a batch consumes a sequence number
+    let mut batch = WriteBatch::new();
+    batch.put(b"key2", b"value2");
+    batch.delete(b"key1");
+    db.write(&batch).expect("Failed to write batch");
+    let latest = db.latest_sequence_number();
+    assert_eq!(latest, start + 3);
+
+    // Reads don't advance the sequence number
+    db.get(b"key2").expect("Failed to get value");
+    assert_eq!(db.latest_sequence_number(), latest);
+
+    // Tailing from a sequence number that hasn't been written yet is refused
+    assert!(db.get_updates_since(latest + 1).is_err());
+
+    drop(db);
+    let _ = fs::remove_dir_all(path);
+}